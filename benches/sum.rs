@@ -0,0 +1,24 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use temp::{sum, Array, ArrayF64};
+
+fn bench_sum(c: &mut Criterion) {
+    let len = 1_000_000;
+    let dense = ArrayF64::new((0..len).map(|idx| Some(idx as f64)));
+    let sparse = ArrayF64::new((0..len).map(|idx| (idx % 7 != 0).then_some(idx as f64)));
+
+    let mut group = c.benchmark_group("sum_f64_1m");
+
+    for (name, array) in [("dense", &dense), ("nulls", &sparse)] {
+        group.bench_function(format!("scalar_{name}"), |b| {
+            b.iter(|| sum(black_box(array)))
+        });
+        group.bench_function(format!("simd_{name}"), |b| {
+            b.iter(|| black_box(array).sum())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_sum);
+criterion_main!(benches);
@@ -0,0 +1,184 @@
+//! [`proptest`] strategies for every array type.
+//!
+//! Generated arrays shrink toward shorter arrays with fewer nulls. Floats
+//! are never `NaN` so that every generated array equals itself.
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+use proptest::arbitrary::{any, Arbitrary};
+use proptest::collection::vec;
+use proptest::prop_oneof;
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+use crate::union::UnionType;
+use crate::utils::Array;
+use crate::{
+    ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize, Union,
+};
+
+/// Length and null density of generated arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayParams {
+    /// Range of lengths to pick from.
+    pub len: Range<usize>,
+    /// Chance of each element being null.
+    pub null_probability: f64,
+}
+
+impl ArrayParams {
+    pub fn new(len: Range<usize>, null_probability: f64) -> Self {
+        Self {
+            len,
+            null_probability,
+        }
+    }
+}
+
+impl Default for ArrayParams {
+    /// Up to 64 elements, a fifth of which are null.
+    fn default() -> Self {
+        Self::new(0..65, 0.2)
+    }
+}
+
+/// Returns a strategy for arrays described by `params`, drawing valid
+/// elements from `value`.
+pub fn array_strategy<A, S>(params: ArrayParams, value: S) -> BoxedStrategy<A>
+where
+    A: Array + Debug + 'static,
+    A::Data: Debug,
+    S: Strategy<Value = A::Data> + 'static,
+{
+    // Null flags shrink to false, leaving fewer nulls
+    let element = (proptest::bool::weighted(params.null_probability), value)
+        .prop_map(|(null, value)| (!null).then_some(value));
+
+    vec(element, params.len).prop_map(A::new).boxed()
+}
+
+/// Floats of any sign and magnitude, including infinities, but never `NaN`.
+fn non_nan_f64() -> impl Strategy<Value = f64> {
+    use proptest::num::f64::*;
+
+    NORMAL | SUBNORMAL | ZERO | INFINITE
+}
+
+/// See [`non_nan_f64`].
+fn non_nan_f32() -> impl Strategy<Value = f32> {
+    use proptest::num::f32::*;
+
+    NORMAL | SUBNORMAL | ZERO | INFINITE
+}
+
+/// Union values of every type but [`UnionType::Null`], which stands in for
+/// the nulls added by [`array_strategy`].
+fn union_value() -> impl Strategy<Value = UnionType> {
+    prop_oneof![
+        any::<u32>().prop_map(UnionType::U32),
+        any::<i32>().prop_map(UnionType::I32),
+        any::<usize>().prop_map(UnionType::USize),
+        any::<isize>().prop_map(UnionType::ISize),
+        non_nan_f32().prop_map(UnionType::F32),
+        non_nan_f64().prop_map(UnionType::F64),
+        any::<bool>().prop_map(UnionType::Boolean),
+        any::<String>().prop_map(UnionType::Text),
+    ]
+}
+
+macro_rules! arbitrary_array {
+    ($($array:ty => $value:expr),* $(,)?) => {
+        $(
+            impl Arbitrary for $array {
+                type Parameters = ArrayParams;
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+                    array_strategy(params, $value)
+                }
+            }
+        )*
+    };
+}
+
+arbitrary_array! {
+    ArrayI32 => any::<i32>(),
+    ArrayU32 => any::<u32>(),
+    ArrayISize => any::<isize>(),
+    ArrayUSize => any::<usize>(),
+    ArrayBoolean => any::<bool>(),
+    ArrayF32 => non_nan_f32(),
+    ArrayF64 => non_nan_f64(),
+    ArrayText => any::<String>(),
+    Union => union_value(),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MutableArray, UnionBuilder};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_mutable_round_trip(one: ArrayF64) {
+            let mut two = MutableArray::new();
+            one.iter().for_each(|value| two.push(value));
+
+            prop_assert_eq!(&one, &two.freeze());
+            prop_assert_eq!(&one, &MutableArray::thaw(&one).freeze());
+        }
+
+        #[test]
+        fn test_union_builder_round_trip(one: Union) {
+            let mut builder = UnionBuilder::new();
+            for idx in 0..one.len() {
+                builder.push(one.get(idx).unwrap_or(UnionType::Null));
+            }
+
+            prop_assert_eq!(one, Union::from_builder(builder));
+        }
+
+        #[test]
+        fn test_params(
+            one in any_with::<ArrayText>(ArrayParams::new(3..6, 1.0)),
+            two in any_with::<ArrayI32>(ArrayParams::new(0..4, 0.0)),
+        ) {
+            prop_assert!((3..6).contains(&one.len()));
+            prop_assert!(one.all_null());
+            prop_assert_eq!(0, two.null_count());
+        }
+    }
+
+    #[cfg(feature = "ipc")]
+    proptest! {
+        #[test]
+        fn test_ipc_round_trip(
+            one: ArrayI32,
+            two in any_with::<ArrayText>(ArrayParams::new(1..20, 0.5)),
+        ) {
+            use crate::{ColumnArray, Field, IpcReader, IpcWriter, RecordBatch, Schema};
+            use crate::utils::DataType;
+
+            let len = one.len().min(two.len());
+            let one = one.slice(0, len).unwrap();
+            let two = two.slice(0, len).unwrap();
+
+            let schema = Schema::new(vec![
+                Field::new("one", DataType::Int32, true),
+                Field::new("two", DataType::Text, true),
+            ]);
+            let columns: Vec<ColumnArray> = vec![one.into(), two.into()];
+            let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+
+            let mut writer = IpcWriter::try_new(Vec::new(), &schema).unwrap();
+            writer.write(&batch).unwrap();
+            writer.finish().unwrap();
+
+            let bytes = writer.into_inner();
+            let mut reader = IpcReader::try_new(bytes.as_slice()).unwrap();
+
+            prop_assert_eq!(Some(batch), reader.next().transpose().unwrap());
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use crate::utils::{valid_values, validity_bitmap, validity_words, Array, BitmapBuffer, Numeric};
+
+/// Pairs up the elements of `lhs` and `rhs` for a binary kernel, with None
+/// wherever either side is null.
+///
+/// A side with a single element is broadcast, being repeated against every
+/// element of the other side.
+///
+/// Panics if the lengths differ and neither is 1, naming `action` in the
+/// message.
+pub(crate) fn broadcast<'a, A>(
+    lhs: &'a A,
+    rhs: &'a A,
+    action: &str,
+) -> impl ExactSizeIterator<Item = Option<(A::Data, A::Data)>> + 'a
+where
+    A: Array,
+{
+    let len = match (lhs.len(), rhs.len()) {
+        (lhs, rhs) if lhs == rhs => lhs,
+        (1, len) | (len, 1) => len,
+        (lhs, rhs) => panic!("Tried to {action} arrays with lengths {lhs} and {rhs}"),
+    };
+
+    let validity = BitmapBuffer::intersect(
+        validity_bitmap(lhs, len).as_deref(),
+        validity_bitmap(rhs, len).as_deref(),
+        len,
+    );
+    let index = |array: &A, idx: usize| if array.len() == 1 { 0 } else { idx };
+
+    (0..len).map(move |idx| {
+        if validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
+        {
+            return None;
+        }
+
+        lhs.get(index(lhs, idx)).zip(rhs.get(index(rhs, idx)))
+    })
+}
+
+/// Returns the element-wise sum of `lhs + rhs`.
+///
+/// Nulls in either array produce a null, as does integer overflow. A
+/// single element array on either side is broadcast against the other.
+///
+/// Panics if both arrays differ in length and neither has a single element.
+pub fn add<A>(lhs: &A, rhs: &A) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let values =
+        broadcast(lhs, rhs, "add").map(|pair| pair.and_then(|(lhs, rhs)| lhs.checked_add(rhs)));
+
+    A::new(values)
+}
+
+/// Returns the element-wise difference of `lhs - rhs`.
+///
+/// See [`add`] for the treatment of nulls, overflow and broadcasting, so
+/// `sub(&column, &mean)` centres a column on a single element `mean`.
+pub fn sub<A>(lhs: &A, rhs: &A) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let values = broadcast(lhs, rhs, "subtract")
+        .map(|pair| pair.and_then(|(lhs, rhs)| lhs.checked_sub(rhs)));
+
+    A::new(values)
+}
+
+/// Returns the element-wise product of `lhs * rhs`.
+///
+/// See [`add`] for the treatment of nulls, overflow and broadcasting.
+pub fn mul<A>(lhs: &A, rhs: &A) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let values = broadcast(lhs, rhs, "multiply")
+        .map(|pair| pair.and_then(|(lhs, rhs)| lhs.checked_mul(rhs)));
+
+    A::new(values)
+}
+
+/// Returns the element-wise quotient of `lhs / rhs`.
+///
+/// An integer division by zero produces a null while float arrays follow
+/// IEEE 754. See [`add`] for the treatment of nulls, overflow and
+/// broadcasting.
+pub fn div<A>(lhs: &A, rhs: &A) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let values =
+        broadcast(lhs, rhs, "divide").map(|pair| pair.and_then(|(lhs, rhs)| lhs.checked_div(rhs)));
+
+    A::new(values)
+}
+
+/// Returns the element-wise remainder of `lhs % rhs`.
+///
+/// Nulls in either array produce a null. An integer division by zero also
+/// produces a null while float arrays follow IEEE 754, giving `NaN`. A
+/// single element array on either side is broadcast against the other.
+///
+/// Panics if both arrays differ in length and neither has a single element.
+pub fn rem<A>(lhs: &A, rhs: &A) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let values = broadcast(lhs, rhs, "compute the remainder of")
+        .map(|pair| pair.and_then(|(lhs, rhs)| lhs.checked_rem(rhs)));
+
+    A::new(values)
+}
+
+/// Returns the remainder of every element in `array` by `rhs`.
+///
+/// See [`rem`] for the treatment of nulls and zero divisors.
+pub fn rem_scalar<A>(array: &A, rhs: A::Data) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    A::new(array.copied_iter().map(|val| val?.checked_rem(rhs)))
+}
+
+/// Returns the element-wise Euclidean remainder of `lhs` by `rhs`.
+///
+/// Unlike [`rem`], the result is never negative for a nonzero `rhs`, so
+/// `-7 rem_euclid 3` is `2` where `-7 % 3` is `-1`.
+///
+/// Panics if both arrays differ in length and neither has a single element.
+pub fn rem_euclid<A>(lhs: &A, rhs: &A) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let values = broadcast(lhs, rhs, "compute the remainder of")
+        .map(|pair| pair.and_then(|(lhs, rhs)| lhs.checked_rem_euclid(rhs)));
+
+    A::new(values)
+}
+
+/// Returns the Euclidean remainder of every element in `array` by `rhs`.
+///
+/// Useful for binning values into buckets, e.g. `rem_euclid_scalar(&ts, 86400)`.
+pub fn rem_euclid_scalar<A>(array: &A, rhs: A::Data) -> A
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    A::new(array.copied_iter().map(|val| val?.checked_rem_euclid(rhs)))
+}
+
+/// Returns the sum of the non-null elements in `array`, or None if every
+/// element is null.
+///
+/// Integer sums wrap around on overflow. With the `simd` feature, float
+/// sums are added up in eight lanes and so may differ from the sequential
+/// sum by rounding.
+pub fn sum<A>(array: &A) -> Option<A::Data>
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    #[cfg(feature = "simd")]
+    if let Some(values) = array.values().filter(|_| !array.all_null()) {
+        return crate::simd::sum(values, validity_words(array));
+    }
+
+    match valid_values(array) {
+        Some(values) => sum_of(values),
+        None => sum_of(array.copied_iter().flatten()),
+    }
+}
+
+/// Returns the smallest non-null element in `array`, or None if every
+/// element is null.
+///
+/// Float `NaN`s are ignored.
+pub fn min<A>(array: &A) -> Option<A::Data>
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    #[cfg(feature = "simd")]
+    if let Some(values) = array.values().filter(|_| !array.all_null()) {
+        return crate::simd::min(values, validity_words(array));
+    }
+
+    match valid_values(array) {
+        Some(values) => min_of(values),
+        None => min_of(array.copied_iter().flatten()),
+    }
+}
+
+/// Returns the largest non-null element in `array`, or None if every
+/// element is null.
+///
+/// Float `NaN`s are ignored.
+pub fn max<A>(array: &A) -> Option<A::Data>
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    #[cfg(feature = "simd")]
+    if let Some(values) = array.values().filter(|_| !array.all_null()) {
+        return crate::simd::max(values, validity_words(array));
+    }
+
+    match valid_values(array) {
+        Some(values) => max_of(values),
+        None => max_of(array.copied_iter().flatten()),
+    }
+}
+
+/// Returns the mean of the non-null elements in `array`, or None if every
+/// element is null.
+///
+/// Elements are added up as `f64`s so integers cannot overflow.
+pub fn mean<A>(array: &A) -> Option<f64>
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    let (total, count) = total_of(array.copied_iter().flatten());
+
+    (count != 0).then(|| total / count as f64)
+}
+
+/// See [`sum`].
+pub(crate) fn sum_of<T: Numeric>(values: impl Iterator<Item = T>) -> Option<T> {
+    values.reduce(Numeric::add_wrapping)
+}
+
+/// See [`min`].
+pub(crate) fn min_of<T: Numeric>(values: impl Iterator<Item = T>) -> Option<T> {
+    values
+        .filter(|val| val.partial_cmp(val).is_some())
+        .reduce(|acc, val| if val < acc { val } else { acc })
+}
+
+/// See [`max`].
+pub(crate) fn max_of<T: Numeric>(values: impl Iterator<Item = T>) -> Option<T> {
+    values
+        .filter(|val| val.partial_cmp(val).is_some())
+        .reduce(|acc, val| if val > acc { val } else { acc })
+}
+
+/// Returns the sum of `values` as an `f64` alongside their count.
+pub(crate) fn total_of<T: Numeric>(values: impl Iterator<Item = T>) -> (f64, usize) {
+    values.fold((0.0, 0), |(total, count), val| {
+        (total + val.as_f64(), count + 1)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayF64, ArrayI32, ArrayU32};
+
+    #[test]
+    fn test_rem_negative_operands() {
+        let one = ArrayI32::from([7, -7, 7, -7]);
+        let two = ArrayI32::from([3, 3, -3, -3]);
+
+        assert_eq!(ArrayI32::from([1, -1, 1, -1]), rem(&one, &two));
+        assert_eq!(ArrayI32::from([1, 2, 1, 2]), rem_euclid(&one, &two));
+
+        let one = ArrayF64::from([7.5, -7.5]);
+
+        assert_eq!(ArrayF64::from([1.5, -1.5]), rem_scalar(&one, 3.0));
+        assert_eq!(ArrayF64::from([1.5, 1.5]), rem_euclid_scalar(&one, 3.0));
+    }
+
+    #[test]
+    fn test_rem_zero_divisor() {
+        let one = ArrayI32::from([10, 0, -10]);
+
+        let two = rem_scalar(&one, 0);
+        assert!(two.all_null());
+        assert_eq!(3, two.len());
+
+        let two = ArrayI32::from([3, 0, 0]);
+        assert_eq!(
+            ArrayI32::from([Some(1), None, None]),
+            rem_euclid(&one, &two)
+        );
+
+        let one = ArrayF64::from([1.0]);
+        assert!(rem_scalar(&one, 0.0).get(0).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_rem_overflow() {
+        let one = ArrayI32::from([i32::MIN, i32::MAX]);
+
+        assert_eq!(ArrayI32::from([0, 0]), rem_scalar(&one, -1));
+        assert_eq!(ArrayI32::from([0, 0]), rem_euclid_scalar(&one, -1));
+    }
+
+    #[test]
+    fn test_rem_nulls() {
+        let one = ArrayU32::from([Some(86_401), None, Some(172_800), Some(5)]);
+        let two = ArrayU32::from([Some(86_400), Some(1), Some(86_400), None]);
+
+        assert_eq!(
+            ArrayU32::from([Some(1), None, Some(0), None]),
+            rem(&one, &two)
+        );
+        assert_eq!(
+            ArrayU32::from([Some(1), None, Some(0), Some(5)]),
+            rem_scalar(&one, 86_400)
+        );
+    }
+
+    #[test]
+    fn test_binary_kernels() {
+        let one = ArrayI32::from([Some(7), None, Some(i32::MAX), Some(-8)]);
+        let two = ArrayI32::from([Some(2), Some(1), Some(1), Some(0)]);
+
+        assert_eq!(
+            ArrayI32::from([Some(9), None, None, Some(-8)]),
+            add(&one, &two)
+        );
+        assert_eq!(
+            ArrayI32::from([Some(5), None, Some(i32::MAX - 1), Some(-8)]),
+            sub(&one, &two)
+        );
+        assert_eq!(
+            ArrayI32::from([Some(14), None, Some(i32::MAX), Some(0)]),
+            mul(&one, &two)
+        );
+        assert_eq!(
+            ArrayI32::from([Some(3), None, Some(i32::MAX), None]),
+            div(&one, &two)
+        );
+
+        let one = ArrayF64::from([1.0, -1.0]);
+        let two = div(&one, &ArrayF64::from([0.0, 0.0]));
+        assert_eq!(Some(f64::INFINITY), two.get(0));
+        assert_eq!(Some(f64::NEG_INFINITY), two.get(1));
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let one = ArrayF64::from([Some(1.0), None, Some(4.0), Some(7.0)]);
+        let mean = ArrayF64::from([mean(&one).unwrap()]);
+
+        let expected = ArrayF64::new(one.copied_iter().map(|val| Some(val? - 4.0)));
+        assert_eq!(expected, sub(&one, &mean));
+        assert_eq!(
+            ArrayF64::from([Some(3.0), None, Some(0.0), Some(-3.0)]),
+            sub(&mean, &one)
+        );
+
+        let one = ArrayU32::from([Some(86_401), None, Some(172_800), Some(5)]);
+        let two = ArrayU32::from([86_400]);
+        assert_eq!(rem_scalar(&one, 86_400), rem(&one, &two));
+        assert_eq!(rem_euclid_scalar(&one, 86_400), rem_euclid(&one, &two));
+
+        let three = mul(&one, &ArrayU32::from([None]));
+        assert_eq!(4, three.len());
+        assert!(three.all_null());
+
+        let four = ArrayU32::from([2]);
+        assert_eq!(ArrayU32::from([4]), add(&four, &four));
+        assert_eq!(0, add(&four, &ArrayU32::from_vec(vec![])).len());
+    }
+
+    #[test]
+    fn test_binary_validity() {
+        for len in [1, 7, 8, 13] {
+            let one = ArrayI32::new((0..len).map(|idx| (idx % 3 != 0).then_some(idx)));
+            let two = ArrayI32::new((0..len).map(|idx| (idx % 2 != 0).then_some(1)));
+            let dense = ArrayI32::new((0..len).map(Some));
+
+            let both = ArrayI32::new(
+                (0..len).map(|idx| (idx % 6 == 1 || idx % 6 == 5).then_some(idx + 1)),
+            );
+            assert_eq!(both, add(&one, &two));
+
+            let either = ArrayI32::new((0..len).map(|idx| (idx % 3 != 0).then_some(idx * 2)));
+            assert_eq!(either, add(&one, &dense));
+            assert_eq!(either, add(&dense, &one));
+
+            let none = add(&dense, &dense);
+            assert_eq!(0, none.null_count());
+            assert!(none.validity().is_none());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to add arrays with lengths 0 and 3")]
+    fn test_broadcast_empty() {
+        add(&ArrayI32::from_vec(vec![]), &ArrayI32::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let one = ArrayI32::from([Some(4), None, Some(-2), Some(9)]);
+
+        assert_eq!(Some(11), sum(&one));
+        assert_eq!(Some(-2), min(&one));
+        assert_eq!(Some(9), max(&one));
+
+        let one = ArrayF64::from([Some(f64::NAN), Some(1.5), None, Some(-0.5)]);
+
+        assert_eq!(Some(-0.5), min(&one));
+        assert_eq!(Some(1.5), max(&one));
+        assert!(sum(&one).unwrap().is_nan());
+
+        let one = ArrayU32::from([None, None]);
+
+        assert_eq!(None, sum(&one));
+        assert_eq!(None, max(&one));
+        assert_eq!(None, mean(&one));
+
+        let one = ArrayI32::from([Some(i32::MAX), None, Some(i32::MAX), Some(1)]);
+        assert_eq!(Some((2.0 * i32::MAX as f64 + 1.0) / 3.0), mean(&one));
+    }
+
+    #[test]
+    fn test_aggregates_by_word() {
+        use crate::utils::VALID_WORDS;
+
+        // 1% nulls, leaving a little over a third of the words fully valid
+        let values = (0..1_000_000).map(|idx| (idx % 100 != 7).then_some(idx % 1_000 - 500));
+        let one = ArrayI32::new(values.clone());
+
+        VALID_WORDS.with(|count| count.set(0));
+        assert_eq!(
+            values.clone().flatten().reduce(i32::wrapping_add),
+            sum(&one)
+        );
+
+        let words = 1_000_000_usize.div_ceil(64);
+        let valid = VALID_WORDS.with(|count| count.get());
+        assert!(valid > words / 3, "{valid} of {words} words");
+
+        assert_eq!(values.clone().flatten().min(), min(&one));
+        assert_eq!(values.clone().flatten().max(), max(&one));
+        assert_eq!(one, one.clone());
+        assert_ne!(
+            one,
+            ArrayI32::new(values.map(|val| val.map(|val| val.max(-499))))
+        );
+    }
+}
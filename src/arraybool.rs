@@ -1,8 +1,9 @@
 use std::alloc::{self, Layout};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{validity_from_mask, Array, BitmapBuffer, DataType, IntoIter, Iter, ALIGNMENT};
 
 pub type Boolean = Option<bool>;
 
@@ -11,8 +12,8 @@ pub type Boolean = Option<bool>;
 pub struct ArrayBoolean {
     /// Pointer to the values buffer
     ptr: Option<NonNull<u8>>,
-    /// Pointer to the validity buffer
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls
+    validity: Option<BitmapBuffer>,
     /// The number of elements in the array
     len: usize,
     /// The number of nulls in the array
@@ -25,6 +26,133 @@ impl ArrayBoolean {
         Self::from_sized_iter(values.into_iter())
     }
 
+    /// Creates an [`ArrayBoolean`] of length `len` with every element set
+    /// to `value`.
+    pub fn full(len: usize, value: bool) -> Self {
+        if len == 0 {
+            return Self::from_vec(vec![]);
+        }
+
+        let values_ptr = Self::allocate_values(len);
+        let bytes = len.div_ceil(8);
+        let byte = if value { u8::MAX } else { 0 };
+
+        unsafe { ptr::write_bytes(values_ptr.as_ptr(), byte, bytes) };
+
+        // The bits past the end stay unset, as they are when built from values
+        if !len.is_multiple_of(8) {
+            let last = byte << (8 - len % 8);
+            unsafe { ptr::write(values_ptr.as_ptr().add(bytes - 1), last) };
+        }
+
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            len,
+            nulls: 0,
+        }
+    }
+
+    /// Creates an [`ArrayBoolean`] of `len` elements from its values buffer,
+    /// packed most significant bit first, along with its validity buffer and
+    /// null count. Values under nulls are cleared.
+    pub(crate) fn from_parts(
+        mut values: Vec<u8>,
+        validity: Option<BitmapBuffer>,
+        nulls: usize,
+        len: usize,
+    ) -> Self {
+        if nulls == len {
+            return Self::from_vec(vec![None; len]);
+        }
+
+        if let Some(validity) = &validity {
+            // The validity buffer is packed least significant bit first
+            for (value, valid) in values.iter_mut().zip(validity.as_bytes()) {
+                *value &= valid.reverse_bits();
+            }
+        }
+
+        if let Some(last) = values.last_mut().filter(|_| !len.is_multiple_of(8)) {
+            *last &= u8::MAX << (8 - len % 8);
+        }
+
+        let values_ptr = Self::allocate_values(len);
+        unsafe { ptr::copy_nonoverlapping(values.as_ptr(), values_ptr.as_ptr(), len.div_ceil(8)) };
+
+        Self {
+            ptr: Some(values_ptr),
+            validity,
+            len,
+            nulls,
+        }
+    }
+
+    /// Returns a bitmap with the bits of the true elements set, nulls
+    /// counting as false.
+    pub(crate) fn true_bits(&self) -> BitmapBuffer {
+        let mut bits = BitmapBuffer::new(self.len);
+
+        for idx in 0..self.len {
+            if self.get(idx) == Some(true) {
+                bits.set_bit(idx);
+            }
+        }
+
+        bits
+    }
+
+    /// Creates an empty [`ArrayBoolean`].
+    pub const fn empty() -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    /// Returns a copy of the array whose valid elements are the true ones of
+    /// `validity`, nulls in `validity` counting as false.
+    ///
+    /// The values under the new nulls are kept, so
+    /// [`ArrayBoolean::clear_validity`] recovers them.
+    ///
+    /// Panics if `validity` and the array differ in length
+    pub fn with_validity(&self, validity: &ArrayBoolean) -> Self {
+        let (validity, nulls) = validity_from_mask(self.len, validity);
+
+        let mut array = self.with_values_buffer();
+        array.validity = validity;
+        array.nulls = nulls;
+        array
+    }
+
+    /// Returns a copy of the array with every element valid, exposing the
+    /// values under its nulls.
+    ///
+    /// Nulls the array was built with read as false.
+    pub fn clear_validity(&self) -> Self {
+        let mut array = self.with_values_buffer();
+        array.validity = None;
+        array.nulls = 0;
+        array
+    }
+
+    /// Returns a clone of the array, allocating a zeroed values buffer if
+    /// it had none.
+    fn with_values_buffer(&self) -> Self {
+        let mut array = self.clone();
+
+        if array.ptr.is_none() && array.len != 0 {
+            let values_ptr = Self::allocate_values(array.len);
+            unsafe { ptr::write_bytes(values_ptr.as_ptr(), 0, array.len.div_ceil(8)) };
+            array.ptr = Some(values_ptr);
+        }
+
+        array
+    }
+
     fn from_sized_iter<S>(sized: S) -> Self
     where
         S: Iterator<Item = Boolean> + ExactSizeIterator,
@@ -32,27 +160,21 @@ impl ArrayBoolean {
         let len = sized.len();
 
         if len == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
+            return Self::empty();
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(len);
+        let values_ptr = Self::allocate_values(len);
+        let mut validity = BitmapBuffer::new(len);
 
         let mut value_byte = 0_u8;
-        let mut null_byte = 0_u8;
         let mut offset = 0;
         let mut nulls = 0;
 
-        for (idx, value) in sized.into_iter().enumerate() {
+        for (idx, value) in sized.enumerate() {
             value_byte <<= 1;
             let bit = match value {
                 Some(value) => {
-                    let pos = 1 << (idx % 8);
-                    null_byte |= pos;
+                    validity.set_bit(idx);
                     if value {
                         1
                     } else {
@@ -61,89 +183,65 @@ impl ArrayBoolean {
                 }
                 None => {
                     nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    null_byte &= pos;
                     0
                 }
             };
 
             value_byte |= bit;
 
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(values_ptr.as_ptr().add(offset), value_byte);
-                    ptr::write(validity_ptr.as_ptr().add(offset), null_byte);
-                }
+            if (idx + 1).is_multiple_of(8) {
+                unsafe { ptr::write(values_ptr.as_ptr().add(offset), value_byte) };
 
-                null_byte = 0_u8;
                 value_byte = 0_u8;
                 offset += 1;
             }
         }
 
         // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
+        if !len.is_multiple_of(8) {
             value_byte <<= 8 - (len % 8);
-            unsafe {
-                ptr::write(values_ptr.as_ptr().add(offset), value_byte);
-                ptr::write(validity_ptr.as_ptr().add(offset), null_byte);
-            };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
+            unsafe { ptr::write(values_ptr.as_ptr().add(offset), value_byte) };
         }
 
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 nulls: len,
             };
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
             len,
             nulls,
         }
     }
 
     /// Returns true if the validity buffers of `Self` and `Other` are equal.
-    ///
-    /// Assumes both buffers are equal in length.
     fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
-
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
-
-                    if own != other {
-                        return false;
-                    }
-                }
-            }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
-        }
-
-        true
+        self.validity == other.validity
     }
 
     /// Returns true if the values of `Self` and `Other` are equal.
     ///
-    /// Assumes both buffers are equal in length.
+    /// Assumes both buffers are equal in length and both arrays have equal
+    /// validity. Values under nulls, as kept by [`ArrayBoolean::with_validity`],
+    /// are ignored.
     fn compare_values(&self, other: &Self) -> bool {
-        let len = (self.len + 7) / 8;
+        if self.all_null() {
+            return true;
+        }
+
+        if self.validity.is_some() {
+            return (0..self.len).all(|idx| self.get(idx) == other.get(idx));
+        }
+
+        let len = self.len.div_ceil(8);
 
         match (self.ptr, other.ptr) {
             (Some(own), Some(other)) => {
@@ -163,52 +261,28 @@ impl ArrayBoolean {
         true
     }
 
-    /// Allocates both values and validity buffers
+    /// Allocates only the values buffer
     ///
     /// Must ensure len != 0
-    fn allocate(len: usize) -> (NonNull<u8>, NonNull<u8>) {
+    fn allocate_values(len: usize) -> NonNull<u8> {
         assert!(len != 0, "ArrayBoolean: Tried to allocate 0 sized memory");
 
-        // Values
-        let values_size = (len + 7) / 8;
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_size = len.div_ceil(8);
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayBoolean: values size overflowed isize::max");
 
         let values_ptr = unsafe { alloc::alloc(values_layout) };
 
-        let values_ptr = match NonNull::new(values_ptr) {
+        match NonNull::new(values_ptr) {
             Some(ptr) => ptr,
             None => alloc::handle_alloc_error(values_layout),
-        };
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayBoolean: validity size overflowed isize::max");
-
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
-
-        (values_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayBoolean drop: validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-        unsafe { alloc::dealloc(ptr, validity_layout) };
+        }
     }
 
     fn dealloc_values(ptr: Option<NonNull<u8>>, len: usize) {
         let Some(ptr) = ptr else { return };
-        let values_size = (len + 7) / 8;
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_size = len.div_ceil(8);
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayBoolean drop: values size overflowed isize::max");
         let ptr = ptr.as_ptr();
 
@@ -270,19 +344,22 @@ impl Array for ArrayBoolean {
             return true;
         }
 
-        let Some(val_ptr) = self.val_ptr else {
-            return false;
-        };
-
-        let byte_index = idx / 8;
-
-        let val_byte = unsafe { ptr::read(val_ptr.as_ptr().add(byte_index)) };
-        val_byte & (1 << (idx % 8)) == 0
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
     }
 
     fn all_null(&self) -> bool {
         self.nulls == self.len
     }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
+    }
 }
 
 impl IntoIterator for ArrayBoolean {
@@ -294,53 +371,35 @@ impl IntoIterator for ArrayBoolean {
     }
 }
 
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayBoolean {}
+unsafe impl Sync for ArrayBoolean {}
+
+impl Default for ArrayBoolean {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Drop for ArrayBoolean {
     fn drop(&mut self) {
         Self::dealloc_values(self.ptr, self.len());
-        Self::dealloc_validity(self.val_ptr, self.len())
     }
 }
 
 impl Clone for ArrayBoolean {
     fn clone(&self) -> Self {
-        if self.len() == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
-        }
-
-        let (values_ptr, validity_ptr) = Self::allocate(self.len());
-
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len());
-                None
-            }
-        };
-
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), count) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.len());
-                None
-            }
-        };
+        let values_ptr = self.ptr.map(|ptr| {
+            let values_ptr = Self::allocate_values(self.len());
+            let count = self.len().div_ceil(8);
+            unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), count) };
+            values_ptr
+        });
 
         Self {
             ptr: values_ptr,
-            val_ptr: validity_ptr,
+            validity: self.validity.clone(),
             len: self.len(),
             nulls: self.nulls,
         }
@@ -397,12 +456,30 @@ impl PartialEq for ArrayBoolean {
 
 impl Eq for ArrayBoolean {}
 
+/// Hashes the elements, so arrays equal regardless of what lies under their
+/// nulls hash the same.
+impl Hash for ArrayBoolean {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
 impl From<ArrayBoolean> for Vec<Option<bool>> {
     fn from(value: ArrayBoolean) -> Self {
         value.into_iter().collect()
     }
 }
 
+impl From<&ArrayBoolean> for Vec<Option<bool>> {
+    fn from(value: &ArrayBoolean) -> Self {
+        value.copied_iter().collect()
+    }
+}
+
 impl From<Vec<bool>> for ArrayBoolean {
     fn from(value: Vec<bool>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -439,6 +516,18 @@ impl<const N: usize> From<[Boolean; N]> for ArrayBoolean {
     }
 }
 
+impl From<&[bool]> for ArrayBoolean {
+    fn from(value: &[bool]) -> Self {
+        Self::from_sized_iter(value.iter().copied().map(Some))
+    }
+}
+
+impl From<&[Boolean]> for ArrayBoolean {
+    fn from(value: &[Boolean]) -> Self {
+        Self::from_sized_iter(value.iter().copied())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -556,4 +645,36 @@ mod test {
 
         assert_eq!(0, one.len());
     }
+
+    #[test]
+    fn test_full() {
+        for len in [0, 1, 7, 8, 13, 64] {
+            assert_eq!(
+                ArrayBoolean::from_vec(vec![Some(true); len]),
+                ArrayBoolean::full(len, true)
+            );
+            assert_eq!(
+                ArrayBoolean::from_vec(vec![Some(false); len]),
+                ArrayBoolean::full(len, false)
+            );
+        }
+
+        let one = ArrayBoolean::full(10, true);
+        assert!(one.validity.is_none());
+        assert_eq!(Some(true), one.get(9));
+    }
+
+    #[test]
+    fn test_with_validity() {
+        let one = ArrayBoolean::from([true, false, true]);
+        let two = one.with_validity(&ArrayBoolean::from([Some(false), Some(true), None]));
+
+        assert_eq!(ArrayBoolean::from([None, Some(false), None]), two);
+        assert_eq!(2, two.null_count());
+        assert_eq!(one, two.clear_validity());
+        assert_eq!(
+            ArrayBoolean::from([false, false]),
+            ArrayBoolean::from_vec(vec![None, None]).clear_validity()
+        );
+    }
 }
@@ -2,7 +2,11 @@ use std::alloc::{self, Layout};
 use std::fmt::Debug;
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{
+    float_steps, valid_values, validity_from_mask, Array, BitmapBuffer, DataType, IntoIter, Iter,
+    ALIGNMENT,
+};
+use crate::ArrayBoolean;
 
 pub type F32 = Option<f32>;
 
@@ -11,8 +15,8 @@ pub type F32 = Option<f32>;
 pub struct ArrayF32 {
     /// Pointer to the values buffer
     ptr: Option<NonNull<f32>>,
-    /// Pointer to the validity buffer
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls
+    validity: Option<BitmapBuffer>,
     /// The number of elements in the array
     len: usize,
     /// The number of nulls in the array
@@ -20,6 +24,57 @@ pub struct ArrayF32 {
 }
 
 impl ArrayF32 {
+    /// Creates an empty [`ArrayF32`].
+    pub const fn empty() -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    /// Returns a copy of the array whose valid elements are the true ones of
+    /// `validity`, nulls in `validity` counting as false.
+    ///
+    /// The values under the new nulls are kept, so
+    /// [`ArrayF32::clear_validity`] recovers them.
+    ///
+    /// Panics if `validity` and the array differ in length
+    pub fn with_validity(&self, validity: &ArrayBoolean) -> Self {
+        let (validity, nulls) = validity_from_mask(self.len, validity);
+
+        let mut array = self.with_values_buffer();
+        array.validity = validity;
+        array.nulls = nulls;
+        array
+    }
+
+    /// Returns a copy of the array with every element valid, exposing the
+    /// values under its nulls.
+    ///
+    /// Nulls the array was built with read as 0.
+    pub fn clear_validity(&self) -> Self {
+        let mut array = self.with_values_buffer();
+        array.validity = None;
+        array.nulls = 0;
+        array
+    }
+
+    /// Returns a clone of the array, allocating a zeroed values buffer if
+    /// it had none.
+    fn with_values_buffer(&self) -> Self {
+        let mut array = self.clone();
+
+        if array.ptr.is_none() && array.len != 0 {
+            let values_ptr = Self::allocate_values(array.len);
+            unsafe { ptr::write_bytes(values_ptr.as_ptr(), 0, array.len) };
+            array.ptr = Some(values_ptr);
+        }
+
+        array
+    }
+
     fn from_sized_iter<S>(sized: S) -> Self
     where
         S: Iterator<Item = F32> + ExactSizeIterator,
@@ -27,68 +82,41 @@ impl ArrayF32 {
         let len = sized.len();
 
         if len == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
+            return Self::empty();
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(len);
-
-        let mut val_byte = 0_u8;
-        let mut val_offset = 0;
+        let values_ptr = Self::allocate_values(len);
+        let mut validity = BitmapBuffer::new(len);
         let mut nulls = 0;
 
-        for (idx, value) in sized.into_iter().enumerate() {
+        for (idx, value) in sized.enumerate() {
             match value {
                 Some(value) => {
                     unsafe { ptr::write(values_ptr.as_ptr().add(idx), value) };
-                    let pos = 1 << (idx % 8);
-                    val_byte |= pos;
+                    validity.set_bit(idx);
                 }
                 None => {
+                    // Zeroed so the values buffer can be read as a slice
+                    unsafe { ptr::write(values_ptr.as_ptr().add(idx), 0.0) };
                     nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    val_byte &= pos;
                 }
             }
-
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte);
-                }
-
-                val_byte = 0_u8;
-                val_offset += 1;
-            }
-        }
-
-        // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
-            unsafe { ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte) };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
         }
 
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 nulls,
             };
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
             len,
             nulls,
         }
@@ -99,93 +127,114 @@ impl ArrayF32 {
         Self::from_sized_iter(values.into_iter())
     }
 
-    /// Returns true if the validity buffers of `Self` and `Other` are equal.
-    ///
-    /// Assumes both buffers are equal in length.
-    fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
+    /// Creates an [`ArrayF32`] of length `len` with every element set to
+    /// `value`.
+    pub fn full(len: usize, value: f32) -> Self {
+        Self::from_fn(len, |_| value)
+    }
 
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
+    /// Creates an [`ArrayF32`] of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self::full(len, 0.0)
+    }
 
-                    if own != other {
-                        return false;
-                    }
-                }
-            }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
-        }
+    /// Creates an [`ArrayF32`] of `len` ones.
+    pub fn ones(len: usize) -> Self {
+        Self::full(len, 1.0)
+    }
 
-        true
+    /// Creates an [`ArrayF32`] of `0.0, 1.0, ..., n - 1.0`.
+    pub fn iota(n: usize) -> Self {
+        Self::from_fn(n, |idx| idx as f32)
     }
 
-    /// Returns true if the values of `Self` and `Other` are equal.
+    /// Creates an [`ArrayF32`] counting from `start` up to, but excluding,
+    /// `stop` in increments of `step`.
     ///
-    /// Assumes both buffers are equal in length.
-    fn compare_values(&self, other: &Self) -> bool {
-        let len = self.len;
+    /// Panics if `step` is 0 or leads away from `stop`.
+    pub fn arange(start: f32, stop: f32, step: f32) -> Self {
+        let len = float_steps(start as f64, stop as f64, step as f64);
+
+        Self::from_fn(len, |idx| start + idx as f32 * step)
+    }
+
+    /// Creates a null free [`ArrayF32`], writing the values straight into
+    /// the values buffer.
+    fn from_fn<F>(len: usize, f: F) -> Self
+    where
+        F: Fn(usize) -> f32,
+    {
+        if len == 0 {
+            return Self::empty();
+        }
+
+        let values_ptr = Self::allocate_values(len);
 
         for idx in 0..len {
-            let own = self.get(idx);
-            let other = other.get(idx);
+            unsafe { ptr::write(values_ptr.as_ptr().add(idx), f(idx)) };
+        }
 
-            if own != other {
-                return false;
-            }
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            len,
+            nulls: 0,
         }
+    }
 
-        true
+    /// Returns the index of the first element with the same bit pattern as
+    /// `value`, if any. Unlike [`Array::index_of`] this can find NaNs, but
+    /// tells `0.0` and `-0.0` apart.
+    pub fn index_of_bits(&self, value: f32) -> Option<usize> {
+        self.index_of_bits_from(value, 0)
+    }
+
+    /// Returns the index of the first element at or after `idx` with the
+    /// same bit pattern as `value`, if any.
+    pub fn index_of_bits_from(&self, value: f32, idx: usize) -> Option<usize> {
+        (idx..self.len).find(|idx| {
+            self.get(*idx)
+                .is_some_and(|other| other.to_bits() == value.to_bits())
+        })
+    }
+
+    /// Returns true if the validity buffers of `Self` and `Other` are equal.
+    fn compare_validity(&self, other: &Self) -> bool {
+        self.validity == other.validity
+    }
+
+    /// Returns true if the values of `Self` and `Other` are equal.
+    ///
+    /// Assumes both arrays have equal validity, so only the values of valid
+    /// elements are compared.
+    fn compare_values(&self, other: &Self) -> bool {
+        match (valid_values(self), valid_values(other)) {
+            (Some(own), Some(other)) => own.eq(other),
+            // Only all null arrays lack a values buffer
+            _ => true,
+        }
     }
 
-    /// Allocates both values and validity buffers
+    /// Allocates only the values buffer
     ///
     /// Must ensure len != 0
-    fn allocate(len: usize) -> (NonNull<f32>, NonNull<u8>) {
-        // Values
+    fn allocate_values(len: usize) -> NonNull<f32> {
         let values_size = len * std::mem::size_of::<f32>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayF32: values size overflowed isize::max");
 
         let values_ptr = unsafe { alloc::alloc(values_layout) };
 
-        let values_ptr = match NonNull::new(values_ptr as *mut f32) {
+        match NonNull::new(values_ptr as *mut f32) {
             Some(ptr) => ptr,
             None => alloc::handle_alloc_error(values_layout),
-        };
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayF32: validity size overflowed isize::max");
-
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
-
-        (values_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayF32 drop: validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-        unsafe { alloc::dealloc(ptr, validity_layout) };
+        }
     }
 
     fn dealloc_values(ptr: Option<NonNull<f32>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let values_size = len * std::mem::size_of::<f32>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayF32 drop: values size overflowed isize::max");
         let ptr = ptr.as_ptr() as *mut u8;
 
@@ -244,67 +293,57 @@ impl Array for ArrayF32 {
             return true;
         }
 
-        let Some(val_ptr) = self.val_ptr else {
-            return false;
-        };
-
-        let byte_index = idx / 8;
-
-        let val_byte = unsafe { ptr::read(val_ptr.as_ptr().add(byte_index)) };
-        val_byte & (1 << (idx % 8)) == 0
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
     }
 
     fn all_null(&self) -> bool {
         self.nulls == self.len
     }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
+    }
+
+    fn values(&self) -> Option<&[Self::Data]> {
+        let ptr = self.ptr?;
+        Some(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len) })
+    }
+}
+
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayF32 {}
+unsafe impl Sync for ArrayF32 {}
+
+impl Default for ArrayF32 {
+    fn default() -> Self {
+        Self::empty()
+    }
 }
 
 impl Drop for ArrayF32 {
     fn drop(&mut self) {
         Self::dealloc_values(self.ptr, self.len());
-        Self::dealloc_validity(self.val_ptr, self.len())
     }
 }
 
 impl Clone for ArrayF32 {
     fn clone(&self) -> Self {
-        if self.len() == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
-        }
-
-        let (values_ptr, validity_ptr) = Self::allocate(self.len());
-
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len());
-                None
-            }
-        };
-
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.len());
-                None
-            }
-        };
+        let values_ptr = self.ptr.map(|ptr| {
+            let values_ptr = Self::allocate_values(self.len());
+            unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
+            values_ptr
+        });
 
         Self {
             ptr: values_ptr,
-            val_ptr: validity_ptr,
+            validity: self.validity.clone(),
             len: self.len(),
             nulls: self.nulls,
         }
@@ -374,6 +413,12 @@ impl From<ArrayF32> for Vec<F32> {
     }
 }
 
+impl From<&ArrayF32> for Vec<F32> {
+    fn from(value: &ArrayF32) -> Self {
+        value.copied_iter().collect()
+    }
+}
+
 impl From<Vec<f32>> for ArrayF32 {
     fn from(value: Vec<f32>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -410,7 +455,20 @@ impl<const N: usize> From<[F32; N]> for ArrayF32 {
     }
 }
 
+impl From<&[f32]> for ArrayF32 {
+    fn from(value: &[f32]) -> Self {
+        Self::from_sized_iter(value.iter().copied().map(Some))
+    }
+}
+
+impl From<&[F32]> for ArrayF32 {
+    fn from(value: &[F32]) -> Self {
+        Self::from_sized_iter(value.iter().copied())
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::legacy_numeric_constants)]
 mod test {
     use super::*;
     use std::f32::consts;
@@ -565,4 +623,19 @@ mod test {
 
         assert_eq!(0, one.len());
     }
+
+    #[test]
+    fn test_index_of_nan() {
+        let one = ArrayF32::from([Some(0.0), None, Some(f32::NAN), Some(-0.0), Some(f32::NAN)]);
+
+        assert_eq!(None, one.index_of(f32::NAN));
+        assert_eq!(Some(2), one.index_of_bits(f32::NAN));
+        assert_eq!(Some(4), one.index_of_bits_from(f32::NAN, 3));
+        assert_eq!(None, one.index_of_bits_from(f32::NAN, 5));
+
+        // Equality treats the zeros as equal but their bits differ
+        assert_eq!(Some(0), one.index_of(-0.0));
+        assert_eq!(Some(3), one.index_of_bits(-0.0));
+        assert_eq!(None, one.index_of_bits(1.0));
+    }
 }
@@ -1,8 +1,13 @@
 use std::alloc::{self, Layout};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{
+    float_steps, valid_values, validity_from_mask, Array, ArrayError, BitmapBuffer, DataType,
+    IntoIter, Iter, ALIGNMENT,
+};
+use crate::ArrayBoolean;
 
 pub type F64 = Option<f64>;
 
@@ -11,8 +16,8 @@ pub type F64 = Option<f64>;
 pub struct ArrayF64 {
     /// Pointer to the values buffer
     ptr: Option<NonNull<f64>>,
-    /// Pointer to the validity buffer
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls
+    validity: Option<BitmapBuffer>,
     /// The number of elements in the array
     len: usize,
     /// The number of nulls in the array
@@ -20,6 +25,57 @@ pub struct ArrayF64 {
 }
 
 impl ArrayF64 {
+    /// Creates an empty [`ArrayF64`].
+    pub const fn empty() -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    /// Returns a copy of the array whose valid elements are the true ones of
+    /// `validity`, nulls in `validity` counting as false.
+    ///
+    /// The values under the new nulls are kept, so
+    /// [`ArrayF64::clear_validity`] recovers them.
+    ///
+    /// Panics if `validity` and the array differ in length
+    pub fn with_validity(&self, validity: &ArrayBoolean) -> Self {
+        let (validity, nulls) = validity_from_mask(self.len, validity);
+
+        let mut array = self.with_values_buffer();
+        array.validity = validity;
+        array.nulls = nulls;
+        array
+    }
+
+    /// Returns a copy of the array with every element valid, exposing the
+    /// values under its nulls.
+    ///
+    /// Nulls the array was built with read as 0.
+    pub fn clear_validity(&self) -> Self {
+        let mut array = self.with_values_buffer();
+        array.validity = None;
+        array.nulls = 0;
+        array
+    }
+
+    /// Returns a clone of the array, allocating a zeroed values buffer if
+    /// it had none.
+    fn with_values_buffer(&self) -> Self {
+        let mut array = self.clone();
+
+        if array.ptr.is_none() && array.len != 0 {
+            let values_ptr = Self::allocate_values(array.len);
+            unsafe { ptr::write_bytes(values_ptr.as_ptr(), 0, array.len) };
+            array.ptr = Some(values_ptr);
+        }
+
+        array
+    }
+
     fn from_sized_iter<S>(sized: S) -> Self
     where
         S: Iterator<Item = F64> + ExactSizeIterator,
@@ -27,68 +83,41 @@ impl ArrayF64 {
         let len = sized.len();
 
         if len == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
+            return Self::empty();
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(len);
-
-        let mut val_byte = 0_u8;
-        let mut val_offset = 0;
+        let values_ptr = Self::allocate_values(len);
+        let mut validity = BitmapBuffer::new(len);
         let mut nulls = 0;
 
-        for (idx, value) in sized.into_iter().enumerate() {
+        for (idx, value) in sized.enumerate() {
             match value {
                 Some(value) => {
                     unsafe { ptr::write(values_ptr.as_ptr().add(idx), value) };
-                    let pos = 1 << (idx % 8);
-                    val_byte |= pos;
+                    validity.set_bit(idx);
                 }
                 None => {
+                    // Zeroed so the values buffer can be read as a slice
+                    unsafe { ptr::write(values_ptr.as_ptr().add(idx), 0.0) };
                     nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    val_byte &= pos;
                 }
             }
-
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte);
-                }
-
-                val_byte = 0_u8;
-                val_offset += 1;
-            }
-        }
-
-        // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
-            unsafe { ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte) };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
         }
 
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 nulls,
             };
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
             len,
             nulls,
         }
@@ -99,93 +128,297 @@ impl ArrayF64 {
         Self::from_sized_iter(values.into_iter())
     }
 
-    /// Returns true if the validity buffers of `Self` and `Other` are equal.
+    /// Creates an [`ArrayF64`] of `0.0, 1.0, ..., n - 1.0`.
+    pub fn iota(n: usize) -> Self {
+        Self::from_fn(n, |idx| idx as f64)
+    }
+
+    /// Creates an [`ArrayF64`] counting from `start` up to, but excluding,
+    /// `stop` in increments of `step`.
     ///
-    /// Assumes both buffers are equal in length.
-    fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
+    /// Panics if `step` is 0 or leads away from `stop`.
+    pub fn arange(start: f64, stop: f64, step: f64) -> Self {
+        let len = float_steps(start, stop, step);
+
+        Self::from_fn(len, |idx| start + idx as f64 * step)
+    }
 
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
+    /// Returns the index of the first element with the same bit pattern as
+    /// `value`, if any. Unlike [`Array::index_of`] this can find NaNs, but
+    /// tells `0.0` and `-0.0` apart.
+    pub fn index_of_bits(&self, value: f64) -> Option<usize> {
+        self.index_of_bits_from(value, 0)
+    }
 
-                    if own != other {
-                        return false;
-                    }
-                }
+    /// Returns the index of the first element at or after `idx` with the
+    /// same bit pattern as `value`, if any.
+    pub fn index_of_bits_from(&self, value: f64, idx: usize) -> Option<usize> {
+        (idx..self.len).find(|idx| {
+            self.get(*idx)
+                .is_some_and(|other| other.to_bits() == value.to_bits())
+        })
+    }
+
+    /// Creates an [`ArrayF64`] of length `len` with every element set to
+    /// `value`.
+    pub fn full(len: usize, value: f64) -> Self {
+        Self::from_fn(len, |_| value)
+    }
+
+    /// Creates an [`ArrayF64`] of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self::full(len, 0.0)
+    }
+
+    /// Creates an [`ArrayF64`] of `len` ones.
+    pub fn ones(len: usize) -> Self {
+        Self::full(len, 1.0)
+    }
+
+    /// Creates an [`ArrayF64`] of length `len` with every element null.
+    pub fn nulls(len: usize) -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len,
+            nulls: len,
+        }
+    }
+
+    /// Creates an [`ArrayF64`] of `n` evenly spaced values from `start` to
+    /// `stop`, both inclusive.
+    ///
+    /// Each value is computed as `start + i * (stop - start) / (n - 1)`
+    /// instead of by adding a step, so rounding errors do not accumulate.
+    pub fn linspace(start: f64, stop: f64, n: usize) -> Self {
+        if n == 1 {
+            return Self::full(1, start);
+        }
+
+        let span = stop - start;
+        let intervals = n as f64 - 1.0;
+
+        Self::from_fn(n, |idx| {
+            if idx == n - 1 {
+                stop
+            } else {
+                start + idx as f64 * span / intervals
             }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
+        })
+    }
+
+    /// Returns the sum of the non-null elements, or None if every element is
+    /// null.
+    ///
+    /// On targets with AVX2 enabled four values are added at a time, which
+    /// may round differently from [`crate::sum`].
+    pub fn sum(&self) -> Option<f64> {
+        if self.all_null() {
+            return None;
         }
 
-        true
+        Some(sum_values(self.values()?, self.validity.as_ref()))
     }
 
-    /// Returns true if the values of `Self` and `Other` are equal.
+    /// Returns the Euclidean norm, the square root of the sum of squares of
+    /// the non-null elements, or 0 if there are none.
     ///
-    /// Assumes both buffers are equal in length.
-    fn compare_values(&self, other: &Self) -> bool {
-        let len = self.len;
+    /// The squares are added with Kahan summation to limit rounding errors.
+    pub fn l2_norm(&self) -> f64 {
+        kahan_sum(self.valid_values().map(|value| value * value)).sqrt()
+    }
 
-        for idx in 0..len {
-            let own = self.get(idx);
-            let other = other.get(idx);
+    /// Returns the sum of the absolute values of the non-null elements, or
+    /// 0 if there are none.
+    pub fn l1_norm(&self) -> f64 {
+        kahan_sum(self.valid_values().map(f64::abs))
+    }
+
+    /// Returns the largest absolute value of the non-null elements, or 0 if
+    /// there are none. As with [`crate::max`], `NaN`s are ignored.
+    pub fn linf_norm(&self) -> f64 {
+        self.valid_values().map(f64::abs).fold(0.0, f64::max)
+    }
+
+    /// Returns the sample variance of the non-null elements, dividing by
+    /// `n - 1`, or None if there are fewer than 2.
+    ///
+    /// Computed in a single pass with Welford's algorithm, which avoids the
+    /// cancellation of subtracting the squared mean.
+    pub fn variance(&self) -> Option<f64> {
+        let (count, m2) = self.welford();
+        (count >= 2).then(|| m2 / (count - 1) as f64)
+    }
+
+    /// Returns the square root of [`ArrayF64::variance`].
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Returns the population variance of the non-null elements, dividing
+    /// by `n`, or None if there are fewer than 2.
+    pub fn population_variance(&self) -> Option<f64> {
+        let (count, m2) = self.welford();
+        (count >= 2).then(|| m2 / count as f64)
+    }
+
+    /// Returns the square root of [`ArrayF64::population_variance`].
+    pub fn population_std_dev(&self) -> Option<f64> {
+        self.population_variance().map(f64::sqrt)
+    }
+
+    /// Returns the `q`th quantile of the non-null elements, interpolating
+    /// linearly between the elements either side of it as pandas does by
+    /// default, or None if every element is null.
+    ///
+    /// Elements are ordered by [`f64::total_cmp`], so `NaN`s sort above
+    /// infinity. Panics if `q` is outside `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        assert!((0.0..=1.0).contains(&q), "Quantile {q} is outside [0, 1]");
+
+        let mut values = self.valid_values().collect::<Vec<_>>();
+        values.sort_by(f64::total_cmp);
+
+        quantile_of_sorted(&values, q)
+    }
+
+    /// Returns the median of the non-null elements, see
+    /// [`ArrayF64::quantile`].
+    pub fn median(&self) -> Option<f64> {
+        self.quantile(0.5)
+    }
+
+    /// Returns the sum of the non-null elements of each window of `window`
+    /// elements ending at every position.
+    ///
+    /// The first `window - 1` positions, lacking a full window, are null as
+    /// are windows of nulls. Runs in linear time by adding each element to a
+    /// running sum as it enters the window and subtracting it as it leaves.
+    ///
+    /// Panics if `window` is 0.
+    pub fn rolling_sum(&self, window: usize) -> ArrayF64 {
+        ArrayF64::from_vec(
+            self.rolling(window)
+                .map(|totals| totals.map(|(sum, _)| sum))
+                .collect(),
+        )
+    }
+
+    /// Returns the mean of the non-null elements of each window of `window`
+    /// elements ending at every position, so nulls shrink the window they
+    /// fall in.
+    ///
+    /// See [`ArrayF64::rolling_sum`].
+    pub fn rolling_mean(&self, window: usize) -> ArrayF64 {
+        ArrayF64::from_vec(
+            self.rolling(window)
+                .map(|totals| totals.map(|(sum, count)| sum / count as f64))
+                .collect(),
+        )
+    }
+
+    /// Returns the sum and count of the non-null elements of each full
+    /// window, or None for partial windows and windows of nulls.
+    fn rolling(&self, window: usize) -> impl Iterator<Item = Option<(f64, usize)>> + '_ {
+        assert!(window != 0, "Tried to roll a window of 0 elements");
+
+        let (mut sum, mut count) = (0.0, 0);
 
-            if own != other {
-                return false;
+        (0..self.len()).map(move |idx| {
+            if let Some(value) = self.get(idx) {
+                sum += value;
+                count += 1;
             }
+
+            if let Some(value) = idx.checked_sub(window).and_then(|idx| self.get(idx)) {
+                sum -= value;
+                count -= 1;
+            }
+
+            (idx + 1 >= window && count != 0).then_some((sum, count))
+        })
+    }
+
+    /// Returns the count of the non-null elements and the sum of their
+    /// squared differences from the mean.
+    fn welford(&self) -> (usize, f64) {
+        let (mut count, mut mean, mut m2) = (0, 0.0, 0.0);
+
+        for value in self.valid_values() {
+            count += 1;
+            let delta = value - mean;
+            mean += delta / count as f64;
+            m2 += delta * (value - mean);
         }
 
-        true
+        (count, m2)
+    }
+
+    fn valid_values(&self) -> impl Iterator<Item = f64> + '_ {
+        valid_values(self).expect("ArrayF64 always exposes its values")
     }
 
-    /// Allocates both values and validity buffers
+    /// Creates a null free [`ArrayF64`], writing the values straight into
+    /// the values buffer.
+    fn from_fn<F>(len: usize, f: F) -> Self
+    where
+        F: Fn(usize) -> f64,
+    {
+        if len == 0 {
+            return Self::nulls(0);
+        }
+
+        let values_ptr = Self::allocate_values(len);
+
+        for idx in 0..len {
+            unsafe { ptr::write(values_ptr.as_ptr().add(idx), f(idx)) };
+        }
+
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            len,
+            nulls: 0,
+        }
+    }
+
+    /// Returns true if the validity buffers of `Self` and `Other` are equal.
+    fn compare_validity(&self, other: &Self) -> bool {
+        self.validity == other.validity
+    }
+
+    /// Returns true if the values of `Self` and `Other` are equal.
+    ///
+    /// Assumes both arrays have equal validity, so only the values of valid
+    /// elements are compared.
+    fn compare_values(&self, other: &Self) -> bool {
+        match (valid_values(self), valid_values(other)) {
+            (Some(own), Some(other)) => own.eq(other),
+            // Only all null arrays lack a values buffer
+            _ => true,
+        }
+    }
+
+    /// Allocates only the values buffer
     ///
     /// Must ensure len != 0
-    fn allocate(len: usize) -> (NonNull<f64>, NonNull<u8>) {
-        // Values
+    fn allocate_values(len: usize) -> NonNull<f64> {
         let values_size = len * std::mem::size_of::<f64>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayF64: values size overflowed isize::max");
 
         let values_ptr = unsafe { alloc::alloc(values_layout) };
 
-        let values_ptr = match NonNull::new(values_ptr as *mut f64) {
+        match NonNull::new(values_ptr as *mut f64) {
             Some(ptr) => ptr,
             None => alloc::handle_alloc_error(values_layout),
-        };
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayF64: validity size overflowed isize::max");
-
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
-
-        (values_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayF64 drop: validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-        unsafe { alloc::dealloc(ptr, validity_layout) };
+        }
     }
 
     fn dealloc_values(ptr: Option<NonNull<f64>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let values_size = len * std::mem::size_of::<f64>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayF64 drop: values size overflowed isize::max");
         let ptr = ptr.as_ptr() as *mut u8;
 
@@ -244,67 +477,314 @@ impl Array for ArrayF64 {
             return true;
         }
 
-        let Some(val_ptr) = self.val_ptr else {
-            return false;
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
+    }
+
+    fn all_null(&self) -> bool {
+        self.nulls == self.len
+    }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
+    }
+
+    fn values(&self) -> Option<&[Self::Data]> {
+        let ptr = self.ptr?;
+        Some(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len) })
+    }
+
+    /// Returns a copy of the elements whose counterpart in `mask` is true.
+    /// Nulls in `mask` count as false.
+    ///
+    /// On targets with AVX2 enabled the values are compacted four at a time.
+    ///
+    /// Errors if `mask` differs in length from the array.
+    fn filter(&self, mask: &ArrayBoolean) -> Result<Self, ArrayError> {
+        if mask.len() != self.len {
+            return Err(ArrayError::LengthMismatch {
+                left: self.len,
+                right: mask.len(),
+            });
+        }
+
+        let selection = mask.true_bits();
+        let len = selection.count_ones();
+
+        let Some(ptr) = self.ptr.filter(|_| len != 0) else {
+            return Ok(Self::nulls(len));
+        };
+
+        let values = unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len) };
+        let values_ptr = Self::allocate_values(len);
+
+        // SAFETY: The values buffer holds exactly one slot per selected value.
+        unsafe { filter_values(values, &selection, values_ptr.as_ptr()) };
+
+        let Some(source) = &self.validity else {
+            return Ok(Self {
+                ptr: Some(values_ptr),
+                validity: None,
+                len,
+                nulls: 0,
+            });
         };
 
-        let byte_index = idx / 8;
+        let mut validity = BitmapBuffer::new(len);
+        let selected = (0..self.len).filter(|idx| selection.is_set(*idx));
+
+        for (idx, selected) in selected.enumerate() {
+            if source.is_set(selected) {
+                validity.set_bit(idx);
+            }
+        }
+
+        let nulls = len - validity.count_ones();
+
+        if nulls == len {
+            Self::dealloc_values(Some(values_ptr), len);
+            return Ok(Self::nulls(len));
+        }
 
-        let val_byte = unsafe { ptr::read(val_ptr.as_ptr().add(byte_index)) };
-        val_byte & (1 << (idx % 8)) == 0
+        Ok(Self {
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
+            len,
+            nulls,
+        })
     }
+}
 
-    fn all_null(&self) -> bool {
-        self.nulls == self.len
+/// Adds up `values` with Kahan summation, carrying the low order bits lost
+/// by each addition into the next.
+fn kahan_sum(values: impl Iterator<Item = f64>) -> f64 {
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+
+    for value in values {
+        let value = value - compensation;
+        let next = sum + value;
+
+        compensation = (next - sum) - value;
+        sum = next;
     }
+
+    sum
 }
 
-impl Drop for ArrayF64 {
-    fn drop(&mut self) {
-        Self::dealloc_values(self.ptr, self.len());
-        Self::dealloc_validity(self.val_ptr, self.len())
+/// Returns the `q`th quantile of the ascending `sorted`, interpolating
+/// linearly between the values either side, or None if it is empty.
+pub(crate) fn quantile_of_sorted(sorted: &[f64], q: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let position = q * last as f64;
+    let (lower, fraction) = (position.floor() as usize, position.fract());
+
+    match sorted.get(lower + 1) {
+        Some(upper) if fraction > 0.0 => Some(sorted[lower] + (upper - sorted[lower]) * fraction),
+        _ => Some(sorted[lower]),
     }
 }
 
-impl Clone for ArrayF64 {
-    fn clone(&self) -> Self {
-        if self.len() == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
+/// Adds every value whose validity bit is set, or every value if there is
+/// no validity buffer.
+#[cfg(not(all(target_arch = "x86_64", target_feature = "avx2")))]
+fn sum_values(values: &[f64], validity: Option<&BitmapBuffer>) -> f64 {
+    match validity {
+        Some(validity) => values
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| validity.is_set(*idx))
+            .map(|(_, value)| value)
+            .sum(),
+        None => values.iter().sum(),
+    }
+}
+
+/// Adds every value whose validity bit is set, or every value if there is
+/// no validity buffer, four lanes at a time.
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+fn sum_values(values: &[f64], validity: Option<&BitmapBuffer>) -> f64 {
+    use std::arch::x86_64::*;
+
+    let chunks = values.chunks_exact(4);
+    let rest = chunks.remainder();
+    let mut lanes = [0.0; 4];
+
+    // SAFETY: AVX2 is enabled for the target and every load and store is
+    // within a chunk of four values.
+    unsafe {
+        let zeros = _mm256_setzero_pd();
+        let mut acc = zeros;
+
+        for (idx, chunk) in chunks.enumerate() {
+            let values = _mm256_loadu_pd(chunk.as_ptr());
+
+            let values = match validity {
+                Some(validity) => {
+                    // Each byte of the bitmap covers two chunks
+                    let bits = (validity.as_bytes()[idx / 2] >> (4 * (idx % 2))) & 0xF;
+                    let lane = |bit: u8| -i64::from((bits >> bit) & 1);
+                    let mask = _mm256_set_epi64x(lane(3), lane(2), lane(1), lane(0));
+
+                    _mm256_blendv_pd(zeros, values, _mm256_castsi256_pd(mask))
+                }
+                None => values,
             };
+
+            acc = _mm256_add_pd(acc, values);
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(self.len());
+        _mm256_storeu_pd(lanes.as_mut_ptr(), acc);
+    }
 
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len());
-                None
-            }
-        };
+    let offset = values.len() - rest.len();
+    let rest = rest
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| validity.is_none_or(|validity| validity.is_set(offset + idx)))
+        .map(|(_, value)| value)
+        .sum::<f64>();
 
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.len());
-                None
+    lanes.iter().sum::<f64>() + rest
+}
+
+/// Writes every value whose bit is set in `selection` to `out`, in order.
+///
+/// # Safety
+///
+/// `out` must be valid for writes of as many values as `selection` has set
+/// bits.
+#[cfg(not(all(target_arch = "x86_64", target_feature = "avx2")))]
+unsafe fn filter_values(values: &[f64], selection: &BitmapBuffer, out: *mut f64) {
+    let selected = values
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| selection.is_set(*idx));
+
+    for (offset, (_, value)) in selected.enumerate() {
+        unsafe { ptr::write(out.add(offset), *value) };
+    }
+}
+
+/// Permutations moving the selected lanes of a chunk of four `f64`s to its
+/// front, indexed by the selection bits. Each lane spans two `i32` indices.
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+const COMPACT_LANES: [[i32; 8]; 16] = {
+    let mut table = [[0; 8]; 16];
+    let mut bits = 0;
+
+    while bits < 16 {
+        let mut lane = 0;
+        let mut front = 0;
+
+        while lane < 4 {
+            if (bits >> lane) & 1 == 1 {
+                table[bits][2 * front] = 2 * lane;
+                table[bits][2 * front + 1] = 2 * lane + 1;
+                front += 1;
             }
-        };
+            lane += 1;
+        }
+        bits += 1;
+    }
+
+    table
+};
+
+/// Store masks covering the first `n` lanes of a chunk, indexed by `n`.
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+const STORE_LANES: [[i64; 4]; 5] = [
+    [0, 0, 0, 0],
+    [-1, 0, 0, 0],
+    [-1, -1, 0, 0],
+    [-1, -1, -1, 0],
+    [-1, -1, -1, -1],
+];
+
+/// Writes every value whose bit is set in `selection` to `out`, in order,
+/// four lanes at a time.
+///
+/// # Safety
+///
+/// `out` must be valid for writes of as many values as `selection` has set
+/// bits.
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+unsafe fn filter_values(values: &[f64], selection: &BitmapBuffer, out: *mut f64) {
+    use std::arch::x86_64::*;
+
+    let chunks = values.chunks_exact(4);
+    let rest = chunks.remainder();
+    let bytes = selection.as_bytes();
+    let mut offset = 0;
+
+    for (idx, chunk) in chunks.enumerate() {
+        // Each byte of the bitmap covers two chunks
+        let bits = usize::from((bytes[idx / 2] >> (4 * (idx % 2))) & 0xF);
+
+        if bits == 0 {
+            continue;
+        }
+
+        let count = bits.count_ones() as usize;
+
+        // SAFETY: AVX2 is enabled for the target, loads stay within the chunk
+        // and the tables, and the store only writes the `count` lanes still
+        // left in `out`.
+        unsafe {
+            let values = _mm256_castpd_ps(_mm256_loadu_pd(chunk.as_ptr()));
+            let lanes = _mm256_loadu_si256(COMPACT_LANES[bits].as_ptr() as *const __m256i);
+            let compacted = _mm256_castps_pd(_mm256_permutevar8x32_ps(values, lanes));
+            let store = _mm256_loadu_si256(STORE_LANES[count].as_ptr() as *const __m256i);
+
+            _mm256_maskstore_pd(out.add(offset), store, compacted);
+        }
+
+        offset += count;
+    }
+
+    let start = values.len() - rest.len();
+
+    for (idx, value) in rest.iter().enumerate() {
+        if selection.is_set(start + idx) {
+            unsafe { ptr::write(out.add(offset), *value) };
+            offset += 1;
+        }
+    }
+}
+
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayF64 {}
+unsafe impl Sync for ArrayF64 {}
+
+impl Default for ArrayF64 {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Drop for ArrayF64 {
+    fn drop(&mut self) {
+        Self::dealloc_values(self.ptr, self.len());
+    }
+}
+
+impl Clone for ArrayF64 {
+    fn clone(&self) -> Self {
+        let values_ptr = self.ptr.map(|ptr| {
+            let values_ptr = Self::allocate_values(self.len());
+            unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
+            values_ptr
+        });
 
         Self {
             ptr: values_ptr,
-            val_ptr: validity_ptr,
+            validity: self.validity.clone(),
             len: self.len(),
             nulls: self.nulls,
         }
@@ -359,6 +839,44 @@ impl PartialEq for ArrayF64 {
     }
 }
 
+/// An [`ArrayF64`] compared and hashed by the bit patterns of its values,
+/// for use as a map key.
+///
+/// Unlike `==` on the array, a `NaN` equals another with the same bits
+/// while `0.0` and `-0.0` differ.
+#[derive(Debug, Clone)]
+pub struct HashableF64Array(pub ArrayF64);
+
+impl HashableF64Array {
+    fn bits(&self) -> impl Iterator<Item = Option<u64>> + '_ {
+        self.0.copied_iter().map(|value| value.map(f64::to_bits))
+    }
+}
+
+impl From<ArrayF64> for HashableF64Array {
+    fn from(value: ArrayF64) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq for HashableF64Array {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len == other.0.len && self.bits().eq(other.bits())
+    }
+}
+
+impl Eq for HashableF64Array {}
+
+impl Hash for HashableF64Array {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len.hash(state);
+
+        for bits in self.bits() {
+            bits.hash(state);
+        }
+    }
+}
+
 impl IntoIterator for ArrayF64 {
     type Item = Option<f64>;
     type IntoIter = IntoIter<Self>;
@@ -374,6 +892,12 @@ impl From<ArrayF64> for Vec<F64> {
     }
 }
 
+impl From<&ArrayF64> for Vec<F64> {
+    fn from(value: &ArrayF64) -> Self {
+        value.copied_iter().collect()
+    }
+}
+
 impl From<Vec<f64>> for ArrayF64 {
     fn from(value: Vec<f64>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -410,7 +934,20 @@ impl<const N: usize> From<[F64; N]> for ArrayF64 {
     }
 }
 
+impl From<&[f64]> for ArrayF64 {
+    fn from(value: &[f64]) -> Self {
+        Self::from_sized_iter(value.iter().copied().map(Some))
+    }
+}
+
+impl From<&[F64]> for ArrayF64 {
+    fn from(value: &[F64]) -> Self {
+        Self::from_sized_iter(value.iter().copied())
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::legacy_numeric_constants)]
 mod test {
     use super::*;
     use std::f64::consts;
@@ -564,4 +1101,354 @@ mod test {
 
         assert_eq!(0, one.len());
     }
+
+    #[test]
+    fn test_full_and_nulls() {
+        let one = ArrayF64::full(4, 2.5);
+        assert_eq!(ArrayF64::from([2.5, 2.5, 2.5, 2.5]), one);
+        assert!(one.validity.is_none());
+        assert_eq!(ArrayF64::from([0.0]), ArrayF64::full(1, 0.0));
+        assert!(ArrayF64::full(0, 1.0).is_empty());
+
+        let two = ArrayF64::nulls(3);
+        assert_eq!(ArrayF64::from([None, None, None]), two);
+        assert!(two.ptr.is_none());
+        assert!(ArrayF64::nulls(0).is_empty());
+    }
+
+    #[test]
+    fn test_linspace() {
+        assert!(ArrayF64::linspace(0.0, 1.0, 0).is_empty());
+        assert_eq!(ArrayF64::from([3.0]), ArrayF64::linspace(3.0, 5.0, 1));
+        assert_eq!(
+            ArrayF64::from([0.0, 0.25, 0.5, 0.75, 1.0]),
+            ArrayF64::linspace(0.0, 1.0, 5)
+        );
+        assert_eq!(
+            ArrayF64::from([1.0, 0.0, -1.0]),
+            ArrayF64::linspace(1.0, -1.0, 3)
+        );
+
+        // Both endpoints are exact regardless of rounding in the step
+        let one = ArrayF64::linspace(0.1, 0.7, 7);
+        assert_eq!(7, one.len());
+        assert_eq!(Some(0.1), one.get(0));
+        assert_eq!(Some(0.7), one.get(6));
+
+        // Each value is rounded once rather than carrying the step's error
+        let one = ArrayF64::linspace(0.0, 1.0, 49);
+        assert!((0..49).all(|idx| one.get(idx) == Some(idx as f64 / 48.0)));
+    }
+
+    #[test]
+    fn test_index_of_nan() {
+        let one = ArrayF64::from([Some(0.0), None, Some(f64::NAN), Some(-0.0), Some(f64::NAN)]);
+
+        assert_eq!(None, one.index_of(f64::NAN));
+        assert_eq!(Some(2), one.index_of_bits(f64::NAN));
+        assert_eq!(Some(4), one.index_of_bits_from(f64::NAN, 3));
+        assert_eq!(None, one.index_of_bits_from(f64::NAN, 5));
+
+        // Equality treats the zeros as equal but their bits differ
+        assert_eq!(Some(0), one.index_of(-0.0));
+        assert_eq!(Some(3), one.index_of_bits(-0.0));
+        assert_eq!(None, one.index_of_bits(1.0));
+    }
+
+    #[test]
+    fn test_sum() {
+        for len in [0, 1, 3, 4, 5, 8, 17, 64, 67] {
+            let values = (0..len).map(|idx| (idx % 5 != 0).then_some(idx as f64 * 0.5));
+            let one = ArrayF64::new(values.clone());
+            let expected = values.flatten().reduce(|acc, val| acc + val);
+
+            assert_eq!(expected, one.sum(), "length {len}");
+        }
+
+        assert_eq!(Some(10.0), ArrayF64::full(20, 0.5).sum());
+        assert_eq!(None, ArrayF64::nulls(3).sum());
+        assert!(ArrayF64::from([Some(f64::NAN), None])
+            .sum()
+            .unwrap()
+            .is_nan());
+    }
+
+    #[test]
+    fn test_variance() {
+        let one = ArrayF64::from([
+            Some(2.0),
+            Some(4.0),
+            None,
+            Some(4.0),
+            Some(4.0),
+            Some(5.0),
+            Some(5.0),
+            Some(7.0),
+            Some(9.0),
+        ]);
+
+        assert_eq!(Some(4.0), one.population_variance());
+        assert_eq!(Some(2.0), one.population_std_dev());
+        assert_eq!(Some(32.0 / 7.0), one.variance());
+
+        let std_dev = one.std_dev().unwrap();
+        assert!((std_dev - (32.0_f64 / 7.0).sqrt()).abs() < f64::EPSILON * 10.0);
+
+        // Large offsets cancel out with the naive sum of squares
+        let two = ArrayF64::from([1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0]);
+        assert!((two.std_dev().unwrap() - 30.0_f64.sqrt()).abs() < f64::EPSILON * 10.0);
+
+        assert_eq!(None, ArrayF64::from([Some(1.0), None]).variance());
+        assert_eq!(None, ArrayF64::nulls(4).population_std_dev());
+        assert_eq!(Some(0.0), ArrayF64::full(5, 3.0).std_dev());
+    }
+
+    #[test]
+    fn test_quantile() {
+        let one = ArrayF64::from([Some(7.0), None, Some(1.0), Some(3.0), Some(15.0)]);
+
+        assert_eq!(Some(1.0), one.quantile(0.0));
+        assert_eq!(Some(15.0), one.quantile(1.0));
+        assert_eq!(Some(2.5), one.quantile(0.25));
+        assert_eq!(Some(5.0), one.median());
+        assert_eq!(Some(9.0), one.quantile(0.75));
+
+        assert_eq!(Some(4.0), ArrayF64::from([4.0]).quantile(0.3));
+        assert_eq!(None, ArrayF64::nulls(2).median());
+    }
+
+    #[test]
+    #[should_panic(expected = "outside [0, 1]")]
+    fn test_quantile_out_of_range() {
+        ArrayF64::from([1.0]).quantile(1.5);
+    }
+
+    #[test]
+    fn test_rolling() {
+        let one = ArrayF64::from([
+            Some(1.0),
+            Some(2.0),
+            None,
+            Some(6.0),
+            None,
+            None,
+            None,
+            Some(4.0),
+        ]);
+
+        assert_eq!(
+            ArrayF64::from([
+                None,
+                None,
+                Some(3.0),
+                Some(8.0),
+                Some(6.0),
+                Some(6.0),
+                None,
+                Some(4.0)
+            ]),
+            one.rolling_sum(3)
+        );
+        assert_eq!(
+            ArrayF64::from([
+                None,
+                None,
+                Some(1.5),
+                Some(4.0),
+                Some(6.0),
+                Some(6.0),
+                None,
+                Some(4.0)
+            ]),
+            one.rolling_mean(3)
+        );
+        assert_eq!(one, one.rolling_sum(1));
+        assert_eq!(
+            ArrayF64::nulls(2),
+            ArrayF64::from([1.0, 2.0]).rolling_mean(3)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to roll a window of 0 elements")]
+    fn test_rolling_zero_window() {
+        ArrayF64::from([1.0]).rolling_mean(0);
+    }
+
+    #[test]
+    fn test_filter() {
+        for len in [0, 1, 3, 4, 5, 8, 17, 64, 67] {
+            let values = (0..len)
+                .map(|idx| (idx % 5 != 0).then_some(idx as f64 * 0.5))
+                .collect::<Vec<_>>();
+            let mask = (0..len).map(|idx| (idx % 7 != 3).then_some(idx % 3 != 1));
+            let mask = ArrayBoolean::new(mask);
+
+            let expected = values
+                .iter()
+                .zip(mask.iter())
+                .filter(|(_, keep)| *keep == Some(true))
+                .map(|(value, _)| *value)
+                .collect::<Vec<_>>();
+            let one = ArrayF64::new(values.clone());
+
+            assert_eq!(
+                Ok(ArrayF64::new(expected.clone())),
+                one.filter(&mask),
+                "length {len}"
+            );
+
+            let dense = ArrayF64::new(values.iter().map(|value| value.or(Some(-1.0))));
+            let expected = ArrayF64::new(expected.iter().map(|value| value.or(Some(-1.0))));
+
+            assert_eq!(Ok(expected), dense.filter(&mask), "length {len}");
+        }
+
+        let one = ArrayF64::from([Some(1.0), None, Some(3.0), None, Some(5.0)]);
+        let mask = ArrayBoolean::from([false, true, false, true, true]);
+        assert_eq!(
+            Ok(ArrayF64::from([None, None, Some(5.0)])),
+            one.filter(&mask)
+        );
+
+        let mask = ArrayBoolean::from([false, true, false, true, false]);
+        assert_eq!(Ok(ArrayF64::nulls(2)), one.filter(&mask));
+        assert_eq!(
+            Ok(ArrayF64::nulls(1)),
+            ArrayF64::nulls(2).filter(&ArrayBoolean::from([true, false]))
+        );
+        assert_eq!(
+            Err(ArrayError::LengthMismatch { left: 5, right: 1 }),
+            one.filter(&ArrayBoolean::from([true]))
+        );
+    }
+
+    #[test]
+    fn test_alignment() {
+        for len in [1, 3, 8, 100] {
+            let one = ArrayF64::new((0..len).map(|idx| (idx % 3 != 1).then_some(idx as f64)));
+            let ptr = one.ptr.unwrap().as_ptr();
+
+            assert_eq!(0, ptr as usize % 64, "length {len}");
+
+            if let Some(validity) = &one.validity {
+                assert_eq!(0, validity.as_bytes().as_ptr() as usize % 64);
+            }
+
+            let two = one.clone();
+            assert_eq!(0, two.ptr.unwrap().as_ptr() as usize % 64);
+        }
+    }
+
+    #[test]
+    fn test_norms() {
+        let one = ArrayF64::from([Some(3.0), None, Some(-4.0)]);
+
+        assert_eq!(5.0, one.l2_norm());
+        assert_eq!(7.0, one.l1_norm());
+        assert_eq!(4.0, one.linf_norm());
+
+        for two in [ArrayF64::from_vec(vec![]), ArrayF64::nulls(3)] {
+            assert_eq!(0.0, two.l2_norm());
+            assert_eq!(0.0, two.l1_norm());
+            assert_eq!(0.0, two.linf_norm());
+        }
+
+        // Naive summation loses every 1e-16 against the leading 1.0
+        let three = (0..10_001).map(|idx| Some(if idx == 0 { 1.0 } else { 1e-16 }));
+        let three = ArrayF64::new(three);
+        assert_eq!(1.0, three.copied_iter().flatten().sum::<f64>());
+        assert!((three.l1_norm() - (1.0 + 1e-12)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_random_filter() {
+        for seed in 0..16 {
+            let len = seed as usize * 37;
+            let one = crate::random_f64(len, seed as f64 / 16.0, -1e6..1e6, seed);
+            let mask = crate::random_bool(len, 0.1, seed + 100);
+
+            let expected = (0..len)
+                .filter(|idx| mask.get(*idx) == Some(true))
+                .map(|idx| one.get(idx))
+                .collect::<Vec<_>>();
+            let expected = ArrayF64::new(expected);
+
+            assert_eq!(Ok(expected), one.filter(&mask), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_iota_arange() {
+        assert_eq!(ArrayF64::from([0.0, 1.0, 2.0]), ArrayF64::iota(3));
+        assert_eq!(
+            ArrayF64::from([0.0, 0.25, 0.5, 0.75]),
+            ArrayF64::arange(0.0, 1.0, 0.25)
+        );
+        assert_eq!(
+            ArrayF64::from([1.0, 0.5, 0.0]),
+            ArrayF64::arange(1.0, -0.25, -0.5)
+        );
+        assert!(ArrayF64::arange(1.0, 1.0, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_sample() {
+        let one = ArrayF64::new((0..500).map(|idx| (idx % 4 != 0).then_some(idx as f64)));
+
+        let sampled = one.sample(50, 7);
+        assert_eq!(50, sampled.len());
+        assert_eq!(sampled, one.sample(50, 7));
+        assert!(sampled.null_count() > 0);
+        assert!(sampled
+            .copied_iter()
+            .flatten()
+            .all(|value| one.contains(value)));
+        assert_eq!(one, one.sample(500, 7));
+        assert_eq!(0, one.sample(0, 7).len());
+
+        let nth = ArrayF64::from([Some(1.0), None, Some(3.0), Some(4.0), None]);
+        assert_eq!(
+            ArrayF64::from([Some(1.0), Some(3.0), None]),
+            nth.every_nth(2)
+        );
+        assert_eq!(ArrayF64::from([1.0]), nth.every_nth(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to take every 0th element")]
+    fn test_every_nth_zero() {
+        ArrayF64::from([1.0]).every_nth(0);
+    }
+
+    #[test]
+    fn test_hashable() {
+        use std::collections::HashMap;
+
+        let one = HashableF64Array::from(ArrayF64::from([Some(f64::NAN), None, Some(0.0)]));
+        let two = HashableF64Array(ArrayF64::from_vec(vec![Some(f64::NAN), None, Some(0.0)]));
+        assert_ne!(one.0, two.0);
+        assert_eq!(one, two);
+
+        let three = HashableF64Array(ArrayF64::from([Some(f64::NAN), None, Some(-0.0)]));
+        assert_ne!(one, three);
+
+        let cache = HashMap::from([(one, "cached")]);
+        assert_eq!(Some(&"cached"), cache.get(&two));
+        assert_eq!(None, cache.get(&three));
+    }
+
+    #[test]
+    fn test_from_slices() {
+        let values = [1.0, 2.5, -3.0];
+        assert_eq!(ArrayF64::from([2.5, -3.0]), ArrayF64::from(&values[1..]));
+
+        let options = vec![Some(1.0), None];
+        let one = ArrayF64::from(options.as_slice());
+        assert_eq!(ArrayF64::from([Some(1.0), None]), one);
+
+        // Leaves the array in place
+        assert_eq!(options, Vec::from(&one));
+        assert_eq!(options, Vec::from(one));
+    }
 }
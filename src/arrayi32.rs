@@ -1,8 +1,14 @@
 use std::alloc::{self, Layout};
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{
+    integer_steps, valid_values, validity_from_mask, Array, BitmapBuffer, DataType, IntoIter, Iter,
+    ALIGNMENT,
+};
+use crate::ArrayBoolean;
 
 pub type I32 = Option<i32>;
 
@@ -11,8 +17,8 @@ pub type I32 = Option<i32>;
 pub struct ArrayI32 {
     /// Pointer to the values buffer
     ptr: Option<NonNull<i32>>,
-    /// Pointer to the validity buffer
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls
+    validity: Option<BitmapBuffer>,
     /// The number of elements in the array
     len: usize,
     /// The number of nulls in the array
@@ -25,6 +31,181 @@ impl ArrayI32 {
         Self::from_sized_iter(values.into_iter())
     }
 
+    /// Creates an [`ArrayI32`] of `0, 1, ..., n - 1`.
+    ///
+    /// Panics if `n - 1` does not fit in a `i32`.
+    pub fn iota(n: usize) -> Self {
+        assert!(
+            n <= i32::MAX as usize + 1,
+            "Tried to count up to {n} in an ArrayI32"
+        );
+
+        Self::from_fn(n, |idx| idx as i32)
+    }
+
+    /// Creates an [`ArrayI32`] counting from `start` up to, but excluding,
+    /// `stop` in increments of `step`.
+    ///
+    /// Panics if `step` is 0 or leads away from `stop`.
+    pub fn arange(start: i32, stop: i32, step: i32) -> Self {
+        let len = integer_steps(start as i128, stop as i128, step as i128);
+
+        Self::from_fn(len, |idx| {
+            (start as i128 + idx as i128 * step as i128) as i32
+        })
+    }
+
+    /// Creates an [`ArrayI32`] of length `len` with every element set to
+    /// `value`.
+    pub fn full(len: usize, value: i32) -> Self {
+        Self::from_fn(len, |_| value)
+    }
+
+    /// Creates an [`ArrayI32`] of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self::full(len, 0)
+    }
+
+    /// Creates an [`ArrayI32`] of `len` ones.
+    pub fn ones(len: usize) -> Self {
+        Self::full(len, 1)
+    }
+
+    /// Creates an [`ArrayI32`] of length `len` with every element null.
+    pub fn nulls(len: usize) -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len,
+            nulls: len,
+        }
+    }
+
+    /// Creates an [`ArrayI32`] holding every value in `range`.
+    pub fn from_range(range: std::ops::Range<i32>) -> Self {
+        Self::from_step_range(range.start, range.end, 1)
+    }
+
+    /// Creates an [`ArrayI32`] counting from `start` up to, but excluding,
+    /// `end` in increments of `step`. A negative `step` counts down.
+    ///
+    /// Panics if `step` is 0.
+    pub fn from_step_range(start: i32, end: i32, step: i32) -> Self {
+        assert!(step != 0, "Tried to create a range with a step of 0");
+
+        let span = end as i64 - start as i64;
+        let step = step as i64;
+
+        let len = if span == 0 || span.signum() != step.signum() {
+            0
+        } else {
+            (span.abs() + step.abs() - 1) / step.abs()
+        };
+
+        Self::from_fn(len as usize, |idx| {
+            (start as i64 + idx as i64 * step) as i32
+        })
+    }
+
+    /// Returns the most frequent non-null element, or None if every element
+    /// is null. Ties go to the smallest of the tied elements.
+    pub fn mode(&self) -> Option<i32> {
+        self.mode_count().0
+    }
+
+    /// Returns the most frequent non-null element, as in [`ArrayI32::mode`],
+    /// along with the number of times it occurs. The count is 0 if every
+    /// element is null.
+    pub fn mode_count(&self) -> (Option<i32>, usize) {
+        let mut counts = HashMap::new();
+
+        for value in self.copied_iter().flatten() {
+            *counts.entry(value).or_insert(0) += 1;
+        }
+
+        counts
+            .into_iter()
+            .max_by(|(lhs, lhs_count), (rhs, rhs_count)| {
+                lhs_count.cmp(rhs_count).then(rhs.cmp(lhs))
+            })
+            .map_or((None, 0), |(value, count)| (Some(value), count))
+    }
+
+    /// Creates a null free [`ArrayI32`], writing the values straight into
+    /// the values buffer.
+    fn from_fn<F>(len: usize, f: F) -> Self
+    where
+        F: Fn(usize) -> i32,
+    {
+        if len == 0 {
+            return Self::nulls(0);
+        }
+
+        let values_ptr = Self::allocate_values(len);
+
+        for idx in 0..len {
+            unsafe { ptr::write(values_ptr.as_ptr().add(idx), f(idx)) };
+        }
+
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            len,
+            nulls: 0,
+        }
+    }
+
+    /// Creates an empty [`ArrayI32`].
+    pub const fn empty() -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    /// Returns a copy of the array whose valid elements are the true ones of
+    /// `validity`, nulls in `validity` counting as false.
+    ///
+    /// The values under the new nulls are kept, so
+    /// [`ArrayI32::clear_validity`] recovers them.
+    ///
+    /// Panics if `validity` and the array differ in length
+    pub fn with_validity(&self, validity: &ArrayBoolean) -> Self {
+        let (validity, nulls) = validity_from_mask(self.len, validity);
+
+        let mut array = self.with_values_buffer();
+        array.validity = validity;
+        array.nulls = nulls;
+        array
+    }
+
+    /// Returns a copy of the array with every element valid, exposing the
+    /// values under its nulls.
+    ///
+    /// Nulls the array was built with read as 0.
+    pub fn clear_validity(&self) -> Self {
+        let mut array = self.with_values_buffer();
+        array.validity = None;
+        array.nulls = 0;
+        array
+    }
+
+    /// Returns a clone of the array, allocating a zeroed values buffer if
+    /// it had none.
+    fn with_values_buffer(&self) -> Self {
+        let mut array = self.clone();
+
+        if array.ptr.is_none() && array.len != 0 {
+            let values_ptr = Self::allocate_values(array.len);
+            unsafe { ptr::write_bytes(values_ptr.as_ptr(), 0, array.len) };
+            array.ptr = Some(values_ptr);
+        }
+
+        array
+    }
+
     fn from_sized_iter<S>(sized: S) -> Self
     where
         S: Iterator<Item = I32> + ExactSizeIterator,
@@ -32,160 +213,83 @@ impl ArrayI32 {
         let len = sized.len();
 
         if len == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
+            return Self::empty();
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(len);
-
-        let mut val_byte = 0_u8;
-        let mut val_offset = 0;
+        let values_ptr = Self::allocate_values(len);
+        let mut validity = BitmapBuffer::new(len);
         let mut nulls = 0;
 
-        for (idx, value) in sized.into_iter().enumerate() {
+        for (idx, value) in sized.enumerate() {
             match value {
                 Some(value) => {
                     unsafe { ptr::write(values_ptr.as_ptr().add(idx), value) };
-                    let pos = 1 << (idx % 8);
-                    val_byte |= pos;
+                    validity.set_bit(idx);
                 }
                 None => {
+                    // Zeroed so the values buffer can be read as a slice
+                    unsafe { ptr::write(values_ptr.as_ptr().add(idx), 0) };
                     nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    val_byte &= pos;
-                }
-            }
-
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte);
                 }
-
-                val_byte = 0_u8;
-                val_offset += 1;
             }
         }
 
-        // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
-            unsafe { ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte) };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
-        }
-
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 nulls,
             };
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
             len,
             nulls,
         }
     }
 
     /// Returns true if the validity buffers of `Self` and `Other` are equal.
-    ///
-    /// Assumes both buffers are equal in length.
     fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
-
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
-
-                    if own != other {
-                        return false;
-                    }
-                }
-            }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
-        }
-
-        true
+        self.validity == other.validity
     }
 
     /// Returns true if the values of `Self` and `Other` are equal.
     ///
-    /// Assumes both buffers are equal in length.
+    /// Assumes both arrays have equal validity, so only the values of valid
+    /// elements are compared.
     fn compare_values(&self, other: &Self) -> bool {
-        let len = self.len;
-
-        for idx in 0..len {
-            let own = self.get(idx);
-            let other = other.get(idx);
-
-            if own != other {
-                return false;
-            }
+        match (valid_values(self), valid_values(other)) {
+            (Some(own), Some(other)) => own.eq(other),
+            // Only all null arrays lack a values buffer
+            _ => true,
         }
-
-        true
     }
 
-    /// Allocates both values and validity buffers
+    /// Allocates only the values buffer
     ///
     /// Must ensure len != 0
-    fn allocate(len: usize) -> (NonNull<i32>, NonNull<u8>) {
-        // Values
+    fn allocate_values(len: usize) -> NonNull<i32> {
         let values_size = len * std::mem::size_of::<i32>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayI32: values size overflowed isize::max");
 
         let values_ptr = unsafe { alloc::alloc(values_layout) };
 
-        let values_ptr = match NonNull::new(values_ptr as *mut i32) {
+        match NonNull::new(values_ptr as *mut i32) {
             Some(ptr) => ptr,
             None => alloc::handle_alloc_error(values_layout),
-        };
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayI32: validity size overflowed isize::max");
-
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
-
-        (values_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayI32 drop: validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-        unsafe { alloc::dealloc(ptr, validity_layout) };
+        }
     }
 
     fn dealloc_values(ptr: Option<NonNull<i32>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let values_size = len * std::mem::size_of::<i32>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayI32 drop: values size overflowed isize::max");
         let ptr = ptr.as_ptr() as *mut u8;
 
@@ -193,52 +297,34 @@ impl ArrayI32 {
     }
 }
 
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayI32 {}
+unsafe impl Sync for ArrayI32 {}
+
+impl Default for ArrayI32 {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Drop for ArrayI32 {
     fn drop(&mut self) {
         Self::dealloc_values(self.ptr, self.len());
-        Self::dealloc_validity(self.val_ptr, self.len())
     }
 }
 
 impl Clone for ArrayI32 {
     fn clone(&self) -> Self {
-        if self.len() == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
-        }
-
-        let (values_ptr, validity_ptr) = Self::allocate(self.len());
-
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len());
-                None
-            }
-        };
-
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.len());
-                None
-            }
-        };
+        let values_ptr = self.ptr.map(|ptr| {
+            let values_ptr = Self::allocate_values(self.len());
+            unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
+            values_ptr
+        });
 
         Self {
             ptr: values_ptr,
-            val_ptr: validity_ptr,
+            validity: self.validity.clone(),
             len: self.len(),
             nulls: self.nulls,
         }
@@ -344,24 +430,43 @@ impl Array for ArrayI32 {
             return true;
         }
 
-        let Some(val_ptr) = self.val_ptr else {
-            return false;
-        };
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
+    }
 
-        let byte_index = idx / 8;
+    fn all_null(&self) -> bool {
+        self.nulls == self.len
+    }
 
-        let val_byte = unsafe { ptr::read(val_ptr.as_ptr().add(byte_index)) };
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
 
-        val_byte & (1 << (idx % 8)) == 0
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
     }
 
-    fn all_null(&self) -> bool {
-        self.nulls == self.len
+    fn values(&self) -> Option<&[Self::Data]> {
+        let ptr = self.ptr?;
+        Some(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len) })
     }
 }
 
 impl Eq for ArrayI32 {}
 
+/// Hashes the elements, so arrays equal regardless of what lies under their
+/// nulls hash the same.
+impl Hash for ArrayI32 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
 impl From<Vec<i32>> for ArrayI32 {
     fn from(value: Vec<i32>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -398,6 +503,18 @@ impl<const N: usize> From<[I32; N]> for ArrayI32 {
     }
 }
 
+impl From<&[i32]> for ArrayI32 {
+    fn from(value: &[i32]) -> Self {
+        Self::from_sized_iter(value.iter().copied().map(Some))
+    }
+}
+
+impl From<&[I32]> for ArrayI32 {
+    fn from(value: &[I32]) -> Self {
+        Self::from_sized_iter(value.iter().copied())
+    }
+}
+
 impl IntoIterator for ArrayI32 {
     type Item = Option<i32>;
     type IntoIter = IntoIter<Self>;
@@ -413,7 +530,14 @@ impl From<ArrayI32> for Vec<Option<i32>> {
     }
 }
 
+impl From<&ArrayI32> for Vec<Option<i32>> {
+    fn from(value: &ArrayI32) -> Self {
+        value.copied_iter().collect()
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_conversion)]
 mod test {
     use super::*;
 
@@ -525,4 +649,155 @@ mod test {
 
         assert_eq!(0, one.len());
     }
+
+    #[test]
+    fn test_full_and_nulls() {
+        let one = ArrayI32::full(3, 7);
+        assert_eq!(ArrayI32::from([7, 7, 7]), one);
+        assert!(one.validity.is_none());
+
+        let one = ArrayI32::full(1, -1);
+        assert_eq!(ArrayI32::from([-1]), one);
+        assert_eq!(0, ArrayI32::full(0, 1).len());
+
+        let two = ArrayI32::nulls(10);
+        assert_eq!(ArrayI32::from_vec(vec![None; 10]), two);
+        assert!(two.ptr.is_none());
+        assert!(two.all_null());
+        assert_eq!(0, ArrayI32::nulls(0).len());
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(ArrayI32::from([0, 1, 2, 3]), ArrayI32::from_range(0..4));
+        assert_eq!(1000, ArrayI32::from_range(0..1000).len());
+        assert_eq!(ArrayI32::from([5]), ArrayI32::from_range(5..6));
+        assert!(ArrayI32::from_range(4..4).is_empty());
+
+        assert_eq!(
+            ArrayI32::from([0, 3, 6, 9]),
+            ArrayI32::from_step_range(0, 10, 3)
+        );
+        assert_eq!(
+            ArrayI32::from([10, 7, 4, 1]),
+            ArrayI32::from_step_range(10, 0, -3)
+        );
+        assert_eq!(ArrayI32::from([2, 0]), ArrayI32::from_step_range(2, -2, -2));
+        assert!(ArrayI32::from_step_range(0, 10, -1).is_empty());
+        assert_eq!(
+            ArrayI32::from([i32::MIN, -1, i32::MAX - 1]),
+            ArrayI32::from_step_range(i32::MIN, i32::MAX, i32::MAX)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to create a range with a step of 0")]
+    fn test_zero_step() {
+        ArrayI32::from_step_range(0, 10, 0);
+    }
+
+    #[test]
+    fn test_iota_arange() {
+        assert_eq!(ArrayI32::from([0, 1, 2, 3]), ArrayI32::iota(4));
+        assert!(ArrayI32::iota(0).is_empty());
+        assert!(ArrayI32::iota(5).validity.is_none());
+
+        assert_eq!(ArrayI32::from([1, 4, 7]), ArrayI32::arange(1, 9, 3));
+        assert_eq!(ArrayI32::from([3, 1, -1]), ArrayI32::arange(3, -2, -2));
+        assert!(ArrayI32::arange(4, 4, 1).is_empty());
+        assert_eq!(
+            ArrayI32::from([i32::MAX - 1]),
+            ArrayI32::arange(i32::MAX - 1, i32::MAX, i32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_mode() {
+        let one = ArrayI32::from([Some(4), None, Some(-2), Some(4), Some(-2), None, Some(9)]);
+
+        assert_eq!(Some(-2), one.mode());
+        assert_eq!((Some(-2), 2), one.mode_count());
+        assert_eq!((Some(7), 1), ArrayI32::from([7]).mode_count());
+        assert_eq!((None, 0), ArrayI32::nulls(3).mode_count());
+        assert_eq!(None, ArrayI32::from_vec(vec![]).mode());
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to count from 0 to 10 in steps of -1")]
+    fn test_arange_backwards() {
+        ArrayI32::arange(0, 10, -1);
+    }
+
+    #[test]
+    fn test_with_validity() {
+        let one = ArrayI32::from([Some(1), None, Some(3), Some(4)]);
+        let mask = ArrayBoolean::from([Some(true), Some(true), Some(false), None]);
+
+        let two = one.with_validity(&mask);
+        assert_eq!(ArrayI32::from([Some(1), Some(0), None, None]), two);
+        assert_eq!(2, two.null_count());
+        assert_eq!(ArrayI32::from([1, 0, 3, 4]), two.clear_validity());
+
+        let all = one.with_validity(&ArrayBoolean::from([false; 4]));
+        assert!(all.all_null());
+        assert_eq!(ArrayI32::nulls(4), all);
+        assert_eq!(ArrayI32::from([1, 0, 3, 4]), all.clear_validity());
+        assert_eq!(ArrayI32::from([0, 0]), ArrayI32::nulls(2).clear_validity());
+    }
+
+    #[test]
+    fn test_with_validity_no_buffer() {
+        let one = ArrayI32::from([5, 6, 7]);
+        assert!(one.validity().is_none());
+
+        let two = one.with_validity(&ArrayBoolean::from([true, false, true]));
+        assert_eq!(ArrayI32::from([Some(5), None, Some(7)]), two);
+        assert_eq!(1, two.null_count());
+        assert!(two.validity().is_some());
+        assert_eq!(one, two.clear_validity());
+
+        let three = one.with_validity(&ArrayBoolean::from([true; 3]));
+        assert!(three.validity().is_none());
+        assert_eq!(one, three);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to set a validity of length 2 on an array of length 3")]
+    fn test_with_validity_length() {
+        ArrayI32::from([1, 2, 3]).with_validity(&ArrayBoolean::from([true, false]));
+    }
+
+    #[test]
+    fn test_hash() {
+        use crate::MutablePrimitiveArray;
+        use std::collections::{hash_map::DefaultHasher, HashSet};
+
+        let hash = |array: &ArrayI32| {
+            let mut hasher = DefaultHasher::new();
+            array.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let one = ArrayI32::from_vec(vec![Some(1), None, Some(3)]);
+
+        let mut builder = MutablePrimitiveArray::<i32>::new();
+        builder.push(Some(1));
+        builder.push(None);
+        builder.push(Some(3));
+        let two = builder.freeze();
+
+        // Keeps 2 under the null
+        let three =
+            ArrayI32::from([1, 2, 3]).with_validity(&ArrayBoolean::from([true, false, true]));
+
+        assert_eq!(one, two);
+        assert_eq!(one, three);
+        assert_eq!(hash(&one), hash(&two));
+        assert_eq!(hash(&one), hash(&three));
+
+        let set = HashSet::from([one, ArrayI32::from([1, 3])]);
+        assert!(set.contains(&two));
+        assert!(set.contains(&three));
+        assert!(!set.contains(&ArrayI32::from([Some(1), Some(3), None])));
+    }
 }
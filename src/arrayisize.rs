@@ -1,8 +1,13 @@
 use std::alloc::{self, Layout};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{
+    integer_steps, valid_values, validity_from_mask, Array, BitmapBuffer, DataType, IntoIter, Iter,
+    ALIGNMENT,
+};
+use crate::ArrayBoolean;
 
 pub type ISize = Option<isize>;
 
@@ -11,8 +16,8 @@ pub type ISize = Option<isize>;
 pub struct ArrayISize {
     /// Pointer to the values buffer
     ptr: Option<NonNull<isize>>,
-    /// Pointer to the validity buffer
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls
+    validity: Option<BitmapBuffer>,
     /// The number of elements in the array
     len: usize,
     /// The number of nulls in the array
@@ -70,19 +75,27 @@ impl Array for ArrayISize {
             return true;
         }
 
-        let Some(val_ptr) = self.val_ptr else {
-            return false;
-        };
-
-        let byte_index = idx / 8;
-
-        let val_byte = unsafe { ptr::read(val_ptr.as_ptr().add(byte_index)) };
-        val_byte & (1 << (idx % 8)) == 0
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
     }
 
     fn all_null(&self) -> bool {
         self.nulls == self.len
     }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
+    }
+
+    fn values(&self) -> Option<&[Self::Data]> {
+        let ptr = self.ptr?;
+        Some(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len) })
+    }
 }
 
 impl IntoIterator for ArrayISize {
@@ -94,14 +107,75 @@ impl IntoIterator for ArrayISize {
     }
 }
 
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayISize {}
+unsafe impl Sync for ArrayISize {}
+
+impl Default for ArrayISize {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Drop for ArrayISize {
     fn drop(&mut self) {
         Self::dealloc_values(self.ptr, self.len());
-        Self::dealloc_validity(self.val_ptr, self.len())
     }
 }
 
 impl ArrayISize {
+    /// Creates an empty [`ArrayISize`].
+    pub const fn empty() -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    /// Returns a copy of the array whose valid elements are the true ones of
+    /// `validity`, nulls in `validity` counting as false.
+    ///
+    /// The values under the new nulls are kept, so
+    /// [`ArrayISize::clear_validity`] recovers them.
+    ///
+    /// Panics if `validity` and the array differ in length
+    pub fn with_validity(&self, validity: &ArrayBoolean) -> Self {
+        let (validity, nulls) = validity_from_mask(self.len, validity);
+
+        let mut array = self.with_values_buffer();
+        array.validity = validity;
+        array.nulls = nulls;
+        array
+    }
+
+    /// Returns a copy of the array with every element valid, exposing the
+    /// values under its nulls.
+    ///
+    /// Nulls the array was built with read as 0.
+    pub fn clear_validity(&self) -> Self {
+        let mut array = self.with_values_buffer();
+        array.validity = None;
+        array.nulls = 0;
+        array
+    }
+
+    /// Returns a clone of the array, allocating a zeroed values buffer if
+    /// it had none.
+    fn with_values_buffer(&self) -> Self {
+        let mut array = self.clone();
+
+        if array.ptr.is_none() && array.len != 0 {
+            let values_ptr = Self::allocate_values(array.len);
+            unsafe { ptr::write_bytes(values_ptr.as_ptr(), 0, array.len) };
+            array.ptr = Some(values_ptr);
+        }
+
+        array
+    }
+
     fn from_sized_iter<S>(sized: S) -> Self
     where
         S: Iterator<Item = ISize> + ExactSizeIterator,
@@ -109,68 +183,41 @@ impl ArrayISize {
         let len = sized.len();
 
         if len == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
+            return Self::empty();
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(len);
-
-        let mut val_byte = 0_u8;
-        let mut val_offset = 0;
+        let values_ptr = Self::allocate_values(len);
+        let mut validity = BitmapBuffer::new(len);
         let mut nulls = 0;
 
-        for (idx, value) in sized.into_iter().enumerate() {
+        for (idx, value) in sized.enumerate() {
             match value {
                 Some(value) => {
                     unsafe { ptr::write(values_ptr.as_ptr().add(idx), value) };
-                    let pos = 1 << (idx % 8);
-                    val_byte |= pos;
+                    validity.set_bit(idx);
                 }
                 None => {
+                    // Zeroed so the values buffer can be read as a slice
+                    unsafe { ptr::write(values_ptr.as_ptr().add(idx), 0) };
                     nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    val_byte &= pos;
                 }
             }
-
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte);
-                }
-
-                val_byte = 0_u8;
-                val_offset += 1;
-            }
-        }
-
-        // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
-            unsafe { ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte) };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
         }
 
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 nulls,
             };
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
             len,
             nulls,
         }
@@ -181,93 +228,100 @@ impl ArrayISize {
         Self::from_sized_iter(values.into_iter())
     }
 
-    /// Returns true if the validity buffers of `Self` and `Other` are equal.
-    ///
-    /// Assumes both buffers are equal in length.
-    fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
+    /// Creates an [`ArrayISize`] of length `len` with every element set to
+    /// `value`.
+    pub fn full(len: usize, value: isize) -> Self {
+        Self::from_fn(len, |_| value)
+    }
 
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
+    /// Creates an [`ArrayISize`] of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self::full(len, 0)
+    }
 
-                    if own != other {
-                        return false;
-                    }
-                }
-            }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
-        }
+    /// Creates an [`ArrayISize`] of `len` ones.
+    pub fn ones(len: usize) -> Self {
+        Self::full(len, 1)
+    }
 
-        true
+    /// Creates an [`ArrayISize`] of `0, 1, ..., n - 1`.
+    pub fn iota(n: usize) -> Self {
+        Self::from_fn(n, |idx| idx as isize)
     }
 
-    /// Returns true if the values of `Self` and `Other` are equal.
+    /// Creates an [`ArrayISize`] counting from `start` up to, but excluding,
+    /// `stop` in increments of `step`.
     ///
-    /// Assumes both buffers are equal in length.
-    fn compare_values(&self, other: &Self) -> bool {
-        let len = self.len;
+    /// Panics if `step` is 0 or leads away from `stop`.
+    pub fn arange(start: isize, stop: isize, step: isize) -> Self {
+        let len = integer_steps(start as i128, stop as i128, step as i128);
+
+        Self::from_fn(len, |idx| {
+            (start as i128 + idx as i128 * step as i128) as isize
+        })
+    }
+
+    /// Creates a null free [`ArrayISize`], writing the values straight into
+    /// the values buffer.
+    fn from_fn<F>(len: usize, f: F) -> Self
+    where
+        F: Fn(usize) -> isize,
+    {
+        if len == 0 {
+            return Self::empty();
+        }
+
+        let values_ptr = Self::allocate_values(len);
 
         for idx in 0..len {
-            let own = self.get(idx);
-            let other = other.get(idx);
+            unsafe { ptr::write(values_ptr.as_ptr().add(idx), f(idx)) };
+        }
 
-            if own != other {
-                return false;
-            }
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            len,
+            nulls: 0,
         }
+    }
 
-        true
+    /// Returns true if the validity buffers of `Self` and `Other` are equal.
+    fn compare_validity(&self, other: &Self) -> bool {
+        self.validity == other.validity
     }
 
-    /// Allocates both values and validity buffers
+    /// Returns true if the values of `Self` and `Other` are equal.
+    ///
+    /// Assumes both arrays have equal validity, so only the values of valid
+    /// elements are compared.
+    fn compare_values(&self, other: &Self) -> bool {
+        match (valid_values(self), valid_values(other)) {
+            (Some(own), Some(other)) => own.eq(other),
+            // Only all null arrays lack a values buffer
+            _ => true,
+        }
+    }
+
+    /// Allocates only the values buffer
     ///
     /// Must ensure len != 0
-    fn allocate(len: usize) -> (NonNull<isize>, NonNull<u8>) {
-        // Values
+    fn allocate_values(len: usize) -> NonNull<isize> {
         let values_size = len * std::mem::size_of::<isize>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayISize: values size overflowed isize::max");
 
         let values_ptr = unsafe { alloc::alloc(values_layout) };
 
-        let values_ptr = match NonNull::new(values_ptr as *mut isize) {
+        match NonNull::new(values_ptr as *mut isize) {
             Some(ptr) => ptr,
             None => alloc::handle_alloc_error(values_layout),
-        };
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayISize: validity size overflowed isize::max");
-
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
-
-        (values_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayISize drop: validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-        unsafe { alloc::dealloc(ptr, validity_layout) };
+        }
     }
 
     fn dealloc_values(ptr: Option<NonNull<isize>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let values_size = len * std::mem::size_of::<isize>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayISize drop: values size overflowed isize::max");
         let ptr = ptr.as_ptr() as *mut u8;
 
@@ -277,43 +331,15 @@ impl ArrayISize {
 
 impl Clone for ArrayISize {
     fn clone(&self) -> Self {
-        if self.len() == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
-        }
-
-        let (values_ptr, validity_ptr) = Self::allocate(self.len());
-
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len());
-                None
-            }
-        };
-
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.len());
-                None
-            }
-        };
+        let values_ptr = self.ptr.map(|ptr| {
+            let values_ptr = Self::allocate_values(self.len());
+            unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
+            values_ptr
+        });
 
         Self {
             ptr: values_ptr,
-            val_ptr: validity_ptr,
+            validity: self.validity.clone(),
             len: self.len(),
             nulls: self.nulls,
         }
@@ -370,12 +396,30 @@ impl PartialEq for ArrayISize {
 
 impl Eq for ArrayISize {}
 
+/// Hashes the elements, so arrays equal regardless of what lies under their
+/// nulls hash the same.
+impl Hash for ArrayISize {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
 impl From<ArrayISize> for Vec<Option<isize>> {
     fn from(value: ArrayISize) -> Self {
         value.into_iter().collect()
     }
 }
 
+impl From<&ArrayISize> for Vec<Option<isize>> {
+    fn from(value: &ArrayISize) -> Self {
+        value.copied_iter().collect()
+    }
+}
+
 impl From<Vec<isize>> for ArrayISize {
     fn from(value: Vec<isize>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -412,7 +456,20 @@ impl<const N: usize> From<[ISize; N]> for ArrayISize {
     }
 }
 
+impl From<&[isize]> for ArrayISize {
+    fn from(value: &[isize]) -> Self {
+        Self::from_sized_iter(value.iter().copied().map(Some))
+    }
+}
+
+impl From<&[ISize]> for ArrayISize {
+    fn from(value: &[ISize]) -> Self {
+        Self::from_sized_iter(value.iter().copied())
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_conversion)]
 mod test {
     use super::*;
 
@@ -1,9 +1,10 @@
 use std::alloc::{self, Layout};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{Array, BitmapBuffer, DataType, IntoIter, Iter, ALIGNMENT};
 
 pub type Text = Option<String>;
 
@@ -12,8 +13,8 @@ pub type Text = Option<String>;
 pub struct ArrayText {
     /// Pointer to the values buffer.
     ptr: Option<NonNull<u8>>,
-    /// Pointer to the validity buffer.
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls.
+    validity: Option<BitmapBuffer>,
     /// Pointer to the offsets buffer.
     offsets_ptr: Option<NonNull<u64>>,
     /// The number of elements in the array.
@@ -25,10 +26,11 @@ pub struct ArrayText {
 }
 
 impl ArrayText {
-    fn empty() -> Self {
+    /// Creates an empty [`ArrayText`].
+    pub const fn empty() -> Self {
         Self {
             ptr: None,
-            val_ptr: None,
+            validity: None,
             offsets_ptr: None,
             len: 0,
             str_len: 0,
@@ -66,22 +68,20 @@ impl ArrayText {
             collected.push(text)
         }
 
-        if str_len == 0 {
+        if collected.iter().all(Option::is_none) {
             // Filled with nulls
             return Self {
                 ptr: None,
                 offsets_ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 str_len: 0,
                 nulls: len,
             };
         }
 
-        let (values_ptr, offsets_ptr, validity_ptr) = Self::allocate(len, str_len);
-
-        let mut val_byte = 0_u8;
-        let mut val_offset = 0;
+        let (values_ptr, offsets_ptr) = Self::allocate(len, str_len);
+        let mut validity = BitmapBuffer::new(len);
         let mut nulls = 0;
         let mut offset = 0;
 
@@ -99,46 +99,22 @@ impl ArrayText {
                     };
 
                     offset += text.len() as u64;
-                    let pos = 1 << (idx % 8);
-                    val_byte |= pos;
-                }
-                None => {
-                    nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    val_byte &= pos;
+                    validity.set_bit(idx);
                 }
-            }
-
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte);
-                }
-
-                val_byte = 0_u8;
-                val_offset += 1;
+                None => nulls += 1,
             }
         }
 
         unsafe { ptr::write(offsets_ptr.as_ptr().add(len), offset) };
 
-        // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
-            unsafe { ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte) };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
-        }
-
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), str_len);
             Self::dealloc_offsets(Some(offsets_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
                 offsets_ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 str_len: 0,
                 nulls,
@@ -146,13 +122,9 @@ impl ArrayText {
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
-            offsets_ptr: if nulls == len {
-                None
-            } else {
-                Some(offsets_ptr)
-            },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
+            offsets_ptr: Some(offsets_ptr),
             len,
             str_len,
             nulls,
@@ -164,29 +136,45 @@ impl ArrayText {
         Self::from_sized_iter(values.into_iter())
     }
 
-    /// Returns true if the validity buffers of `Self` and `Other` are equal.
-    ///
-    /// Assumes both buffers are equal in length.
-    fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
-
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
+    /// Creates an [`ArrayText`] of length `len` with every element set to
+    /// `value`.
+    pub fn full(len: usize, value: &str) -> Self {
+        if len == 0 {
+            return Self::empty();
+        }
 
-                    if own != other {
-                        return false;
-                    }
-                }
+        let width = value.len();
+        let str_len = width
+            .checked_mul(len)
+            .expect("ArrayText: Values size overflowed usize::max");
+        let (values_ptr, offsets_ptr) = Self::allocate(len, str_len);
+
+        for idx in 0..len {
+            unsafe {
+                ptr::write(offsets_ptr.as_ptr().add(idx), (idx * width) as u64);
+                ptr::copy_nonoverlapping(
+                    value.as_ptr(),
+                    values_ptr.as_ptr().add(idx * width),
+                    width,
+                );
             }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
         }
 
-        true
+        unsafe { ptr::write(offsets_ptr.as_ptr().add(len), str_len as u64) };
+
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            offsets_ptr: Some(offsets_ptr),
+            len,
+            str_len,
+            nulls: 0,
+        }
+    }
+
+    /// Returns true if the validity buffers of `Self` and `Other` are equal.
+    fn compare_validity(&self, other: &Self) -> bool {
+        self.validity == other.validity
     }
 
     /// Returns true if the offsets buffer of `Self` and `other` are equal.
@@ -267,36 +255,20 @@ impl ArrayText {
             return true;
         }
 
-        let Some(ptr) = self.val_ptr else {
-            return false;
-        };
-        let byte_index = idx / 8;
-
-        let byte = unsafe { ptr::read(ptr.as_ptr().add(byte_index)) };
-
-        byte & (1 << (idx % 8)) == 0
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
     }
 
-    /// Allocates the required buffers
+    /// Allocates the values and offsets buffers
     ///
     /// Must ensure len != 0 and str_len != 0
-    fn allocate(len: usize, str_len: usize) -> (NonNull<u8>, NonNull<u64>, NonNull<u8>) {
+    fn allocate(len: usize, str_len: usize) -> (NonNull<u8>, NonNull<u64>) {
         assert!(len != 0, "ArrayText: Tried to allocate 0 sized memory");
-        assert!(str_len != 0, "ArrayText: Tried to allocate 0 sized memory");
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayText: validity size overflowed isize::max");
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
 
         // Offsets
         let offset_size = (len + 1) * std::mem::size_of::<u64>();
-        let offset_layout = Layout::from_size_align(offset_size, 8)
+        let offset_layout = Layout::from_size_align(offset_size, ALIGNMENT)
             .expect("ArrayText: Offsets size overflowed isize::max");
         let offsets_ptr = unsafe { alloc::alloc(offset_layout) };
         let offsets_ptr = match NonNull::new(offsets_ptr as *mut u64) {
@@ -304,9 +276,9 @@ impl ArrayText {
             None => alloc::handle_alloc_error(offset_layout),
         };
 
-        // Data
-        let values_size = str_len * std::mem::size_of::<u8>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        // Data, never empty so that arrays of empty strings still own a buffer
+        let values_size = str_len.max(1) * std::mem::size_of::<u8>();
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayText: Values size overflowed isize::max");
         let values_ptr = unsafe { alloc::alloc(values_layout) };
         let values_ptr = match NonNull::new(values_ptr) {
@@ -314,23 +286,13 @@ impl ArrayText {
             None => alloc::handle_alloc_error(values_layout),
         };
 
-        (values_ptr, offsets_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayText drop: Validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-
-        unsafe { alloc::dealloc(ptr, validity_layout) }
+        (values_ptr, offsets_ptr)
     }
 
     fn dealloc_values(ptr: Option<NonNull<u8>>, str_len: usize) {
         let Some(ptr) = ptr else { return };
-        let size = str_len * std::mem::size_of::<u8>();
-        let layout = Layout::from_size_align(size, 8)
+        let size = str_len.max(1) * std::mem::size_of::<u8>();
+        let layout = Layout::from_size_align(size, ALIGNMENT)
             .expect("ArrayText drop: Values size overflowed isize::max");
         let ptr = ptr.as_ptr();
 
@@ -341,7 +303,7 @@ impl ArrayText {
     fn dealloc_offsets(ptr: Option<NonNull<u64>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let size = (len + 1) * std::mem::size_of::<u64>();
-        let layout = Layout::from_size_align(size, 8)
+        let layout = Layout::from_size_align(size, ALIGNMENT)
             .expect("ArrayText drop: Offsets size overflowed isize::max");
         let ptr = ptr.as_ptr() as *mut u8;
 
@@ -351,8 +313,10 @@ impl ArrayText {
 
 impl Array for ArrayText {
     type Data = String;
-    type Ref<'a> = &'a str
-        where Self: 'a;
+    type Ref<'a>
+        = &'a str
+    where
+        Self: 'a;
 
     fn new<I>(values: I) -> Self
     where
@@ -387,61 +351,53 @@ impl Array for ArrayText {
     fn all_null(&self) -> bool {
         self.nulls == self.len
     }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
+    }
+}
+
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayText {}
+unsafe impl Sync for ArrayText {}
+
+impl Default for ArrayText {
+    fn default() -> Self {
+        Self::empty()
+    }
 }
 
 impl Drop for ArrayText {
     fn drop(&mut self) {
         Self::dealloc_offsets(self.offsets_ptr, self.len);
         Self::dealloc_values(self.ptr, self.str_len);
-        Self::dealloc_validity(self.val_ptr, self.len);
     }
 }
 
 impl Clone for ArrayText {
     fn clone(&self) -> Self {
-        if self.len == 0 || self.str_len == 0 {
-            return Self::empty();
-        }
-
-        let (values_ptr, offset_ptr, validity_ptr) = Self::allocate(self.len, self.str_len);
-
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.str_len) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.str_len);
-                None
-            }
-        };
+        let (ptr, offsets_ptr) = match (self.ptr, self.offsets_ptr) {
+            (Some(ptr), Some(offsets)) => {
+                let (values_ptr, offsets_ptr) = Self::allocate(self.len, self.str_len);
 
-        let offsets_ptr = match self.offsets_ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), offset_ptr.as_ptr(), self.len + 1) };
-                Some(offset_ptr)
-            }
-            None => {
-                Self::dealloc_offsets(Some(offset_ptr), self.len);
-                None
-            }
-        };
+                unsafe {
+                    ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.str_len);
+                    ptr::copy(offsets.as_ptr(), offsets_ptr.as_ptr(), self.len + 1);
+                };
 
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len);
-                None
+                (Some(values_ptr), Some(offsets_ptr))
             }
+            _ => (None, None),
         };
 
         Self {
-            ptr: values_ptr,
-            val_ptr: validity_ptr,
+            ptr,
+            validity: self.validity.clone(),
             offsets_ptr,
             len: self.len,
             str_len: self.str_len,
@@ -482,6 +438,17 @@ impl PartialEq for ArrayText {
 
 impl Eq for ArrayText {}
 
+/// Hashes the strings in turn, so equal arrays hash the same.
+impl Hash for ArrayText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
 impl IntoIterator for ArrayText {
     type Item = Option<String>;
     type IntoIter = IntoIter<Self>;
@@ -517,6 +484,12 @@ impl From<ArrayText> for Vec<Option<String>> {
     }
 }
 
+impl From<&ArrayText> for Vec<Option<String>> {
+    fn from(value: &ArrayText) -> Self {
+        value.iter().map(|text| text.map(Into::into)).collect()
+    }
+}
+
 impl From<Vec<String>> for ArrayText {
     fn from(value: Vec<String>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -571,6 +544,30 @@ impl<const N: usize> From<[Text; N]> for ArrayText {
     }
 }
 
+impl From<&[String]> for ArrayText {
+    fn from(value: &[String]) -> Self {
+        Self::from_sized_iter(value.iter().cloned().map(Some))
+    }
+}
+
+impl From<&[&str]> for ArrayText {
+    fn from(value: &[&str]) -> Self {
+        Self::from_sized_iter(value.iter().map(|text| Some(Into::<String>::into(*text))))
+    }
+}
+
+impl From<&[Option<&str>]> for ArrayText {
+    fn from(value: &[Option<&str>]) -> Self {
+        Self::from_sized_iter(value.iter().map(|text| text.map(Into::into)))
+    }
+}
+
+impl From<&[Text]> for ArrayText {
+    fn from(value: &[Text]) -> Self {
+        Self::from_sized_iter(value.iter().cloned())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -714,6 +711,8 @@ mod tests {
 
         assert!(one.check_null(4));
 
+        assert_eq!(one, one.clone());
+
         let mut iter = one.into_iter();
 
         assert_eq!(None, iter.next().unwrap());
@@ -728,5 +727,47 @@ mod tests {
 
         assert!(one.is_empty());
         assert_eq!(0, one.len());
+
+        let one = ArrayText::from([Some("".into()), None, Some("".into())]);
+
+        assert_eq!(1, one.null_count());
+        assert_eq!(Some(""), one.get_ref(0));
+        assert_eq!(one, one.clone());
+    }
+
+    #[test]
+    fn test_alignment() {
+        let one = ArrayText::from([Some("a".into()), None, Some("bcd".into())]);
+
+        assert_eq!(0, one.ptr.unwrap().as_ptr() as usize % 64);
+        assert_eq!(0, one.offsets_ptr.unwrap().as_ptr() as usize % 64);
+        assert_eq!(
+            0,
+            one.validity.as_ref().unwrap().as_bytes().as_ptr() as usize % 64
+        );
+    }
+
+    #[test]
+    fn test_full() {
+        let one = ArrayText::full(3, "né");
+
+        assert_eq!(ArrayText::from(["né", "né", "né"]), one);
+        assert_eq!(9, one.str_len);
+        assert_eq!(ArrayText::from(["", ""]), ArrayText::full(2, ""));
+        assert!(ArrayText::full(0, "a").is_empty());
+    }
+
+    #[test]
+    fn test_from_slices() {
+        let words = ["a", "bc", "d"];
+        assert_eq!(ArrayText::from(["bc", "d"]), ArrayText::from(&words[1..]));
+
+        let options = vec![Some("a"), None];
+        let one = ArrayText::from(options.as_slice());
+        assert_eq!(ArrayText::new([Some("a".into()), None]), one);
+        assert_eq!(vec![Some("a".to_string()), None], Vec::from(&one));
+
+        let owned = vec![Some("x".to_string()), None];
+        assert_eq!(owned, Vec::from(&ArrayText::from(&owned[..])));
     }
 }
@@ -1,8 +1,13 @@
 use std::alloc::{self, Layout};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::ptr::{self, NonNull};
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::utils::{
+    integer_steps, valid_values, validity_from_mask, Array, BitmapBuffer, DataType, IntoIter, Iter,
+    ALIGNMENT,
+};
+use crate::ArrayBoolean;
 
 pub type U32 = Option<u32>;
 
@@ -11,8 +16,8 @@ pub type U32 = Option<u32>;
 pub struct ArrayU32 {
     /// Pointer to the values buffer
     ptr: Option<NonNull<u32>>,
-    /// Pointer to the validity buffer
-    val_ptr: Option<NonNull<u8>>,
+    /// The validity buffer, None if the array has no nulls
+    validity: Option<BitmapBuffer>,
     /// The number of elements in the array
     len: usize,
     /// The number of nulls in the array
@@ -20,6 +25,57 @@ pub struct ArrayU32 {
 }
 
 impl ArrayU32 {
+    /// Creates an empty [`ArrayU32`].
+    pub const fn empty() -> Self {
+        Self {
+            ptr: None,
+            validity: None,
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    /// Returns a copy of the array whose valid elements are the true ones of
+    /// `validity`, nulls in `validity` counting as false.
+    ///
+    /// The values under the new nulls are kept, so
+    /// [`ArrayU32::clear_validity`] recovers them.
+    ///
+    /// Panics if `validity` and the array differ in length
+    pub fn with_validity(&self, validity: &ArrayBoolean) -> Self {
+        let (validity, nulls) = validity_from_mask(self.len, validity);
+
+        let mut array = self.with_values_buffer();
+        array.validity = validity;
+        array.nulls = nulls;
+        array
+    }
+
+    /// Returns a copy of the array with every element valid, exposing the
+    /// values under its nulls.
+    ///
+    /// Nulls the array was built with read as 0.
+    pub fn clear_validity(&self) -> Self {
+        let mut array = self.with_values_buffer();
+        array.validity = None;
+        array.nulls = 0;
+        array
+    }
+
+    /// Returns a clone of the array, allocating a zeroed values buffer if
+    /// it had none.
+    fn with_values_buffer(&self) -> Self {
+        let mut array = self.clone();
+
+        if array.ptr.is_none() && array.len != 0 {
+            let values_ptr = Self::allocate_values(array.len);
+            unsafe { ptr::write_bytes(values_ptr.as_ptr(), 0, array.len) };
+            array.ptr = Some(values_ptr);
+        }
+
+        array
+    }
+
     fn from_sized_iter<S>(sized: S) -> Self
     where
         S: Iterator<Item = U32> + ExactSizeIterator,
@@ -27,68 +83,41 @@ impl ArrayU32 {
         let len = sized.len();
 
         if len == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
+            return Self::empty();
         }
 
-        let (values_ptr, validity_ptr) = Self::allocate(len);
-
-        let mut val_byte = 0_u8;
-        let mut val_offset = 0;
+        let values_ptr = Self::allocate_values(len);
+        let mut validity = BitmapBuffer::new(len);
         let mut nulls = 0;
 
-        for (idx, value) in sized.into_iter().enumerate() {
+        for (idx, value) in sized.enumerate() {
             match value {
                 Some(value) => {
                     unsafe { ptr::write(values_ptr.as_ptr().add(idx), value) };
-                    let pos = 1 << (idx % 8);
-                    val_byte |= pos;
+                    validity.set_bit(idx);
                 }
                 None => {
+                    // Zeroed so the values buffer can be read as a slice
+                    unsafe { ptr::write(values_ptr.as_ptr().add(idx), 0) };
                     nulls += 1;
-                    let pos = !(1 << (idx % 8));
-                    val_byte &= pos;
                 }
             }
-
-            if (idx + 1) % 8 == 0 {
-                unsafe {
-                    ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte);
-                }
-
-                val_byte = 0_u8;
-                val_offset += 1;
-            }
-        }
-
-        // Condition in for loop wouldn't have been triggered for the write
-        if len % 8 != 0 {
-            unsafe { ptr::write(validity_ptr.as_ptr().add(val_offset), val_byte) };
-        }
-
-        if nulls == 0 {
-            Self::dealloc_validity(Some(validity_ptr), len);
         }
 
         if nulls == len {
             Self::dealloc_values(Some(values_ptr), len);
-            Self::dealloc_validity(Some(validity_ptr), len);
 
             return Self {
                 ptr: None,
-                val_ptr: None,
+                validity: None,
                 len,
                 nulls,
             };
         }
 
         Self {
-            ptr: if nulls == len { None } else { Some(values_ptr) },
-            val_ptr: if nulls == 0 { None } else { Some(validity_ptr) },
+            ptr: Some(values_ptr),
+            validity: (nulls != 0).then_some(validity),
             len,
             nulls,
         }
@@ -99,93 +128,107 @@ impl ArrayU32 {
         Self::from_sized_iter(values.into_iter())
     }
 
-    /// Returns true if the validity buffers of `Self` and `Other` are equal.
-    ///
-    /// Assumes both buffers are equal in length.
-    fn compare_validity(&self, other: &Self) -> bool {
-        let buffer_len = (self.len + 7) / 8;
+    /// Creates an [`ArrayU32`] of length `len` with every element set to
+    /// `value`.
+    pub fn full(len: usize, value: u32) -> Self {
+        Self::from_fn(len, |_| value)
+    }
 
-        match (self.val_ptr, other.val_ptr) {
-            (Some(own), Some(other)) => {
-                for offset in 0..buffer_len {
-                    let own = unsafe { ptr::read(own.as_ptr().add(offset)) };
-                    let other = unsafe { ptr::read(other.as_ptr().add(offset)) };
+    /// Creates an [`ArrayU32`] of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self::full(len, 0)
+    }
 
-                    if own != other {
-                        return false;
-                    }
-                }
-            }
-            (None, Some(_)) => return false,
-            (Some(_), None) => return false,
-            (None, None) => return true,
-        }
+    /// Creates an [`ArrayU32`] of `len` ones.
+    pub fn ones(len: usize) -> Self {
+        Self::full(len, 1)
+    }
 
-        true
+    /// Creates an [`ArrayU32`] of `0, 1, ..., n - 1`.
+    ///
+    /// Panics if `n - 1` does not fit in a `u32`.
+    pub fn iota(n: usize) -> Self {
+        assert!(
+            n <= u32::MAX as usize + 1,
+            "Tried to count up to {n} in an ArrayU32"
+        );
+
+        Self::from_fn(n, |idx| idx as u32)
     }
 
-    /// Returns true if the values of `Self` and `Other` are equal.
+    /// Creates an [`ArrayU32`] counting from `start` up to, but excluding,
+    /// `stop` in increments of `step`.
     ///
-    /// Assumes both buffers are equal in length.
-    fn compare_values(&self, other: &Self) -> bool {
-        let len = self.len;
+    /// Panics if `step` is 0 or leads away from `stop`.
+    pub fn arange(start: u32, stop: u32, step: u32) -> Self {
+        let len = integer_steps(start as i128, stop as i128, step as i128);
+
+        Self::from_fn(len, |idx| {
+            (start as i128 + idx as i128 * step as i128) as u32
+        })
+    }
+
+    /// Creates a null free [`ArrayU32`], writing the values straight into
+    /// the values buffer.
+    fn from_fn<F>(len: usize, f: F) -> Self
+    where
+        F: Fn(usize) -> u32,
+    {
+        if len == 0 {
+            return Self::empty();
+        }
+
+        let values_ptr = Self::allocate_values(len);
 
         for idx in 0..len {
-            let own = self.get(idx);
-            let other = other.get(idx);
+            unsafe { ptr::write(values_ptr.as_ptr().add(idx), f(idx)) };
+        }
 
-            if own != other {
-                return false;
-            }
+        Self {
+            ptr: Some(values_ptr),
+            validity: None,
+            len,
+            nulls: 0,
         }
+    }
 
-        true
+    /// Returns true if the validity buffers of `Self` and `Other` are equal.
+    fn compare_validity(&self, other: &Self) -> bool {
+        self.validity == other.validity
     }
 
-    /// Allocates both values and validity buffers
+    /// Returns true if the values of `Self` and `Other` are equal.
+    ///
+    /// Assumes both arrays have equal validity, so only the values of valid
+    /// elements are compared.
+    fn compare_values(&self, other: &Self) -> bool {
+        match (valid_values(self), valid_values(other)) {
+            (Some(own), Some(other)) => own.eq(other),
+            // Only all null arrays lack a values buffer
+            _ => true,
+        }
+    }
+
+    /// Allocates only the values buffer
     ///
     /// Must ensure len != 0
-    fn allocate(len: usize) -> (NonNull<u32>, NonNull<u8>) {
-        // Values
+    fn allocate_values(len: usize) -> NonNull<u32> {
         let values_size = len * std::mem::size_of::<u32>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayU32: values size overflowed isize::max");
 
         let values_ptr = unsafe { alloc::alloc(values_layout) };
 
-        let values_ptr = match NonNull::new(values_ptr as *mut u32) {
+        match NonNull::new(values_ptr as *mut u32) {
             Some(ptr) => ptr,
             None => alloc::handle_alloc_error(values_layout),
-        };
-
-        // Validity
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayU32: validity size overflowed isize::max");
-
-        let validity_ptr = unsafe { alloc::alloc(validity_layout) };
-
-        let validity_ptr = match NonNull::new(validity_ptr) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(validity_layout),
-        };
-
-        (values_ptr, validity_ptr)
-    }
-
-    fn dealloc_validity(ptr: Option<NonNull<u8>>, len: usize) {
-        let Some(val_ptr) = ptr else { return };
-        let validity_size = (len + 7) / 8;
-        let validity_layout = Layout::from_size_align(validity_size, 8)
-            .expect("ArrayU32 drop: validity size overflowed isize::max");
-        let ptr = val_ptr.as_ptr();
-        unsafe { alloc::dealloc(ptr, validity_layout) };
+        }
     }
 
     fn dealloc_values(ptr: Option<NonNull<u32>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let values_size = len * std::mem::size_of::<u32>();
-        let values_layout = Layout::from_size_align(values_size, 8)
+        let values_layout = Layout::from_size_align(values_size, ALIGNMENT)
             .expect("ArrayU32 drop: values size overflowed isize::max");
         let ptr = ptr.as_ptr() as *mut u8;
 
@@ -244,19 +287,27 @@ impl Array for ArrayU32 {
             return true;
         }
 
-        let Some(val_ptr) = self.val_ptr else {
-            return false;
-        };
-
-        let byte_index = idx / 8;
-
-        let val_byte = unsafe { ptr::read(val_ptr.as_ptr().add(byte_index)) };
-        val_byte & (1 << (idx % 8)) == 0
+        self.validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
     }
 
     fn all_null(&self) -> bool {
         self.nulls == self.len
     }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        self.validity.as_ref()
+    }
+
+    fn values(&self) -> Option<&[Self::Data]> {
+        let ptr = self.ptr?;
+        Some(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len) })
+    }
 }
 
 impl IntoIterator for ArrayU32 {
@@ -268,52 +319,34 @@ impl IntoIterator for ArrayU32 {
     }
 }
 
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for ArrayU32 {}
+unsafe impl Sync for ArrayU32 {}
+
+impl Default for ArrayU32 {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Drop for ArrayU32 {
     fn drop(&mut self) {
         Self::dealloc_values(self.ptr, self.len());
-        Self::dealloc_validity(self.val_ptr, self.len())
     }
 }
 
 impl Clone for ArrayU32 {
     fn clone(&self) -> Self {
-        if self.len() == 0 {
-            return Self {
-                ptr: None,
-                val_ptr: None,
-                len: 0,
-                nulls: 0,
-            };
-        }
-
-        let (values_ptr, validity_ptr) = Self::allocate(self.len());
-
-        let validity_ptr = match self.val_ptr {
-            Some(ptr) => {
-                let count = (self.len() + 7) / 8;
-                unsafe { ptr::copy(ptr.as_ptr(), validity_ptr.as_ptr(), count) };
-                Some(validity_ptr)
-            }
-            None => {
-                Self::dealloc_validity(Some(validity_ptr), self.len());
-                None
-            }
-        };
-
-        let values_ptr = match self.ptr {
-            Some(ptr) => {
-                unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
-                Some(values_ptr)
-            }
-            None => {
-                Self::dealloc_values(Some(values_ptr), self.len());
-                None
-            }
-        };
+        let values_ptr = self.ptr.map(|ptr| {
+            let values_ptr = Self::allocate_values(self.len());
+            unsafe { ptr::copy(ptr.as_ptr(), values_ptr.as_ptr(), self.len()) };
+            values_ptr
+        });
 
         Self {
             ptr: values_ptr,
-            val_ptr: validity_ptr,
+            validity: self.validity.clone(),
             len: self.len(),
             nulls: self.nulls,
         }
@@ -370,12 +403,30 @@ impl PartialEq for ArrayU32 {
 
 impl Eq for ArrayU32 {}
 
+/// Hashes the elements, so arrays equal regardless of what lies under their
+/// nulls hash the same.
+impl Hash for ArrayU32 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
 impl From<ArrayU32> for Vec<Option<u32>> {
     fn from(value: ArrayU32) -> Self {
         value.into_iter().collect()
     }
 }
 
+impl From<&ArrayU32> for Vec<Option<u32>> {
+    fn from(value: &ArrayU32) -> Self {
+        value.copied_iter().collect()
+    }
+}
+
 impl From<Vec<u32>> for ArrayU32 {
     fn from(value: Vec<u32>) -> Self {
         Self::from_sized_iter(value.into_iter().map(Some))
@@ -412,7 +463,20 @@ impl<const N: usize> From<[U32; N]> for ArrayU32 {
     }
 }
 
+impl From<&[u32]> for ArrayU32 {
+    fn from(value: &[u32]) -> Self {
+        Self::from_sized_iter(value.iter().copied().map(Some))
+    }
+}
+
+impl From<&[U32]> for ArrayU32 {
+    fn from(value: &[U32]) -> Self {
+        Self::from_sized_iter(value.iter().copied())
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_conversion, clippy::map_identity)]
 mod test {
     use super::*;
 
@@ -529,4 +593,15 @@ mod test {
 
         assert_eq!(0, one.len());
     }
+
+    #[test]
+    fn test_full_zeros_ones() {
+        assert_eq!(ArrayU32::from([4, 4]), ArrayU32::full(2, 4));
+        assert_eq!(ArrayU32::from([0, 0, 0]), ArrayU32::zeros(3));
+        assert_eq!(ArrayU32::from([1]), ArrayU32::ones(1));
+        assert!(ArrayU32::ones(0).is_empty());
+        assert!(ArrayU32::zeros(5).validity.is_none());
+
+        assert_eq!(crate::ArrayF32::from([1.0, 1.0]), crate::ArrayF32::ones(2));
+    }
 }
@@ -0,0 +1,320 @@
+use crate::arithmetic;
+use crate::utils::{Array, ArrayError, Numeric};
+
+/// Logical array made up of several arrays, its chunks, laid end to end.
+///
+/// Appending a chunk never copies the elements already held, unlike
+/// [`Array::concat`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkedArray<A: Array> {
+    chunks: Vec<A>,
+    /// The index of the first element of every chunk followed by the total
+    /// length, so chunk `i` spans `offsets[i]..offsets[i + 1]`.
+    offsets: Vec<usize>,
+}
+
+impl<A: Array> ChunkedArray<A> {
+    pub fn new(chunks: Vec<A>) -> Self {
+        let mut own = Self {
+            chunks: Vec::with_capacity(chunks.len()),
+            offsets: vec![0],
+        };
+
+        for chunk in chunks {
+            own.push_chunk(chunk);
+        }
+
+        own
+    }
+
+    /// Appends `chunk` onto the end of the array.
+    pub fn push_chunk(&mut self, chunk: A) {
+        self.offsets.push(self.len() + chunk.len());
+        self.chunks.push(chunk);
+    }
+
+    pub fn chunks(&self) -> &[A] {
+        &self.chunks
+    }
+
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns the total number of elements across all chunks.
+    pub fn len(&self) -> usize {
+        self.offsets[self.offsets.len() - 1]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of nulls across all chunks.
+    pub fn null_count(&self) -> usize {
+        self.chunks.iter().map(Array::null_count).sum()
+    }
+
+    /// Returns the chunk holding `idx` and the position of `idx` within it.
+    fn locate(&self, idx: usize) -> Option<(&A, usize)> {
+        if idx >= self.len() {
+            return None;
+        }
+
+        // Empty chunks share their offset with the next chunk so the last
+        // chunk starting at or before `idx` is always the one holding it.
+        let chunk = self.offsets.partition_point(|offset| *offset <= idx) - 1;
+
+        Some((&self.chunks[chunk], idx - self.offsets[chunk]))
+    }
+
+    /// Returns a copy of the value at `idx` if any.
+    ///
+    /// Returns None if `idx` is out of range
+    pub fn get(&self, idx: usize) -> Option<A::Data> {
+        let (chunk, idx) = self.locate(idx)?;
+        chunk.get(idx)
+    }
+
+    /// Returns a reference to the value at `idx` if any.
+    ///
+    /// Returns None if `idx` is out of range
+    pub fn get_ref(&self, idx: usize) -> Option<A::Ref<'_>> {
+        let (chunk, idx) = self.locate(idx)?;
+        chunk.get_ref(idx)
+    }
+
+    /// Returns true if the element at `idx` is null.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn check_null(&self, idx: usize) -> bool {
+        let (chunk, idx) = self.locate(idx).unwrap_or_else(|| {
+            panic!("Tried to index {} when array length is {}", idx, self.len())
+        });
+
+        chunk.check_null(idx)
+    }
+
+    /// Returns an iterator over the values of every chunk in order.
+    pub fn iter(&self) -> impl Iterator<Item = Option<A::Ref<'_>>> + '_ {
+        self.chunks.iter().flat_map(Array::iter)
+    }
+
+    /// Returns a copy of the `len` elements starting at `offset`.
+    ///
+    /// Chunks wholly outside the range are dropped while those straddling
+    /// its ends are trimmed. Errors if the range goes past the end of the
+    /// array.
+    pub fn slice(&self, offset: usize, len: usize) -> Result<Self, ArrayError> {
+        let end = offset.saturating_add(len);
+
+        if end > self.len() {
+            return Err(ArrayError::IndexOutOfBounds {
+                idx: end,
+                len: self.len(),
+            });
+        }
+
+        let mut sliced = Self::new(vec![]);
+
+        for (chunk, start) in self.chunks.iter().zip(&self.offsets) {
+            let from = offset.max(*start) - start;
+            let to = end.min(start + chunk.len()).saturating_sub(*start);
+
+            if from >= to {
+                continue;
+            }
+
+            sliced.push_chunk(A::new((from..to).map(|idx| chunk.get(idx))));
+        }
+
+        Ok(sliced)
+    }
+
+    /// Compacts every chunk into a single array.
+    ///
+    /// A lone chunk is cloned as is.
+    pub fn rechunk(&self) -> A {
+        if let [chunk] = self.chunks.as_slice() {
+            return chunk.clone();
+        }
+
+        let values = self
+            .chunks
+            .iter()
+            .flat_map(|chunk| (0..chunk.len()).map(|idx| chunk.get(idx)))
+            .collect::<Vec<_>>();
+
+        A::new(values)
+    }
+}
+
+impl<A> ChunkedArray<A>
+where
+    A: Array,
+    A::Data: Numeric,
+{
+    /// Returns the sum of the non-null elements, or None if every element is
+    /// null.
+    ///
+    /// See [`arithmetic::sum`].
+    pub fn sum(&self) -> Option<A::Data> {
+        self.chunks
+            .iter()
+            .filter_map(arithmetic::sum)
+            .reduce(Numeric::add_wrapping)
+    }
+
+    /// Returns the smallest non-null element, or None if every element is
+    /// null.
+    ///
+    /// See [`arithmetic::min`].
+    pub fn min(&self) -> Option<A::Data> {
+        self.chunks
+            .iter()
+            .filter_map(arithmetic::min)
+            .reduce(|acc, val| if val < acc { val } else { acc })
+    }
+
+    /// Returns the largest non-null element, or None if every element is
+    /// null.
+    ///
+    /// See [`arithmetic::max`].
+    pub fn max(&self) -> Option<A::Data> {
+        self.chunks
+            .iter()
+            .filter_map(arithmetic::max)
+            .reduce(|acc, val| if val > acc { val } else { acc })
+    }
+}
+
+impl<A: Array> From<Vec<A>> for ChunkedArray<A> {
+    fn from(value: Vec<A>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<A: Array> From<A> for ChunkedArray<A> {
+    fn from(value: A) -> Self {
+        Self::new(vec![value])
+    }
+}
+
+impl<A: Array> Extend<A> for ChunkedArray<A> {
+    /// Appends every chunk of `iter` in turn, as with
+    /// [`ChunkedArray::push_chunk`].
+    fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T) {
+        for chunk in iter {
+            self.push_chunk(chunk);
+        }
+    }
+}
+
+impl<A: Array> FromIterator<A> for ChunkedArray<A> {
+    fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
+        let mut own = Self::new(vec![]);
+        own.extend(iter);
+
+        own
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayF64, ArrayI32, ArrayText};
+
+    fn chunked() -> ChunkedArray<ArrayI32> {
+        ChunkedArray::new(vec![
+            ArrayI32::from([Some(1), None, Some(3)]),
+            ArrayI32::from_vec(vec![]),
+            ArrayI32::from([Some(4)]),
+            ArrayI32::from([None, Some(6), Some(-7)]),
+        ])
+    }
+
+    #[test]
+    fn test_access_across_chunks() {
+        let one = chunked();
+        let model = [Some(1), None, Some(3), Some(4), None, Some(6), Some(-7)];
+
+        assert_eq!(7, one.len());
+        assert_eq!(4, one.num_chunks());
+        assert_eq!(2, one.null_count());
+
+        for (idx, value) in model.iter().enumerate() {
+            assert_eq!(*value, one.get(idx));
+            assert_eq!(value.is_none(), one.check_null(idx));
+        }
+
+        assert_eq!(None, one.get(7));
+        assert_eq!(model.to_vec(), one.iter().collect::<Vec<_>>());
+
+        let two = ChunkedArray::from(vec![
+            ArrayText::from([Some("a".into())]),
+            ArrayText::from([None, Some("bc".into())]),
+        ]);
+
+        assert_eq!(Some("bc"), two.get_ref(2));
+        assert_eq!(None, two.get_ref(1));
+    }
+
+    #[test]
+    fn test_rechunk_and_slice() {
+        let one = chunked();
+        let model = ArrayI32::from([Some(1), None, Some(3), Some(4), None, Some(6), Some(-7)]);
+
+        assert_eq!(model, one.rechunk());
+        assert_eq!(
+            ArrayI32::from_vec(vec![]),
+            ChunkedArray::new(vec![]).rechunk()
+        );
+
+        for offset in 0..=model.len() {
+            for len in 0..=model.len() - offset {
+                let two = one.slice(offset, len).unwrap();
+                let three = model.split_at(offset).unwrap().1.split_at(len).unwrap().0;
+
+                assert_eq!(len, two.len());
+                assert_eq!(three, two.rechunk());
+                assert!(two.chunks().iter().all(|chunk| !chunk.is_empty()));
+            }
+        }
+
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 8, len: 7 }),
+            one.slice(5, 3)
+        );
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let one = chunked();
+
+        assert_eq!(Some(7), one.sum());
+        assert_eq!(Some(-7), one.min());
+        assert_eq!(Some(6), one.max());
+
+        let two = ChunkedArray::new(vec![
+            ArrayF64::from([None, Some(f64::NAN)]),
+            ArrayF64::from([Some(2.5), Some(-1.0)]),
+        ]);
+
+        assert_eq!(Some(-1.0), two.min());
+        assert_eq!(Some(2.5), two.max());
+        assert_eq!(None, ChunkedArray::from(ArrayF64::from([None, None])).sum());
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut one = ChunkedArray::from_iter([ArrayI32::from([Some(1), None])]);
+        assert_eq!(ArrayI32::from([Some(1), None]), one.rechunk());
+
+        one.extend([ArrayI32::from_vec(vec![]), ArrayI32::from([Some(3)])]);
+
+        assert_eq!(3, one.num_chunks());
+        assert_eq!(3, one.len());
+        assert_eq!(Some(3), one.get(2));
+        assert_eq!(ArrayI32::from([Some(1), None, Some(3)]), one.rechunk());
+    }
+}
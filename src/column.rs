@@ -0,0 +1,367 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::statistics::ArrayStatistics;
+use crate::union::Scalar;
+use crate::utils::{Array, ArrayError, DataType};
+use crate::{
+    ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize, Union,
+};
+
+/// Shared handle to a [`ColumnArray`], letting batches reuse columns without
+/// copying them.
+pub type ArrayRef = Arc<ColumnArray>;
+
+/// Type erased column wrapping any of the concrete arrays.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ColumnArray {
+    Int32(ArrayI32),
+    UInt32(ArrayU32),
+    ISize(ArrayISize),
+    USize(ArrayUSize),
+    Boolean(ArrayBoolean),
+    F32(ArrayF32),
+    F64(ArrayF64),
+    Text(ArrayText),
+    Union(Union),
+}
+
+impl ColumnArray {
+    /// Returns the number of elements in the column.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Int32(array) => array.len(),
+            Self::UInt32(array) => array.len(),
+            Self::ISize(array) => array.len(),
+            Self::USize(array) => array.len(),
+            Self::Boolean(array) => array.len(),
+            Self::F32(array) => array.len(),
+            Self::F64(array) => array.len(),
+            Self::Text(array) => array.len(),
+            Self::Union(array) => array.len(),
+        }
+    }
+
+    /// Returns true if the column has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of null elements in the column.
+    pub fn null_count(&self) -> usize {
+        match self {
+            Self::Int32(array) => array.null_count(),
+            Self::UInt32(array) => array.null_count(),
+            Self::ISize(array) => array.null_count(),
+            Self::USize(array) => array.null_count(),
+            Self::Boolean(array) => array.null_count(),
+            Self::F32(array) => array.null_count(),
+            Self::F64(array) => array.null_count(),
+            Self::Text(array) => array.null_count(),
+            Self::Union(array) => array.null_count(),
+        }
+    }
+
+    /// Returns true if the element at `idx` is null.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn is_null(&self, idx: usize) -> bool {
+        assert!(
+            idx < self.len(),
+            "Tried to index {idx} when column length is {}",
+            self.len()
+        );
+
+        match self {
+            Self::Int32(array) => array.check_null(idx),
+            Self::UInt32(array) => array.check_null(idx),
+            Self::ISize(array) => array.check_null(idx),
+            Self::USize(array) => array.check_null(idx),
+            Self::Boolean(array) => array.check_null(idx),
+            Self::F32(array) => array.check_null(idx),
+            Self::F64(array) => array.check_null(idx),
+            Self::Text(array) => array.check_null(idx),
+            Self::Union(array) => array.check_null(idx),
+        }
+    }
+
+    /// Returns the wrapped array if it is an `A`.
+    pub fn downcast_ref<A: Array + 'static>(&self) -> Option<&A> {
+        let array: &dyn Any = match self {
+            Self::Int32(array) => array,
+            Self::UInt32(array) => array,
+            Self::ISize(array) => array,
+            Self::USize(array) => array,
+            Self::Boolean(array) => array,
+            Self::F32(array) => array,
+            Self::F64(array) => array,
+            Self::Text(array) => array,
+            Self::Union(array) => array,
+        };
+
+        array.downcast_ref()
+    }
+
+    /// Returns the [`DataType`] of the wrapped array.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::Int32(array) => array.data_type(),
+            Self::UInt32(array) => array.data_type(),
+            Self::ISize(array) => array.data_type(),
+            Self::USize(array) => array.data_type(),
+            Self::Boolean(array) => array.data_type(),
+            Self::F32(array) => array.data_type(),
+            Self::F64(array) => array.data_type(),
+            Self::Text(array) => array.data_type(),
+            Self::Union(array) => array.data_type(),
+        }
+    }
+
+    /// Returns a copy of the value at `idx`, with nulls as [`Scalar::Null`].
+    ///
+    /// Returns None if `idx` is out of range
+    pub fn scalar(&self, idx: usize) -> Option<Scalar> {
+        if idx >= self.len() {
+            return None;
+        }
+
+        let value = match self {
+            Self::Int32(array) => array.get(idx).map(Scalar::I32),
+            Self::UInt32(array) => array.get(idx).map(Scalar::U32),
+            Self::ISize(array) => array.get(idx).map(Scalar::ISize),
+            Self::USize(array) => array.get(idx).map(Scalar::USize),
+            Self::Boolean(array) => array.get(idx).map(Scalar::Boolean),
+            Self::F32(array) => array.get(idx).map(Scalar::F32),
+            Self::F64(array) => array.get(idx).map(Scalar::F64),
+            Self::Text(array) => array.get(idx).map(Scalar::Text),
+            Self::Union(array) => array.get(idx),
+        };
+
+        Some(value.unwrap_or(Scalar::Null))
+    }
+
+    /// Returns the statistics of the wrapped array.
+    ///
+    /// See [`Array::statistics`].
+    pub fn statistics(&self) -> ArrayStatistics {
+        match self {
+            Self::Int32(array) => array.statistics(),
+            Self::UInt32(array) => array.statistics(),
+            Self::ISize(array) => array.statistics(),
+            Self::USize(array) => array.statistics(),
+            Self::Boolean(array) => array.statistics(),
+            Self::F32(array) => array.statistics(),
+            Self::F64(array) => array.statistics(),
+            Self::Text(array) => array.statistics(),
+            Self::Union(array) => array.statistics(),
+        }
+    }
+
+    /// Returns a copy of the `len` elements starting at `offset`.
+    ///
+    /// See [`Array::slice`].
+    pub fn slice(&self, offset: usize, len: usize) -> Result<ColumnArray, ArrayError> {
+        let column = match self {
+            Self::Int32(array) => array.slice(offset, len)?.into(),
+            Self::UInt32(array) => array.slice(offset, len)?.into(),
+            Self::ISize(array) => array.slice(offset, len)?.into(),
+            Self::USize(array) => array.slice(offset, len)?.into(),
+            Self::Boolean(array) => array.slice(offset, len)?.into(),
+            Self::F32(array) => array.slice(offset, len)?.into(),
+            Self::F64(array) => array.slice(offset, len)?.into(),
+            Self::Text(array) => array.slice(offset, len)?.into(),
+            Self::Union(array) => array.slice(offset, len)?.into(),
+        };
+
+        Ok(column)
+    }
+
+    /// Returns a copy of the elements whose counterpart in `mask` is true.
+    ///
+    /// See [`Array::filter`].
+    pub fn filter(&self, mask: &ArrayBoolean) -> Result<ColumnArray, ArrayError> {
+        let column = match self {
+            Self::Int32(array) => array.filter(mask)?.into(),
+            Self::UInt32(array) => array.filter(mask)?.into(),
+            Self::ISize(array) => array.filter(mask)?.into(),
+            Self::USize(array) => array.filter(mask)?.into(),
+            Self::Boolean(array) => array.filter(mask)?.into(),
+            Self::F32(array) => array.filter(mask)?.into(),
+            Self::F64(array) => array.filter(mask)?.into(),
+            Self::Text(array) => array.filter(mask)?.into(),
+            Self::Union(array) => array.filter(mask)?.into(),
+        };
+
+        Ok(column)
+    }
+
+    /// Returns a copy of the elements at `indices`, in order.
+    ///
+    /// See [`Array::take`].
+    pub fn take(&self, indices: &ArrayUSize) -> Result<ColumnArray, ArrayError> {
+        let column = match self {
+            Self::Int32(array) => array.take(indices)?.into(),
+            Self::UInt32(array) => array.take(indices)?.into(),
+            Self::ISize(array) => array.take(indices)?.into(),
+            Self::USize(array) => array.take(indices)?.into(),
+            Self::Boolean(array) => array.take(indices)?.into(),
+            Self::F32(array) => array.take(indices)?.into(),
+            Self::F64(array) => array.take(indices)?.into(),
+            Self::Text(array) => array.take(indices)?.into(),
+            Self::Union(array) => array.take(indices)?.into(),
+        };
+
+        Ok(column)
+    }
+
+    /// Returns a column holding the elements of `self` followed by those of
+    /// `other`.
+    ///
+    /// Errors if the columns differ in type. See [`Array::concat`].
+    pub fn concat(&self, other: &ColumnArray) -> Result<ColumnArray, ArrayError> {
+        let column = match (self, other) {
+            (Self::Int32(lhs), Self::Int32(rhs)) => lhs.concat(rhs).into(),
+            (Self::UInt32(lhs), Self::UInt32(rhs)) => lhs.concat(rhs).into(),
+            (Self::ISize(lhs), Self::ISize(rhs)) => lhs.concat(rhs).into(),
+            (Self::USize(lhs), Self::USize(rhs)) => lhs.concat(rhs).into(),
+            (Self::Boolean(lhs), Self::Boolean(rhs)) => lhs.concat(rhs).into(),
+            (Self::F32(lhs), Self::F32(rhs)) => lhs.concat(rhs).into(),
+            (Self::F64(lhs), Self::F64(rhs)) => lhs.concat(rhs).into(),
+            (Self::Text(lhs), Self::Text(rhs)) => lhs.concat(rhs).into(),
+            (Self::Union(lhs), Self::Union(rhs)) => lhs.concat(rhs).into(),
+            (lhs, rhs) => {
+                return Err(ArrayError::TypeMismatch {
+                    expected: lhs.data_type(),
+                    got: rhs.data_type(),
+                })
+            }
+        };
+
+        Ok(column)
+    }
+
+    /// Builds a column of `data_type` from `values`.
+    ///
+    /// Panics if a non-null value is not of `data_type`, which any value is
+    /// for [`DataType::Union`].
+    pub(crate) fn from_scalars(data_type: DataType, values: Vec<Scalar>) -> ColumnArray {
+        let union = Union::from_vec(values);
+
+        let column = match data_type {
+            DataType::Int32 => ArrayI32::try_from(&union).map(Self::from),
+            DataType::UInt32 => ArrayU32::try_from(&union).map(Self::from),
+            DataType::ISize => ArrayISize::try_from(&union).map(Self::from),
+            DataType::USize => ArrayUSize::try_from(&union).map(Self::from),
+            DataType::Boolean => ArrayBoolean::try_from(&union).map(Self::from),
+            DataType::F32 => ArrayF32::try_from(&union).map(Self::from),
+            DataType::F64 => ArrayF64::try_from(&union).map(Self::from),
+            DataType::Text => ArrayText::try_from(&union).map(Self::from),
+            DataType::Union => return union.into(),
+        };
+
+        column.expect("Values are of the column's type")
+    }
+}
+
+impl From<ArrayI32> for ColumnArray {
+    fn from(value: ArrayI32) -> Self {
+        Self::Int32(value)
+    }
+}
+
+impl From<ArrayU32> for ColumnArray {
+    fn from(value: ArrayU32) -> Self {
+        Self::UInt32(value)
+    }
+}
+
+impl From<ArrayISize> for ColumnArray {
+    fn from(value: ArrayISize) -> Self {
+        Self::ISize(value)
+    }
+}
+
+impl From<ArrayUSize> for ColumnArray {
+    fn from(value: ArrayUSize) -> Self {
+        Self::USize(value)
+    }
+}
+
+impl From<ArrayBoolean> for ColumnArray {
+    fn from(value: ArrayBoolean) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<ArrayF32> for ColumnArray {
+    fn from(value: ArrayF32) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl From<ArrayF64> for ColumnArray {
+    fn from(value: ArrayF64) -> Self {
+        Self::F64(value)
+    }
+}
+
+impl From<ArrayText> for ColumnArray {
+    fn from(value: ArrayText) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<Union> for ColumnArray {
+    fn from(value: Union) -> Self {
+        Self::Union(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::UnionType;
+
+    #[test]
+    fn test_nulls() {
+        let one = ColumnArray::from(ArrayF64::from([Some(1.5), None, Some(2.0)]));
+
+        assert_eq!(1, one.null_count());
+        assert_eq!(
+            vec![false, true, false],
+            (0..3).map(|idx| one.is_null(idx)).collect::<Vec<_>>()
+        );
+
+        let two = ColumnArray::from(Union::from([UnionType::Null, UnionType::I32(3)]));
+        assert_eq!(1, two.null_count());
+        assert!(two.is_null(0));
+        assert!(!two.is_null(1));
+
+        assert_eq!(
+            2,
+            ColumnArray::from(ArrayText::from_vec(vec![None, None])).null_count()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to index 3 when column length is 3")]
+    fn test_is_null_out_of_bounds() {
+        ColumnArray::from(ArrayI32::from([1, 2, 3])).is_null(3);
+    }
+
+    #[test]
+    fn test_downcast_ref() {
+        let one = ColumnArray::from(ArrayF64::from([1.5]));
+
+        assert_eq!(Some(&ArrayF64::from([1.5])), one.downcast_ref::<ArrayF64>());
+        assert_eq!(None, one.downcast_ref::<ArrayF32>());
+        assert_eq!(None, one.downcast_ref::<Union>());
+
+        let two = ColumnArray::from(ArrayText::from(["a"]));
+        assert_eq!(
+            Some(1),
+            two.downcast_ref::<ArrayText>().map(|array| array.len())
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::cmp::Ordering;
+
+use crate::arithmetic::broadcast;
+use crate::utils::Array;
+use crate::ArrayBoolean;
+
+/// Compares every pair of non-null elements from `lhs` and `rhs`, keeping
+/// those whose ordering satisfies `keep`. Unordered pairs, such as float
+/// `NaN`s, have no ordering.
+fn compare<A, F>(lhs: &A, rhs: &A, keep: F) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+    F: Fn(Option<Ordering>) -> bool,
+{
+    let values = broadcast(lhs, rhs, "compare")
+        .map(|pair| pair.map(|(lhs, rhs)| keep(lhs.partial_cmp(&rhs))));
+
+    ArrayBoolean::new(values)
+}
+
+/// Compares every non-null element of `array` with `value`, keeping those
+/// whose ordering satisfies `keep`.
+pub(crate) fn compare_scalar<A, F>(array: &A, value: &A::Data, keep: F) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+    F: Fn(Option<Ordering>) -> bool,
+{
+    let values = (0..array.len()).map(|idx| Some(keep(array.get(idx)?.partial_cmp(value))));
+
+    ArrayBoolean::new(values)
+}
+
+/// Returns whether each element of `lhs` equals its counterpart in `rhs`.
+///
+/// Nulls in either array produce a null and float `NaN`s compare false. A
+/// single element array on either side is broadcast against the other.
+///
+/// Panics if both arrays differ in length and neither has a single element.
+pub fn eq<A>(lhs: &A, rhs: &A) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    compare(lhs, rhs, |ordering| ordering.is_some_and(Ordering::is_eq))
+}
+
+/// Returns whether each element of `lhs` differs from its counterpart in
+/// `rhs`. Unlike the other kernels, float `NaN`s compare true.
+///
+/// See [`eq`].
+pub fn neq<A>(lhs: &A, rhs: &A) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    compare(lhs, rhs, |ordering| ordering != Some(Ordering::Equal))
+}
+
+/// Returns whether each element of `lhs` is less than its counterpart in
+/// `rhs`.
+///
+/// See [`eq`].
+pub fn lt<A>(lhs: &A, rhs: &A) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    compare(lhs, rhs, |ordering| ordering.is_some_and(Ordering::is_lt))
+}
+
+/// Returns whether each element of `lhs` is at most its counterpart in
+/// `rhs`.
+///
+/// See [`eq`].
+pub fn lt_eq<A>(lhs: &A, rhs: &A) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    compare(lhs, rhs, |ordering| ordering.is_some_and(Ordering::is_le))
+}
+
+/// Returns whether each element of `lhs` is greater than its counterpart in
+/// `rhs`.
+///
+/// See [`eq`].
+pub fn gt<A>(lhs: &A, rhs: &A) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    compare(lhs, rhs, |ordering| ordering.is_some_and(Ordering::is_gt))
+}
+
+/// Returns whether each element of `lhs` is at least its counterpart in
+/// `rhs`.
+///
+/// See [`eq`].
+pub fn gt_eq<A>(lhs: &A, rhs: &A) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    compare(lhs, rhs, |ordering| ordering.is_some_and(Ordering::is_ge))
+}
+
+/// Returns whether each element of `array` equals `value`.
+///
+/// Nulls produce a null and float `NaN`s compare false. With the `simd`
+/// feature, arrays exposing their values buffer are compared eight elements
+/// at a time.
+pub fn eq_scalar<A>(array: &A, value: A::Data) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    #[cfg(feature = "simd")]
+    if let Some(values) = array.values() {
+        let (validity, nulls) = (array.validity(), array.null_count());
+        return crate::simd::compare_scalar(values, &value, validity, nulls, |lhs, rhs| lhs == rhs);
+    }
+
+    compare_scalar(array, &value, |ordering| {
+        ordering.is_some_and(Ordering::is_eq)
+    })
+}
+
+/// Returns whether each element of `array` is less than `value`.
+///
+/// See [`eq_scalar`].
+pub fn lt_scalar<A>(array: &A, value: A::Data) -> ArrayBoolean
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    #[cfg(feature = "simd")]
+    if let Some(values) = array.values() {
+        let (validity, nulls) = (array.validity(), array.null_count());
+        return crate::simd::compare_scalar(values, &value, validity, nulls, |lhs, rhs| lhs < rhs);
+    }
+
+    compare_scalar(array, &value, |ordering| {
+        ordering.is_some_and(Ordering::is_lt)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayF64, ArrayI32, ArrayText, Union, UnionType};
+
+    #[test]
+    fn test_compare() {
+        let one = ArrayI32::from([Some(1), Some(2), None, Some(4)]);
+        let two = ArrayI32::from([Some(2), Some(2), Some(3), Some(3)]);
+
+        assert_eq!(
+            ArrayBoolean::from([Some(false), Some(true), None, Some(false)]),
+            eq(&one, &two)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(false), None, Some(true)]),
+            neq(&one, &two)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(false), None, Some(false)]),
+            lt(&one, &two)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(true), None, Some(false)]),
+            lt_eq(&one, &two)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(false), Some(false), None, Some(true)]),
+            gt(&one, &two)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(false), Some(true), None, Some(true)]),
+            gt_eq(&one, &two)
+        );
+
+        let one = ArrayF64::from([f64::NAN, 1.0]);
+        assert_eq!(ArrayBoolean::from([false, true]), eq(&one, &one));
+        assert_eq!(ArrayBoolean::from([true, false]), neq(&one, &one));
+
+        let one = ArrayText::from(["apple", "pear"]);
+        let two = ArrayText::from(["banana", "banana"]);
+        assert_eq!(ArrayBoolean::from([true, false]), lt(&one, &two));
+    }
+
+    #[test]
+    fn test_compare_broadcast() {
+        let one = ArrayI32::from([Some(1), None, Some(3)]);
+        let two = ArrayI32::from([2]);
+
+        assert_eq!(
+            ArrayBoolean::from([Some(true), None, Some(false)]),
+            lt(&one, &two)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(false), None, Some(true)]),
+            lt(&two, &one)
+        );
+
+        let two = ArrayI32::from([None]);
+        let three = gt_eq(&one, &two);
+        assert_eq!(3, three.len());
+        assert!(three.all_null());
+    }
+
+    #[test]
+    fn test_compare_union() {
+        let one = Union::from([
+            Some(UnionType::I32(1)),
+            None,
+            Some(UnionType::Text("a".into())),
+        ]);
+        let two = Union::from([Some(UnionType::I32(1)), Some(UnionType::I32(2)), None]);
+
+        assert!(one.validity().is_none());
+        assert_eq!(ArrayBoolean::from([Some(true), None, None]), eq(&one, &two));
+    }
+
+    #[test]
+    fn test_compare_scalar() {
+        let one = ArrayI32::from([Some(1), None, Some(3), Some(2)]);
+
+        assert_eq!(
+            ArrayBoolean::from([Some(false), None, Some(false), Some(true)]),
+            eq_scalar(&one, 2)
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), None, Some(false), Some(false)]),
+            lt_scalar(&one, 2)
+        );
+
+        let two = ArrayF64::from([f64::NAN, 0.5]);
+        assert_eq!(
+            ArrayBoolean::from([false, false]),
+            eq_scalar(&two, f64::NAN)
+        );
+        assert_eq!(ArrayBoolean::from([false, true]), lt_scalar(&two, 1.0));
+
+        let three = ArrayText::from(["a", "b"]);
+        assert_eq!(
+            ArrayBoolean::from([false, true]),
+            eq_scalar(&three, "b".into())
+        );
+        assert!(lt_scalar(&ArrayI32::nulls(3), 0).all_null());
+    }
+}
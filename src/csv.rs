@@ -0,0 +1,673 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+use crate::column::ColumnArray;
+use crate::record_batch::RecordBatch;
+use crate::schema::{Field, Schema, SchemaError};
+use crate::union::{UnionRef, UnionType};
+use crate::utils::{Array, DataType};
+use crate::{
+    ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize, Union,
+};
+
+/// Errors raised while reading or writing CSV.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// A quoted value starting on `line` is never closed.
+    UnterminatedQuote {
+        line: usize,
+    },
+    /// The record starting on `line` has a different number of values than
+    /// the first record or schema.
+    FieldCount {
+        line: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// A value could not be parsed as the type of its column.
+    Parse {
+        line: usize,
+        column: String,
+        value: String,
+        data_type: DataType,
+    },
+    Schema(SchemaError),
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "CSV io error: {error}"),
+            Self::UnterminatedQuote { line } => {
+                write!(f, "Quoted value starting on line {line} is never closed")
+            }
+            Self::FieldCount {
+                line,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Record on line {line} has {got} values when {expected} were expected"
+            ),
+            Self::Parse {
+                line,
+                column,
+                value,
+                data_type,
+            } => write!(
+                f,
+                "Could not parse \"{value}\" on line {line} of column \"{column}\" as {data_type:?}"
+            ),
+            Self::Schema(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Schema(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<SchemaError> for CsvError {
+    fn from(value: SchemaError) -> Self {
+        Self::Schema(value)
+    }
+}
+
+/// Options controlling how [`write_csv`] formats a [`RecordBatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvWriteOptions {
+    pub delimiter: char,
+    pub quote: char,
+    /// Whether to start with a row of field names.
+    pub header: bool,
+    /// Text written in place of nulls.
+    pub null: String,
+    /// Number of digits written after the decimal point of floats. Floats
+    /// are written in full when None.
+    pub float_precision: Option<usize>,
+}
+
+impl Default for CsvWriteOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            header: true,
+            null: String::new(),
+            float_precision: None,
+        }
+    }
+}
+
+/// Writes `batch` to `writer` as CSV, one line per row.
+///
+/// Values containing the delimiter, the quote character or a line break
+/// are quoted, with quote characters within them doubled.
+pub fn write_csv<W: Write>(
+    batch: &RecordBatch,
+    mut writer: W,
+    opts: CsvWriteOptions,
+) -> Result<(), CsvError> {
+    if opts.header {
+        let names = batch.schema().fields().iter().map(|field| &field.name);
+        write_row(&mut writer, names, &opts)?;
+    }
+
+    for row in 0..batch.num_rows() {
+        let cells = batch
+            .columns()
+            .iter()
+            .map(|column| cell(column, row, &opts).unwrap_or_else(|| opts.null.clone()));
+
+        write_row(&mut writer, cells, &opts)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_row<W, I, S>(writer: &mut W, cells: I, opts: &CsvWriteOptions) -> io::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut line = String::new();
+
+    for (idx, cell) in cells.enumerate() {
+        if idx != 0 {
+            line.push(opts.delimiter);
+        }
+
+        quote(&mut line, cell.as_ref(), opts);
+    }
+
+    line.push('\n');
+    writer.write_all(line.as_bytes())
+}
+
+/// Pushes `cell` onto `line`, quoting it if needed.
+fn quote(line: &mut String, cell: &str, opts: &CsvWriteOptions) {
+    let needs_quotes = cell
+        .chars()
+        .any(|chr| chr == opts.delimiter || chr == opts.quote || chr == '\n' || chr == '\r');
+
+    if !needs_quotes {
+        line.push_str(cell);
+        return;
+    }
+
+    line.push(opts.quote);
+
+    for chr in cell.chars() {
+        if chr == opts.quote {
+            line.push(chr);
+        }
+
+        line.push(chr);
+    }
+
+    line.push(opts.quote);
+}
+
+fn float(value: f64, opts: &CsvWriteOptions) -> String {
+    match opts.float_precision {
+        Some(precision) => format!("{value:.precision$}"),
+        None => value.to_string(),
+    }
+}
+
+/// Returns the text of the value at `idx`, or None if it is null.
+fn cell(column: &ColumnArray, idx: usize, opts: &CsvWriteOptions) -> Option<String> {
+    match column {
+        ColumnArray::Int32(array) => array.get(idx).map(|value| value.to_string()),
+        ColumnArray::UInt32(array) => array.get(idx).map(|value| value.to_string()),
+        ColumnArray::ISize(array) => array.get(idx).map(|value| value.to_string()),
+        ColumnArray::USize(array) => array.get(idx).map(|value| value.to_string()),
+        ColumnArray::F32(array) => array.get(idx).map(|value| float(value as f64, opts)),
+        ColumnArray::F64(array) => array.get(idx).map(|value| float(value, opts)),
+        ColumnArray::Boolean(array) => array.get(idx).map(|value| value.to_string()),
+        ColumnArray::Text(array) => array.get(idx),
+        ColumnArray::Union(array) => match array.get_ref(idx)? {
+            UnionRef::U32(value) => Some(value.to_string()),
+            UnionRef::I32(value) => Some(value.to_string()),
+            UnionRef::USize(value) => Some(value.to_string()),
+            UnionRef::ISize(value) => Some(value.to_string()),
+            UnionRef::F32(value) => Some(float(value as f64, opts)),
+            UnionRef::F64(value) => Some(float(value, opts)),
+            UnionRef::Boolean(value) => Some(value.to_string()),
+            UnionRef::Text(value) => Some(value.to_owned()),
+            UnionRef::Null => None,
+        },
+    }
+}
+
+/// Options controlling how [`read_csv`] parses its input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvReadOptions {
+    pub delimiter: char,
+    pub quote: char,
+    /// Whether the first record holds the field names.
+    pub has_header: bool,
+    /// Unquoted values read as nulls.
+    pub null_values: Vec<String>,
+    /// Schema of the columns. Types are inferred when None.
+    pub schema: Option<Schema>,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            has_header: true,
+            null_values: vec![String::new()],
+            schema: None,
+        }
+    }
+}
+
+/// Reads CSV from `reader` into a single [`RecordBatch`].
+///
+/// Without an explicit schema the type of each column is inferred from all
+/// its non null values, trying [`DataType::ISize`], [`DataType::F64`] and
+/// [`DataType::Boolean`] in that order before falling back to
+/// [`DataType::Text`]. Fields are named `column_1`, `column_2`, ... when
+/// there is neither a header nor a schema.
+pub fn read_csv<R: Read>(mut reader: R, opts: CsvReadOptions) -> Result<RecordBatch, CsvError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut records = parse_records(&input, &opts)?.into_iter();
+
+    let header = match opts.has_header {
+        true => records.next(),
+        false => None,
+    };
+    let records = records.collect::<Vec<_>>();
+
+    let width = match (&opts.schema, &header, records.first()) {
+        (Some(schema), _, _) => schema.fields().len(),
+        (None, Some((_, header)), _) => header.len(),
+        (None, None, Some((_, record))) => record.len(),
+        (None, None, None) => 0,
+    };
+
+    for (line, record) in header.iter().chain(records.iter()) {
+        if record.len() != width {
+            return Err(CsvError::FieldCount {
+                line: *line,
+                expected: width,
+                got: record.len(),
+            });
+        }
+    }
+
+    let mut fields = Vec::with_capacity(width);
+    let mut columns = Vec::with_capacity(width);
+
+    for idx in 0..width {
+        let cells = records
+            .iter()
+            .map(|(line, record)| {
+                let cell = &record[idx];
+                let null = !cell.quoted && opts.null_values.contains(&cell.text);
+
+                (*line, (!null).then_some(cell.text.as_str()))
+            })
+            .collect::<Vec<_>>();
+
+        let field = match (&opts.schema, &header) {
+            (Some(schema), _) => schema.fields()[idx].clone(),
+            (None, header) => {
+                let name = match header {
+                    Some((_, header)) => header[idx].text.clone(),
+                    None => format!("column_{}", idx + 1),
+                };
+                let nullable = cells.iter().any(|(_, cell)| cell.is_none());
+
+                Field::new(name, infer(&cells), nullable)
+            }
+        };
+
+        columns.push(build_column(&field, &cells)?);
+        fields.push(field);
+    }
+
+    Ok(RecordBatch::try_new(Schema::new(fields), columns)?)
+}
+
+/// A single value of a record.
+struct Cell {
+    text: String,
+    quoted: bool,
+}
+
+/// Splits `input` into records paired with the line they start on.
+fn parse_records(input: &str, opts: &CsvReadOptions) -> Result<Vec<(usize, Vec<Cell>)>, CsvError> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut text = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start = 1;
+
+    let mut chars = input.chars().peekable();
+
+    while let Some(chr) = chars.next() {
+        if in_quotes {
+            if chr == opts.quote {
+                // A doubled quote is an escaped quote
+                match chars.peek() == Some(&opts.quote) {
+                    true => text.push(chars.next().unwrap_or(chr)),
+                    false => in_quotes = false,
+                }
+            } else {
+                line += (chr == '\n') as usize;
+                text.push(chr);
+            }
+
+            continue;
+        }
+
+        match chr {
+            chr if chr == opts.delimiter => {
+                record.push(Cell {
+                    text: std::mem::take(&mut text),
+                    quoted,
+                });
+                quoted = false;
+            }
+            chr if chr == opts.quote && text.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                // Blank lines hold no records
+                if !record.is_empty() || !text.is_empty() || quoted {
+                    record.push(Cell {
+                        text: std::mem::take(&mut text),
+                        quoted,
+                    });
+                    records.push((start, std::mem::take(&mut record)));
+                }
+
+                quoted = false;
+                line += 1;
+                start = line;
+            }
+            chr => text.push(chr),
+        }
+    }
+
+    if in_quotes {
+        return Err(CsvError::UnterminatedQuote { line: start });
+    }
+
+    if !record.is_empty() || !text.is_empty() || quoted {
+        record.push(Cell { text, quoted });
+        records.push((start, record));
+    }
+
+    Ok(records)
+}
+
+/// Infers the type of a column from its non null values.
+fn infer(cells: &[(usize, Option<&str>)]) -> DataType {
+    let values = || cells.iter().filter_map(|(_, cell)| *cell);
+
+    if values().next().is_none() {
+        return DataType::Text;
+    }
+
+    if values().all(|value| value.parse::<isize>().is_ok()) {
+        DataType::ISize
+    } else if values().all(|value| value.parse::<f64>().is_ok()) {
+        DataType::F64
+    } else if values().all(|value| value.parse::<bool>().is_ok()) {
+        DataType::Boolean
+    } else {
+        DataType::Text
+    }
+}
+
+fn parse_cells<A, F>(
+    field: &Field,
+    cells: &[(usize, Option<&str>)],
+    parse: F,
+) -> Result<A, CsvError>
+where
+    A: Array,
+    F: Fn(&str) -> Option<A::Data>,
+{
+    let values = cells
+        .iter()
+        .map(|(line, cell)| {
+            let Some(cell) = cell else {
+                return Ok(None);
+            };
+
+            parse(cell).map(Some).ok_or_else(|| CsvError::Parse {
+                line: *line,
+                column: field.name.clone(),
+                value: cell.to_string(),
+                data_type: field.data_type,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(A::new(values))
+}
+
+fn parsed<T: FromStr>(cell: &str) -> Option<T> {
+    cell.parse().ok()
+}
+
+fn build_column(field: &Field, cells: &[(usize, Option<&str>)]) -> Result<ColumnArray, CsvError> {
+    let column = match field.data_type {
+        DataType::Int32 => parse_cells::<ArrayI32, _>(field, cells, parsed)?.into(),
+        DataType::UInt32 => parse_cells::<ArrayU32, _>(field, cells, parsed)?.into(),
+        DataType::ISize => parse_cells::<ArrayISize, _>(field, cells, parsed)?.into(),
+        DataType::USize => parse_cells::<ArrayUSize, _>(field, cells, parsed)?.into(),
+        DataType::F32 => parse_cells::<ArrayF32, _>(field, cells, parsed)?.into(),
+        DataType::F64 => parse_cells::<ArrayF64, _>(field, cells, parsed)?.into(),
+        DataType::Boolean => parse_cells::<ArrayBoolean, _>(field, cells, parsed)?.into(),
+        DataType::Text => {
+            parse_cells::<ArrayText, _>(field, cells, |cell| Some(cell.to_owned()))?.into()
+        }
+        DataType::Union => {
+            parse_cells::<Union, _>(field, cells, |cell| Some(UnionType::parse(cell)))?.into()
+        }
+    };
+
+    Ok(column)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{Field, Schema};
+    use crate::utils::DataType;
+    use crate::{ArrayBoolean, ArrayF64, ArrayI32, ArrayText, Union, UnionType};
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+            Field::new("flag", DataType::Boolean, true),
+            Field::new("raw", DataType::Union, true),
+        ]);
+
+        let columns = vec![
+            ArrayI32::from([1, 2, 3]).into(),
+            ArrayF64::from([Some(1.5), None, Some(-2.0 / 3.0)]).into(),
+            ArrayText::from([Some("plain".into()), Some("a, b".into()), None]).into(),
+            ArrayBoolean::from([Some(true), None, Some(false)]).into(),
+            Union::from_vec(vec![
+                UnionType::Text("say \"hi\"".into()),
+                UnionType::Null,
+                UnionType::U32(7),
+            ])
+            .into(),
+        ];
+
+        RecordBatch::try_new(schema, columns).unwrap()
+    }
+
+    fn write(batch: &RecordBatch, opts: CsvWriteOptions) -> String {
+        let mut out = vec![];
+        write_csv(batch, &mut out, opts).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_defaults() {
+        let expected = [
+            "id,price,name,flag,raw",
+            "1,1.5,plain,true,\"say \"\"hi\"\"\"",
+            "2,,\"a, b\",,",
+            "3,-0.6666666666666666,,false,7",
+            "",
+        ];
+
+        assert_eq!(
+            expected.join("\n"),
+            write(&batch(), CsvWriteOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_write_options() {
+        let opts = CsvWriteOptions {
+            delimiter: ';',
+            quote: '\'',
+            header: false,
+            null: "NA".into(),
+            float_precision: Some(2),
+        };
+
+        let expected = [
+            "1;1.50;plain;true;say \"hi\"",
+            "2;NA;a, b;NA;NA",
+            "3;-0.67;NA;false;7",
+            "",
+        ];
+
+        assert_eq!(expected.join("\n"), write(&batch(), opts));
+    }
+
+    #[test]
+    fn test_write_quoting() {
+        let schema = Schema::new(vec![Field::new("a;b", DataType::Text, false)]);
+        let column = ArrayText::from(["it's", "two\nlines", "x;y", "fine"]);
+        let batch = RecordBatch::try_new(schema, vec![column.into()]).unwrap();
+
+        let opts = CsvWriteOptions {
+            delimiter: ';',
+            quote: '\'',
+            ..Default::default()
+        };
+
+        let expected = ["'a;b'", "'it''s'", "'two\nlines'", "'x;y'", "fine", ""];
+        assert_eq!(expected.join("\n"), write(&batch, opts));
+    }
+
+    fn read(input: &str, opts: CsvReadOptions) -> Result<RecordBatch, CsvError> {
+        read_csv(input.as_bytes(), opts)
+    }
+
+    #[test]
+    fn test_read_inference() {
+        let input = "id,price,name,flag,count\r\n\
+                     1,1.5,plain,true,\n\
+                     -2,,\"a, \"\"b\"\"\",,\n\
+                     \n\
+                     3,4,\"two\nlines\",false,\n";
+
+        let batch = read(input, CsvReadOptions::default()).unwrap();
+
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::ISize, false),
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, false),
+            Field::new("flag", DataType::Boolean, true),
+            Field::new("count", DataType::Text, true),
+        ]);
+
+        let columns = vec![
+            ArrayISize::from([1, -2, 3]).into(),
+            ArrayF64::from([Some(1.5), None, Some(4.0)]).into(),
+            ArrayText::from(["plain", "a, \"b\"", "two\nlines"]).into(),
+            ArrayBoolean::from([Some(true), None, Some(false)]).into(),
+            ArrayText::from_vec(vec![None, None, None]).into(),
+        ];
+
+        assert_eq!(RecordBatch::try_new(schema, columns).unwrap(), batch);
+    }
+
+    #[test]
+    fn test_read_schema_and_options() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, true),
+            Field::new("b", DataType::F32, false),
+            Field::new("c", DataType::Union, true),
+        ]);
+
+        let opts = CsvReadOptions {
+            delimiter: ';',
+            quote: '\'',
+            has_header: false,
+            null_values: vec!["NA".into(), "-".into()],
+            schema: Some(schema.clone()),
+        };
+
+        let batch = read("1;2.5;x\nNA;3;'NA'\n-;0;7\n", opts.clone()).unwrap();
+
+        let columns = vec![
+            ArrayU32::from([Some(1), None, None]).into(),
+            ArrayF32::from([2.5, 3.0, 0.0]).into(),
+            Union::from_vec(vec![
+                UnionType::Text("x".into()),
+                UnionType::Text("NA".into()),
+                UnionType::U32(7),
+            ])
+            .into(),
+        ];
+
+        assert_eq!(RecordBatch::try_new(schema, columns).unwrap(), batch);
+
+        let error = read("1;2.5;x\n-1;3;y", opts).unwrap_err();
+        assert!(matches!(
+            error,
+            CsvError::Parse { line: 2, column, value, data_type: DataType::UInt32 }
+                if column == "a" && value == "-1"
+        ));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let opts = CsvReadOptions::default;
+
+        assert!(matches!(
+            read("a,b\n1,2\n\n3\n", opts()),
+            Err(CsvError::FieldCount {
+                line: 4,
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert!(matches!(
+            read("a,b\n1,\"2\n3,4\n", opts()),
+            Err(CsvError::UnterminatedQuote { line: 2 })
+        ));
+
+        let batch = read("", opts()).unwrap();
+        assert_eq!(0, batch.num_columns());
+
+        let batch = read(
+            "1,x\n2,y",
+            CsvReadOptions {
+                has_header: false,
+                ..opts()
+            },
+        )
+        .unwrap();
+        let names = batch.schema().fields().iter().map(|field| &field.name);
+        assert!(names.eq(["column_1", "column_2"].iter()));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let batch = batch();
+        let schema = batch.schema().clone();
+
+        let mut out = vec![];
+        write_csv(&batch, &mut out, CsvWriteOptions::default()).unwrap();
+
+        let opts = CsvReadOptions {
+            schema: Some(schema),
+            ..Default::default()
+        };
+
+        assert_eq!(batch, read_csv(out.as_slice(), opts).unwrap());
+    }
+}
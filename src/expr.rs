@@ -0,0 +1,886 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::column::{ArrayRef, ColumnArray};
+use crate::record_batch::RecordBatch;
+use crate::schema::Schema;
+use crate::union::{Scalar, Union};
+use crate::utils::DataType;
+
+/// Errors raised while parsing or evaluating an [`Expr`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprError {
+    /// The expression refers to a column the batch does not have.
+    UnknownColumn(String),
+    /// An operator was applied to operands of types it does not support.
+    /// Each operand is given as written along with its type.
+    TypeMismatch {
+        op: &'static str,
+        operands: Vec<(String, DataType)>,
+    },
+    /// The input is not a valid expression. `pos` is the byte offset of the
+    /// offending token.
+    Parse { pos: usize, message: String },
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownColumn(name) => write!(f, "Unknown column {name}"),
+            Self::TypeMismatch { op, operands } => {
+                let operands = operands
+                    .iter()
+                    .map(|(expr, data_type)| format!("{expr} of type {data_type:?}"))
+                    .collect::<Vec<_>>()
+                    .join(" and ");
+                write!(f, "Cannot apply {op} to {operands}")
+            }
+            Self::Parse { pos, message } => write!(f, "Parse error at {pos}: {message}"),
+        }
+    }
+}
+
+impl Error for ExprError {}
+
+/// Operators taking two operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::LtEq => "<=",
+            Self::Gt => ">",
+            Self::GtEq => ">=",
+            Self::And => "&&",
+            Self::Or => "||",
+        }
+    }
+
+    fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            Self::Eq | Self::NotEq | Self::Lt | Self::LtEq | Self::Gt | Self::GtEq
+        )
+    }
+}
+
+/// Operators taking a single operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+impl UnaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Neg => "-",
+            Self::Not => "!",
+        }
+    }
+}
+
+/// Expression computing a column from the columns of a [`RecordBatch`].
+///
+/// Expressions are either built directly or parsed from text, see
+/// [`Expr::parse`] for the grammar.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Column of the batch, by name.
+    Col(String),
+    /// Value repeated for every row.
+    Lit(Scalar),
+    BinaryOp {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    UnaryOp {
+        op: UnaryOp,
+        expr: Box<Expr>,
+    },
+    /// True where the operand is null.
+    IsNull(Box<Expr>),
+}
+
+impl Expr {
+    pub fn col(name: impl Into<String>) -> Self {
+        Self::Col(name.into())
+    }
+
+    pub fn lit(value: impl Into<Scalar>) -> Self {
+        Self::Lit(value.into())
+    }
+
+    pub fn binary(lhs: Expr, op: BinaryOp, rhs: Expr) -> Self {
+        Self::BinaryOp {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        }
+    }
+
+    pub fn unary(op: UnaryOp, expr: Expr) -> Self {
+        Self::UnaryOp {
+            op,
+            expr: Box::new(expr),
+        }
+    }
+
+    pub fn is_null(expr: Expr) -> Self {
+        Self::IsNull(Box::new(expr))
+    }
+
+    /// Parses `input` into an expression.
+    ///
+    /// From loosest to tightest binding, the grammar has `||` (or `or`),
+    /// `&&` (or `and`), prefix `!` (or `not`), a single comparison with one
+    /// of `==`, `=`, `!=`, `<>`, `<`, `<=`, `>` and `>=`, the postfix
+    /// `is null` and `is not null`, `+` and `-`, `*` and `/`, and finally
+    /// prefix `-`. Binary operators are left associative and parentheses
+    /// group as usual.
+    ///
+    /// Integers are read as [`Scalar::ISize`] and other numbers as
+    /// [`Scalar::F64`]. Text is quoted with `'` or `"`, escaping quotes and
+    /// backslashes with a backslash. `true`, `false` and `null` are literals
+    /// while other words name columns, as do backtick quoted names.
+    /// Keywords are not case sensitive.
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let expr = parser.or()?;
+
+        match parser.peek() {
+            Token::End => Ok(expr),
+            _ => Err(parser.error("Expected the end of the expression")),
+        }
+    }
+
+    /// Evaluates the expression on every row of `batch`.
+    ///
+    /// Arithmetic follows the promotion rules of [`Scalar`] addition and
+    /// comparisons those of its [`PartialOrd`] impl, so numbers of any
+    /// type can be mixed. Null operands give null results except under
+    /// `&&` and `||`, which use three-valued logic so `false && null` is
+    /// false and `true || null` true. Integer overflow and division by zero
+    /// also give nulls.
+    ///
+    /// Types are checked against the schema before anything is computed,
+    /// erroring if a column is missing or an operator does not apply to
+    /// its operands. Operands of [`Union`] columns are checked row by row
+    /// instead: arithmetic on values that are not numbers gives nulls and
+    /// comparisons between unrelated kinds are false.
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef, ExprError> {
+        let data_type = self.check(batch.schema())?;
+
+        if let Self::Col(name) = self {
+            let idx = batch.schema().index_of(name).expect("Column was checked");
+            return Ok(Arc::clone(&batch.columns()[idx]));
+        }
+
+        let values = self.values(batch);
+
+        let column = ColumnArray::from_scalars(data_type.unwrap_or(DataType::Union), values);
+
+        Ok(Arc::new(column))
+    }
+
+    /// Returns the type of the expression's values under `schema`, or None
+    /// if they are all null.
+    fn check(&self, schema: &Schema) -> Result<Option<DataType>, ExprError> {
+        let data_type = match self {
+            Self::Col(name) => schema
+                .field_with_name(name)
+                .map(|field| field.data_type)
+                .ok_or_else(|| ExprError::UnknownColumn(name.clone()))?,
+            Self::Lit(value) => return Ok(value.borrow().data_type()),
+            Self::IsNull(expr) => {
+                expr.check(schema)?;
+                DataType::Boolean
+            }
+            Self::UnaryOp { op, expr } => {
+                let Some(data_type) = expr.check(schema)? else {
+                    return Ok(None);
+                };
+
+                match (op, data_type) {
+                    (_, DataType::Union) => match op {
+                        UnaryOp::Neg => DataType::Union,
+                        UnaryOp::Not => DataType::Boolean,
+                    },
+                    (UnaryOp::Neg, DataType::UInt32 | DataType::USize) => DataType::ISize,
+                    (UnaryOp::Neg, data_type) if data_type.is_numeric() => data_type,
+                    (UnaryOp::Not, DataType::Boolean) => DataType::Boolean,
+                    (op, data_type) => {
+                        return Err(ExprError::TypeMismatch {
+                            op: op.symbol(),
+                            operands: vec![(expr.to_string(), data_type)],
+                        })
+                    }
+                }
+            }
+            Self::BinaryOp { op, lhs, rhs } => {
+                let (lhs_type, rhs_type) = (lhs.check(schema)?, rhs.check(schema)?);
+
+                if op.is_comparison() || matches!(op, BinaryOp::And | BinaryOp::Or) {
+                    let valid = match (lhs_type, rhs_type) {
+                        (Some(DataType::Union), _) | (_, Some(DataType::Union)) => true,
+                        _ if op.is_comparison() => match (lhs_type, rhs_type) {
+                            (Some(lhs), Some(rhs)) => {
+                                lhs == rhs || (lhs.is_numeric() && rhs.is_numeric())
+                            }
+                            _ => true,
+                        },
+                        _ => [lhs_type, rhs_type]
+                            .into_iter()
+                            .flatten()
+                            .all(|data_type| data_type == DataType::Boolean),
+                    };
+
+                    if !valid {
+                        return Err(binary_mismatch(*op, lhs, lhs_type, rhs, rhs_type));
+                    }
+
+                    DataType::Boolean
+                } else {
+                    match (lhs_type, rhs_type) {
+                        (Some(DataType::Union), _) | (_, Some(DataType::Union)) => DataType::Union,
+                        (Some(lhs), Some(rhs)) if lhs.is_numeric() && rhs.is_numeric() => {
+                            lhs.promote(rhs)
+                        }
+                        (Some(data_type), None) | (None, Some(data_type))
+                            if data_type.is_numeric() =>
+                        {
+                            data_type
+                        }
+                        (None, None) => return Ok(None),
+                        _ => return Err(binary_mismatch(*op, lhs, lhs_type, rhs, rhs_type)),
+                    }
+                }
+            }
+        };
+
+        Ok(Some(data_type))
+    }
+
+    /// Computes the value of every row of `batch`, assuming the expression
+    /// was checked against its schema.
+    fn values(&self, batch: &RecordBatch) -> Vec<Scalar> {
+        let rows = batch.num_rows();
+
+        match self {
+            Self::Col(name) => {
+                let column = batch.column_by_name(name).expect("Column was checked");
+                (0..rows)
+                    .map(|idx| column.scalar(idx).unwrap_or(Scalar::Null))
+                    .collect()
+            }
+            Self::Lit(value) => vec![value.clone(); rows],
+            Self::IsNull(expr) => expr
+                .values(batch)
+                .into_iter()
+                .map(|value| Scalar::Boolean(value == Scalar::Null))
+                .collect(),
+            Self::UnaryOp { op, expr } => expr
+                .values(batch)
+                .into_iter()
+                .map(|value| match (op, value) {
+                    (UnaryOp::Not, Scalar::Boolean(value)) => Scalar::Boolean(!value),
+                    (UnaryOp::Neg, value) => negate(value),
+                    _ => Scalar::Null,
+                })
+                .collect(),
+            Self::BinaryOp { op, lhs, rhs } => lhs
+                .values(batch)
+                .into_iter()
+                .zip(rhs.values(batch))
+                .map(|(lhs, rhs)| apply(*op, &lhs, &rhs))
+                .collect(),
+        }
+    }
+}
+
+fn binary_mismatch(
+    op: BinaryOp,
+    lhs: &Expr,
+    lhs_type: Option<DataType>,
+    rhs: &Expr,
+    rhs_type: Option<DataType>,
+) -> ExprError {
+    let operands = [(lhs, lhs_type), (rhs, rhs_type)]
+        .into_iter()
+        .filter_map(|(expr, data_type)| Some((expr.to_string(), data_type?)))
+        .collect();
+
+    ExprError::TypeMismatch {
+        op: op.symbol(),
+        operands,
+    }
+}
+
+fn negate(value: Scalar) -> Scalar {
+    let negated = match value {
+        Scalar::I32(value) => value.checked_neg().map(Scalar::I32),
+        Scalar::ISize(value) => value.checked_neg().map(Scalar::ISize),
+        Scalar::U32(value) => Some(Scalar::ISize(-(value as isize))),
+        Scalar::USize(value) => isize::try_from(value)
+            .ok()
+            .map(|value| Scalar::ISize(-value)),
+        Scalar::F32(value) => Some(Scalar::F32(-value)),
+        Scalar::F64(value) => Some(Scalar::F64(-value)),
+        _ => None,
+    };
+
+    negated.unwrap_or(Scalar::Null)
+}
+
+fn apply(op: BinaryOp, lhs: &Scalar, rhs: &Scalar) -> Scalar {
+    let as_bool = |value: &Scalar| match value {
+        Scalar::Boolean(value) => Some(*value),
+        _ => None,
+    };
+
+    match op {
+        BinaryOp::And => match (as_bool(lhs), as_bool(rhs)) {
+            (Some(false), _) | (_, Some(false)) => Scalar::Boolean(false),
+            (Some(true), Some(true)) => Scalar::Boolean(true),
+            _ => Scalar::Null,
+        },
+        BinaryOp::Or => match (as_bool(lhs), as_bool(rhs)) {
+            (Some(true), _) | (_, Some(true)) => Scalar::Boolean(true),
+            (Some(false), Some(false)) => Scalar::Boolean(false),
+            _ => Scalar::Null,
+        },
+        _ if matches!(lhs, Scalar::Null) || matches!(rhs, Scalar::Null) => Scalar::Null,
+        BinaryOp::Add => (lhs + rhs).unwrap_or(Scalar::Null),
+        BinaryOp::Sub => (lhs - rhs).unwrap_or(Scalar::Null),
+        BinaryOp::Mul => (lhs * rhs).unwrap_or(Scalar::Null),
+        BinaryOp::Div => (lhs / rhs).unwrap_or(Scalar::Null),
+        BinaryOp::Eq => Scalar::Boolean(lhs.num_eq(rhs)),
+        BinaryOp::NotEq => Scalar::Boolean(!lhs.num_eq(rhs)),
+        BinaryOp::Lt => Scalar::Boolean(lhs < rhs),
+        BinaryOp::LtEq => Scalar::Boolean(lhs <= rhs),
+        BinaryOp::Gt => Scalar::Boolean(lhs > rhs),
+        BinaryOp::GtEq => Scalar::Boolean(lhs >= rhs),
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Writes the expression in the grammar of [`Expr::parse`], with every
+/// operation parenthesised.
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Col(name) if is_identifier(name) => write!(f, "{name}"),
+            Self::Col(name) => write!(f, "`{name}`"),
+            Self::Lit(Scalar::Text(value)) => {
+                write!(f, "'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+            Self::Lit(Scalar::F32(value)) => write!(f, "{value:?}"),
+            Self::Lit(Scalar::F64(value)) => write!(f, "{value:?}"),
+            Self::Lit(value) => write!(f, "{value}"),
+            Self::BinaryOp { op, lhs, rhs } => write!(f, "({lhs} {} {rhs})", op.symbol()),
+            Self::UnaryOp { op, expr } => write!(f, "{}{expr}", op.symbol()),
+            Self::IsNull(expr) => write!(f, "({expr} is null)"),
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|char| char.is_alphanumeric() || char == '_')
+        && keyword(name).is_none()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Scalar),
+    Text(String),
+    Ident(String),
+    /// Lowercased keyword.
+    Keyword(&'static str),
+    Symbol(&'static str),
+    End,
+}
+
+const KEYWORDS: [&str; 7] = ["and", "or", "not", "is", "null", "true", "false"];
+
+/// Longer symbols come first so they are matched before their prefixes.
+const SYMBOLS: [&str; 17] = [
+    "&&", "||", "==", "!=", "<>", "<=", ">=", "<", ">", "=", "!", "+", "-", "*", "/", "(", ")",
+];
+
+fn keyword(word: &str) -> Option<&'static str> {
+    KEYWORDS
+        .into_iter()
+        .find(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = vec![];
+    let mut pos = 0;
+
+    let error = |pos, message: &str| ExprError::Parse {
+        pos,
+        message: message.into(),
+    };
+
+    while let Some(char) = input[pos..].chars().next() {
+        let rest = &input[pos..];
+        let start = pos;
+
+        let token = if char.is_whitespace() {
+            pos += char.len_utf8();
+            continue;
+        } else if char.is_ascii_digit()
+            || (char == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            let mut len = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+
+            // Exponent, with an optional sign
+            if rest[len..].starts_with(['e', 'E']) {
+                let exponent = &rest[len + 1..];
+                let sign = usize::from(exponent.starts_with(['+', '-']));
+                let digits = exponent[sign..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(exponent.len() - sign);
+
+                if digits > 0 {
+                    len += 1 + sign + digits;
+                }
+            }
+
+            let number = &rest[..len];
+            pos += len;
+
+            let value = match number.parse::<isize>() {
+                Ok(value) => Scalar::ISize(value),
+                Err(_) => number
+                    .parse::<f64>()
+                    .map(Scalar::F64)
+                    .map_err(|_| error(start, "Invalid number"))?,
+            };
+
+            Token::Number(value)
+        } else if matches!(char, '\'' | '"' | '`') {
+            let mut value = String::new();
+            let mut chars = rest.char_indices().skip(1);
+
+            loop {
+                match chars.next() {
+                    Some((idx, quote)) if quote == char => {
+                        pos += idx + 1;
+                        break;
+                    }
+                    Some((_, '\\')) if char != '`' => match chars.next() {
+                        Some((_, escaped)) => value.push(escaped),
+                        None => return Err(error(start, "Unterminated quote")),
+                    },
+                    Some((_, other)) => value.push(other),
+                    None => return Err(error(start, "Unterminated quote")),
+                }
+            }
+
+            match char {
+                '`' => Token::Ident(value),
+                _ => Token::Text(value),
+            }
+        } else if char.is_alphabetic() || char == '_' {
+            let len = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            pos += len;
+
+            match keyword(word) {
+                Some(keyword) => Token::Keyword(keyword),
+                None => Token::Ident(word.into()),
+            }
+        } else {
+            let symbol = SYMBOLS
+                .into_iter()
+                .find(|symbol| rest.starts_with(symbol))
+                .ok_or_else(|| error(start, &format!("Unexpected character {char:?}")))?;
+            pos += symbol.len();
+
+            Token::Symbol(symbol)
+        };
+
+        tokens.push((start, token));
+    }
+
+    tokens.push((input.len(), Token::End));
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser, with a method for each level of precedence.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].1.clone();
+        self.pos = (self.pos + 1).min(self.tokens.len() - 1);
+        token
+    }
+
+    /// Consumes the next token if it is one of `tokens`.
+    fn eat(&mut self, tokens: &[Token]) -> Option<Token> {
+        match tokens.contains(self.peek()) {
+            true => Some(self.advance()),
+            false => None,
+        }
+    }
+
+    fn error(&self, message: &str) -> ExprError {
+        ExprError::Parse {
+            pos: self.tokens[self.pos].0,
+            message: message.into(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.and()?;
+
+        while self
+            .eat(&[Token::Symbol("||"), Token::Keyword("or")])
+            .is_some()
+        {
+            expr = Expr::binary(expr, BinaryOp::Or, self.and()?);
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.not()?;
+
+        while self
+            .eat(&[Token::Symbol("&&"), Token::Keyword("and")])
+            .is_some()
+        {
+            expr = Expr::binary(expr, BinaryOp::And, self.not()?);
+        }
+
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, ExprError> {
+        match self.eat(&[Token::Symbol("!"), Token::Keyword("not")]) {
+            Some(_) => Ok(Expr::unary(UnaryOp::Not, self.not()?)),
+            None => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ExprError> {
+        let expr = self.is_null()?;
+
+        let op = match self.peek() {
+            Token::Symbol("==" | "=") => BinaryOp::Eq,
+            Token::Symbol("!=" | "<>") => BinaryOp::NotEq,
+            Token::Symbol("<") => BinaryOp::Lt,
+            Token::Symbol("<=") => BinaryOp::LtEq,
+            Token::Symbol(">") => BinaryOp::Gt,
+            Token::Symbol(">=") => BinaryOp::GtEq,
+            _ => return Ok(expr),
+        };
+
+        self.advance();
+        let expr = Expr::binary(expr, op, self.is_null()?);
+
+        match self.peek() {
+            Token::Symbol("==" | "=" | "!=" | "<>" | "<" | "<=" | ">" | ">=") => {
+                Err(self.error("Comparisons cannot be chained"))
+            }
+            _ => Ok(expr),
+        }
+    }
+
+    fn is_null(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.additive()?;
+
+        while self.eat(&[Token::Keyword("is")]).is_some() {
+            let negated = self.eat(&[Token::Keyword("not")]).is_some();
+
+            if self.eat(&[Token::Keyword("null")]).is_none() {
+                return Err(self.error("Expected null"));
+            }
+
+            expr = match negated {
+                true => Expr::unary(UnaryOp::Not, Expr::is_null(expr)),
+                false => Expr::is_null(expr),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn additive(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.multiplicative()?;
+
+        while let Some(token) = self.eat(&[Token::Symbol("+"), Token::Symbol("-")]) {
+            let op = match token {
+                Token::Symbol("+") => BinaryOp::Add,
+                _ => BinaryOp::Sub,
+            };
+            expr = Expr::binary(expr, op, self.multiplicative()?);
+        }
+
+        Ok(expr)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.negation()?;
+
+        while let Some(token) = self.eat(&[Token::Symbol("*"), Token::Symbol("/")]) {
+            let op = match token {
+                Token::Symbol("*") => BinaryOp::Mul,
+                _ => BinaryOp::Div,
+            };
+            expr = Expr::binary(expr, op, self.negation()?);
+        }
+
+        Ok(expr)
+    }
+
+    fn negation(&mut self) -> Result<Expr, ExprError> {
+        match self.eat(&[Token::Symbol("-")]) {
+            Some(_) => Ok(Expr::unary(UnaryOp::Neg, self.negation()?)),
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ExprError> {
+        let expr = match self.peek().clone() {
+            Token::Number(value) => Expr::Lit(value),
+            Token::Text(value) => Expr::Lit(Scalar::Text(value)),
+            Token::Ident(name) => Expr::Col(name),
+            Token::Keyword("true") => Expr::lit(true),
+            Token::Keyword("false") => Expr::lit(false),
+            Token::Keyword("null") => Expr::Lit(Scalar::Null),
+            Token::Symbol("(") => {
+                self.advance();
+                let expr = self.or()?;
+
+                if self.eat(&[Token::Symbol(")")]).is_none() {
+                    return Err(self.error("Expected )"));
+                }
+
+                return Ok(expr);
+            }
+            Token::End => return Err(self.error("Unexpected end of the expression")),
+            _ => return Err(self.error("Expected a column, literal or (")),
+        };
+
+        self.advance();
+
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Field;
+    use crate::{ArrayBoolean, ArrayF64, ArrayI32, ArrayISize, ArrayText};
+
+    fn batch() -> RecordBatch {
+        RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("x", DataType::Int32, true),
+                Field::new("y", DataType::F64, false),
+                Field::new("name", DataType::Text, false),
+            ]),
+            vec![
+                ArrayI32::from([Some(2), None, Some(7)]).into(),
+                ArrayF64::from([0.5, 1.5, 2.5]).into(),
+                ArrayText::from(["a", "b", "c"]).into(),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn eval(input: &str) -> ColumnArray {
+        Expr::parse(input)
+            .unwrap()
+            .evaluate(&batch())
+            .unwrap()
+            .as_ref()
+            .clone()
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            Expr::binary(
+                Expr::lit(1_isize),
+                BinaryOp::Add,
+                Expr::binary(Expr::lit(2_isize), BinaryOp::Mul, Expr::lit(3_isize)),
+            ),
+            Expr::parse("1 + 2 * 3").unwrap()
+        );
+        assert_eq!(
+            Expr::binary(
+                Expr::binary(Expr::col("a"), BinaryOp::Sub, Expr::col("b")),
+                BinaryOp::Sub,
+                Expr::col("c"),
+            ),
+            Expr::parse("a - b - c").unwrap()
+        );
+        assert_eq!(
+            Expr::binary(
+                Expr::binary(
+                    Expr::unary(UnaryOp::Not, Expr::col("a")),
+                    BinaryOp::And,
+                    Expr::binary(Expr::col("b"), BinaryOp::Gt, Expr::lit(1_isize)),
+                ),
+                BinaryOp::Or,
+                Expr::unary(UnaryOp::Not, Expr::is_null(Expr::col("c"))),
+            ),
+            Expr::parse("NOT a and b > 1 || c is not null").unwrap()
+        );
+
+        assert_eq!(
+            ColumnArray::from(ArrayISize::from([Some(-12), None, Some(-22)])),
+            eval("-(x + 4) * 2")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayF64::from([Some(3.0), None, Some(12.0)])),
+            eval("x + y * 2 + 0")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([true, false, true])),
+            eval("name = 'a' or (y > 2 and `name` != \"b\")")
+        );
+    }
+
+    #[test]
+    fn test_null_semantics() {
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([Some(false), None, Some(true)])),
+            eval("x > 5")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([Some(false), None, Some(true)])),
+            eval("x == 7.0")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([Some(false), None, Some(false)])),
+            eval("x > 5 && y < 2")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([Some(true), Some(true), Some(true)])),
+            eval("x > 5 || y > 0")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([false, true, false])),
+            eval("x is null")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayISize::from([None, None, Some(1)])),
+            eval("x / (x - 2)")
+        );
+        assert_eq!(
+            ColumnArray::from(Union::from_vec(vec![Scalar::Null; 3])),
+            eval("null + null")
+        );
+
+        // Columns are shared rather than copied
+        let batch = batch();
+        let column = Expr::col("y").evaluate(&batch).unwrap();
+        assert!(Arc::ptr_eq(&batch.columns()[1], &column));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Err(ExprError::UnknownColumn("z".into())),
+            Expr::parse("x + z > 1").unwrap().evaluate(&batch())
+        );
+
+        let error = Expr::parse("name > 5")
+            .unwrap()
+            .evaluate(&batch())
+            .unwrap_err();
+        assert_eq!(
+            ExprError::TypeMismatch {
+                op: ">",
+                operands: vec![
+                    ("name".into(), DataType::Text),
+                    ("5".into(), DataType::ISize)
+                ],
+            },
+            error
+        );
+        assert_eq!(
+            "Cannot apply > to name of type Text and 5 of type ISize",
+            error.to_string()
+        );
+        assert!(matches!(
+            Expr::parse("!y").unwrap().evaluate(&batch()),
+            Err(ExprError::TypeMismatch { op: "!", .. })
+        ));
+
+        assert!(matches!(
+            Expr::parse("x > "),
+            Err(ExprError::Parse { pos: 4, .. })
+        ));
+        assert!(matches!(Expr::parse("(x"), Err(ExprError::Parse { .. })));
+        assert!(matches!(
+            Expr::parse("1 < x < 3"),
+            Err(ExprError::Parse { .. })
+        ));
+        assert!(matches!(
+            Expr::parse("x # 1"),
+            Err(ExprError::Parse { pos: 2, .. })
+        ));
+        assert!(matches!(
+            Expr::parse("'open"),
+            Err(ExprError::Parse { pos: 0, .. })
+        ));
+    }
+}
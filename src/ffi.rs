@@ -0,0 +1,560 @@
+use std::error::Error;
+use std::ffi::{c_char, c_void, CStr};
+use std::fmt::Display;
+
+use crate::column::ColumnArray;
+use crate::utils::{Array, DataType};
+use crate::{
+    ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize,
+};
+
+/// Schema description as laid out by the Arrow C Data Interface.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowSchema {
+    pub format: *const c_char,
+    pub name: *const c_char,
+    pub metadata: *const c_char,
+    pub flags: i64,
+    pub n_children: i64,
+    pub children: *mut *mut ArrowSchema,
+    pub dictionary: *mut ArrowSchema,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    pub private_data: *mut c_void,
+}
+
+/// Array data as laid out by the Arrow C Data Interface.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowArray {
+    pub length: i64,
+    pub null_count: i64,
+    pub offset: i64,
+    pub n_buffers: i64,
+    pub n_children: i64,
+    pub buffers: *mut *const c_void,
+    pub children: *mut *mut ArrowArray,
+    pub dictionary: *mut ArrowArray,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    pub private_data: *mut c_void,
+}
+
+/// Errors raised while importing data through the C Data Interface.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FfiError {
+    /// The format string does not correspond to a supported [`DataType`].
+    UnknownFormat(String),
+    /// The named pointer was null when a value was required.
+    NullPointer(&'static str),
+    /// The number of buffers does not match the one required by the format.
+    BufferCount { expected: i64, got: i64 },
+    /// The length or offset of the array was negative.
+    InvalidLength { length: i64, offset: i64 },
+    /// The reported null count disagrees with the validity buffer.
+    NullCountMismatch { reported: i64, counted: usize },
+    /// The structure had already been released by its producer.
+    Released,
+    /// A text value was not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl Display for FfiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownFormat(format) => write!(f, "Unknown format string \"{format}\""),
+            Self::NullPointer(name) => write!(f, "Found null pointer for {name}"),
+            Self::BufferCount { expected, got } => {
+                write!(f, "Expected {expected} buffers but got {got}")
+            }
+            Self::InvalidLength { length, offset } => {
+                write!(f, "Invalid array length {length} with offset {offset}")
+            }
+            Self::NullCountMismatch { reported, counted } => write!(
+                f,
+                "Reported null count {reported} but validity buffer has {counted} nulls"
+            ),
+            Self::Released => write!(f, "Tried to import a released structure"),
+            Self::InvalidUtf8 => write!(f, "Text buffer contained invalid utf-8"),
+        }
+    }
+}
+
+impl Error for FfiError {}
+
+/// Owns the imported structures, releasing each exactly once on drop.
+struct Imported {
+    array: ArrowArray,
+    schema: ArrowSchema,
+}
+
+impl Drop for Imported {
+    fn drop(&mut self) {
+        if let Some(release) = self.array.release {
+            unsafe { release(&mut self.array) };
+        }
+
+        if let Some(release) = self.schema.release {
+            unsafe { release(&mut self.schema) };
+        }
+    }
+}
+
+impl Imported {
+    /// Returns the buffer at `idx`, which may be null.
+    fn buffer(&self, idx: usize) -> *const c_void {
+        unsafe { *self.array.buffers.add(idx) }
+    }
+
+    /// Returns the buffer at `idx`, erroring if it is null.
+    fn required_buffer(&self, idx: usize, name: &'static str) -> Result<*const c_void, FfiError> {
+        let buffer = self.buffer(idx);
+
+        if buffer.is_null() {
+            return Err(FfiError::NullPointer(name));
+        }
+
+        Ok(buffer)
+    }
+
+    /// Reads the validity bitmap, returning whether each element is valid.
+    fn validity(&self, len: usize, offset: usize) -> Result<Vec<bool>, FfiError> {
+        let buffer = self.buffer(0) as *const u8;
+
+        if buffer.is_null() {
+            if self.array.null_count > 0 {
+                return Err(FfiError::NullPointer("validity buffer"));
+            }
+
+            return Ok(vec![true; len]);
+        }
+
+        let validity = (offset..offset + len)
+            .map(|idx| {
+                let byte = unsafe { *buffer.add(idx / 8) };
+                byte & (1 << (idx % 8)) != 0
+            })
+            .collect::<Vec<_>>();
+
+        let counted = validity.iter().filter(|valid| !**valid).count();
+
+        if self.array.null_count >= 0 && self.array.null_count as usize != counted {
+            return Err(FfiError::NullCountMismatch {
+                reported: self.array.null_count,
+                counted,
+            });
+        }
+
+        Ok(validity)
+    }
+
+    /// Reads a fixed sized primitive buffer into options.
+    fn primitive<T: Copy>(&self, len: usize, offset: usize) -> Result<Vec<Option<T>>, FfiError> {
+        let validity = self.validity(len, offset)?;
+
+        if len == 0 {
+            return Ok(vec![]);
+        }
+
+        let values = self.required_buffer(1, "values buffer")? as *const T;
+
+        let values = validity
+            .into_iter()
+            .enumerate()
+            .map(|(idx, valid)| valid.then(|| unsafe { *values.add(offset + idx) }))
+            .collect();
+
+        Ok(values)
+    }
+
+    /// Reads a bit packed boolean buffer into options.
+    fn boolean(&self, len: usize, offset: usize) -> Result<Vec<Option<bool>>, FfiError> {
+        let validity = self.validity(len, offset)?;
+
+        if len == 0 {
+            return Ok(vec![]);
+        }
+
+        let values = self.required_buffer(1, "values buffer")? as *const u8;
+
+        let values = validity
+            .into_iter()
+            .enumerate()
+            .map(|(idx, valid)| {
+                let idx = offset + idx;
+                let byte = unsafe { *values.add(idx / 8) };
+                valid.then_some(byte & (1 << (idx % 8)) != 0)
+            })
+            .collect();
+
+        Ok(values)
+    }
+
+    /// Reads a variable sized text buffer using offsets of type `O`.
+    fn text<O>(&self, len: usize, offset: usize) -> Result<Vec<Option<String>>, FfiError>
+    where
+        O: Copy + TryInto<usize>,
+    {
+        let validity = self.validity(len, offset)?;
+
+        if len == 0 {
+            return Ok(vec![]);
+        }
+
+        let offsets = self.required_buffer(1, "offsets buffer")? as *const O;
+        let data = self.buffer(2) as *const u8;
+
+        let read_offset = |idx: usize| -> Result<usize, FfiError> {
+            let value = unsafe { *offsets.add(idx) };
+            value.try_into().map_err(|_| FfiError::InvalidLength {
+                length: self.array.length,
+                offset: self.array.offset,
+            })
+        };
+
+        let mut values = Vec::with_capacity(len);
+
+        for (idx, valid) in validity.into_iter().enumerate() {
+            if !valid {
+                values.push(None);
+                continue;
+            }
+
+            let start = read_offset(offset + idx)?;
+            let end = read_offset(offset + idx + 1)?;
+
+            if end < start {
+                return Err(FfiError::InvalidLength {
+                    length: self.array.length,
+                    offset: self.array.offset,
+                });
+            }
+
+            if start == end {
+                values.push(Some(String::new()));
+                continue;
+            }
+
+            if data.is_null() {
+                return Err(FfiError::NullPointer("data buffer"));
+            }
+
+            let bytes = unsafe { std::slice::from_raw_parts(data.add(start), end - start) };
+            let text = std::str::from_utf8(bytes).map_err(|_| FfiError::InvalidUtf8)?;
+
+            values.push(Some(text.to_owned()));
+        }
+
+        Ok(values)
+    }
+}
+
+/// Format of the imported column as understood by this crate.
+enum Format {
+    Supported(DataType),
+    /// Utf8 with 32 bit offsets, stored as [`DataType::Text`].
+    SmallText,
+}
+
+fn parse_format(format: &str) -> Result<Format, FfiError> {
+    let isize_format = if std::mem::size_of::<isize>() == 8 {
+        "l"
+    } else {
+        "i"
+    };
+    let usize_format = if std::mem::size_of::<usize>() == 8 {
+        "L"
+    } else {
+        "I"
+    };
+
+    let kind = match format {
+        "i" => DataType::Int32,
+        "I" => DataType::UInt32,
+        "b" => DataType::Boolean,
+        "f" => DataType::F32,
+        "g" => DataType::F64,
+        "U" => DataType::Text,
+        "u" => return Ok(Format::SmallText),
+        _ if format == isize_format => DataType::ISize,
+        _ if format == usize_format => DataType::USize,
+        _ => return Err(FfiError::UnknownFormat(format.into())),
+    };
+
+    Ok(Format::Supported(kind))
+}
+
+/// Imports an array exported through the Arrow C Data Interface.
+///
+/// The data is copied into a newly allocated array, after which the release
+/// callbacks of both `array` and `schema` are called exactly once, whether or
+/// not the import succeeded.
+///
+/// # Safety
+///
+/// `array` and `schema` must have been produced by a valid Arrow C Data
+/// Interface producer and must not have been released yet. Every pointer
+/// they hold has to be valid for the lengths they describe. Ownership of
+/// both structs moves into this call, so the caller must not release or use
+/// them afterwards.
+pub unsafe fn from_c_array(
+    array: ArrowArray,
+    schema: ArrowSchema,
+) -> Result<ColumnArray, FfiError> {
+    let released = array.release.is_none() || schema.release.is_none();
+    let imported = Imported { array, schema };
+
+    if released {
+        return Err(FfiError::Released);
+    }
+
+    if imported.schema.format.is_null() {
+        return Err(FfiError::NullPointer("format"));
+    }
+
+    let format = unsafe { CStr::from_ptr(imported.schema.format) }
+        .to_str()
+        .map_err(|_| FfiError::InvalidUtf8)?;
+    let format = parse_format(format)?;
+
+    let expected = match format {
+        Format::Supported(DataType::Text) | Format::SmallText => 3,
+        _ => 2,
+    };
+
+    if imported.array.n_buffers != expected {
+        return Err(FfiError::BufferCount {
+            expected,
+            got: imported.array.n_buffers,
+        });
+    }
+
+    if imported.array.buffers.is_null() {
+        return Err(FfiError::NullPointer("buffers"));
+    }
+
+    let (length, offset) = (imported.array.length, imported.array.offset);
+
+    if length < 0 || offset < 0 || imported.array.null_count > length {
+        return Err(FfiError::InvalidLength { length, offset });
+    }
+
+    let (len, offset) = (length as usize, offset as usize);
+
+    let column = match format {
+        Format::Supported(DataType::Int32) => {
+            ArrayI32::from_vec(imported.primitive(len, offset)?).into()
+        }
+        Format::Supported(DataType::UInt32) => {
+            ArrayU32::from_vec(imported.primitive(len, offset)?).into()
+        }
+        Format::Supported(DataType::ISize) => {
+            ArrayISize::from_vec(imported.primitive(len, offset)?).into()
+        }
+        Format::Supported(DataType::USize) => {
+            ArrayUSize::from_vec(imported.primitive(len, offset)?).into()
+        }
+        Format::Supported(DataType::F32) => {
+            ArrayF32::from_vec(imported.primitive(len, offset)?).into()
+        }
+        Format::Supported(DataType::F64) => {
+            ArrayF64::from_vec(imported.primitive(len, offset)?).into()
+        }
+        Format::Supported(DataType::Boolean) => {
+            ArrayBoolean::from_vec(imported.boolean(len, offset)?).into()
+        }
+        Format::Supported(DataType::Text) => {
+            ArrayText::from_vec(imported.text::<i64>(len, offset)?).into()
+        }
+        Format::SmallText => ArrayText::from_vec(imported.text::<i32>(len, offset)?).into(),
+        Format::Supported(DataType::Union) => unreachable!("Union has no format mapping"),
+    };
+
+    Ok(column)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::ptr;
+    use std::rc::Rc;
+
+    /// Buffers kept alive until the producer side releases them.
+    struct Private {
+        buffers: Vec<Vec<u8>>,
+        pointers: Vec<*const c_void>,
+        releases: Rc<Cell<usize>>,
+    }
+
+    unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+        let array = unsafe { &mut *array };
+        let private = unsafe { Box::from_raw(array.private_data as *mut Private) };
+        private.releases.set(private.releases.get() + 1);
+        array.release = None;
+    }
+
+    unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+        let schema = unsafe { &mut *schema };
+        let private =
+            unsafe { Box::from_raw(schema.private_data as *mut (Vec<u8>, Rc<Cell<usize>>)) };
+        private.1.set(private.1.get() + 1);
+        schema.release = None;
+    }
+
+    fn schema(format: &str) -> ArrowSchema {
+        schema_counted(format, Rc::default())
+    }
+
+    fn schema_counted(format: &str, releases: Rc<Cell<usize>>) -> ArrowSchema {
+        let mut format = format.as_bytes().to_vec();
+        format.push(0);
+        let format = Box::new((format, releases));
+
+        ArrowSchema {
+            format: format.0.as_ptr() as *const c_char,
+            name: ptr::null(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_schema),
+            private_data: Box::into_raw(format) as *mut c_void,
+        }
+    }
+
+    /// Exports `buffers`, where an empty buffer is exported as a null pointer.
+    fn array(length: i64, null_count: i64, buffers: Vec<Vec<u8>>) -> ArrowArray {
+        array_counted(length, null_count, buffers, Rc::default())
+    }
+
+    fn array_counted(
+        length: i64,
+        null_count: i64,
+        buffers: Vec<Vec<u8>>,
+        releases: Rc<Cell<usize>>,
+    ) -> ArrowArray {
+        let pointers = buffers
+            .iter()
+            .map(|buffer| {
+                if buffer.is_empty() {
+                    ptr::null()
+                } else {
+                    buffer.as_ptr() as *const c_void
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut private = Box::new(Private {
+            buffers,
+            pointers,
+            releases,
+        });
+
+        ArrowArray {
+            length,
+            null_count,
+            offset: 0,
+            n_buffers: private.buffers.len() as i64,
+            n_children: 0,
+            buffers: private.pointers.as_mut_ptr(),
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_array),
+            private_data: Box::into_raw(private) as *mut c_void,
+        }
+    }
+
+    fn bytes<T: Copy>(values: &[T]) -> Vec<u8> {
+        let size = std::mem::size_of_val(values);
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const u8, size) }.to_vec()
+    }
+
+    #[test]
+    fn test_import_primitive() {
+        let values = bytes(&[1.5_f64, 0.0, -2.5, 4.0]);
+        let one = array(4, 1, vec![vec![0b1101], values]);
+
+        let one = unsafe { from_c_array(one, schema("g")) }.unwrap();
+        let two = ArrayF64::from([Some(1.5), None, Some(-2.5), Some(4.0)]);
+
+        assert_eq!(ColumnArray::F64(two), one);
+
+        let values = bytes(&[7_i32, -8]);
+        let one = array(2, 0, vec![vec![], values]);
+
+        let one = unsafe { from_c_array(one, schema("i")) }.unwrap();
+        assert_eq!(ColumnArray::Int32(ArrayI32::from([7, -8])), one);
+
+        let one = array(3, -1, vec![vec![0b011], vec![0b001]]);
+
+        let one = unsafe { from_c_array(one, schema("b")) }.unwrap();
+        let two = ArrayBoolean::from([Some(true), Some(false), None]);
+        assert_eq!(ColumnArray::Boolean(two), one);
+    }
+
+    #[test]
+    fn test_import_text() {
+        let offsets = bytes(&[0_i64, 3, 3, 8]);
+        let data = "onebuble".as_bytes().to_vec();
+        let one = array(3, 1, vec![vec![0b101], offsets, data]);
+
+        let one = unsafe { from_c_array(one, schema("U")) }.unwrap();
+        let two = ArrayText::from([Some("one".into()), None, Some("buble".into())]);
+
+        assert_eq!(ColumnArray::Text(two.clone()), one);
+
+        let offsets = bytes(&[0_i32, 3, 3, 8]);
+        let data = "onebuble".as_bytes().to_vec();
+        let one = array(3, 1, vec![vec![0b101], offsets, data]);
+
+        let one = unsafe { from_c_array(one, schema("u")) }.unwrap();
+        assert_eq!(ColumnArray::Text(two), one);
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let one = array(2, 0, vec![vec![], bytes(&[1_i32, 2])]);
+        let one = unsafe { from_c_array(one, schema("+s")) };
+        assert_eq!(Err(FfiError::UnknownFormat("+s".into())), one);
+
+        let one = array(2, 0, vec![vec![], vec![]]);
+        let one = unsafe { from_c_array(one, schema("i")) };
+        assert_eq!(Err(FfiError::NullPointer("values buffer")), one);
+
+        let one = array(3, 2, vec![vec![0b110], bytes(&[1_i32, 2, 3])]);
+        assert_eq!(
+            Err(FfiError::NullCountMismatch {
+                reported: 2,
+                counted: 1
+            }),
+            unsafe { from_c_array(one, schema("i")) }
+        );
+
+        let one = array(3, 0, vec![vec![], bytes(&[1_i32, 2, 3])]);
+        assert_eq!(
+            Err(FfiError::BufferCount {
+                expected: 3,
+                got: 2
+            }),
+            unsafe { from_c_array(one, schema("U")) }
+        );
+    }
+
+    #[test]
+    fn test_release_once() {
+        let releases = Rc::new(Cell::new(0));
+
+        let one = array_counted(1, 0, vec![vec![], bytes(&[1_u32])], releases.clone());
+        let two = schema_counted("I", releases.clone());
+        assert!(unsafe { from_c_array(one, two) }.is_ok());
+        assert_eq!(2, releases.get());
+
+        let releases = Rc::new(Cell::new(0));
+
+        let one = array_counted(1, 0, vec![vec![], bytes(&[1_u32])], releases.clone());
+        let two = schema_counted("?", releases.clone());
+        assert!(unsafe { from_c_array(one, two) }.is_err());
+        assert_eq!(2, releases.get());
+    }
+}
@@ -0,0 +1,412 @@
+//! Minimal flatbuffer encoding, covering just what the Arrow IPC messages
+//! need.
+//!
+//! Unlike the reference builder, buffers are written front to back: a table
+//! is followed by the objects it references so every offset points forward.
+
+/// Value of a single table field.
+pub(crate) enum Value {
+    Bool(bool),
+    U8(u8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    String(String),
+    Table(Table),
+    Tables(Vec<Table>),
+    /// Vector of 8 byte aligned structs given as their raw bytes.
+    Structs {
+        bytes: Vec<u8>,
+        count: usize,
+    },
+    I32s(Vec<i32>),
+}
+
+impl Value {
+    /// Returns the inline size of the value, which is also its alignment.
+    fn inline_size(&self) -> usize {
+        match self {
+            Self::Bool(_) | Self::U8(_) => 1,
+            Self::I16(_) => 2,
+            Self::I64(_) => 8,
+            _ => 4,
+        }
+    }
+}
+
+/// Table whose fields are identified by their id in the schema.
+#[derive(Default)]
+pub(crate) struct Table {
+    fields: Vec<(u16, Value)>,
+}
+
+impl Table {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with(mut self, id: u16, value: Value) -> Self {
+        self.fields.push((id, value));
+        self
+    }
+}
+
+/// Encodes `root` as a finished flatbuffer, padded to a multiple of 8 bytes.
+pub(crate) fn finish(root: &Table) -> Vec<u8> {
+    let mut buf = vec![0; 4];
+
+    let root = write_table(&mut buf, root);
+    patch(&mut buf, 0, root);
+    pad(&mut buf, 8);
+
+    buf
+}
+
+fn pad(buf: &mut Vec<u8>, align: usize) {
+    while !buf.len().is_multiple_of(align) {
+        buf.push(0);
+    }
+}
+
+/// Points the offset at `at` to `target`.
+fn patch(buf: &mut [u8], at: usize, target: usize) {
+    let offset = (target - at) as u32;
+    buf[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+}
+
+fn write_table(buf: &mut Vec<u8>, table: &Table) -> usize {
+    let mut order = table.fields.iter().collect::<Vec<_>>();
+    order.sort_by_key(|(_, value)| std::cmp::Reverse(value.inline_size()));
+
+    // Inline layout, starting after the vtable offset
+    let mut size = 4_usize;
+    let mut layout = Vec::with_capacity(order.len());
+
+    for (id, value) in order {
+        let field_size = value.inline_size();
+        size = size.next_multiple_of(field_size);
+        layout.push((*id, value, size));
+        size += field_size;
+    }
+
+    // Vtable
+    let slots = table
+        .fields
+        .iter()
+        .map(|(id, _)| *id as usize + 1)
+        .max()
+        .unwrap_or_default();
+
+    pad(buf, 2);
+    let vtable = buf.len();
+    buf.extend_from_slice(&((4 + 2 * slots) as u16).to_le_bytes());
+    buf.extend_from_slice(&(size as u16).to_le_bytes());
+
+    for slot in 0..slots {
+        let offset = layout
+            .iter()
+            .find(|(id, _, _)| *id as usize == slot)
+            .map(|(_, _, offset)| *offset as u16)
+            .unwrap_or_default();
+        buf.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    // Inline fields
+    pad(buf, 8);
+    let start = buf.len();
+    buf.extend_from_slice(&((start - vtable) as i32).to_le_bytes());
+    buf.resize(start + size, 0);
+
+    let mut children = vec![];
+
+    for (_, value, offset) in layout {
+        let at = start + offset;
+
+        match value {
+            Value::Bool(value) => buf[at] = *value as u8,
+            Value::U8(value) => buf[at] = *value,
+            Value::I16(value) => buf[at..at + 2].copy_from_slice(&value.to_le_bytes()),
+            Value::I32(value) => buf[at..at + 4].copy_from_slice(&value.to_le_bytes()),
+            Value::I64(value) => buf[at..at + 8].copy_from_slice(&value.to_le_bytes()),
+            _ => children.push((at, value)),
+        }
+    }
+
+    // Referenced objects
+    for (at, value) in children {
+        let target = write_object(buf, value);
+        patch(buf, at, target);
+    }
+
+    start
+}
+
+/// Writes an object referenced by offset, returning its position.
+fn write_object(buf: &mut Vec<u8>, value: &Value) -> usize {
+    match value {
+        Value::String(text) => {
+            pad(buf, 4);
+            let start = buf.len();
+            buf.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            buf.extend_from_slice(text.as_bytes());
+            buf.push(0);
+            start
+        }
+        Value::Table(table) => write_table(buf, table),
+        Value::Tables(tables) => {
+            pad(buf, 4);
+            let start = buf.len();
+            buf.extend_from_slice(&(tables.len() as u32).to_le_bytes());
+            buf.resize(start + 4 + 4 * tables.len(), 0);
+
+            for (idx, table) in tables.iter().enumerate() {
+                let target = write_table(buf, table);
+                patch(buf, start + 4 + 4 * idx, target);
+            }
+
+            start
+        }
+        Value::Structs { bytes, count } => {
+            // The elements following the length must be 8 byte aligned
+            pad(buf, 4);
+            if !(buf.len() + 4).is_multiple_of(8) {
+                buf.extend_from_slice(&[0; 4]);
+            }
+
+            let start = buf.len();
+            buf.extend_from_slice(&(*count as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
+            start
+        }
+        Value::I32s(values) => {
+            pad(buf, 4);
+            let start = buf.len();
+            buf.extend_from_slice(&(values.len() as u32).to_le_bytes());
+            values
+                .iter()
+                .for_each(|value| buf.extend_from_slice(&value.to_le_bytes()));
+            start
+        }
+        _ => unreachable!("Scalars are stored inline"),
+    }
+}
+
+/// Error raised when a flatbuffer is truncated or holds out of range
+/// offsets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct InvalidFlatbuffer;
+
+type Decoded<T> = Result<T, InvalidFlatbuffer>;
+
+/// Bounds checked view of a table within a flatbuffer.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TableRef<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TableRef<'a> {
+    /// Returns the root table of the finished flatbuffer `buf`.
+    pub(crate) fn root(buf: &'a [u8]) -> Decoded<Self> {
+        Self::at(buf, indirect(buf, 0)?)
+    }
+
+    fn at(buf: &'a [u8], pos: usize) -> Decoded<Self> {
+        let table = Self { buf, pos };
+        // Reject tables whose vtable cannot be read up front
+        table.vtable()?;
+        Ok(table)
+    }
+
+    /// Returns the position and length of the table's vtable.
+    fn vtable(&self) -> Decoded<(usize, usize)> {
+        let offset = i32::from_le_bytes(read(self.buf, self.pos)?) as isize;
+        let vtable = (self.pos as isize)
+            .checked_sub(offset)
+            .and_then(|pos| usize::try_from(pos).ok())
+            .ok_or(InvalidFlatbuffer)?;
+        let len = u16::from_le_bytes(read(self.buf, vtable)?) as usize;
+
+        Ok((vtable, len))
+    }
+
+    /// Returns the absolute position of field `id`, if it is present.
+    fn field(&self, id: u16) -> Decoded<Option<usize>> {
+        let (vtable, len) = self.vtable()?;
+        let entry = 4 + 2 * id as usize;
+
+        if entry + 2 > len {
+            return Ok(None);
+        }
+
+        let offset = u16::from_le_bytes(read(self.buf, vtable + entry)?) as usize;
+
+        Ok((offset != 0).then_some(self.pos + offset))
+    }
+
+    fn scalar<const N: usize>(&self, id: u16) -> Decoded<Option<[u8; N]>> {
+        self.field(id)?.map(|pos| read(self.buf, pos)).transpose()
+    }
+
+    pub(crate) fn bool(&self, id: u16, default: bool) -> Decoded<bool> {
+        Ok(self.scalar::<1>(id)?.map_or(default, |[byte]| byte != 0))
+    }
+
+    pub(crate) fn u8(&self, id: u16, default: u8) -> Decoded<u8> {
+        Ok(self.scalar::<1>(id)?.map_or(default, |[byte]| byte))
+    }
+
+    pub(crate) fn i16(&self, id: u16, default: i16) -> Decoded<i16> {
+        Ok(self.scalar(id)?.map_or(default, i16::from_le_bytes))
+    }
+
+    pub(crate) fn i32(&self, id: u16, default: i32) -> Decoded<i32> {
+        Ok(self.scalar(id)?.map_or(default, i32::from_le_bytes))
+    }
+
+    pub(crate) fn i64(&self, id: u16, default: i64) -> Decoded<i64> {
+        Ok(self.scalar(id)?.map_or(default, i64::from_le_bytes))
+    }
+
+    pub(crate) fn table(&self, id: u16) -> Decoded<Option<TableRef<'a>>> {
+        self.field(id)?
+            .map(|pos| Self::at(self.buf, indirect(self.buf, pos)?))
+            .transpose()
+    }
+
+    pub(crate) fn string(&self, id: u16) -> Decoded<Option<&'a str>> {
+        self.vector(id, 1)?
+            .map(|bytes| std::str::from_utf8(bytes).map_err(|_| InvalidFlatbuffer))
+            .transpose()
+    }
+
+    /// Returns the elements of a vector of tables, which is empty if absent.
+    pub(crate) fn tables(&self, id: u16) -> Decoded<Vec<TableRef<'a>>> {
+        let Some(pos) = self.field(id)? else {
+            return Ok(vec![]);
+        };
+
+        let start = indirect(self.buf, pos)?;
+        let len = u32::from_le_bytes(read(self.buf, start)?) as usize;
+
+        (0..len)
+            .map(|idx| {
+                let at = start + 4 + 4 * idx;
+                Self::at(self.buf, indirect(self.buf, at)?)
+            })
+            .collect()
+    }
+
+    /// Returns the raw bytes of a vector whose elements are `size` bytes.
+    pub(crate) fn vector(&self, id: u16, size: usize) -> Decoded<Option<&'a [u8]>> {
+        let Some(pos) = self.field(id)? else {
+            return Ok(None);
+        };
+
+        let start = indirect(self.buf, pos)?;
+        let len = u32::from_le_bytes(read(self.buf, start)?) as usize;
+        let end = len
+            .checked_mul(size)
+            .and_then(|bytes| bytes.checked_add(start + 4))
+            .ok_or(InvalidFlatbuffer)?;
+
+        self.buf
+            .get(start + 4..end)
+            .map(Some)
+            .ok_or(InvalidFlatbuffer)
+    }
+
+    pub(crate) fn i32s(&self, id: u16) -> Decoded<Option<Vec<i32>>> {
+        Ok(self.vector(id, 4)?.map(|bytes| {
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| i32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect()
+        }))
+    }
+}
+
+fn read<const N: usize>(buf: &[u8], pos: usize) -> Decoded<[u8; N]> {
+    buf.get(pos..pos + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(InvalidFlatbuffer)
+}
+
+/// Follows the offset stored at `pos`.
+fn indirect(buf: &[u8], pos: usize) -> Decoded<usize> {
+    let offset = u32::from_le_bytes(read(buf, pos)?) as usize;
+
+    pos.checked_add(offset).ok_or(InvalidFlatbuffer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let child = Table::new()
+            .with(0, Value::String("Bublé".into()))
+            .with(1, Value::Bool(true));
+
+        let root = Table::new()
+            .with(0, Value::I16(-4))
+            .with(1, Value::U8(3))
+            .with(2, Value::Table(child))
+            .with(4, Value::I64(i64::MIN))
+            .with(
+                5,
+                Value::Tables(vec![Table::new(), Table::new().with(0, Value::I32(9))]),
+            )
+            .with(
+                6,
+                Value::Structs {
+                    bytes: 7_i64.to_le_bytes().into(),
+                    count: 1,
+                },
+            )
+            .with(7, Value::I32s(vec![1, -2, 3]));
+
+        let buf = finish(&root);
+        assert!(buf.len().is_multiple_of(8));
+
+        let root = TableRef::root(&buf).unwrap();
+        assert_eq!(Ok(-4), root.i16(0, 0));
+        assert_eq!(Ok(3), root.u8(1, 0));
+        assert_eq!(Ok(i64::MIN), root.i64(4, 0));
+        assert_eq!(Ok(Some(vec![1, -2, 3])), root.i32s(7));
+
+        let structs = root.vector(6, 8).unwrap().unwrap();
+        assert_eq!(7_i64.to_le_bytes(), structs);
+        // Struct elements are 8 byte aligned within the buffer
+        assert!((structs.as_ptr() as usize - buf.as_ptr() as usize).is_multiple_of(8));
+
+        let child = root.table(2).unwrap().unwrap();
+        assert_eq!(Ok(Some("Bublé")), child.string(0));
+        assert_eq!(Ok(true), child.bool(1, false));
+
+        let tables = root.tables(5).unwrap();
+        assert_eq!(2, tables.len());
+        assert_eq!(Ok(-1), tables[0].i32(0, -1));
+        assert_eq!(Ok(9), tables[1].i32(0, -1));
+    }
+
+    #[test]
+    fn test_defaults_and_truncation() {
+        let buf = finish(&Table::new().with(1, Value::Bool(true)));
+        let root = TableRef::root(&buf).unwrap();
+
+        assert_eq!(Ok(7), root.i32(0, 7));
+        assert_eq!(Ok(12), root.i64(9, 12));
+        assert_eq!(Ok(None), root.table(3).map(|table| table.map(|_| ())));
+        assert_eq!(Ok(None), root.string(0));
+        assert!(root.tables(2).unwrap().is_empty());
+
+        let buf = finish(&Table::new().with(0, Value::String("text".into())));
+        for len in 0..buf.len() - 8 {
+            let truncated = &buf[..len];
+            let decoded = TableRef::root(truncated).and_then(|root| root.string(0));
+            assert!(decoded.is_err());
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Deterministic random arrays and batches for property tests and
+//! benchmarks.
+//!
+//! Every generator is seeded, so a failing case can be reproduced from its
+//! seed alone.
+
+use std::ops::Range;
+
+use crate::column::ColumnArray;
+use crate::record_batch::RecordBatch;
+use crate::schema::Schema;
+use crate::union::UnionType;
+use crate::utils::{Array, DataType, Rng};
+use crate::{
+    ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize, Union,
+};
+
+/// Chance of each element of a nullable field being null in
+/// [`random_batch`].
+const BATCH_NULL_PROBABILITY: f64 = 0.1;
+
+impl Rng {
+    /// Returns `len` values from `value`, each null with probability
+    /// `null_probability`.
+    fn values<T, F>(&mut self, len: usize, null_probability: f64, mut value: F) -> Vec<Option<T>>
+    where
+        F: FnMut(&mut Self) -> T,
+    {
+        (0..len)
+            .map(|_| match self.chance(null_probability) {
+                true => None,
+                false => Some(value(self)),
+            })
+            .collect()
+    }
+}
+
+/// Returns `len` floats drawn uniformly from `range`, each null with
+/// probability `null_probability`.
+pub fn random_f64(len: usize, null_probability: f64, range: Range<f64>, seed: u64) -> ArrayF64 {
+    let mut rng = Rng(seed);
+    let values = rng.values(len, null_probability, |rng| {
+        range.start + rng.next_f64() * (range.end - range.start)
+    });
+
+    ArrayF64::new(values)
+}
+
+/// Returns `len` integers drawn uniformly from `range`, each null with
+/// probability `null_probability`.
+///
+/// Panics if `range` is empty.
+pub fn random_i32(len: usize, null_probability: f64, range: Range<i32>, seed: u64) -> ArrayI32 {
+    let mut rng = Rng(seed);
+    let range = i64::from(range.start)..i64::from(range.end);
+    let values = rng.values(len, null_probability, |rng| {
+        rng.in_range(range.clone()) as i32
+    });
+
+    ArrayI32::new(values)
+}
+
+/// Returns `len` fair coin flips, each null with probability
+/// `null_probability`.
+pub fn random_bool(len: usize, null_probability: f64, seed: u64) -> ArrayBoolean {
+    let mut rng = Rng(seed);
+    let values = rng.values(len, null_probability, |rng| rng.chance(0.5));
+
+    ArrayBoolean::new(values)
+}
+
+/// Returns `len` strings of up to `max_len` characters drawn from
+/// `charset`, each null with probability `null_probability`.
+///
+/// Panics if `charset` is empty.
+pub fn random_text(
+    len: usize,
+    null_probability: f64,
+    charset: &str,
+    max_len: usize,
+    seed: u64,
+) -> ArrayText {
+    let charset = charset.chars().collect::<Vec<_>>();
+    assert!(
+        !charset.is_empty(),
+        "Tried to generate text from an empty charset"
+    );
+
+    let mut rng = Rng(seed);
+    let values = rng.values(len, null_probability, |rng| {
+        let len = rng.below(max_len as u64 + 1);
+        (0..len)
+            .map(|_| charset[rng.below(charset.len() as u64) as usize])
+            .collect::<String>()
+    });
+
+    ArrayText::new(values)
+}
+
+/// Returns a batch of `rows` random rows matching `schema`.
+///
+/// Elements of nullable fields are null one time in ten while the other
+/// fields have no nulls. Union columns mix integers, floats, text and
+/// nulls.
+pub fn random_batch(schema: &Schema, rows: usize, seed: u64) -> RecordBatch {
+    let mut seeds = Rng(seed);
+
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let seed = seeds.next_u64();
+            let nulls = match field.nullable {
+                true => BATCH_NULL_PROBABILITY,
+                false => 0.0,
+            };
+
+            random_column(field.data_type, rows, nulls, seed)
+        })
+        .collect();
+
+    RecordBatch::try_new(schema.clone(), columns).expect("Generated columns match their schema")
+}
+
+fn random_column(data_type: DataType, len: usize, nulls: f64, seed: u64) -> ColumnArray {
+    let mut rng = Rng(seed);
+
+    match data_type {
+        DataType::Int32 => random_i32(len, nulls, -1_000..1_000, seed).into(),
+        DataType::UInt32 => {
+            ArrayU32::new(rng.values(len, nulls, |rng| rng.below(1_000) as u32)).into()
+        }
+        DataType::ISize => {
+            ArrayISize::new(rng.values(len, nulls, |rng| rng.in_range(-1_000..1_000) as isize))
+                .into()
+        }
+        DataType::USize => {
+            ArrayUSize::new(rng.values(len, nulls, |rng| rng.below(1_000) as usize)).into()
+        }
+        DataType::Boolean => random_bool(len, nulls, seed).into(),
+        DataType::F32 => {
+            ArrayF32::new(rng.values(len, nulls, |rng| rng.next_f64() as f32 * 100.0)).into()
+        }
+        DataType::F64 => random_f64(len, nulls, -100.0..100.0, seed).into(),
+        DataType::Text => random_text(len, nulls, "abcdefghij", 8, seed).into(),
+        DataType::Union => {
+            let values = rng.values(len, nulls, |rng| match rng.below(3) {
+                0 => UnionType::I32(rng.in_range(-1_000..1_000) as i32),
+                1 => UnionType::F64(rng.next_f64() * 100.0),
+                _ => UnionType::Text(format!("u{}", rng.below(100))),
+            });
+
+            Union::new(values).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Field;
+
+    #[test]
+    fn test_generators() {
+        let one = random_f64(1_000, 0.25, -1.0..1.0, 7);
+
+        assert_eq!(one, random_f64(1_000, 0.25, -1.0..1.0, 7));
+        assert_ne!(one, random_f64(1_000, 0.25, -1.0..1.0, 8));
+        assert!((150..350).contains(&one.null_count()));
+        assert!(one
+            .copied_iter()
+            .flatten()
+            .all(|val| (-1.0..1.0).contains(&val)));
+
+        let two = random_i32(500, 0.0, -3..3, 1);
+        assert_eq!(0, two.null_count());
+        assert!(two
+            .copied_iter()
+            .flatten()
+            .all(|val| (-3..3).contains(&val)));
+
+        let three = random_text(200, 0.5, "xyz", 4, 3);
+        assert!(three
+            .iter()
+            .flatten()
+            .all(|val| val.len() <= 4 && val.chars().all(|char| "xyz".contains(char))));
+
+        assert!(random_bool(100, 1.0, 0).all_null());
+    }
+
+    #[test]
+    fn test_random_batch() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Text, true),
+            Field::new("c", DataType::Union, true),
+            Field::new("d", DataType::F32, false),
+        ]);
+        let one = random_batch(&schema, 300, 11);
+
+        assert_eq!(&schema, one.schema());
+        assert_eq!(300, one.num_rows());
+        assert_eq!(one, random_batch(&schema, 300, 11));
+        assert!(one.rows().all(|row| row.get_at(0) != Some(UnionType::Null)));
+        assert!(one.rows().any(|row| row.get_at(1) == Some(UnionType::Null)));
+    }
+}
@@ -0,0 +1,1513 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::slice::ChunksExact;
+
+use crate::column::ColumnArray;
+use crate::flatbuffer::{self, InvalidFlatbuffer, Table, TableRef, Value};
+use crate::record_batch::RecordBatch;
+use crate::schema::{Field, Schema, SchemaError};
+use crate::union::{UnionRef, UnionType};
+use crate::utils::{Array, DataType, NativeType};
+use crate::{
+    ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize, Union,
+};
+
+/// Marker preceding every encapsulated message.
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+/// First bytes of the Arrow file format, which wraps a stream.
+const FILE_MAGIC: [u8; 4] = *b"ARRO";
+/// `MetadataVersion::V5`.
+const METADATA_VERSION: i16 = 4;
+/// `MetadataVersion::V4`, the oldest version whose layout is supported.
+const METADATA_V4: i16 = 3;
+
+/// `MessageHeader` union discriminants.
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_DICTIONARY_BATCH: u8 = 2;
+const HEADER_RECORD_BATCH: u8 = 3;
+
+/// `Type` union discriminants.
+const TYPE_NULL: u8 = 1;
+const TYPE_INT: u8 = 2;
+const TYPE_FLOATING_POINT: u8 = 3;
+const TYPE_UTF8: u8 = 5;
+const TYPE_BOOL: u8 = 6;
+const TYPE_UNION: u8 = 14;
+const TYPE_LARGE_UTF8: u8 = 20;
+
+/// `Precision` enum values.
+const PRECISION_SINGLE: i16 = 1;
+const PRECISION_DOUBLE: i16 = 2;
+
+/// `UnionMode` enum values.
+const UNION_SPARSE: i16 = 0;
+const UNION_DENSE: i16 = 1;
+
+/// Names of the children of a union field, indexed by type id.
+const UNION_CHILDREN: [(&str, DataType); 8] = [
+    ("u32", DataType::UInt32),
+    ("i32", DataType::Int32),
+    ("usize", DataType::USize),
+    ("isize", DataType::ISize),
+    ("f32", DataType::F32),
+    ("f64", DataType::F64),
+    ("bool", DataType::Boolean),
+    ("text", DataType::Text),
+];
+/// Type id of the null child of a union.
+const UNION_NULL: i8 = 8;
+
+/// Errors raised while reading or writing the Arrow IPC format.
+#[derive(Debug)]
+pub enum IpcError {
+    Io(io::Error),
+    /// A batch did not match the schema of the stream.
+    SchemaMismatch,
+    /// Tried to write to a stream which was already finished.
+    Finished,
+    /// The message metadata could not be decoded.
+    InvalidMetadata,
+    /// The stream uses a type or feature which is not supported.
+    Unsupported(String),
+    /// A buffer lies outside the message body or is too short for its array.
+    InvalidBuffer,
+    NullCountMismatch {
+        reported: usize,
+        counted: usize,
+    },
+    /// Offsets are out of order or point outside their buffer.
+    InvalidOffsets,
+    InvalidUtf8,
+    /// The decoded columns do not conform to the stream schema.
+    Schema(SchemaError),
+}
+
+impl Display for IpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "IPC io error: {error}"),
+            Self::SchemaMismatch => write!(f, "Batch schema differs from the stream schema"),
+            Self::Finished => write!(f, "Tried to write to a finished stream"),
+            Self::InvalidMetadata => write!(f, "Invalid IPC message metadata"),
+            Self::Unsupported(feature) => write!(f, "Unsupported IPC feature: {feature}"),
+            Self::InvalidBuffer => write!(f, "IPC buffer is out of bounds or too short"),
+            Self::NullCountMismatch { reported, counted } => write!(
+                f,
+                "IPC field node reports {reported} nulls but its validity buffer has {counted}"
+            ),
+            Self::InvalidOffsets => write!(f, "Invalid offsets in IPC buffer"),
+            Self::InvalidUtf8 => write!(f, "IPC text buffer is not valid UTF-8"),
+            Self::Schema(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for IpcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Schema(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for IpcError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<InvalidFlatbuffer> for IpcError {
+    fn from(_: InvalidFlatbuffer) -> Self {
+        Self::InvalidMetadata
+    }
+}
+
+impl From<SchemaError> for IpcError {
+    fn from(value: SchemaError) -> Self {
+        Self::Schema(value)
+    }
+}
+
+/// Writes [`RecordBatch`]es in the Arrow IPC stream format.
+///
+/// The schema message is written on creation, followed by one record batch
+/// message per call to [`IpcWriter::write`]. [`IpcWriter::finish`] writes the
+/// end of stream marker.
+pub struct IpcWriter<W: Write> {
+    writer: W,
+    schema: Schema,
+    finished: bool,
+}
+
+impl<W: Write> IpcWriter<W> {
+    pub fn try_new(mut writer: W, schema: &Schema) -> Result<Self, IpcError> {
+        write_message(&mut writer, HEADER_SCHEMA, schema_table(schema), &[])?;
+
+        Ok(Self {
+            writer,
+            schema: schema.clone(),
+            finished: false,
+        })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), IpcError> {
+        if self.finished {
+            return Err(IpcError::Finished);
+        }
+
+        if *batch.schema() != self.schema {
+            return Err(IpcError::SchemaMismatch);
+        }
+
+        write_batch(&mut self.writer, batch)
+    }
+
+    /// Writes the end of stream marker and flushes the underlying writer.
+    pub fn finish(&mut self) -> Result<(), IpcError> {
+        if self.finished {
+            return Err(IpcError::Finished);
+        }
+
+        write_end_of_stream(&mut self.writer)?;
+        self.finished = true;
+
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes [`RecordBatch`]es in the Arrow IPC stream format, taking the
+/// schema of the stream from the first batch.
+///
+/// Unlike [`IpcWriter`], nothing is written until the first call to
+/// [`StreamWriter::write`], which writes the schema message ahead of the
+/// batch. Every later batch must share that schema. A stream finished
+/// before any batch is only an end of stream marker.
+pub struct StreamWriter<W: Write> {
+    writer: W,
+    /// The schema of the stream, set by the first batch
+    schema: Option<Schema>,
+    finished: bool,
+}
+
+impl<W: Write> StreamWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            schema: None,
+            finished: false,
+        }
+    }
+
+    /// Returns the schema of the stream, None until a batch is written.
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), IpcError> {
+        if self.finished {
+            return Err(IpcError::Finished);
+        }
+
+        match &self.schema {
+            Some(schema) if schema != batch.schema() => return Err(IpcError::SchemaMismatch),
+            Some(_) => {}
+            None => {
+                let header = schema_table(batch.schema());
+                write_message(&mut self.writer, HEADER_SCHEMA, header, &[])?;
+                self.schema = Some(batch.schema().clone());
+            }
+        }
+
+        write_batch(&mut self.writer, batch)
+    }
+
+    /// Writes the end of stream marker and flushes the underlying writer.
+    pub fn finish(&mut self) -> Result<(), IpcError> {
+        if self.finished {
+            return Err(IpcError::Finished);
+        }
+
+        write_end_of_stream(&mut self.writer)?;
+        self.finished = true;
+
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes the record batch message of `batch`.
+fn write_batch<W: Write>(writer: &mut W, batch: &RecordBatch) -> Result<(), IpcError> {
+    let mut body = Body::default();
+    batch
+        .columns()
+        .iter()
+        .for_each(|column| body.push_column(column));
+
+    let header = Table::new()
+        .with(0, Value::I64(batch.num_rows() as i64))
+        .with(1, structs(&body.nodes))
+        .with(2, structs(&body.buffers));
+
+    write_message(writer, HEADER_RECORD_BATCH, header, &body.bytes)?;
+
+    Ok(())
+}
+
+/// Writes the end of stream marker and flushes `writer`.
+fn write_end_of_stream<W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_all(&CONTINUATION.to_le_bytes())?;
+    writer.write_all(&0_u32.to_le_bytes())?;
+    writer.flush()
+}
+
+fn write_message<W: Write>(
+    writer: &mut W,
+    header_type: u8,
+    header: Table,
+    body: &[u8],
+) -> io::Result<()> {
+    let message = Table::new()
+        .with(0, Value::I16(METADATA_VERSION))
+        .with(1, Value::U8(header_type))
+        .with(2, Value::Table(header))
+        .with(3, Value::I64(body.len() as i64));
+
+    let metadata = flatbuffer::finish(&message);
+
+    writer.write_all(&CONTINUATION.to_le_bytes())?;
+    writer.write_all(&(metadata.len() as u32).to_le_bytes())?;
+    writer.write_all(&metadata)?;
+    writer.write_all(body)
+}
+
+/// Encodes `(i64, i64)` pairs as a vector of `FieldNode` or `Buffer` structs.
+fn structs(pairs: &[(i64, i64)]) -> Value {
+    let bytes = pairs
+        .iter()
+        .flat_map(|(one, two)| one.to_le_bytes().into_iter().chain(two.to_le_bytes()))
+        .collect();
+
+    Value::Structs {
+        bytes,
+        count: pairs.len(),
+    }
+}
+
+fn schema_table(schema: &Schema) -> Table {
+    let fields = schema.fields().iter().map(field_table).collect();
+
+    Table::new()
+        .with(1, Value::Tables(fields))
+        .with(2, metadata_tables(schema.metadata()))
+}
+
+/// Encodes `metadata` as a vector of `KeyValue` tables.
+fn metadata_tables(metadata: &BTreeMap<String, String>) -> Value {
+    let pairs = metadata
+        .iter()
+        .map(|(key, value)| {
+            Table::new()
+                .with(0, Value::String(key.clone()))
+                .with(1, Value::String(value.clone()))
+        })
+        .collect();
+
+    Value::Tables(pairs)
+}
+
+fn field_table(field: &Field) -> Table {
+    let mut children = vec![];
+
+    let (type_id, kind) = match field.data_type {
+        DataType::Int32 => (TYPE_INT, int_table(32, true)),
+        DataType::UInt32 => (TYPE_INT, int_table(32, false)),
+        DataType::ISize => (TYPE_INT, int_table(isize::BITS, true)),
+        DataType::USize => (TYPE_INT, int_table(usize::BITS, false)),
+        DataType::F32 => (
+            TYPE_FLOATING_POINT,
+            Table::new().with(0, Value::I16(PRECISION_SINGLE)),
+        ),
+        DataType::F64 => (
+            TYPE_FLOATING_POINT,
+            Table::new().with(0, Value::I16(PRECISION_DOUBLE)),
+        ),
+        DataType::Boolean => (TYPE_BOOL, Table::new()),
+        DataType::Text => (TYPE_LARGE_UTF8, Table::new()),
+        DataType::Union => {
+            children = UNION_CHILDREN
+                .iter()
+                .map(|(name, kind)| field_table(&Field::new(*name, *kind, true)))
+                .collect();
+            children.push(
+                Table::new()
+                    .with(0, Value::String("null".into()))
+                    .with(1, Value::Bool(true))
+                    .with(2, Value::U8(TYPE_NULL))
+                    .with(3, Value::Table(Table::new()))
+                    .with(5, Value::Tables(vec![])),
+            );
+
+            let type_ids = (0..=UNION_NULL as i32).collect();
+
+            (
+                TYPE_UNION,
+                Table::new()
+                    .with(0, Value::I16(UNION_DENSE))
+                    .with(1, Value::I32s(type_ids)),
+            )
+        }
+    };
+
+    Table::new()
+        .with(0, Value::String(field.name.clone()))
+        .with(1, Value::Bool(field.nullable))
+        .with(2, Value::U8(type_id))
+        .with(3, Value::Table(kind))
+        .with(5, Value::Tables(children))
+        .with(6, metadata_tables(&field.metadata))
+}
+
+fn int_table(bit_width: u32, signed: bool) -> Table {
+    Table::new()
+        .with(0, Value::I32(bit_width as i32))
+        .with(1, Value::Bool(signed))
+}
+
+/// Field nodes, buffer locations and body bytes of a record batch message.
+#[derive(Default)]
+struct Body {
+    nodes: Vec<(i64, i64)>,
+    buffers: Vec<(i64, i64)>,
+    bytes: Vec<u8>,
+}
+
+impl Body {
+    fn push_buffer(&mut self, buffer: &[u8]) {
+        let offset = self.bytes.len();
+        self.bytes.extend_from_slice(buffer);
+        self.bytes.resize(self.bytes.len().next_multiple_of(8), 0);
+        self.buffers.push((offset as i64, buffer.len() as i64));
+    }
+
+    /// Pushes the field node and validity buffer of `array`.
+    fn push_validity<A: Array>(&mut self, array: &A) {
+        let len = array.len();
+        let nulls = (0..len).filter(|idx| array.check_null(*idx)).count();
+
+        self.nodes.push((len as i64, nulls as i64));
+
+        if nulls == 0 {
+            self.push_buffer(&[]);
+            return;
+        }
+
+        let mut bitmap = vec![0_u8; len.div_ceil(8)];
+        (0..len)
+            .filter(|idx| !array.check_null(*idx))
+            .for_each(|idx| bitmap[idx / 8] |= 1 << (idx % 8));
+
+        self.push_buffer(&bitmap);
+    }
+
+    fn push_primitive<T: NativeType>(&mut self, array: &T::Array) {
+        self.push_validity(array);
+
+        let values = array
+            .copied_iter()
+            .map(Option::unwrap_or_default)
+            .collect::<Vec<_>>();
+        let size = std::mem::size_of_val(values.as_slice());
+        let bytes = unsafe { std::slice::from_raw_parts(values.as_ptr() as *const u8, size) };
+
+        self.push_buffer(bytes);
+    }
+
+    fn push_boolean(&mut self, array: &ArrayBoolean) {
+        self.push_validity(array);
+
+        let mut bitmap = vec![0_u8; array.len().div_ceil(8)];
+        array
+            .copied_iter()
+            .enumerate()
+            .filter(|(_, value)| *value == Some(true))
+            .for_each(|(idx, _)| bitmap[idx / 8] |= 1 << (idx % 8));
+
+        self.push_buffer(&bitmap);
+    }
+
+    fn push_text(&mut self, array: &ArrayText) {
+        self.push_validity(array);
+
+        let mut offsets = Vec::with_capacity(array.len() + 1);
+        let mut data = vec![];
+        offsets.push(0_i64);
+
+        for value in array.iter() {
+            data.extend_from_slice(value.unwrap_or_default().as_bytes());
+            offsets.push(data.len() as i64);
+        }
+
+        let offsets = offsets
+            .into_iter()
+            .flat_map(i64::to_le_bytes)
+            .collect::<Vec<_>>();
+
+        self.push_buffer(&offsets);
+        self.push_buffer(&data);
+    }
+
+    /// Pushes a dense union whose nulls are stored in a child of type null.
+    fn push_union(&mut self, array: &crate::Union) {
+        let mut types = Vec::with_capacity(array.len());
+        let mut offsets = Vec::with_capacity(array.len());
+        let mut counts = [0_i32; UNION_NULL as usize + 1];
+
+        let mut uint32 = vec![];
+        let mut int32 = vec![];
+        let mut uintsize = vec![];
+        let mut intsize = vec![];
+        let mut float32 = vec![];
+        let mut float64 = vec![];
+        let mut boolean = vec![];
+        let mut text = vec![];
+
+        for value in array.iter() {
+            let kind = match value.unwrap_or(UnionRef::Null) {
+                UnionRef::U32(value) => {
+                    uint32.push(Some(value));
+                    0
+                }
+                UnionRef::I32(value) => {
+                    int32.push(Some(value));
+                    1
+                }
+                UnionRef::USize(value) => {
+                    uintsize.push(Some(value));
+                    2
+                }
+                UnionRef::ISize(value) => {
+                    intsize.push(Some(value));
+                    3
+                }
+                UnionRef::F32(value) => {
+                    float32.push(Some(value));
+                    4
+                }
+                UnionRef::F64(value) => {
+                    float64.push(Some(value));
+                    5
+                }
+                UnionRef::Boolean(value) => {
+                    boolean.push(Some(value));
+                    6
+                }
+                UnionRef::Text(value) => {
+                    text.push(Some(value.to_owned()));
+                    7
+                }
+                UnionRef::Null => UNION_NULL,
+            };
+
+            types.push(kind as u8);
+            offsets.push(counts[kind as usize]);
+            counts[kind as usize] += 1;
+        }
+
+        // Unions have neither a validity buffer nor a null count
+        self.nodes.push((array.len() as i64, 0));
+        self.push_buffer(&types);
+        let offsets = offsets
+            .into_iter()
+            .flat_map(i32::to_le_bytes)
+            .collect::<Vec<_>>();
+        self.push_buffer(&offsets);
+
+        self.push_primitive::<u32>(&ArrayU32::from_vec(uint32));
+        self.push_primitive::<i32>(&ArrayI32::from_vec(int32));
+        self.push_primitive::<usize>(&ArrayUSize::from_vec(uintsize));
+        self.push_primitive::<isize>(&ArrayISize::from_vec(intsize));
+        self.push_primitive::<f32>(&ArrayF32::from_vec(float32));
+        self.push_primitive::<f64>(&ArrayF64::from_vec(float64));
+        self.push_boolean(&ArrayBoolean::from_vec(boolean));
+        self.push_text(&ArrayText::from_vec(text));
+
+        let nulls = counts[UNION_NULL as usize] as i64;
+        self.nodes.push((nulls, nulls));
+    }
+
+    fn push_column(&mut self, column: &ColumnArray) {
+        match column {
+            ColumnArray::Int32(array) => self.push_primitive::<i32>(array),
+            ColumnArray::UInt32(array) => self.push_primitive::<u32>(array),
+            ColumnArray::ISize(array) => self.push_primitive::<isize>(array),
+            ColumnArray::USize(array) => self.push_primitive::<usize>(array),
+            ColumnArray::F32(array) => self.push_primitive::<f32>(array),
+            ColumnArray::F64(array) => self.push_primitive::<f64>(array),
+            ColumnArray::Boolean(array) => self.push_boolean(array),
+            ColumnArray::Text(array) => self.push_text(array),
+            ColumnArray::Union(array) => self.push_union(array),
+        }
+    }
+}
+
+/// Reads [`RecordBatch`]es from the Arrow IPC stream format.
+///
+/// Also accepts the file format, as long as an end of stream marker
+/// precedes its footer, and streams written before the continuation marker
+/// was introduced. Dictionary encoded fields, compressed bodies and nested
+/// unions are not supported.
+pub struct IpcReader<R: Read> {
+    reader: R,
+    schema: Schema,
+    layouts: Vec<Layout>,
+    finished: bool,
+}
+
+impl<R: Read> IpcReader<R> {
+    /// Creates an [`IpcReader`], reading the schema message at the start of
+    /// the stream.
+    pub fn try_new(mut reader: R) -> Result<Self, IpcError> {
+        let (schema, layouts) = read_schema(&mut reader)?.ok_or(IpcError::InvalidMetadata)?;
+
+        Ok(Self {
+            reader,
+            schema,
+            layouts,
+            finished: false,
+        })
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_batch(&mut self) -> Result<Option<RecordBatch>, IpcError> {
+        let Some((metadata, body)) = read_message(&mut self.reader)? else {
+            return Ok(None);
+        };
+
+        let (version, header_type, header) = decode_message(&metadata)?;
+
+        match header_type {
+            HEADER_RECORD_BATCH => {}
+            HEADER_SCHEMA => return Err(IpcError::InvalidMetadata),
+            HEADER_DICTIONARY_BATCH => {
+                return Err(IpcError::Unsupported("dictionary batches".into()))
+            }
+            other => return Err(IpcError::Unsupported(format!("message type {other}"))),
+        }
+
+        if header.table(3)?.is_some() {
+            return Err(IpcError::Unsupported("compressed record batches".into()));
+        }
+
+        let rows = usize::try_from(header.i64(0, 0)?).map_err(|_| IpcError::InvalidMetadata)?;
+        let nodes = header.vector(1, 16)?.unwrap_or_default();
+        let buffers = header.vector(2, 16)?.unwrap_or_default();
+
+        let mut body = BodyReader {
+            body: &body,
+            nodes: nodes.chunks_exact(16),
+            buffers: buffers.chunks_exact(16),
+            union_validity: version < METADATA_VERSION,
+        };
+
+        let mut columns = Vec::with_capacity(self.layouts.len());
+
+        for (field, layout) in self.schema.fields().iter().zip(&self.layouts) {
+            let column = body.column(layout)?;
+
+            if column.len() != rows {
+                return Err(SchemaError::LengthMismatch {
+                    name: field.name.clone(),
+                    expected: rows,
+                    got: column.len(),
+                }
+                .into());
+            }
+
+            columns.push(column);
+        }
+
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+}
+
+impl<R: Read> Iterator for IpcReader<R> {
+    type Item = Result<RecordBatch, IpcError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let batch = self.read_batch();
+        self.finished = !matches!(batch, Ok(Some(_)));
+
+        batch.transpose()
+    }
+}
+
+/// Reads [`RecordBatch`]es from the Arrow IPC stream format, as written by
+/// [`StreamWriter`].
+///
+/// The schema message is read on the first call to `next`, so a stream
+/// holding only an end of stream marker yields no batches rather than an
+/// error. Every batch is decoded against that schema and a second schema
+/// message is rejected. See [`IpcReader`] for the formats accepted.
+pub struct StreamReader<R: Read> {
+    inner: IpcReader<R>,
+    /// Whether the schema message has been read
+    started: bool,
+}
+
+impl<R: Read> StreamReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            inner: IpcReader {
+                reader,
+                schema: Schema::new(vec![]),
+                layouts: vec![],
+                finished: false,
+            },
+            started: false,
+        }
+    }
+
+    /// Returns the schema of the stream, None until it has been read.
+    pub fn schema(&self) -> Option<&Schema> {
+        self.started.then_some(&self.inner.schema)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.reader
+    }
+}
+
+impl<R: Read> Iterator for StreamReader<R> {
+    type Item = Result<RecordBatch, IpcError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started && !self.inner.finished {
+            match read_schema(&mut self.inner.reader) {
+                Ok(Some((schema, layouts))) => {
+                    self.inner.schema = schema;
+                    self.inner.layouts = layouts;
+                    self.started = true;
+                }
+                Ok(None) => self.inner.finished = true,
+                Err(error) => {
+                    self.inner.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        self.inner.next()
+    }
+}
+
+/// Reads the schema message at the start of a stream along with the layout
+/// of every field, returning None if the stream ends straight away.
+fn read_schema<R: Read>(reader: &mut R) -> Result<Option<(Schema, Vec<Layout>)>, IpcError> {
+    let Some((metadata, _)) = read_message(reader)? else {
+        return Ok(None);
+    };
+    let (_, header_type, header) = decode_message(&metadata)?;
+
+    if header_type != HEADER_SCHEMA {
+        return Err(IpcError::InvalidMetadata);
+    }
+
+    if header.i16(0, 0)? != 0 {
+        return Err(IpcError::Unsupported("big endian streams".into()));
+    }
+
+    let mut fields = vec![];
+    let mut layouts = vec![];
+
+    for field in header.tables(1)? {
+        let name = field.string(0)?.unwrap_or_default();
+        let layout = parse_layout(field, false)?;
+
+        let data_type = match &layout {
+            Layout::Primitive(data_type) => *data_type,
+            Layout::Boolean => DataType::Boolean,
+            Layout::Utf8 { .. } => DataType::Text,
+            Layout::Union { .. } => DataType::Union,
+            Layout::Null => unreachable!("Null fields are only parsed within unions"),
+        };
+
+        fields.push(
+            Field::new(name, data_type, field.bool(1, false)?)
+                .with_metadata(read_metadata(field, 6)?),
+        );
+        layouts.push(layout);
+    }
+
+    let schema = Schema::new(fields).with_metadata(read_metadata(header, 2)?);
+
+    Ok(Some((schema, layouts)))
+}
+
+/// Metadata and body of an encapsulated message.
+type Message = (Vec<u8>, Vec<u8>);
+
+/// Reads the next encapsulated message, returning None at the end of the
+/// stream.
+/// Decodes the vector of `KeyValue` tables at `id` of `table`.
+fn read_metadata(table: TableRef<'_>, id: u16) -> Result<BTreeMap<String, String>, IpcError> {
+    let mut metadata = BTreeMap::new();
+
+    for pair in table.tables(id)? {
+        let key = pair.string(0)?.unwrap_or_default();
+        let value = pair.string(1)?.unwrap_or_default();
+        metadata.insert(key.to_owned(), value.to_owned());
+    }
+
+    Ok(metadata)
+}
+
+fn read_message<R: Read>(reader: &mut R) -> Result<Option<Message>, IpcError> {
+    let mut word = [0; 4];
+
+    match reader.read_exact(&mut word) {
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+
+    // Skip the rest of the file format's magic and the padding up to the
+    // first message
+    if word == FILE_MAGIC {
+        reader.read_exact(&mut word)?;
+
+        loop {
+            reader.read_exact(&mut word)?;
+
+            if word != [0; 4] {
+                break;
+            }
+        }
+    }
+
+    // Older streams start directly with the metadata length
+    if u32::from_le_bytes(word) == CONTINUATION {
+        reader.read_exact(&mut word)?;
+    }
+
+    let len = u32::from_le_bytes(word) as usize;
+
+    if len == 0 {
+        return Ok(None);
+    }
+
+    let mut metadata = vec![0; len];
+    reader.read_exact(&mut metadata)?;
+
+    let body_len = TableRef::root(&metadata)?.i64(3, 0)?;
+    let body_len = u64::try_from(body_len).map_err(|_| IpcError::InvalidMetadata)?;
+
+    let mut body = vec![];
+    reader.take(body_len).read_to_end(&mut body)?;
+
+    if body.len() as u64 != body_len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(Some((metadata, body)))
+}
+
+/// Decodes the metadata of a message into its version, header type and
+/// header.
+fn decode_message(metadata: &[u8]) -> Result<(i16, u8, TableRef<'_>), IpcError> {
+    let message = TableRef::root(metadata)?;
+    let version = message.i16(0, 0)?;
+
+    if version < METADATA_V4 {
+        return Err(IpcError::Unsupported(format!("metadata version {version}")));
+    }
+
+    let header = message.table(2)?.ok_or(IpcError::InvalidMetadata)?;
+
+    Ok((version, message.u8(1, 0)?, header))
+}
+
+/// Physical layout of a column, which its [`DataType`] does not fully
+/// describe.
+#[derive(Debug, Clone, PartialEq)]
+enum Layout {
+    Null,
+    Primitive(DataType),
+    Boolean,
+    Utf8 {
+        large: bool,
+    },
+    Union {
+        dense: bool,
+        type_ids: Vec<i32>,
+        children: Vec<Layout>,
+    },
+}
+
+fn parse_layout(field: TableRef<'_>, in_union: bool) -> Result<Layout, IpcError> {
+    let name = field.string(0)?.unwrap_or_default();
+
+    if field.table(4)?.is_some() {
+        return Err(IpcError::Unsupported(format!(
+            "dictionary encoded field \"{name}\""
+        )));
+    }
+
+    let kind = field.table(3)?.ok_or(IpcError::InvalidMetadata)?;
+
+    let layout = match field.u8(2, 0)? {
+        TYPE_NULL if in_union => Layout::Null,
+        TYPE_INT => match (kind.i32(0, 0)?, kind.bool(1, false)?) {
+            (32, true) => Layout::Primitive(DataType::Int32),
+            (32, false) => Layout::Primitive(DataType::UInt32),
+            (64, true) if isize::BITS == 64 => Layout::Primitive(DataType::ISize),
+            (64, false) if usize::BITS == 64 => Layout::Primitive(DataType::USize),
+            (width, signed) => {
+                return Err(IpcError::Unsupported(format!(
+                    "{width} bit {} integer field \"{name}\"",
+                    if signed { "signed" } else { "unsigned" }
+                )))
+            }
+        },
+        TYPE_FLOATING_POINT => match kind.i16(0, 0)? {
+            PRECISION_SINGLE => Layout::Primitive(DataType::F32),
+            PRECISION_DOUBLE => Layout::Primitive(DataType::F64),
+            _ => {
+                return Err(IpcError::Unsupported(format!(
+                    "half precision field \"{name}\""
+                )))
+            }
+        },
+        TYPE_BOOL => Layout::Boolean,
+        TYPE_UTF8 => Layout::Utf8 { large: false },
+        TYPE_LARGE_UTF8 => Layout::Utf8 { large: true },
+        TYPE_UNION if !in_union => {
+            let children = field
+                .tables(5)?
+                .into_iter()
+                .map(|child| parse_layout(child, true))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let type_ids = kind
+                .i32s(1)?
+                .unwrap_or_else(|| (0..children.len() as i32).collect());
+
+            if type_ids.len() != children.len() {
+                return Err(IpcError::InvalidMetadata);
+            }
+
+            Layout::Union {
+                dense: kind.i16(0, UNION_SPARSE)? == UNION_DENSE,
+                type_ids,
+                children,
+            }
+        }
+        other => {
+            return Err(IpcError::Unsupported(format!(
+                "type {other} of field \"{name}\""
+            )))
+        }
+    };
+
+    Ok(layout)
+}
+
+/// Walks the field nodes and buffers of a record batch message, decoding
+/// columns out of its body.
+struct BodyReader<'a> {
+    body: &'a [u8],
+    nodes: ChunksExact<'a, u8>,
+    buffers: ChunksExact<'a, u8>,
+    /// Unions had a validity buffer before `MetadataVersion::V5`
+    union_validity: bool,
+}
+
+impl<'a> BodyReader<'a> {
+    /// Returns the length and null count of the next field node.
+    fn node(&mut self) -> Result<(usize, usize), IpcError> {
+        let (len, nulls) = pair(self.nodes.next().ok_or(IpcError::InvalidMetadata)?);
+        let len = usize::try_from(len).map_err(|_| IpcError::InvalidMetadata)?;
+        let nulls = usize::try_from(nulls).map_err(|_| IpcError::InvalidMetadata)?;
+
+        if nulls > len {
+            return Err(IpcError::InvalidMetadata);
+        }
+
+        Ok((len, nulls))
+    }
+
+    fn buffer(&mut self) -> Result<&'a [u8], IpcError> {
+        let (offset, len) = pair(self.buffers.next().ok_or(IpcError::InvalidMetadata)?);
+
+        let start = usize::try_from(offset).map_err(|_| IpcError::InvalidBuffer)?;
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| start.checked_add(len))
+            .ok_or(IpcError::InvalidBuffer)?;
+
+        self.body.get(start..end).ok_or(IpcError::InvalidBuffer)
+    }
+
+    /// Returns the first `len` bytes of the next buffer.
+    fn sized_buffer(&mut self, len: Option<usize>) -> Result<&'a [u8], IpcError> {
+        let buffer = self.buffer()?;
+
+        len.and_then(|len| buffer.get(..len))
+            .ok_or(IpcError::InvalidBuffer)
+    }
+
+    /// Reads a validity buffer, checking it against the node's null count.
+    fn validity(&mut self, len: usize, nulls: usize) -> Result<Option<&'a [u8]>, IpcError> {
+        let buffer = self.buffer()?;
+
+        if buffer.is_empty() {
+            if nulls != 0 {
+                return Err(IpcError::NullCountMismatch {
+                    reported: nulls,
+                    counted: 0,
+                });
+            }
+
+            return Ok(None);
+        }
+
+        let bitmap = buffer
+            .get(..len.div_ceil(8))
+            .ok_or(IpcError::InvalidBuffer)?;
+        let counted = (0..len).filter(|idx| !is_set(bitmap, *idx)).count();
+
+        if counted != nulls {
+            return Err(IpcError::NullCountMismatch {
+                reported: nulls,
+                counted,
+            });
+        }
+
+        Ok(Some(bitmap))
+    }
+
+    fn primitive<T, const N: usize>(&mut self, from: fn([u8; N]) -> T) -> Result<T::Array, IpcError>
+    where
+        T: NativeType,
+    {
+        let (len, nulls) = self.node()?;
+        let validity = self.validity(len, nulls)?;
+        let values = self.sized_buffer(len.checked_mul(N))?;
+
+        let values = values
+            .chunks_exact(N)
+            .enumerate()
+            .map(|(idx, chunk)| is_valid(validity, idx).then(|| from(chunk.try_into().unwrap())));
+
+        Ok(T::Array::new(values))
+    }
+
+    fn boolean(&mut self) -> Result<ArrayBoolean, IpcError> {
+        let (len, nulls) = self.node()?;
+        let validity = self.validity(len, nulls)?;
+        let values = self.sized_buffer(Some(len.div_ceil(8)))?;
+
+        let values = (0..len).map(|idx| is_valid(validity, idx).then(|| is_set(values, idx)));
+
+        Ok(ArrayBoolean::new(values))
+    }
+
+    fn text(&mut self, large: bool) -> Result<ArrayText, IpcError> {
+        let (len, nulls) = self.node()?;
+        let validity = self.validity(len, nulls)?;
+        let offsets = self.buffer()?;
+        let data = self.buffer()?;
+
+        // Empty arrays may omit their offsets entirely
+        if len == 0 {
+            return Ok(ArrayText::from_vec(vec![]));
+        }
+
+        let width = if large { 8 } else { 4 };
+        let offsets = (len + 1)
+            .checked_mul(width)
+            .and_then(|size| offsets.get(..size))
+            .ok_or(IpcError::InvalidBuffer)?
+            .chunks_exact(width)
+            .map(|chunk| {
+                let offset = match large {
+                    true => i64::from_le_bytes(chunk.try_into().unwrap()),
+                    false => i32::from_le_bytes(chunk.try_into().unwrap()) as i64,
+                };
+
+                usize::try_from(offset).map_err(|_| IpcError::InvalidOffsets)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let values = offsets
+            .windows(2)
+            .enumerate()
+            .map(|(idx, range)| {
+                let text = data
+                    .get(range[0]..range[1])
+                    .ok_or(IpcError::InvalidOffsets)?;
+
+                if !is_valid(validity, idx) {
+                    return Ok(None);
+                }
+
+                let text = std::str::from_utf8(text).map_err(|_| IpcError::InvalidUtf8)?;
+                Ok(Some(text.to_owned()))
+            })
+            .collect::<Result<Vec<_>, IpcError>>()?;
+
+        Ok(ArrayText::from_vec(values))
+    }
+
+    fn union(
+        &mut self,
+        dense: bool,
+        type_ids: &[i32],
+        children: &[Layout],
+    ) -> Result<Union, IpcError> {
+        let (len, _) = self.node()?;
+
+        if self.union_validity {
+            self.buffer()?;
+        }
+
+        let types = self.sized_buffer(Some(len))?;
+        let offsets = match dense {
+            true => Some(self.sized_buffer(len.checked_mul(4))?),
+            false => None,
+        };
+
+        let children = children
+            .iter()
+            .map(|layout| match layout {
+                Layout::Null => Ok((self.node()?.0, None)),
+                layout => {
+                    let column = self.column(layout)?;
+                    Ok((column.len(), Some(column)))
+                }
+            })
+            .collect::<Result<Vec<_>, IpcError>>()?;
+
+        let values = (0..len)
+            .map(|row| {
+                let kind = types[row] as i8 as i32;
+                let child = type_ids
+                    .iter()
+                    .position(|id| *id == kind)
+                    .ok_or(IpcError::InvalidBuffer)?;
+
+                let idx = match offsets {
+                    Some(offsets) => {
+                        let offset = offsets[4 * row..4 * row + 4].try_into().unwrap();
+                        usize::try_from(i32::from_le_bytes(offset))
+                            .map_err(|_| IpcError::InvalidOffsets)?
+                    }
+                    None => row,
+                };
+
+                let (child_len, column) = &children[child];
+
+                if idx >= *child_len {
+                    return Err(IpcError::InvalidOffsets);
+                }
+
+                Ok(column
+                    .as_ref()
+                    .and_then(|column| column.scalar(idx))
+                    .unwrap_or(UnionType::Null))
+            })
+            .collect::<Result<Vec<_>, IpcError>>()?;
+
+        Ok(Union::from_vec(values))
+    }
+
+    fn column(&mut self, layout: &Layout) -> Result<ColumnArray, IpcError> {
+        let column = match layout {
+            Layout::Primitive(DataType::Int32) => self.primitive(i32::from_le_bytes)?.into(),
+            Layout::Primitive(DataType::UInt32) => self.primitive(u32::from_le_bytes)?.into(),
+            Layout::Primitive(DataType::ISize) => self
+                .primitive::<isize, 8>(|bytes| i64::from_le_bytes(bytes) as isize)?
+                .into(),
+            Layout::Primitive(DataType::USize) => self
+                .primitive::<usize, 8>(|bytes| u64::from_le_bytes(bytes) as usize)?
+                .into(),
+            Layout::Primitive(DataType::F32) => self.primitive(f32::from_le_bytes)?.into(),
+            Layout::Primitive(DataType::F64) => self.primitive(f64::from_le_bytes)?.into(),
+            Layout::Boolean => self.boolean()?.into(),
+            Layout::Utf8 { large } => self.text(*large)?.into(),
+            Layout::Union {
+                dense,
+                type_ids,
+                children,
+            } => self.union(*dense, type_ids, children)?.into(),
+            Layout::Primitive(_) | Layout::Null => {
+                unreachable!("Layouts are only built from supported types")
+            }
+        };
+
+        Ok(column)
+    }
+}
+
+fn pair(chunk: &[u8]) -> (i64, i64) {
+    let one = i64::from_le_bytes(chunk[..8].try_into().unwrap());
+    let two = i64::from_le_bytes(chunk[8..].try_into().unwrap());
+
+    (one, two)
+}
+
+fn is_set(bitmap: &[u8], idx: usize) -> bool {
+    bitmap[idx / 8] & (1 << (idx % 8)) != 0
+}
+
+fn is_valid(validity: Option<&[u8]>, idx: usize) -> bool {
+    validity.is_none_or(|bitmap| is_set(bitmap, idx))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("count", DataType::UInt32, true),
+            Field::new("offset", DataType::ISize, true),
+            Field::new("size", DataType::USize, false),
+            Field::new("ratio", DataType::F32, true),
+            Field::new("price", DataType::F64, true)
+                .with_metadata(BTreeMap::from([("currency".into(), "GHS".into())])),
+            Field::new("name", DataType::Text, true),
+            Field::new("flag", DataType::Boolean, true),
+            Field::new("raw", DataType::Union, true),
+        ])
+        .with_metadata(BTreeMap::from([("source".into(), "ipc.rs".into())]));
+
+        let rows = 11;
+        // Every `step`th row is null
+        let valid = |idx: usize, step: usize| !idx.is_multiple_of(step);
+
+        let raw = (0..rows).map(|idx| match idx % 5 {
+            0 => UnionType::Text(format!("row {idx}")),
+            1 => UnionType::Null,
+            2 => UnionType::F32(idx as f32 / 2.0),
+            3 => UnionType::Boolean(idx % 2 == 0),
+            _ => UnionType::ISize(-(idx as isize)),
+        });
+
+        let columns = vec![
+            ArrayI32::from_range(0..rows as i32).into(),
+            ArrayU32::new((0..rows).map(|idx| valid(idx, 3).then_some(idx as u32))).into(),
+            ArrayISize::new((0..rows).map(|idx| valid(idx, 4).then_some(-(idx as isize)))).into(),
+            ArrayUSize::new((0..rows).map(Some)).into(),
+            ArrayF32::new((0..rows).map(|idx| valid(idx, 2).then_some(idx as f32))).into(),
+            ArrayF64::new((0..rows).map(|idx| valid(idx, 5).then_some(idx as f64 * 1.5))).into(),
+            ArrayText::new((0..rows).map(|idx| valid(idx, 3).then(|| "Bublé".repeat(idx)))).into(),
+            ArrayBoolean::new((0..rows).map(|idx| valid(idx, 7).then_some(idx % 3 == 0))).into(),
+            Union::from_vec(raw.collect()).into(),
+        ];
+
+        RecordBatch::try_new(schema, columns).unwrap()
+    }
+
+    /// Splits a stream into its `(metadata, body)` messages.
+    fn messages(mut stream: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let mut messages = vec![];
+
+        loop {
+            assert_eq!(CONTINUATION.to_le_bytes(), stream[..4]);
+            let len = u32::from_le_bytes(stream[4..8].try_into().unwrap()) as usize;
+
+            if len == 0 {
+                assert_eq!(8, stream.len());
+                return messages;
+            }
+
+            assert!(len.is_multiple_of(8));
+            let metadata = &stream[8..8 + len];
+
+            // bodyLength is the only 8 byte field of the message table
+            let root = u32::from_le_bytes(metadata[..4].try_into().unwrap()) as usize;
+            let body_len =
+                i64::from_le_bytes(metadata[root + 8..root + 16].try_into().unwrap()) as usize;
+
+            messages.push((metadata, &stream[8 + len..8 + len + body_len]));
+            stream = &stream[8 + len + body_len..];
+        }
+    }
+
+    #[test]
+    fn test_stream_framing() {
+        let batch = batch();
+        let mut writer = IpcWriter::try_new(vec![], batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let stream = writer.into_inner();
+        let messages = messages(&stream);
+
+        assert_eq!(3, messages.len());
+        assert!(messages[0].1.is_empty());
+        assert!(messages[1].1.len().is_multiple_of(8));
+        assert_eq!(messages[1], messages[2]);
+    }
+
+    #[test]
+    fn test_writer_errors() {
+        let batch = batch();
+        let schema = Schema::new(vec![Field::new("id", DataType::Int32, false)]);
+        let mut writer = IpcWriter::try_new(vec![], &schema).unwrap();
+
+        assert!(matches!(
+            writer.write(&batch),
+            Err(IpcError::SchemaMismatch)
+        ));
+
+        writer.finish().unwrap();
+        assert!(matches!(writer.finish(), Err(IpcError::Finished)));
+    }
+
+    /// Writes a stream holding the schema of `batch` followed by a single
+    /// record batch message whose body is first passed through `tamper`.
+    fn tampered<F>(batch: &RecordBatch, tamper: F) -> Vec<u8>
+    where
+        F: FnOnce(&mut Body),
+    {
+        let mut stream = vec![];
+        write_message(
+            &mut stream,
+            HEADER_SCHEMA,
+            schema_table(batch.schema()),
+            &[],
+        )
+        .unwrap();
+
+        let mut body = Body::default();
+        batch
+            .columns()
+            .iter()
+            .for_each(|column| body.push_column(column));
+        tamper(&mut body);
+
+        let header = Table::new()
+            .with(0, Value::I64(batch.num_rows() as i64))
+            .with(1, structs(&body.nodes))
+            .with(2, structs(&body.buffers));
+
+        write_message(&mut stream, HEADER_RECORD_BATCH, header, &body.bytes).unwrap();
+        stream
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let one = batch();
+        let schema = one.schema().clone();
+        let empty = RecordBatch::try_new(
+            schema.clone(),
+            one.columns()
+                .iter()
+                .map(|column| match column.as_ref() {
+                    ColumnArray::Int32(_) => ArrayI32::from_vec(vec![]).into(),
+                    ColumnArray::UInt32(_) => ArrayU32::from_vec(vec![]).into(),
+                    ColumnArray::ISize(_) => ArrayISize::from_vec(vec![]).into(),
+                    ColumnArray::USize(_) => ArrayUSize::from_vec(vec![]).into(),
+                    ColumnArray::F32(_) => ArrayF32::from_vec(vec![]).into(),
+                    ColumnArray::F64(_) => ArrayF64::from_vec(vec![]).into(),
+                    ColumnArray::Boolean(_) => ArrayBoolean::from_vec(vec![]).into(),
+                    ColumnArray::Text(_) => ArrayText::from_vec(vec![]).into(),
+                    ColumnArray::Union(_) => Union::from_vec(vec![]).into(),
+                })
+                .collect(),
+        )
+        .unwrap();
+
+        let mut writer = IpcWriter::try_new(vec![], &schema).unwrap();
+        writer.write(&one).unwrap();
+        writer.write(&empty).unwrap();
+        writer.write(&one).unwrap();
+        writer.finish().unwrap();
+        let stream = writer.into_inner();
+
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert_eq!(&schema, reader.schema());
+
+        assert_eq!(one, reader.next().unwrap().unwrap());
+        assert_eq!(empty, reader.next().unwrap().unwrap());
+        assert_eq!(one, reader.next().unwrap().unwrap());
+        assert!(reader.next().is_none());
+
+        // The file format and streams without continuation markers
+        let mut file = FILE_MAGIC.to_vec();
+        file.extend_from_slice(b"W1\0\0");
+        file.extend_from_slice(&stream);
+        assert_eq!(3, IpcReader::try_new(file.as_slice()).unwrap().count());
+
+        let legacy = messages(&stream)
+            .into_iter()
+            .flat_map(|(metadata, body)| {
+                let len = (metadata.len() as u32).to_le_bytes();
+                [len.as_slice(), metadata, body].concat()
+            })
+            .collect::<Vec<_>>();
+        let batches = IpcReader::try_new(legacy.as_slice())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![one.clone(), empty, one], batches);
+    }
+
+    #[test]
+    fn test_reader_validation() {
+        let batch = batch();
+
+        // The count column holds 4 nulls
+        let stream = tampered(&batch, |body| body.nodes[1].1 = 3);
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(IpcError::NullCountMismatch {
+                reported: 3,
+                counted: 4
+            }))
+        ));
+        assert!(reader.next().is_none());
+
+        let stream = tampered(&batch, |body| body.buffers[3].1 = body.bytes.len() as i64);
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert!(matches!(reader.next(), Some(Err(IpcError::InvalidBuffer))));
+
+        let stream = tampered(&batch, |body| {
+            body.nodes.truncate(3);
+        });
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(IpcError::InvalidMetadata))
+        ));
+
+        // Claims more rows than the buffers of the id column hold
+        let stream = tampered(&batch, |body| body.nodes[0].0 = 64);
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert!(matches!(reader.next(), Some(Err(IpcError::InvalidBuffer))));
+    }
+
+    #[test]
+    fn test_reader_text_errors() {
+        let schema = Schema::new(vec![Field::new("name", DataType::Text, false)]);
+        let batch =
+            RecordBatch::try_new(schema, vec![ArrayText::from(["ab", "cd"]).into()]).unwrap();
+
+        // Offsets are the second buffer and data the third
+        let stream = tampered(&batch, |body| {
+            let offsets = body.buffers[1].0 as usize;
+            body.bytes[offsets + 8..offsets + 16].copy_from_slice(&3_i64.to_le_bytes());
+            body.bytes[offsets + 16..offsets + 24].copy_from_slice(&2_i64.to_le_bytes());
+        });
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert!(matches!(reader.next(), Some(Err(IpcError::InvalidOffsets))));
+
+        let stream = tampered(&batch, |body| {
+            let data = body.buffers[2].0 as usize;
+            body.bytes[data] = 0xFF;
+        });
+        let mut reader = IpcReader::try_new(stream.as_slice()).unwrap();
+        assert!(matches!(reader.next(), Some(Err(IpcError::InvalidUtf8))));
+
+        assert!(matches!(
+            IpcReader::try_new([0xFF_u8; 3].as_slice()),
+            Err(IpcError::InvalidMetadata)
+        ));
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        let one = batch();
+        let batches = [one.head(4), one.clone(), one.tail(0)];
+
+        let mut writer = StreamWriter::new(vec![]);
+        assert_eq!(None, writer.schema());
+
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+
+        assert_eq!(Some(one.schema()), writer.schema());
+        writer.finish().unwrap();
+        let stream = writer.into_inner();
+
+        let mut reader = StreamReader::new(stream.as_slice());
+        assert_eq!(None, reader.schema());
+
+        for batch in &batches {
+            assert_eq!(batch, &reader.next().unwrap().unwrap());
+        }
+
+        assert_eq!(Some(one.schema()), reader.schema());
+        assert!(reader.next().is_none());
+
+        // Both writers produce the same stream
+        let mut writer = IpcWriter::try_new(vec![], one.schema()).unwrap();
+        batches
+            .iter()
+            .for_each(|batch| writer.write(batch).unwrap());
+        writer.finish().unwrap();
+        assert_eq!(stream, writer.into_inner());
+    }
+
+    #[test]
+    fn test_stream_schema_checks() {
+        let one = batch();
+        let two = one.select(&["id", "name"]).unwrap();
+
+        let mut writer = StreamWriter::new(vec![]);
+        writer.write(&one).unwrap();
+        assert!(matches!(writer.write(&two), Err(IpcError::SchemaMismatch)));
+        writer.finish().unwrap();
+        assert!(matches!(writer.write(&one), Err(IpcError::Finished)));
+
+        // A stream finished before any batch holds no schema
+        let mut writer = StreamWriter::new(vec![]);
+        writer.finish().unwrap();
+        let empty = writer.into_inner();
+        assert_eq!(0, StreamReader::new(empty.as_slice()).count());
+
+        // A second stream appended without its end of stream marker
+        let mut writer = StreamWriter::new(vec![]);
+        writer.write(&one).unwrap();
+        let mut stream = writer.into_inner();
+
+        let mut writer = StreamWriter::new(vec![]);
+        writer.write(&two).unwrap();
+        writer.finish().unwrap();
+        stream.extend(writer.into_inner());
+
+        let mut reader = StreamReader::new(stream.as_slice());
+        assert_eq!(one, reader.next().unwrap().unwrap());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(IpcError::InvalidMetadata))
+        ));
+        assert!(reader.next().is_none());
+    }
+}
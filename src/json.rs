@@ -0,0 +1,225 @@
+use std::error::Error;
+use std::fmt::{Display, Write as _};
+use std::io::{self, Write};
+
+use crate::column::ColumnArray;
+use crate::record_batch::RecordBatch;
+use crate::union::UnionRef;
+use crate::utils::Array;
+
+/// Errors raised while reading or writing JSON.
+#[derive(Debug)]
+pub enum JsonError {
+    Io(io::Error),
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "JSON io error: {error}"),
+        }
+    }
+}
+
+impl Error for JsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for JsonError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Writes `batch` as newline delimited JSON, one object per row keyed by
+/// field name.
+///
+/// Nulls, NaNs and infinities are written as `null`.
+pub fn write_ndjson<W: Write>(batch: &RecordBatch, mut writer: W) -> Result<(), JsonError> {
+    let names = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| {
+            let mut name = String::new();
+            push_string(&mut name, &field.name);
+            name
+        })
+        .collect::<Vec<_>>();
+
+    let mut line = String::new();
+
+    for row in 0..batch.num_rows() {
+        line.clear();
+        line.push('{');
+
+        for (idx, (name, column)) in names.iter().zip(batch.columns()).enumerate() {
+            if idx != 0 {
+                line.push(',');
+            }
+
+            line.push_str(name);
+            line.push(':');
+            push_value(&mut line, column, row);
+        }
+
+        line.push_str("}\n");
+        writer.write_all(line.as_bytes())?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the values of `column` as a single JSON array.
+pub fn write_json_array<W: Write>(column: &ColumnArray, mut writer: W) -> Result<(), JsonError> {
+    let mut out = String::from("[");
+
+    for idx in 0..column.len() {
+        if idx != 0 {
+            out.push(',');
+        }
+
+        push_value(&mut out, column, idx);
+    }
+
+    out.push(']');
+    writer.write_all(out.as_bytes())?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Pushes `text` as a quoted and escaped JSON string.
+fn push_string(out: &mut String, text: &str) {
+    out.push('"');
+
+    for chr in text.chars() {
+        match chr {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            chr if chr.is_control() => {
+                let _ = write!(out, "\\u{:04x}", chr as u32);
+            }
+            chr => out.push(chr),
+        }
+    }
+
+    out.push('"');
+}
+
+/// Pushes a number or boolean, whose JSON form is their display form.
+fn push_literal<T: Display>(out: &mut String, value: T) {
+    let _ = write!(out, "{value}");
+}
+
+/// JSON has no representation for NaN or the infinities.
+fn push_float(out: &mut String, value: f64) {
+    match value.is_finite() {
+        true => push_literal(out, value),
+        false => out.push_str("null"),
+    }
+}
+
+fn push_or_null<T>(out: &mut String, value: Option<T>, push: fn(&mut String, T)) {
+    match value {
+        Some(value) => push(out, value),
+        None => out.push_str("null"),
+    }
+}
+
+fn push_value(out: &mut String, column: &ColumnArray, idx: usize) {
+    match column {
+        ColumnArray::Int32(array) => push_or_null(out, array.get(idx), push_literal),
+        ColumnArray::UInt32(array) => push_or_null(out, array.get(idx), push_literal),
+        ColumnArray::ISize(array) => push_or_null(out, array.get(idx), push_literal),
+        ColumnArray::USize(array) => push_or_null(out, array.get(idx), push_literal),
+        ColumnArray::F32(array) => push_or_null(out, array.get(idx).map(f64::from), push_float),
+        ColumnArray::F64(array) => push_or_null(out, array.get(idx), push_float),
+        ColumnArray::Boolean(array) => push_or_null(out, array.get(idx), push_literal),
+        ColumnArray::Text(array) => push_or_null(out, array.get_ref(idx), push_string),
+        ColumnArray::Union(array) => match array.get_ref(idx) {
+            Some(UnionRef::U32(value)) => push_literal(out, value),
+            Some(UnionRef::I32(value)) => push_literal(out, value),
+            Some(UnionRef::USize(value)) => push_literal(out, value),
+            Some(UnionRef::ISize(value)) => push_literal(out, value),
+            Some(UnionRef::F32(value)) => push_float(out, value as f64),
+            Some(UnionRef::F64(value)) => push_float(out, value),
+            Some(UnionRef::Boolean(value)) => push_literal(out, value),
+            Some(UnionRef::Text(value)) => push_string(out, value),
+            Some(UnionRef::Null) | None => out.push_str("null"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{Field, Schema};
+    use crate::utils::DataType;
+    use crate::{ArrayBoolean, ArrayF64, ArrayI32, ArrayText, Union, UnionType};
+
+    #[test]
+    fn test_write_ndjson() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::F64, true),
+            Field::new("na\"me", DataType::Text, true),
+            Field::new("flag", DataType::Boolean, true),
+            Field::new("raw", DataType::Union, true),
+        ]);
+
+        let columns = vec![
+            ArrayI32::from([1, -2, 3]).into(),
+            ArrayF64::from([Some(1.5), None, Some(f64::NAN)]).into(),
+            ArrayText::from([Some("tab\there".into()), Some("\"q\" \\".into()), None]).into(),
+            ArrayBoolean::from([Some(true), None, Some(false)]).into(),
+            Union::from_vec(vec![
+                UnionType::Text("Bublé".into()),
+                UnionType::Null,
+                UnionType::F32(2.0),
+            ])
+            .into(),
+        ];
+
+        let batch = RecordBatch::try_new(schema, columns).unwrap();
+        let mut out = vec![];
+        write_ndjson(&batch, &mut out).unwrap();
+
+        let expected = [
+            r#"{"id":1,"price":1.5,"na\"me":"tab\there","flag":true,"raw":"Bublé"}"#,
+            r#"{"id":-2,"price":null,"na\"me":"\"q\" \\","flag":null,"raw":null}"#,
+            r#"{"id":3,"price":null,"na\"me":null,"flag":false,"raw":2}"#,
+            "",
+        ];
+
+        assert_eq!(expected.join("\n"), String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_write_json_array() {
+        let write = |column: ColumnArray| {
+            let mut out = vec![];
+            write_json_array(&column, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            "[1,null,3]",
+            write(ArrayI32::from([Some(1), None, Some(3)]).into())
+        );
+        assert_eq!(
+            r#"["a\u0001",null]"#,
+            write(ArrayText::from([Some("a\u{1}".into()), None]).into())
+        );
+        assert_eq!("[]", write(ArrayF64::from_vec(vec![]).into()));
+    }
+}
@@ -0,0 +1,119 @@
+#![allow(unused_imports, dead_code)]
+
+mod arrayi32;
+pub use arrayi32::*;
+
+mod arrayu32;
+pub use arrayu32::*;
+
+mod arrayisize;
+pub use arrayisize::*;
+
+mod arrayusize;
+pub use arrayusize::*;
+
+mod arraybool;
+pub use arraybool::*;
+
+mod arrayf32;
+pub use arrayf32::*;
+
+mod arrayf64;
+pub use arrayf64::*;
+
+mod arraytext;
+pub use arraytext::*;
+
+mod union;
+pub use union::*;
+
+mod statistics;
+pub use statistics::*;
+
+mod scalar;
+pub use scalar::*;
+
+mod arithmetic;
+pub use arithmetic::*;
+
+mod comparison;
+pub use comparison::*;
+
+mod rank;
+pub use rank::*;
+
+mod text;
+pub use text::*;
+
+mod column;
+pub use column::*;
+
+mod ffi;
+pub use ffi::*;
+
+mod mutable;
+pub use mutable::*;
+
+mod schema;
+pub use schema::*;
+
+mod record_batch;
+pub use record_batch::*;
+
+mod chunked;
+pub use chunked::*;
+
+mod expr;
+pub use expr::*;
+
+#[cfg(feature = "ipc")]
+mod flatbuffer;
+
+#[cfg(feature = "ipc")]
+pub mod ipc;
+#[cfg(feature = "ipc")]
+pub use ipc::*;
+
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "csv")]
+pub use csv::*;
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use json::*;
+
+#[cfg(feature = "parquet")]
+mod thrift;
+
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "parquet")]
+pub use parquet::*;
+
+#[cfg(feature = "calamine")]
+mod spreadsheet;
+#[cfg(feature = "calamine")]
+pub use spreadsheet::*;
+
+#[cfg(feature = "simd")]
+mod simd;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "rayon")]
+pub use parallel::*;
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
+#[cfg(feature = "proptest")]
+pub use arbitrary::*;
+
+#[cfg(any(test, feature = "test-utils"))]
+mod gen;
+#[cfg(any(test, feature = "test-utils"))]
+pub use gen::*;
+
+mod utils;
+pub use utils::*;
@@ -0,0 +1,496 @@
+use std::fmt::Debug;
+
+use crate::utils::{Array, NativeType};
+use crate::{ArrayBoolean, ArrayText};
+
+/// Mutable counterpart of a fixed sized primitive array.
+pub type MutablePrimitiveArray<T> = MutableArray<<T as NativeType>::Array>;
+
+/// Mutable counterpart of [`ArrayBoolean`].
+pub type MutableArrayBoolean = MutableArray<ArrayBoolean>;
+
+/// Mutable counterpart of [`ArrayText`].
+pub type MutableArrayText = MutableArray<ArrayText>;
+
+/// Values which can be pushed onto a [`MutableArray`] of `Self::Array`,
+/// letting [`array!`](crate::array) infer the array from its elements.
+pub trait ArrayElement {
+    /// The array storing values of this type.
+    type Array: Array;
+
+    fn into_data(self) -> <Self::Array as Array>::Data;
+}
+
+impl<T> ArrayElement for T
+where
+    T: NativeType,
+{
+    type Array = T::Array;
+
+    fn into_data(self) -> T {
+        self
+    }
+}
+
+impl ArrayElement for bool {
+    type Array = ArrayBoolean;
+
+    fn into_data(self) -> bool {
+        self
+    }
+}
+
+impl ArrayElement for &str {
+    type Array = ArrayText;
+
+    fn into_data(self) -> String {
+        self.to_string()
+    }
+}
+
+impl ArrayElement for String {
+    type Array = ArrayText;
+
+    fn into_data(self) -> String {
+        self
+    }
+}
+
+/// Creates an array from a list of elements, with `None` for nulls and
+/// every other element wrapped in `Some`.
+///
+/// The array is inferred from the elements, as with `array![1, None, 3]`
+/// building an [`ArrayI32`](crate::ArrayI32), or named by their type
+/// before a `;`, as with `array![f64; 1.0, None]` or `array![str; "a", None]`.
+/// Arrays of only nulls need the type, while empty ones can do with an
+/// annotation instead.
+#[macro_export]
+macro_rules! array {
+    () => {
+        $crate::MutableArray::new().freeze()
+    };
+    (@wrap [$($out:tt)*]) => {
+        [$($out)*]
+    };
+    (@wrap [$($out:tt)*] None $(, $($rest:tt)*)?) => {
+        $crate::array!(@wrap [$($out)* None,] $($($rest)*)?)
+    };
+    (@wrap [$($out:tt)*] Some($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::array!(@wrap [$($out)* Some($value),] $($($rest)*)?)
+    };
+    (@wrap [$($out:tt)*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::array!(@wrap [$($out)* Some($value),] $($($rest)*)?)
+    };
+    (@build $kind:ty; $($values:tt)*) => {{
+        let mut builder = $crate::MutableArray::new();
+
+        // Collected first so every element takes the same type
+        for value in $crate::array!(@wrap [] $($values)*) {
+            builder.push_element::<$kind>(value);
+        }
+
+        builder.freeze()
+    }};
+    (str; $($values:tt)*) => {
+        $crate::array!(@build &str; $($values)*)
+    };
+    ($kind:ident; $($values:tt)*) => {
+        $crate::array!(@build $kind; $($values)*)
+    };
+    ($($values:tt)*) => {
+        $crate::array!(@build _; $($values)*)
+    };
+}
+
+/// Growable validity bitmap tracking its own null count.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Validity {
+    bytes: Vec<u8>,
+    len: usize,
+    nulls: usize,
+}
+
+impl Validity {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(capacity.div_ceil(8)),
+            len: 0,
+            nulls: 0,
+        }
+    }
+
+    fn is_valid(&self, idx: usize) -> bool {
+        self.bytes[idx / 8] & (1 << (idx % 8)) != 0
+    }
+
+    fn push(&mut self, valid: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+
+        self.len += 1;
+        self.nulls += 1;
+        self.set(self.len - 1, valid);
+    }
+
+    fn set(&mut self, idx: usize, valid: bool) {
+        let was_valid = self.is_valid(idx);
+        let byte = &mut self.bytes[idx / 8];
+
+        match (was_valid, valid) {
+            (false, true) => {
+                *byte |= 1 << (idx % 8);
+                self.nulls -= 1;
+            }
+            (true, false) => {
+                *byte &= !(1 << (idx % 8));
+                self.nulls += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let removed = (len..self.len).filter(|idx| !self.is_valid(*idx)).count();
+
+        // Clear the now unused bits so they never leak back in on a push
+        for idx in len..self.len.min(len.div_ceil(8) * 8) {
+            self.bytes[idx / 8] &= !(1 << (idx % 8));
+        }
+
+        self.bytes.truncate(len.div_ceil(8));
+        self.nulls -= removed;
+        self.len = len;
+    }
+}
+
+/// Growable and mutable array which can be frozen into its immutable
+/// counterpart, `A`.
+///
+/// Null slots hold the default value of the element type.
+#[derive(Clone, PartialEq)]
+pub struct MutableArray<A>
+where
+    A: Array,
+    A::Data: Clone + Default,
+{
+    values: Vec<A::Data>,
+    validity: Validity,
+}
+
+impl<A> MutableArray<A>
+where
+    A: Array,
+    A::Data: Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            validity: Validity::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a [`MutableArray`] holding a copy of the values in `array`.
+    pub fn thaw(array: &A) -> Self {
+        let mut own = Self::with_capacity(array.len());
+        own.extend_from_array(array);
+        own
+    }
+
+    /// Converts `self` into its immutable counterpart.
+    pub fn freeze(self) -> A {
+        let Self { values, validity } = self;
+
+        let values = values
+            .into_iter()
+            .enumerate()
+            .map(|(idx, value)| validity.is_valid(idx).then_some(value));
+
+        A::new(values)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the number of nulls in the array.
+    pub fn null_count(&self) -> usize {
+        self.validity.nulls
+    }
+
+    /// Returns a copy of the value at `idx` if any.
+    ///
+    /// Returns None if `idx` is out of range
+    pub fn get(&self, idx: usize) -> Option<A::Data> {
+        if idx >= self.len() || !self.validity.is_valid(idx) {
+            return None;
+        }
+
+        Some(self.values[idx].clone())
+    }
+
+    /// Replaces the value at `idx`.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set(&mut self, idx: usize, value: Option<A::Data>) {
+        assert!(
+            idx < self.len(),
+            "Tried to index {} when array length is {}",
+            idx,
+            self.len()
+        );
+
+        self.validity.set(idx, value.is_some());
+        self.values[idx] = value.unwrap_or_default();
+    }
+
+    pub fn push(&mut self, value: Option<A::Data>) {
+        self.validity.push(value.is_some());
+        self.values.push(value.unwrap_or_default());
+    }
+
+    /// Pushes a value convertible into the elements of the array, such as a
+    /// `&str` onto text arrays.
+    pub fn push_element<T>(&mut self, value: Option<T>)
+    where
+        T: ArrayElement<Array = A>,
+    {
+        self.push(value.map(T::into_data));
+    }
+
+    /// Shortens the array to `len` elements, doing nothing if the array is
+    /// already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+        self.validity.truncate(len);
+    }
+
+    /// Appends every element of `array` onto `self`.
+    pub fn extend_from_array(&mut self, array: &A) {
+        self.values.reserve(array.len());
+
+        for idx in 0..array.len() {
+            self.push(array.get(idx));
+        }
+    }
+}
+
+impl<A> Default for MutableArray<A>
+where
+    A: Array,
+    A::Data: Clone + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> Debug for MutableArray<A>
+where
+    A: Array,
+    A::Data: Clone + Default + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let vals = (0..self.len()).map(|idx| self.get(idx));
+
+        f.debug_list().entries(vals).finish()
+    }
+}
+
+impl<A> From<&A> for MutableArray<A>
+where
+    A: Array,
+    A::Data: Clone + Default,
+{
+    fn from(value: &A) -> Self {
+        Self::thaw(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayF64, ArrayI32};
+
+    /// Small xorshift generator to keep the tests deterministic.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    fn check<A>(array: &MutableArray<A>, model: &[Option<A::Data>])
+    where
+        A: Array,
+        A::Data: Clone + Default + PartialEq + Debug,
+    {
+        assert_eq!(model.len(), array.len());
+        assert_eq!(
+            model.iter().filter(|val| val.is_none()).count(),
+            array.null_count()
+        );
+
+        for (idx, value) in model.iter().enumerate() {
+            assert_eq!(*value, array.get(idx));
+        }
+    }
+
+    #[test]
+    fn test_random_mutations() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let mut array = MutablePrimitiveArray::<i32>::new();
+        let mut model: Vec<Option<i32>> = vec![];
+
+        for _ in 0..2_000 {
+            let value = match rng.below(3) {
+                0 => None,
+                _ => Some(rng.next() as i32),
+            };
+
+            match rng.below(10) {
+                0 => {
+                    let len = rng.below(model.len() + 1);
+                    array.truncate(len);
+                    model.truncate(len);
+                }
+                1..=4 if !model.is_empty() => {
+                    let idx = rng.below(model.len());
+                    array.set(idx, value);
+                    model[idx] = value;
+                }
+                _ => {
+                    array.push(value);
+                    model.push(value);
+                }
+            }
+
+            check(&array, &model);
+        }
+
+        assert_eq!(ArrayI32::from_vec(model), array.freeze());
+    }
+
+    #[test]
+    fn test_freeze_thaw() {
+        let one = ArrayF64::from([Some(1.0), None, Some(3.5)]);
+        let mut two = MutablePrimitiveArray::<f64>::thaw(&one);
+
+        check(&two, &[Some(1.0), None, Some(3.5)]);
+
+        two.set(1, Some(2.0));
+        two.set(2, None);
+        two.extend_from_array(&one);
+
+        let two = two.freeze();
+        let three = ArrayF64::from([Some(1.0), Some(2.0), None, Some(1.0), None, Some(3.5)]);
+
+        assert_eq!(three, two);
+        // The source array is untouched
+        assert_eq!(ArrayF64::from([Some(1.0), None, Some(3.5)]), one);
+    }
+
+    #[test]
+    fn test_bool_and_text() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        let mut one = MutableArrayBoolean::new();
+        let mut two = MutableArrayText::new();
+        let mut bool_model = vec![];
+        let mut text_model = vec![];
+
+        for _ in 0..500 {
+            let valid = rng.below(4) != 0;
+            let flag = valid.then_some(rng.below(2) == 0);
+            let text = valid.then(|| rng.next().to_string());
+
+            if rng.below(3) == 0 && !bool_model.is_empty() {
+                let idx = rng.below(bool_model.len());
+                one.set(idx, flag);
+                two.set(idx, text.clone());
+                bool_model[idx] = flag;
+                text_model[idx] = text;
+            } else {
+                one.push(flag);
+                two.push(text.clone());
+                bool_model.push(flag);
+                text_model.push(text);
+            }
+        }
+
+        check(&one, &bool_model);
+        check(&two, &text_model);
+
+        assert_eq!(ArrayBoolean::from_vec(bool_model), one.freeze());
+        assert_eq!(ArrayText::from_vec(text_model), two.freeze());
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to index 3 when array length is 3")]
+    fn test_set_out_of_bounds() {
+        let mut one = MutablePrimitiveArray::<u32>::thaw(&[1_u32, 2, 3].into());
+        one.set(3, Some(4));
+    }
+
+    #[test]
+    fn test_array_macro() {
+        use crate::{ArrayBoolean, ArrayF32, ArrayISize, ArrayText, ArrayU32, ArrayUSize};
+
+        assert_eq!(
+            ArrayI32::from([Some(1), Some(2), None, Some(4)]),
+            array![1, 2, None, 4]
+        );
+        assert_eq!(ArrayI32::from([None, Some(2)]), array![None, Some(2)]);
+        assert_eq!(ArrayU32::from([3, 4]), array![3u32, 4]);
+        assert_eq!(ArrayISize::from([Some(-1), None]), array![isize; -1, None,]);
+        assert_eq!(ArrayUSize::from([7]), array![usize; 7]);
+        assert_eq!(ArrayF32::from([Some(0.5), None]), array![f32; 0.5, None]);
+        assert_eq!(ArrayF64::from([Some(1.0), None]), array![f64; 1.0, None]);
+        assert_eq!(ArrayF64::from([2.5]), array![2.5]);
+        assert_eq!(ArrayBoolean::from([Some(true), None]), array![true, None]);
+
+        let text = ArrayText::new([Some("a".into()), None, Some("b".into())]);
+        assert_eq!(text, array![str; "a", None, "b"]);
+        assert_eq!(text, array!["a", None, "b"]);
+
+        let empty: ArrayI32 = array![];
+        assert_eq!(ArrayI32::default(), empty);
+        assert_eq!(ArrayText::empty(), array![str;]);
+        assert_eq!(ArrayI32::nulls(2), array![i32; None, None]);
+    }
+
+    #[test]
+    fn test_default() {
+        const EMPTY: ArrayF64 = ArrayF64::empty();
+
+        assert!(EMPTY.is_empty());
+        assert_eq!(ArrayF64::from_vec(vec![]), ArrayF64::default());
+        assert_eq!(
+            crate::Union::from_builder(Default::default()),
+            crate::Union::default()
+        );
+        assert_eq!(
+            crate::ArrayBoolean::from_vec(vec![]),
+            crate::ArrayBoolean::empty()
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::arithmetic::{max_of, min_of, sum_of, total_of};
+use crate::utils::{Array, Numeric};
+
+/// Aggregates over numeric arrays computed on the rayon thread pool.
+///
+/// The array is split into one chunk per thread, each aggregated with the
+/// sequential kernels before the partial results are combined. Small
+/// arrays are better served by [`crate::sum`] and friends.
+pub trait ParallelAggregate: Array + Sync
+where
+    Self::Data: Numeric + Send,
+{
+    /// Parallel [`crate::sum`]. Float sums may round differently as the
+    /// elements are added in a different order.
+    fn par_sum(&self) -> Option<Self::Data> {
+        par_chunks(self)
+            .filter_map(|range| sum_of(range.filter_map(|idx| self.get(idx))))
+            .reduce_with(Numeric::add_wrapping)
+    }
+
+    /// Parallel [`crate::min`].
+    fn par_min(&self) -> Option<Self::Data> {
+        par_chunks(self)
+            .filter_map(|range| min_of(range.filter_map(|idx| self.get(idx))))
+            .reduce_with(|acc, val| if val < acc { val } else { acc })
+    }
+
+    /// Parallel [`crate::max`].
+    fn par_max(&self) -> Option<Self::Data> {
+        par_chunks(self)
+            .filter_map(|range| max_of(range.filter_map(|idx| self.get(idx))))
+            .reduce_with(|acc, val| if val > acc { val } else { acc })
+    }
+
+    /// Parallel [`crate::mean`].
+    fn par_mean(&self) -> Option<f64> {
+        let (total, count) = par_chunks(self)
+            .map(|range| total_of(range.filter_map(|idx| self.get(idx))))
+            .reduce(|| (0.0, 0), |acc, val| (acc.0 + val.0, acc.1 + val.1));
+
+        (count != 0).then(|| total / count as f64)
+    }
+}
+
+impl<A> ParallelAggregate for A
+where
+    A: Array + Sync,
+    A::Data: Numeric + Send,
+{
+}
+
+/// Splits `array` into a chunk of indices per thread.
+///
+/// Chunks start on multiples of 8 so that no byte of the validity buffer is
+/// shared between two chunks.
+fn par_chunks<A: Array>(array: &A) -> impl ParallelIterator<Item = Range<usize>> {
+    let len = array.len();
+    let size = len
+        .div_ceil(rayon::current_num_threads())
+        .next_multiple_of(8)
+        .max(8);
+
+    (0..len.div_ceil(size))
+        .into_par_iter()
+        .map(move |chunk| chunk * size..len.min((chunk + 1) * size))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{max, mean, min, sum, ArrayF64, ArrayI32, ArrayUSize};
+
+    #[test]
+    fn test_parallel_aggregates() {
+        let one = ArrayI32::new((0..10_003).map(|idx| (idx % 9 != 4).then_some(idx - 5_000)));
+
+        assert_eq!(sum(&one), one.par_sum());
+        assert_eq!(min(&one), one.par_min());
+        assert_eq!(max(&one), one.par_max());
+        assert_eq!(mean(&one), one.par_mean());
+
+        // Whole numbers add up exactly in any order
+        let two = ArrayF64::new((0..10_003).map(|idx| (idx % 5 != 0).then_some(idx as f64)));
+
+        assert_eq!(sum(&two), two.par_sum());
+        assert_eq!(Some(1.0), two.par_min());
+        assert_eq!(Some(10_002.0), two.par_max());
+        assert_eq!(mean(&two), two.par_mean());
+    }
+
+    #[test]
+    fn test_parallel_aggregates_edge_cases() {
+        let one = ArrayUSize::from_vec(vec![]);
+
+        assert_eq!(None, one.par_sum());
+        assert_eq!(None, one.par_mean());
+
+        let one = ArrayF64::from([None, Some(f64::NAN), None]);
+
+        assert_eq!(None, one.par_min());
+        assert_eq!(None, one.par_max());
+        assert!(one.par_sum().unwrap().is_nan());
+
+        let one = ArrayUSize::from([Some(3), None, Some(5)]);
+
+        assert_eq!(Some(8), one.par_sum());
+        assert_eq!(Some(4.0), one.par_mean());
+    }
+}
@@ -0,0 +1,925 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::column::ColumnArray;
+use crate::record_batch::RecordBatch;
+use crate::schema::Field;
+use crate::thrift::{self, Struct, Value};
+use crate::utils::{Array, DataType};
+use crate::{ArrayBoolean, ArrayText};
+
+/// First and last bytes of a Parquet file.
+const MAGIC: [u8; 4] = *b"PAR1";
+
+/// `Type` enum values.
+const TYPE_BOOLEAN: i32 = 0;
+const TYPE_INT32: i32 = 1;
+const TYPE_INT64: i32 = 2;
+const TYPE_FLOAT: i32 = 4;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+
+/// `FieldRepetitionType` enum values.
+const REQUIRED: i32 = 0;
+const OPTIONAL: i32 = 1;
+
+/// `ConvertedType` enum values.
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_UINT_32: i32 = 13;
+const CONVERTED_UINT_64: i32 = 14;
+
+/// `LogicalType` union discriminants.
+const LOGICAL_STRING: i16 = 1;
+const LOGICAL_INTEGER: i16 = 10;
+
+/// `Encoding` enum values.
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const ENCODING_RLE_DICTIONARY: i32 = 8;
+
+/// `PageType` enum values.
+const PAGE_DATA: i32 = 0;
+const PAGE_DICTIONARY: i32 = 2;
+
+/// Errors raised while writing Parquet.
+#[derive(Debug)]
+pub enum ParquetError {
+    Io(io::Error),
+    /// The batch holds a type which cannot be written.
+    Unsupported(String),
+}
+
+impl Display for ParquetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Parquet io error: {error}"),
+            Self::Unsupported(feature) => write!(f, "Unsupported Parquet feature: {feature}"),
+        }
+    }
+}
+
+impl Error for ParquetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParquetError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Codec applied to the pages of a Parquet file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    Uncompressed,
+    Snappy,
+}
+
+impl Compression {
+    /// `CompressionCodec` enum value.
+    fn codec(self) -> i32 {
+        match self {
+            Self::Uncompressed => 0,
+            Self::Snappy => 1,
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Uncompressed => data.to_vec(),
+            Self::Snappy => snappy(data),
+        }
+    }
+}
+
+/// Writes `batch` to `writer` as a Parquet file holding a single row group.
+///
+/// Each column is written as one data page, preceded by a dictionary page
+/// for text columns whose values repeat twice on average. Columns are
+/// optional if their field is nullable or they hold nulls. Unsigned
+/// integers are annotated as such and `usize` and `isize` are written as 64
+/// bit integers. Union columns are not supported.
+pub fn write_parquet<W: Write>(
+    batch: &RecordBatch,
+    mut writer: W,
+    compression: Compression,
+) -> Result<(), ParquetError> {
+    let fields = batch.schema().fields();
+
+    if let Some(field) = fields
+        .iter()
+        .find(|field| field.data_type == DataType::Union)
+    {
+        return Err(ParquetError::Unsupported(format!(
+            "union field \"{}\"",
+            field.name
+        )));
+    }
+
+    writer.write_all(&MAGIC)?;
+
+    let mut offset = MAGIC.len();
+    let mut schema = vec![Value::Struct(
+        Struct::new()
+            .with(4, Value::string("schema"))
+            .with(5, Value::I32(fields.len() as i32)),
+    )];
+    let mut chunks = vec![];
+    let mut total_size = 0;
+
+    for (field, column) in fields.iter().zip(batch.columns()) {
+        let optional = field.nullable || column.null_count() > 0;
+        let (chunk, size) = column_chunk(field, column, optional, offset, compression);
+
+        writer.write_all(&chunk.bytes)?;
+        schema.push(Value::Struct(schema_element(field, optional)));
+        chunks.push(Value::Struct(chunk.metadata));
+
+        offset += chunk.bytes.len();
+        total_size += size;
+    }
+
+    let row_group = Struct::new()
+        .with(1, Value::List(chunks))
+        .with(2, Value::I64(total_size as i64))
+        .with(3, Value::I64(batch.num_rows() as i64));
+
+    let mut metadata = Struct::new()
+        .with(1, Value::I32(1))
+        .with(2, Value::List(schema))
+        .with(3, Value::I64(batch.num_rows() as i64))
+        .with(4, Value::List(vec![Value::Struct(row_group)]))
+        .with(6, Value::string("modav-arrow"));
+
+    if !batch.schema().metadata().is_empty() {
+        let pairs = batch
+            .schema()
+            .metadata()
+            .iter()
+            .map(|(key, value)| {
+                Value::Struct(
+                    Struct::new()
+                        .with(1, Value::string(key.clone()))
+                        .with(2, Value::string(value.clone())),
+                )
+            })
+            .collect();
+
+        metadata = metadata.with(5, Value::List(pairs));
+    }
+
+    let metadata = thrift::encode(&metadata);
+
+    writer.write_all(&metadata)?;
+    writer.write_all(&(metadata.len() as u32).to_le_bytes())?;
+    writer.write_all(&MAGIC)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn schema_element(field: &Field, optional: bool) -> Struct {
+    let int_type = |bit_width: i8, signed: bool| {
+        let int = Struct::new()
+            .with(1, Value::I8(bit_width))
+            .with(2, Value::Bool(signed));
+
+        Value::Struct(Struct::new().with(LOGICAL_INTEGER, Value::Struct(int)))
+    };
+
+    let element = Struct::new()
+        .with(1, Value::I32(physical_type(field.data_type)))
+        .with(3, Value::I32(if optional { OPTIONAL } else { REQUIRED }))
+        .with(4, Value::string(field.name.clone()));
+
+    match field.data_type {
+        DataType::UInt32 => element
+            .with(6, Value::I32(CONVERTED_UINT_32))
+            .with(10, int_type(32, false)),
+        DataType::USize => element
+            .with(6, Value::I32(CONVERTED_UINT_64))
+            .with(10, int_type(64, false)),
+        DataType::Text => element.with(6, Value::I32(CONVERTED_UTF8)).with(
+            10,
+            Value::Struct(Struct::new().with(LOGICAL_STRING, Value::Struct(Struct::new()))),
+        ),
+        _ => element,
+    }
+}
+
+fn physical_type(data_type: DataType) -> i32 {
+    match data_type {
+        DataType::Int32 | DataType::UInt32 => TYPE_INT32,
+        DataType::ISize | DataType::USize => TYPE_INT64,
+        DataType::F32 => TYPE_FLOAT,
+        DataType::F64 => TYPE_DOUBLE,
+        DataType::Boolean => TYPE_BOOLEAN,
+        DataType::Text => TYPE_BYTE_ARRAY,
+        DataType::Union => unreachable!("Union fields are rejected before writing"),
+    }
+}
+
+/// Pages of a column chunk along with its `ColumnChunk` metadata.
+struct Chunk {
+    bytes: Vec<u8>,
+    metadata: Struct,
+}
+
+/// Encodes `column` as a chunk starting `offset` bytes into the file,
+/// returning it along with its uncompressed size.
+fn column_chunk(
+    field: &Field,
+    column: &ColumnArray,
+    optional: bool,
+    offset: usize,
+    compression: Compression,
+) -> (Chunk, usize) {
+    let mut bytes = vec![];
+    let mut size = 0;
+    let mut encodings = vec![Value::I32(ENCODING_PLAIN), Value::I32(ENCODING_RLE)];
+    let mut dictionary_offset = None;
+
+    let (values, dictionary) = encode_values(column);
+
+    if let Some((dictionary, len)) = dictionary {
+        let header = Struct::new()
+            .with(1, Value::I32(len as i32))
+            .with(2, Value::I32(ENCODING_PLAIN));
+
+        dictionary_offset = Some(offset);
+        size += write_page(
+            &mut bytes,
+            PAGE_DICTIONARY,
+            (7, header),
+            &dictionary,
+            compression,
+        );
+        encodings.push(Value::I32(ENCODING_RLE_DICTIONARY));
+    }
+
+    let encoding = match dictionary_offset {
+        Some(_) => ENCODING_RLE_DICTIONARY,
+        None => ENCODING_PLAIN,
+    };
+
+    // Levels are only written for optional columns, as they are all 1 for
+    // required ones
+    let mut data = vec![];
+
+    if optional {
+        let levels = (0..column.len())
+            .map(|idx| !column.is_null(idx) as u32)
+            .collect::<Vec<_>>();
+
+        let mut encoded = vec![];
+        rle_hybrid(&mut encoded, &levels, 1);
+        data.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        data.extend_from_slice(&encoded);
+    }
+
+    data.extend_from_slice(&values);
+
+    let header = Struct::new()
+        .with(1, Value::I32(column.len() as i32))
+        .with(2, Value::I32(encoding))
+        .with(3, Value::I32(ENCODING_RLE))
+        .with(4, Value::I32(ENCODING_RLE));
+
+    let data_offset = offset + bytes.len();
+    size += write_page(&mut bytes, PAGE_DATA, (5, header), &data, compression);
+
+    let mut metadata = Struct::new()
+        .with(1, Value::I32(physical_type(field.data_type)))
+        .with(2, Value::List(encodings))
+        .with(3, Value::List(vec![Value::string(field.name.clone())]))
+        .with(4, Value::I32(compression.codec()))
+        .with(5, Value::I64(column.len() as i64))
+        .with(6, Value::I64(size as i64))
+        .with(7, Value::I64(bytes.len() as i64))
+        .with(9, Value::I64(data_offset as i64));
+
+    if let Some(dictionary_offset) = dictionary_offset {
+        metadata = metadata.with(11, Value::I64(dictionary_offset as i64));
+    }
+
+    let metadata = Struct::new()
+        .with(2, Value::I64(offset as i64))
+        .with(3, Value::Struct(metadata));
+
+    (Chunk { bytes, metadata }, size)
+}
+
+/// Writes a page holding `data`, whose type specific header is at the given
+/// field id of its `PageHeader`. Returns the uncompressed size of the page.
+fn write_page(
+    buf: &mut Vec<u8>,
+    page_type: i32,
+    (id, header): (i16, Struct),
+    data: &[u8],
+    compression: Compression,
+) -> usize {
+    let compressed = compression.compress(data);
+
+    let header = Struct::new()
+        .with(1, Value::I32(page_type))
+        .with(2, Value::I32(data.len() as i32))
+        .with(3, Value::I32(compressed.len() as i32))
+        .with(id, Value::Struct(header));
+    let header = thrift::encode(&header);
+
+    buf.extend_from_slice(&header);
+    buf.extend_from_slice(&compressed);
+
+    header.len() + data.len()
+}
+
+/// Encodes the non-null values of `column` for its data page, along with
+/// the plain encoded dictionary they index into and its length, if any.
+fn encode_values(column: &ColumnArray) -> (Vec<u8>, Option<(Vec<u8>, usize)>) {
+    let values = match column {
+        ColumnArray::Int32(array) => array
+            .copied_iter()
+            .flatten()
+            .flat_map(i32::to_le_bytes)
+            .collect(),
+        ColumnArray::UInt32(array) => array
+            .copied_iter()
+            .flatten()
+            .flat_map(u32::to_le_bytes)
+            .collect(),
+        ColumnArray::ISize(array) => array
+            .copied_iter()
+            .flatten()
+            .flat_map(|value| (value as i64).to_le_bytes())
+            .collect(),
+        ColumnArray::USize(array) => array
+            .copied_iter()
+            .flatten()
+            .flat_map(|value| (value as u64).to_le_bytes())
+            .collect(),
+        ColumnArray::F32(array) => array
+            .copied_iter()
+            .flatten()
+            .flat_map(f32::to_le_bytes)
+            .collect(),
+        ColumnArray::F64(array) => array
+            .copied_iter()
+            .flatten()
+            .flat_map(f64::to_le_bytes)
+            .collect(),
+        ColumnArray::Boolean(array) => plain_booleans(array),
+        ColumnArray::Text(array) => return encode_text(array),
+        ColumnArray::Union(_) => unreachable!("Union fields are rejected before writing"),
+    };
+
+    (values, None)
+}
+
+/// Packs booleans into bits, least significant first.
+fn plain_booleans(array: &ArrayBoolean) -> Vec<u8> {
+    let mut bytes = vec![0; (array.len() - array.null_count()).div_ceil(8)];
+
+    for (idx, value) in array.iter().flatten().enumerate() {
+        bytes[idx / 8] |= (value as u8) << (idx % 8);
+    }
+
+    bytes
+}
+
+/// Dictionary encodes text whose values repeat twice on average, plain
+/// encoding it otherwise.
+fn encode_text(array: &ArrayText) -> (Vec<u8>, Option<(Vec<u8>, usize)>) {
+    let plain = |values: &mut dyn Iterator<Item = &str>| {
+        let mut bytes = vec![];
+
+        for value in values {
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(value.as_bytes());
+        }
+
+        bytes
+    };
+
+    let mut entries = HashMap::new();
+    let mut dictionary = vec![];
+    let mut indices = vec![];
+
+    for value in array.iter().flatten() {
+        let index = *entries.entry(value).or_insert_with(|| {
+            dictionary.push(value);
+            dictionary.len() as u32 - 1
+        });
+
+        indices.push(index);
+    }
+
+    if indices.is_empty() || dictionary.len() * 2 > indices.len() {
+        return (plain(&mut array.iter().flatten()), None);
+    }
+
+    let bit_width = u32::BITS - (dictionary.len() as u32 - 1).leading_zeros();
+    let bit_width = bit_width.max(1);
+
+    let mut values = vec![bit_width as u8];
+    rle_hybrid(&mut values, &indices, bit_width);
+
+    let len = dictionary.len();
+
+    (values, Some((plain(&mut dictionary.into_iter()), len)))
+}
+
+/// Encodes `values` of `bit_width` bits with the RLE and bit-packing hybrid
+/// encoding, using runs for 8 or more repeats and bit-packing the rest.
+fn rle_hybrid(buf: &mut Vec<u8>, values: &[u32], bit_width: u32) {
+    let run_len = |start: usize| {
+        values[start..]
+            .iter()
+            .take_while(|value| **value == values[start])
+            .count()
+    };
+
+    let mut start = 0;
+
+    while start < values.len() {
+        let run = run_len(start);
+
+        if run >= 8 {
+            thrift::write_varint(buf, (run as u64) << 1);
+            buf.extend_from_slice(&values[start].to_le_bytes()[..bit_width.div_ceil(8) as usize]);
+            start += run;
+            continue;
+        }
+
+        // Bit-packed runs hold groups of 8 values, so only the last one can
+        // be padded
+        let mut end = (start + 8).min(values.len());
+
+        while end < values.len() && run_len(end) < 8 {
+            end = (end + 8).min(values.len());
+        }
+
+        let groups = (end - start).div_ceil(8);
+        thrift::write_varint(buf, ((groups as u64) << 1) | 1);
+
+        let mut packed = vec![0_u8; groups * bit_width as usize];
+
+        for (idx, value) in values[start..end].iter().enumerate() {
+            for bit in 0..bit_width {
+                let pos = idx * bit_width as usize + bit as usize;
+                packed[pos / 8] |= (((value >> bit) & 1) as u8) << (pos % 8);
+            }
+        }
+
+        buf.extend_from_slice(&packed);
+        start = end;
+    }
+}
+
+/// Compresses `data` in the raw Snappy format, replacing repeats of 4 or
+/// more bytes within the preceding 64 KiB by copies.
+fn snappy(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    thrift::write_varint(&mut out, data.len() as u64);
+
+    // Last position, plus one, of each hashed 4 byte sequence
+    let mut table = vec![0_usize; 1 << 14];
+    let mut literal = 0;
+    let mut pos = 0;
+
+    while pos + 4 <= data.len() {
+        let word = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+        let slot = (word.wrapping_mul(0x1E35_A7BD) >> 18) as usize;
+        let candidate = std::mem::replace(&mut table[slot], pos + 1);
+
+        let matched = candidate
+            .checked_sub(1)
+            .filter(|start| pos - start <= u16::MAX as usize)
+            .filter(|start| data[*start..*start + 4] == data[pos..pos + 4]);
+
+        let Some(start) = matched else {
+            pos += 1;
+            continue;
+        };
+
+        let len = 4 + data[pos + 4..]
+            .iter()
+            .zip(&data[start + 4..])
+            .take_while(|(one, two)| one == two)
+            .count();
+
+        snappy_literal(&mut out, &data[literal..pos]);
+        snappy_copy(&mut out, pos - start, len);
+
+        pos += len;
+        literal = pos;
+    }
+
+    snappy_literal(&mut out, &data[literal..]);
+
+    out
+}
+
+fn snappy_literal(out: &mut Vec<u8>, literal: &[u8]) {
+    let Some(len) = literal.len().checked_sub(1) else {
+        return;
+    };
+
+    if len < 60 {
+        out.push((len as u8) << 2);
+    } else {
+        let width = (usize::BITS - len.leading_zeros()).div_ceil(8) as usize;
+        out.push((59 + width as u8) << 2);
+        out.extend_from_slice(&len.to_le_bytes()[..width]);
+    }
+
+    out.extend_from_slice(literal);
+}
+
+/// Writes copies of at most 64 bytes with a 2 byte offset.
+fn snappy_copy(out: &mut Vec<u8>, offset: usize, mut len: usize) {
+    while len > 0 {
+        let chunk = len.min(64);
+        out.push((((chunk - 1) as u8) << 2) | 2);
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        len -= chunk;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::union::Scalar;
+    use crate::{ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayU32, ArrayUSize, Union};
+    use std::collections::BTreeMap;
+
+    /// Expands raw Snappy data.
+    fn unsnappy(data: &[u8]) -> Vec<u8> {
+        let mut pos = 0;
+        let len = thrift::read_varint(data, &mut pos).unwrap() as usize;
+        let mut out = Vec::with_capacity(len);
+
+        while pos < data.len() {
+            let tag = data[pos];
+            pos += 1;
+
+            match tag & 3 {
+                0 => {
+                    let len = match tag >> 2 {
+                        len @ 0..60 => len as usize + 1,
+                        width => {
+                            let width = (width - 59) as usize;
+                            let mut bytes = [0; 8];
+                            bytes[..width].copy_from_slice(&data[pos..pos + width]);
+                            pos += width;
+                            usize::from_le_bytes(bytes) + 1
+                        }
+                    };
+
+                    out.extend_from_slice(&data[pos..pos + len]);
+                    pos += len;
+                }
+                2 => {
+                    let len = (tag >> 2) as usize + 1;
+                    let offset = u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+                    pos += 2;
+
+                    for _ in 0..len {
+                        out.push(out[out.len() - offset]);
+                    }
+                }
+                _ => panic!("Only literals and 2 byte offset copies are written"),
+            }
+        }
+
+        assert_eq!(len, out.len());
+        out
+    }
+
+    /// Decodes `count` values of the RLE and bit-packing hybrid encoding.
+    fn un_rle(data: &[u8], pos: &mut usize, count: usize, bit_width: usize) -> Vec<u32> {
+        let mut values = vec![];
+
+        while values.len() < count {
+            let header = thrift::read_varint(data, pos).unwrap() as usize;
+
+            if header & 1 == 0 {
+                let mut bytes = [0; 4];
+                let width = bit_width.div_ceil(8);
+                bytes[..width].copy_from_slice(&data[*pos..*pos + width]);
+                *pos += width;
+                values.extend(std::iter::repeat_n(u32::from_le_bytes(bytes), header >> 1));
+            } else {
+                let len = (header >> 1) * 8;
+                let packed = &data[*pos..*pos + (header >> 1) * bit_width];
+                *pos += packed.len();
+
+                for idx in 0..len {
+                    let value = (0..bit_width).fold(0, |value, bit| {
+                        let pos = idx * bit_width + bit;
+                        value | (((packed[pos / 8] >> (pos % 8)) & 1) as u32) << bit
+                    });
+                    values.push(value);
+                }
+            }
+        }
+
+        values.truncate(count);
+        values
+    }
+
+    fn int(value: &Value) -> i64 {
+        match value {
+            Value::I32(value) => *value as i64,
+            Value::I64(value) => *value,
+            other => panic!("{other:?} is not an integer"),
+        }
+    }
+
+    fn structs(value: &Value) -> Vec<&Struct> {
+        match value {
+            Value::List(values) => values
+                .iter()
+                .map(|value| match value {
+                    Value::Struct(value) => value,
+                    other => panic!("{other:?} is not a struct"),
+                })
+                .collect(),
+            other => panic!("{other:?} is not a list"),
+        }
+    }
+
+    /// Reads back the columns of a file written by [`write_parquet`] as
+    /// scalars.
+    fn read(file: &[u8]) -> (Struct, Vec<Vec<Scalar>>) {
+        assert_eq!(MAGIC, file[..4]);
+        assert_eq!(MAGIC, file[file.len() - 4..]);
+
+        let len = u32::from_le_bytes(file[file.len() - 8..file.len() - 4].try_into().unwrap());
+        let start = file.len() - 8 - len as usize;
+        let (metadata, read) = thrift::decode(&file[start..]).unwrap();
+        assert_eq!(len as usize, read);
+
+        let schema = structs(metadata.get(2).unwrap());
+        let row_group = structs(metadata.get(4).unwrap())[0];
+        let rows = int(metadata.get(3).unwrap()) as usize;
+
+        let columns = structs(row_group.get(1).unwrap())
+            .into_iter()
+            .zip(&schema[1..])
+            .map(|(chunk, element)| {
+                let chunk = match chunk.get(3).unwrap() {
+                    Value::Struct(chunk) => chunk,
+                    other => panic!("{other:?} is not a struct"),
+                };
+                let codec = int(chunk.get(4).unwrap());
+                let physical = int(element.get(1).unwrap()) as i32;
+                let converted = element.get(6).map(int);
+                let optional = int(element.get(3).unwrap()) as i32 == OPTIONAL;
+
+                let mut pos = chunk
+                    .get(11)
+                    .or(chunk.get(9))
+                    .map(|offset| int(offset) as usize)
+                    .unwrap();
+                let mut dictionary = None;
+
+                loop {
+                    let (header, read) = thrift::decode(&file[pos..]).unwrap();
+                    pos += read;
+
+                    let size = int(header.get(3).unwrap()) as usize;
+                    let page = &file[pos..pos + size];
+                    pos += size;
+
+                    let page = match codec {
+                        0 => page.to_vec(),
+                        _ => unsnappy(page),
+                    };
+                    assert_eq!(int(header.get(2).unwrap()) as usize, page.len());
+
+                    if int(header.get(1).unwrap()) as i32 == PAGE_DICTIONARY {
+                        dictionary = Some(page);
+                        continue;
+                    }
+
+                    let mut at = 0;
+                    let levels = match optional {
+                        true => {
+                            let len = u32::from_le_bytes(page[..4].try_into().unwrap());
+                            at = 4;
+                            let levels = un_rle(&page, &mut at, rows, 1);
+                            assert_eq!(4 + len as usize, at);
+                            levels
+                        }
+                        false => vec![1; rows],
+                    };
+                    let valid = levels.iter().sum::<u32>() as usize;
+
+                    let mut values = match (&dictionary, physical) {
+                        (Some(dictionary), _) => {
+                            let bit_width = page[at] as usize;
+                            at += 1;
+
+                            let mut entries = vec![];
+                            let mut pos = 0;
+                            while pos < dictionary.len() {
+                                let len = u32::from_le_bytes(
+                                    dictionary[pos..pos + 4].try_into().unwrap(),
+                                ) as usize;
+                                entries.push(
+                                    String::from_utf8(dictionary[pos + 4..pos + 4 + len].to_vec())
+                                        .unwrap(),
+                                );
+                                pos += 4 + len;
+                            }
+
+                            un_rle(&page, &mut at, valid, bit_width)
+                                .into_iter()
+                                .map(|idx| Scalar::Text(entries[idx as usize].clone()))
+                                .collect::<Vec<_>>()
+                        }
+                        (None, TYPE_BOOLEAN) => (0..valid)
+                            .map(|idx| Scalar::Boolean((page[at + idx / 8] >> (idx % 8)) & 1 == 1))
+                            .collect(),
+                        (None, TYPE_BYTE_ARRAY) => (0..valid)
+                            .map(|_| {
+                                let len = u32::from_le_bytes(page[at..at + 4].try_into().unwrap())
+                                    as usize;
+                                let value = String::from_utf8(page[at + 4..at + 4 + len].to_vec());
+                                at += 4 + len;
+                                Scalar::Text(value.unwrap())
+                            })
+                            .collect(),
+                        (None, TYPE_INT32 | TYPE_FLOAT) => page[at..]
+                            .chunks_exact(4)
+                            .map(|bytes| {
+                                let bytes = bytes.try_into().unwrap();
+                                match (physical, converted) {
+                                    (TYPE_FLOAT, _) => Scalar::F32(f32::from_le_bytes(bytes)),
+                                    (_, Some(13)) => Scalar::U32(u32::from_le_bytes(bytes)),
+                                    _ => Scalar::I32(i32::from_le_bytes(bytes)),
+                                }
+                            })
+                            .collect(),
+                        (None, _) => page[at..]
+                            .chunks_exact(8)
+                            .map(|bytes| {
+                                let bytes = bytes.try_into().unwrap();
+                                match (physical, converted) {
+                                    (TYPE_DOUBLE, _) => Scalar::F64(f64::from_le_bytes(bytes)),
+                                    (_, Some(14)) => {
+                                        Scalar::USize(u64::from_le_bytes(bytes) as usize)
+                                    }
+                                    _ => Scalar::ISize(i64::from_le_bytes(bytes) as isize),
+                                }
+                            })
+                            .collect(),
+                    }
+                    .into_iter();
+
+                    return levels
+                        .into_iter()
+                        .map(|level| match level {
+                            1 => values.next().unwrap(),
+                            _ => Scalar::Null,
+                        })
+                        .collect::<Vec<_>>();
+                }
+            })
+            .collect();
+
+        (metadata, columns)
+    }
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("count", DataType::UInt32, true),
+            Field::new("offset", DataType::ISize, true),
+            Field::new("size", DataType::USize, false),
+            Field::new("ratio", DataType::F32, true),
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+            Field::new("city", DataType::Text, true),
+            Field::new("flag", DataType::Boolean, true),
+        ])
+        .with_metadata(BTreeMap::from([("source".into(), "parquet.rs".into())]));
+
+        let rows = 50;
+        // Every `step`th row is null
+        let valid = |idx: usize, step: usize| !idx.is_multiple_of(step);
+        let cities = ["Accra", "Kumasi", "Tamale"];
+
+        let columns = vec![
+            ArrayI32::from_range(-25..rows as i32 - 25).into(),
+            ArrayU32::new((0..rows).map(|idx| valid(idx, 3).then_some(u32::MAX - idx as u32)))
+                .into(),
+            ArrayISize::new((0..rows).map(|idx| valid(idx, 4).then_some(-(idx as isize)))).into(),
+            ArrayUSize::new((0..rows).map(|idx| Some(usize::MAX - idx))).into(),
+            ArrayF32::new((0..rows).map(|idx| valid(idx, 2).then_some(idx as f32 / 4.0))).into(),
+            ArrayF64::new((0..rows).map(|idx| valid(idx, 5).then_some(idx as f64 * 1.5))).into(),
+            ArrayText::new((0..rows).map(|idx| valid(idx, 3).then(|| "Bublé".repeat(idx)))).into(),
+            ArrayText::new(
+                (0..rows).map(|idx| valid(idx, 7).then(|| cities[idx * idx % 3].to_owned())),
+            )
+            .into(),
+            ArrayBoolean::new((0..rows).map(|idx| valid(idx, 6).then_some(idx % 5 < 2))).into(),
+        ];
+
+        RecordBatch::try_new(schema, columns).unwrap()
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        let batch = batch();
+
+        for compression in [Compression::Uncompressed, Compression::Snappy] {
+            let mut file = vec![];
+            write_parquet(&batch, &mut file, compression).unwrap();
+
+            let (metadata, columns) = read(&file);
+
+            assert_eq!(Some(&Value::I64(50)), metadata.get(3));
+            assert!(metadata.get(5).is_some());
+
+            for (idx, column) in columns.into_iter().enumerate() {
+                let expected = (0..batch.num_rows())
+                    .map(|row| batch.columns()[idx].scalar(row).unwrap())
+                    .collect::<Vec<_>>();
+
+                assert_eq!(expected, column, "{}", batch.schema().fields()[idx].name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_parquet_schema() {
+        let mut file = vec![];
+        write_parquet(&batch(), &mut file, Compression::Uncompressed).unwrap();
+        let (metadata, _) = read(&file);
+
+        let schema = structs(metadata.get(2).unwrap());
+        assert_eq!(Some(&Value::I32(9)), schema[0].get(5));
+
+        // Required fields without nulls, and text dictionary encoded only
+        // when its values repeat
+        assert_eq!(Some(&Value::I32(REQUIRED)), schema[1].get(3));
+        assert_eq!(Some(&Value::I32(OPTIONAL)), schema[2].get(3));
+        assert_eq!(Some(&Value::I32(CONVERTED_UINT_64)), schema[4].get(6));
+
+        let chunks = structs(structs(metadata.get(4).unwrap())[0].get(1).unwrap());
+        let dictionary_offset = |idx: usize| match chunks[idx].get(3) {
+            Some(Value::Struct(chunk)) => chunk.get(11).cloned(),
+            _ => None,
+        };
+        assert_eq!(None, dictionary_offset(6));
+        assert!(dictionary_offset(7).is_some());
+
+        let schema = Schema::new(vec![Field::new("raw", DataType::Union, true)]);
+        let batch = RecordBatch::try_new(schema, vec![Union::from_vec(vec![]).into()]).unwrap();
+        let mut file = vec![];
+
+        assert!(matches!(
+            write_parquet(&batch, &mut file, Compression::Snappy),
+            Err(ParquetError::Unsupported(_))
+        ));
+        assert!(file.is_empty());
+    }
+
+    #[test]
+    fn test_encodings() {
+        let values = [
+            [3; 20].as_slice(),
+            &[0, 1, 2, 3, 4, 5, 6, 7],
+            &[1; 9],
+            &[2, 3],
+        ]
+        .concat();
+
+        let mut encoded = vec![];
+        rle_hybrid(&mut encoded, &values, 3);
+        assert_eq!(values, un_rle(&encoded, &mut 0, values.len(), 3).as_slice());
+        // A run, a group of 8 packed values, a run and a padded group
+        assert_eq!(2 + 4 + 2 + 4, encoded.len());
+
+        let data = b"abcdabcdabcdabcdabcd, then something else entirely. abcdabcd".repeat(3);
+        let compressed = snappy(&data);
+        assert!(compressed.len() < data.len() / 2);
+        assert_eq!(data, unsnappy(&compressed));
+
+        let data = (0..1_000_u32)
+            .flat_map(|idx| idx.wrapping_mul(2_654_435_761).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(data, unsnappy(&snappy(&data)));
+        assert_eq!(vec![0], snappy(&[]));
+    }
+}
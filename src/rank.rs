@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+
+use crate::utils::{Array, TotalOrd};
+use crate::{ArrayF64, ArrayUSize};
+
+/// How [`rank`] numbers elements that are tied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankMethod {
+    /// The mean of the positions the tied elements span.
+    Average,
+    /// The lowest position the tied elements span.
+    Min,
+    /// The highest position the tied elements span.
+    Max,
+    /// Consecutive ranks for each distinct value, leaving no gaps.
+    Dense,
+    /// Distinct positions following the order of the elements.
+    Ordinal,
+}
+
+/// Returns the indices that order `array` ascending under [`TotalOrd`], with
+/// nulls last.
+///
+/// The sort is stable so equal elements keep their order.
+pub fn sort_to_indices<A>(array: &A) -> ArrayUSize
+where
+    A: Array,
+    A::Data: TotalOrd,
+{
+    let mut indices = (0..array.len()).collect::<Vec<_>>();
+
+    indices.sort_by_cached_key(|idx| SortValue(array.get(*idx)));
+
+    ArrayUSize::new(indices.into_iter().map(Some))
+}
+
+/// Orders values under [`TotalOrd`], with nulls last.
+struct SortValue<T>(Option<T>);
+
+impl<T: TotalOrd> PartialEq for SortValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<T: TotalOrd> Eq for SortValue<T> {}
+
+impl<T: TotalOrd> PartialOrd for SortValue<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: TotalOrd> Ord for SortValue<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.0, &other.0) {
+            (Some(lhs), Some(rhs)) => lhs.total_order(rhs),
+            (lhs, rhs) => lhs.is_none().cmp(&rhs.is_none()),
+        }
+    }
+}
+
+/// Returns the 1-based rank of each element of `array` in ascending order,
+/// with ties numbered following `method`.
+///
+/// Nulls have null ranks and take up no rank numbers. Values compare under
+/// [`TotalOrd`], so `-0.0` ranks below `0.0` and `NaN`s above infinity.
+pub fn rank<A>(array: &A, method: RankMethod) -> ArrayF64
+where
+    A: Array,
+    A::Data: TotalOrd,
+{
+    let order = sort_to_indices(array)
+        .copied_iter()
+        .flatten()
+        .take(array.len() - array.null_count())
+        .collect::<Vec<_>>();
+
+    let mut ranks = vec![None; array.len()];
+    let mut start = 0;
+    let mut dense = 0.0;
+
+    while start < order.len() {
+        let value = array.get(order[start]).expect("Nulls are sorted last");
+        let end = start
+            + order[start..]
+                .iter()
+                .take_while(|idx| {
+                    let other = array.get(**idx).expect("Nulls are sorted last");
+                    value.total_order(&other).is_eq()
+                })
+                .count();
+
+        dense += 1.0;
+
+        for (pos, idx) in order[start..end].iter().enumerate() {
+            let rank = match method {
+                RankMethod::Average => (start + end + 1) as f64 / 2.0,
+                RankMethod::Min => (start + 1) as f64,
+                RankMethod::Max => end as f64,
+                RankMethod::Dense => dense,
+                RankMethod::Ordinal => (start + pos + 1) as f64,
+            };
+
+            ranks[*idx] = Some(rank);
+        }
+
+        start = end;
+    }
+
+    ArrayF64::new(ranks)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayI32, ArrayText};
+
+    #[test]
+    fn test_sort_to_indices() {
+        let one = ArrayF64::from([Some(2.0), None, Some(-1.0), Some(f64::NAN), Some(2.0)]);
+
+        assert_eq!(ArrayUSize::from([2, 0, 4, 3, 1]), sort_to_indices(&one));
+
+        let two = ArrayText::from(["b", "a", "B"]);
+        assert_eq!(ArrayUSize::from([2, 1, 0]), sort_to_indices(&two));
+    }
+
+    #[test]
+    fn test_rank() {
+        let one = ArrayF64::from([
+            Some(3.0),
+            None,
+            Some(1.0),
+            Some(3.0),
+            Some(2.0),
+            Some(3.0),
+            None,
+            Some(1.0),
+        ]);
+
+        let ranks = |method| rank(&one, method);
+
+        assert_eq!(
+            ArrayF64::from([
+                Some(5.0),
+                None,
+                Some(1.5),
+                Some(5.0),
+                Some(3.0),
+                Some(5.0),
+                None,
+                Some(1.5)
+            ]),
+            ranks(RankMethod::Average)
+        );
+        assert_eq!(
+            ArrayF64::from([
+                Some(3.0),
+                None,
+                Some(1.0),
+                Some(3.0),
+                Some(2.0),
+                Some(3.0),
+                None,
+                Some(1.0)
+            ]),
+            ranks(RankMethod::Dense)
+        );
+        assert_eq!(
+            ArrayF64::from([
+                Some(4.0),
+                None,
+                Some(1.0),
+                Some(4.0),
+                Some(3.0),
+                Some(4.0),
+                None,
+                Some(1.0)
+            ]),
+            ranks(RankMethod::Min)
+        );
+        assert_eq!(
+            ArrayF64::from([
+                Some(6.0),
+                None,
+                Some(2.0),
+                Some(6.0),
+                Some(3.0),
+                Some(6.0),
+                None,
+                Some(2.0)
+            ]),
+            ranks(RankMethod::Max)
+        );
+        assert_eq!(
+            ArrayF64::from([
+                Some(4.0),
+                None,
+                Some(1.0),
+                Some(5.0),
+                Some(3.0),
+                Some(6.0),
+                None,
+                Some(2.0)
+            ]),
+            ranks(RankMethod::Ordinal)
+        );
+    }
+
+    #[test]
+    fn test_rank_edge_cases() {
+        assert_eq!(
+            ArrayF64::nulls(3),
+            rank(&ArrayI32::nulls(3), RankMethod::Average)
+        );
+        assert_eq!(
+            ArrayF64::from_vec(vec![]),
+            rank(&ArrayF64::from_vec(vec![]), RankMethod::Dense)
+        );
+        assert_eq!(
+            ArrayF64::from([2.0, 2.0, 2.0]),
+            rank(&ArrayI32::from([7, 7, 7]), RankMethod::Average)
+        );
+    }
+}
@@ -0,0 +1,2640 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::hash::Hasher;
+use std::sync::{Arc, OnceLock};
+
+use crate::arithmetic::mean;
+use crate::arrayf64::quantile_of_sorted;
+use crate::column::{ArrayRef, ColumnArray};
+use crate::mutable::{MutableArray, MutableArrayBoolean, MutableArrayText, MutablePrimitiveArray};
+use crate::schema::{Field, Schema, SchemaError};
+use crate::statistics::{hash_scalar, ArrayStatistics};
+use crate::union::{FromUnionValue, Scalar, Union, UnionBuilder, UnionRef};
+use crate::utils::{every_nth_indices, sample_indices, Array, ArrayError, DataType, TotalOrd};
+use crate::{ArrayBoolean, ArrayF64, ArrayText, ArrayUSize};
+
+/// Column and direction to order rows by in [`RecordBatch::sort_by`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SortKey {
+    /// Index of the column.
+    pub column: usize,
+    pub descending: bool,
+    /// Whether nulls come before all values, regardless of `descending`.
+    pub nulls_first: bool,
+}
+
+impl SortKey {
+    /// Creates an ascending [`SortKey`] with nulls last.
+    pub fn new(column: usize) -> Self {
+        Self {
+            column,
+            descending: false,
+            nulls_first: false,
+        }
+    }
+
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls_first = true;
+        self
+    }
+}
+
+/// Where [`RecordBatch::sort_by_column`] places nulls, regardless of the
+/// direction of the sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullPlacement {
+    First,
+    Last,
+}
+
+/// Errors raised when sorting a [`RecordBatch`] by the names of its columns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortError {
+    /// No column has the given name.
+    ColumnNotFound { name: String },
+    /// The values of the column have no order to sort by, as with unions
+    /// holding values of different types.
+    UnsortableType { name: String, data_type: DataType },
+}
+
+impl Display for SortError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ColumnNotFound { name } => write!(f, "No column named \"{name}\" to sort by"),
+            Self::UnsortableType { name, data_type } => write!(
+                f,
+                "Column \"{name}\" of type {data_type:?} cannot be sorted"
+            ),
+        }
+    }
+}
+
+impl Error for SortError {}
+
+/// Which of a set of duplicate rows [`RecordBatch::unique`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    First,
+    Last,
+}
+
+/// How [`RecordBatch::pivot`] combines the values of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agg {
+    /// The sum of the values, following the promotion rules of [`Scalar`]
+    /// addition. Null should it overflow.
+    Sum,
+    /// The mean of the values as an `f64`.
+    Mean,
+    Min,
+    Max,
+    /// The number of non-null values.
+    Count,
+    First,
+    Last,
+}
+
+impl Agg {
+    /// Returns the type of the aggregates of values of `data_type`.
+    fn data_type(self, data_type: DataType) -> DataType {
+        match self {
+            Self::Mean => DataType::F64,
+            Self::Count => DataType::USize,
+            _ => data_type,
+        }
+    }
+
+    /// Combines the non-null `values` of a group. Float `NaN`s are ignored
+    /// by [`Agg::Min`] and [`Agg::Max`].
+    fn apply(self, values: &[Scalar]) -> Scalar {
+        let numbers = || values.iter().filter(|value| value.is_numeric());
+        let ordered = || {
+            values
+                .iter()
+                .filter(|value| value.partial_cmp(value).is_some())
+        };
+
+        let aggregate = match self {
+            Self::Sum => {
+                let mut numbers = numbers();
+                numbers
+                    .next()
+                    .map(|first| numbers.try_fold(first.clone(), |acc, value| &acc + value))
+            }
+            Self::Mean => {
+                let (total, count) = numbers()
+                    .filter_map(|value| value.to_f64().ok())
+                    .fold((0.0, 0), |(total, count), value| (total + value, count + 1));
+
+                (count != 0).then(|| Ok(Scalar::F64(total / count as f64)))
+            }
+            Self::Min => ordered()
+                .reduce(|acc, value| if value < acc { value } else { acc })
+                .cloned()
+                .map(Ok),
+            Self::Max => ordered()
+                .reduce(|acc, value| if value > acc { value } else { acc })
+                .cloned()
+                .map(Ok),
+            Self::Count => Some(Ok(Scalar::USize(values.len()))),
+            Self::First => values.first().cloned().map(Ok),
+            Self::Last => values.last().cloned().map(Ok),
+        };
+
+        aggregate.and_then(Result::ok).unwrap_or(Scalar::Null)
+    }
+}
+
+/// Equal length columns described by a [`Schema`].
+///
+/// Columns are held behind [`ArrayRef`]s so batches derived through
+/// [`RecordBatch::with_column`] and friends share them rather than copy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordBatch {
+    schema: Schema,
+    columns: Vec<ArrayRef>,
+    statistics: StatisticsCache,
+}
+
+/// Lazily computed [`ArrayStatistics`] of each column of a batch.
+///
+/// Derived values, so any two caches compare equal.
+#[derive(Clone)]
+struct StatisticsCache(Vec<OnceLock<ArrayStatistics>>);
+
+impl StatisticsCache {
+    fn new(len: usize) -> Self {
+        Self((0..len).map(|_| OnceLock::new()).collect())
+    }
+}
+
+impl PartialEq for StatisticsCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Debug for StatisticsCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatisticsCache").finish_non_exhaustive()
+    }
+}
+
+impl RecordBatch {
+    /// Creates a [`RecordBatch`], checking that `columns` match the fields of
+    /// `schema` in number and type and that all columns are equal in length.
+    pub fn try_new(schema: Schema, columns: Vec<ColumnArray>) -> Result<Self, SchemaError> {
+        Self::try_new_shared(schema, columns.into_iter().map(Arc::new).collect())
+    }
+
+    /// Creates a [`RecordBatch`] from columns which may be shared with other
+    /// batches.
+    ///
+    /// See [`RecordBatch::try_new`].
+    pub fn try_new_shared(schema: Schema, columns: Vec<ArrayRef>) -> Result<Self, SchemaError> {
+        let fields = schema.fields();
+
+        if fields.len() != columns.len() {
+            return Err(SchemaError::ColumnCount {
+                expected: fields.len(),
+                got: columns.len(),
+            });
+        }
+
+        let rows = columns
+            .first()
+            .map(|column| column.len())
+            .unwrap_or_default();
+
+        for (field, column) in fields.iter().zip(columns.iter()) {
+            if field.data_type != column.data_type() {
+                return Err(SchemaError::TypeMismatch {
+                    name: field.name.clone(),
+                    expected: field.data_type,
+                    got: column.data_type(),
+                });
+            }
+
+            if column.len() != rows {
+                return Err(SchemaError::LengthMismatch {
+                    name: field.name.clone(),
+                    expected: rows,
+                    got: column.len(),
+                });
+            }
+        }
+
+        let statistics = StatisticsCache::new(columns.len());
+
+        Ok(Self {
+            schema,
+            columns,
+            statistics,
+        })
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn columns(&self) -> &[ArrayRef] {
+        &self.columns
+    }
+
+    /// Returns the column at `idx`.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn column(&self, idx: usize) -> &ColumnArray {
+        &self.columns[idx]
+    }
+
+    /// Returns the first column named `name`, if any.
+    pub fn column_by_name(&self, name: &str) -> Option<&ColumnArray> {
+        let idx = self.schema.index_of(name)?;
+        Some(&self.columns[idx])
+    }
+
+    /// Returns the statistics of the column at `idx`, computing them on
+    /// first use.
+    ///
+    /// Cached statistics survive [`RecordBatch::with_column`] and friends
+    /// for every column but the replaced one.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn statistics(&self, idx: usize) -> &ArrayStatistics {
+        self.statistics.0[idx].get_or_init(|| self.columns[idx].statistics())
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.columns
+            .first()
+            .map(|column| column.len())
+            .unwrap_or_default()
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns the first column named `name`.
+    fn column_index(&self, name: &str) -> Result<usize, SchemaError> {
+        self.schema
+            .index_of(name)
+            .ok_or_else(|| SchemaError::FieldNotFound { name: name.into() })
+    }
+
+    /// Returns a batch with `column` added under `name` as a nullable field.
+    ///
+    /// If a field named `name` already exists it is an error unless `replace`
+    /// is set, in which case the new column takes its place. Errors if
+    /// `column` differs in length from the batch. `self` is left untouched
+    /// and its columns are shared with the result.
+    pub fn with_column(
+        &self,
+        name: impl Into<String>,
+        column: ArrayRef,
+        replace: bool,
+    ) -> Result<RecordBatch, SchemaError> {
+        let name = name.into();
+
+        if self.num_columns() != 0 && column.len() != self.num_rows() {
+            return Err(SchemaError::LengthMismatch {
+                name,
+                expected: self.num_rows(),
+                got: column.len(),
+            });
+        }
+
+        let field = Field::new(name, column.data_type(), true);
+        let mut fields = self.schema.fields().to_vec();
+        let mut columns = self.columns.clone();
+
+        match self.schema.index_of(&field.name) {
+            Some(_) if !replace => return Err(SchemaError::DuplicateField { name: field.name }),
+            Some(idx) => {
+                fields[idx] = field;
+                columns[idx] = column;
+            }
+            None => {
+                fields.push(field);
+                columns.push(column);
+            }
+        }
+
+        Ok(self.with_fields(fields, columns))
+    }
+
+    /// Returns a batch without the column named `name`.
+    ///
+    /// Errors if there is no such column.
+    pub fn drop_column(&self, name: &str) -> Result<RecordBatch, SchemaError> {
+        let idx = self.column_index(name)?;
+        let mut fields = self.schema.fields().to_vec();
+        let mut columns = self.columns.clone();
+
+        fields.remove(idx);
+        columns.remove(idx);
+
+        Ok(self.with_fields(fields, columns))
+    }
+
+    /// Returns a batch without the columns named in `names`, the complement
+    /// of [`RecordBatch::select`].
+    ///
+    /// Errors if a name has no column.
+    pub fn drop_columns(&self, names: &[&str]) -> Result<RecordBatch, SchemaError> {
+        let dropped = names
+            .iter()
+            .map(|name| self.column_index(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (fields, columns) = self
+            .schema
+            .fields()
+            .iter()
+            .zip(self.columns.iter())
+            .enumerate()
+            .filter(|(idx, _)| !dropped.contains(idx))
+            .map(|(_, (field, column))| (field.clone(), column.clone()))
+            .unzip();
+
+        Ok(self.with_fields(fields, columns))
+    }
+
+    /// Returns a batch with the column named `old` renamed to `new`.
+    ///
+    /// Errors if there is no column named `old` or another column is already
+    /// named `new`.
+    pub fn rename_column(&self, old: &str, new: &str) -> Result<RecordBatch, SchemaError> {
+        let idx = self.column_index(old)?;
+
+        if old != new && self.schema.index_of(new).is_some() {
+            return Err(SchemaError::DuplicateField { name: new.into() });
+        }
+
+        let mut fields = self.schema.fields().to_vec();
+        fields[idx].name = new.into();
+
+        Ok(self.with_fields(fields, self.columns.clone()))
+    }
+
+    /// Returns a batch holding only the columns named in `names`, in the
+    /// order given.
+    ///
+    /// Errors if a name has no column or is given more than once.
+    pub fn select(&self, names: &[&str]) -> Result<RecordBatch, SchemaError> {
+        let mut fields = Vec::with_capacity(names.len());
+        let mut columns = Vec::with_capacity(names.len());
+
+        for (pos, name) in names.iter().enumerate() {
+            if names[..pos].contains(name) {
+                return Err(SchemaError::DuplicateField {
+                    name: name.to_string(),
+                });
+            }
+
+            let idx = self.column_index(name)?;
+            fields.push(self.schema.fields()[idx].clone());
+            columns.push(self.columns[idx].clone());
+        }
+
+        Ok(self.with_fields(fields, columns))
+    }
+
+    /// Builds a batch from already validated `fields` and `columns`, keeping
+    /// the schema metadata.
+    fn with_fields(&self, fields: Vec<Field>, columns: Vec<ArrayRef>) -> RecordBatch {
+        let schema = Schema::new(fields).with_metadata(self.schema.metadata().clone());
+
+        // Keep the statistics of columns carried over from `self`
+        let statistics = columns
+            .iter()
+            .map(|column| {
+                self.columns
+                    .iter()
+                    .position(|own| Arc::ptr_eq(own, column))
+                    .map(|idx| self.statistics.0[idx].clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        Self {
+            schema,
+            columns,
+            statistics: StatisticsCache(statistics),
+        }
+    }
+
+    /// Returns a view over the row at `idx`.
+    ///
+    /// Errors if `idx` is out of bounds.
+    pub fn row(&self, idx: usize) -> Result<Row<'_>, ArrayError> {
+        if idx >= self.num_rows() {
+            return Err(ArrayError::IndexOutOfBounds {
+                idx,
+                len: self.num_rows(),
+            });
+        }
+
+        Ok(Row { batch: self, idx })
+    }
+
+    /// Returns an iterator over the rows of the batch.
+    pub fn rows(&self) -> Rows<'_> {
+        Rows {
+            batch: self,
+            idx: 0,
+        }
+    }
+
+    /// Returns a copy of the batch with its rows ordered by `keys`.
+    ///
+    /// Rows are compared by the first key, with later keys breaking ties.
+    /// The sort is stable so rows equal under every key keep their order.
+    /// Text is compared by its UTF-8 bytes and floats by
+    /// [`f64::total_cmp`]. Union values of different types are ordered by
+    /// type.
+    ///
+    /// Panics if a key refers to a column which does not exist.
+    pub fn sort_by(&self, keys: &[SortKey]) -> RecordBatch {
+        for key in keys {
+            assert!(
+                key.column < self.num_columns(),
+                "Tried to sort by column {} when batch has {} columns",
+                key.column,
+                self.num_columns()
+            );
+        }
+
+        let mut indices = (0..self.num_rows()).collect::<Vec<_>>();
+
+        indices.sort_by(|lhs, rhs| {
+            keys.iter()
+                .map(|key| compare_rows(&self.columns[key.column], *lhs, *rhs, key))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        self.take(&ArrayUSize::new(indices.into_iter().map(Some)))
+            .expect("Sort indices are always in bounds")
+    }
+
+    /// Returns a copy of the batch with its rows ordered by the column named
+    /// `name`, with its nulls placed as `nulls` requires.
+    ///
+    /// Errors if there is no such column or it cannot be sorted. See
+    /// [`RecordBatch::sort_by`].
+    pub fn sort_by_column(
+        &self,
+        name: &str,
+        descending: bool,
+        nulls: NullPlacement,
+    ) -> Result<RecordBatch, SortError> {
+        self.sort_by_columns(&[(name, descending)], nulls)
+    }
+
+    /// Returns a copy of the batch with its rows ordered by the named
+    /// columns, each paired with whether it is descending. The nulls of
+    /// every column are placed as `nulls` requires.
+    ///
+    /// Errors on the first name with no column or whose column cannot be
+    /// sorted. See [`RecordBatch::sort_by`].
+    pub fn sort_by_columns(
+        &self,
+        keys: &[(&str, bool)],
+        nulls: NullPlacement,
+    ) -> Result<RecordBatch, SortError> {
+        let keys = keys
+            .iter()
+            .map(|(name, descending)| {
+                let column =
+                    self.schema
+                        .index_of(name)
+                        .ok_or_else(|| SortError::ColumnNotFound {
+                            name: (*name).into(),
+                        })?;
+
+                if let ColumnArray::Union(union) = self.columns[column].as_ref() {
+                    if union.type_distribution().len() > 1 {
+                        return Err(SortError::UnsortableType {
+                            name: (*name).into(),
+                            data_type: DataType::Union,
+                        });
+                    }
+                }
+
+                let key = SortKey {
+                    column,
+                    descending: *descending,
+                    nulls_first: nulls == NullPlacement::First,
+                };
+
+                Ok(key)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.sort_by(&keys))
+    }
+
+    /// Returns a batch holding the rows of `a` followed by those of `b`.
+    ///
+    /// Both batches must have fields of the same names and types in the
+    /// same order. A field of the result is nullable if it is in either
+    /// batch and the schema metadata is that of `a`. Errors on the first
+    /// field that differs.
+    pub fn concat(a: &RecordBatch, b: &RecordBatch) -> Result<RecordBatch, SchemaError> {
+        let (left, right) = (a.schema.fields(), b.schema.fields());
+
+        if left.len() != right.len() {
+            return Err(SchemaError::ColumnCount {
+                expected: left.len(),
+                got: right.len(),
+            });
+        }
+
+        let mut fields = Vec::with_capacity(left.len());
+        let mut columns = Vec::with_capacity(left.len());
+
+        for (idx, (lhs, rhs)) in left.iter().zip(right).enumerate() {
+            if lhs.name != rhs.name {
+                return Err(SchemaError::NameMismatch {
+                    idx,
+                    expected: lhs.name.clone(),
+                    got: rhs.name.clone(),
+                });
+            }
+
+            let column =
+                a.columns[idx]
+                    .concat(&b.columns[idx])
+                    .map_err(|_| SchemaError::TypeMismatch {
+                        name: rhs.name.clone(),
+                        expected: lhs.data_type,
+                        got: rhs.data_type,
+                    })?;
+
+            let mut field = lhs.clone();
+            field.nullable |= rhs.nullable;
+
+            fields.push(field);
+            columns.push(Arc::new(column));
+        }
+
+        Ok(RecordBatch {
+            schema: Schema::new(fields).with_metadata(a.schema.metadata().clone()),
+            statistics: StatisticsCache::new(columns.len()),
+            columns,
+        })
+    }
+
+    /// Returns a batch holding the rows of `self` followed by those of
+    /// `other`, see [`RecordBatch::concat`].
+    pub fn try_append(&self, other: &RecordBatch) -> Result<RecordBatch, SchemaError> {
+        Self::concat(self, other)
+    }
+
+    /// Returns a batch holding the rows whose counterpart in `mask` is true.
+    /// Nulls in `mask` count as false.
+    ///
+    /// Errors if `mask` differs in length from the batch.
+    pub fn filter(&self, mask: &ArrayBoolean) -> Result<RecordBatch, ArrayError> {
+        if mask.len() != self.num_rows() {
+            return Err(ArrayError::LengthMismatch {
+                left: self.num_rows(),
+                right: mask.len(),
+            });
+        }
+
+        self.map_columns(|column| column.filter(mask))
+    }
+
+    /// Returns a batch holding the rows whose counterpart in `mask` is true,
+    /// like [`RecordBatch::filter`].
+    ///
+    /// Panics if `mask` differs in length from the batch.
+    pub fn filter_rows(&self, mask: &ArrayBoolean) -> RecordBatch {
+        assert_eq!(
+            mask.len(),
+            self.num_rows(),
+            "Tried to filter a batch of {} rows with a mask of {} elements",
+            self.num_rows(),
+            mask.len()
+        );
+
+        self.filter(mask).expect("Mask length was checked")
+    }
+
+    /// Returns the batch without duplicate rows, keeping either the first or
+    /// last of each set of duplicates in its original position.
+    ///
+    /// Rows are compared on the `subset` columns, or on every column if it is
+    /// None. Nulls are equal to each other and so are float `NaN`s, while
+    /// numbers only equal numbers of the same type.
+    ///
+    /// Errors if a column of `subset` is missing.
+    pub fn unique(&self, subset: Option<&[&str]>, keep: Keep) -> Result<RecordBatch, SchemaError> {
+        let kept = self.first_of_keys(subset, keep)?;
+
+        Ok(self.filter_rows(&ArrayBoolean::from(kept)))
+    }
+
+    /// Returns a mask which is true for every row duplicating an earlier row,
+    /// the rows [`RecordBatch::unique`] drops when keeping the first.
+    pub fn duplicated(&self, subset: Option<&[&str]>) -> Result<ArrayBoolean, SchemaError> {
+        let kept = self.first_of_keys(subset, Keep::First)?;
+
+        Ok(ArrayBoolean::from_vec(
+            kept.into_iter().map(|kept| Some(!kept)).collect(),
+        ))
+    }
+
+    /// Returns whether each row is the first, or last, with its values in the
+    /// `subset` columns.
+    fn first_of_keys(&self, subset: Option<&[&str]>, keep: Keep) -> Result<Vec<bool>, SchemaError> {
+        let columns = match subset {
+            Some(names) => names
+                .iter()
+                .map(|name| self.column_index(name))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => (0..self.num_columns()).collect(),
+        };
+
+        let rows = self.num_rows();
+        let order = match keep {
+            Keep::First => (0..rows).collect::<Vec<_>>(),
+            Keep::Last => (0..rows).rev().collect(),
+        };
+
+        let mut keys = Interner::default();
+        let mut kept = vec![false; rows];
+
+        for idx in order {
+            let key = columns
+                .iter()
+                .map(|column| self.column(*column).scalar(idx).expect("Row is in bounds"))
+                .collect::<Vec<_>>();
+
+            let seen = keys.values.len();
+            kept[idx] = keys.intern(key) == seen;
+        }
+
+        Ok(kept)
+    }
+
+    /// Returns `n` rows picked uniformly at random without replacement, in
+    /// their original order. Every column keeps the same rows.
+    ///
+    /// See [`Array::sample`].
+    pub fn sample(&self, n: usize, seed: u64) -> RecordBatch {
+        self.take_rows(sample_indices(self.num_rows(), n, seed))
+    }
+
+    /// Returns every `n`th row, starting with the first.
+    ///
+    /// Panics if `n` is 0.
+    pub fn every_nth(&self, n: usize) -> RecordBatch {
+        self.take_rows(every_nth_indices(self.num_rows(), n))
+    }
+
+    /// Takes the rows at `indices`, which must all be in bounds.
+    fn take_rows(&self, indices: Vec<usize>) -> RecordBatch {
+        self.take(&ArrayUSize::new(indices.into_iter().map(Some)))
+            .expect("Row indices are in bounds")
+    }
+
+    /// Returns a batch holding the rows at `indices`, in order. Null indices
+    /// produce rows of nulls.
+    ///
+    /// Errors if any index is out of bounds.
+    pub fn take(&self, indices: &ArrayUSize) -> Result<RecordBatch, ArrayError> {
+        self.map_columns(|column| column.take(indices))
+    }
+
+    /// Returns a batch holding the `len` rows starting at `offset`.
+    ///
+    /// Errors if the range goes past the end of the batch.
+    pub fn slice(&self, offset: usize, len: usize) -> Result<RecordBatch, ArrayError> {
+        if offset.saturating_add(len) > self.num_rows() {
+            return Err(ArrayError::IndexOutOfBounds {
+                idx: offset.saturating_add(len),
+                len: self.num_rows(),
+            });
+        }
+
+        self.map_columns(|column| column.slice(offset, len))
+    }
+
+    /// Returns a batch holding the first `n` rows, or every row if there are
+    /// fewer.
+    pub fn head(&self, n: usize) -> RecordBatch {
+        let len = n.min(self.num_rows());
+
+        self.slice(0, len)
+            .expect("The head of a batch is always in bounds")
+    }
+
+    /// Returns a batch holding the last `n` rows, or every row if there are
+    /// fewer.
+    pub fn tail(&self, n: usize) -> RecordBatch {
+        let len = n.min(self.num_rows());
+
+        self.slice(self.num_rows() - len, len)
+            .expect("The tail of a batch is always in bounds")
+    }
+
+    /// Builds a batch with the same schema from `f` applied to every column.
+    fn map_columns<F>(&self, f: F) -> Result<RecordBatch, ArrayError>
+    where
+        F: Fn(&ColumnArray) -> Result<ColumnArray, ArrayError>,
+    {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| f(column).map(Arc::new))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            schema: self.schema.clone(),
+            statistics: StatisticsCache::new(self.num_columns()),
+            columns,
+        })
+    }
+
+    /// Copies every row of the batch into its own vector of values.
+    ///
+    /// Prefer [`RecordBatch::rows`] for anything but small batches.
+    pub fn to_rows(&self) -> Vec<Vec<Scalar>> {
+        self.rows().map(|row| row.values()).collect()
+    }
+
+    /// Reshapes the batch so each distinct value of the `key` column becomes
+    /// a column, holding the `value`s of its rows combined by `agg` for each
+    /// distinct value of the `index` column.
+    ///
+    /// The result starts with the distinct values of `index`, followed by a
+    /// column for each key named by its [`Display`](std::fmt::Display)
+    /// form, both in the order they first appear. Null values are left out
+    /// of the aggregates, as are rows with a null key. Index and key pairs
+    /// with no rows are null.
+    ///
+    /// Errors if a column is missing, if `agg` is [`Agg::Sum`] or
+    /// [`Agg::Mean`] and the values are neither numbers nor a union, or if a
+    /// key would name a column twice.
+    pub fn pivot(
+        &self,
+        index: &str,
+        key: &str,
+        value: &str,
+        agg: Agg,
+    ) -> Result<RecordBatch, SchemaError> {
+        let index_column = self.column(self.column_index(index)?);
+        let key_column = self.column(self.column_index(key)?);
+        let value_column = self.column(self.column_index(value)?);
+        let value_type = value_column.data_type();
+
+        let numeric = value_type.is_numeric() || value_type == DataType::Union;
+        if matches!(agg, Agg::Sum | Agg::Mean) && !numeric {
+            return Err(SchemaError::TypeMismatch {
+                name: value.into(),
+                expected: DataType::F64,
+                got: value_type,
+            });
+        }
+
+        let (mut rows, mut keys) = (Interner::default(), Interner::default());
+        let mut first_rows = vec![];
+        let mut groups: HashMap<(usize, usize), Vec<Scalar>> = HashMap::new();
+
+        for idx in 0..self.num_rows() {
+            let scalar = |column: &ColumnArray| column.scalar(idx).expect("Row is in bounds");
+
+            let row = rows.intern(scalar(index_column));
+            if row == first_rows.len() {
+                first_rows.push(idx);
+            }
+
+            let key = match scalar(key_column) {
+                Scalar::Null => continue,
+                key => keys.intern(key),
+            };
+
+            let group = groups.entry((row, key)).or_default();
+            match scalar(value_column) {
+                Scalar::Null => {}
+                value => group.push(value),
+            }
+        }
+
+        let index_field = self
+            .schema
+            .field_with_name(index)
+            .expect("Column was found");
+        let first_rows = ArrayUSize::from_vec(first_rows.into_iter().map(Some).collect());
+
+        let mut fields = vec![index_field.clone()];
+        let mut columns = vec![index_column
+            .take(&first_rows)
+            .expect("First rows are in bounds")];
+
+        for (key, name) in keys.values.iter().enumerate() {
+            let name = name.to_string();
+            if fields.iter().any(|field| field.name == name) {
+                return Err(SchemaError::DuplicateField { name });
+            }
+
+            let aggregates = (0..rows.values.len())
+                .map(|row| match groups.get(&(row, key)) {
+                    Some(group) => agg.apply(group),
+                    None => Scalar::Null,
+                })
+                .collect();
+
+            let column = ColumnArray::from_scalars(agg.data_type(value_type), aggregates);
+            fields.push(Field::new(
+                name,
+                column.data_type(),
+                column.null_count() > 0,
+            ));
+            columns.push(column);
+        }
+
+        RecordBatch::try_new(Schema::new(fields), columns)
+    }
+
+    /// Unpivots the `values` columns into rows, the inverse of
+    /// [`RecordBatch::pivot`].
+    ///
+    /// The result has the `ids` columns followed by a `var_name` column
+    /// holding the name of the column each value came from, and a
+    /// `value_name` column holding the value. Each of the `values` columns
+    /// adds a row for every row of the batch, in order, repeating the ids.
+    ///
+    /// Numeric values of differing types are cast to a common type following
+    /// the promotion rules of [`Scalar`] addition, while union columns take
+    /// values of any type. Errors if a column is missing, if the new names
+    /// are already taken, if the `values` columns are of incompatible types,
+    /// or if a value does not fit the common type.
+    pub fn melt(
+        &self,
+        ids: &[&str],
+        values: &[&str],
+        var_name: &str,
+        value_name: &str,
+    ) -> Result<RecordBatch, SchemaError> {
+        let ids = ids
+            .iter()
+            .map(|name| self.column_index(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = values
+            .iter()
+            .map(|name| self.column_index(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut fields = ids
+            .iter()
+            .map(|idx| self.schema.fields()[*idx].clone())
+            .collect::<Vec<_>>();
+
+        for name in [var_name, value_name] {
+            if fields.iter().any(|field| field.name == name) || var_name == value_name {
+                return Err(SchemaError::DuplicateField { name: name.into() });
+            }
+
+            fields.push(Field::new(name, DataType::Text, false));
+        }
+
+        let value_fields = values
+            .iter()
+            .map(|idx| &self.schema.fields()[*idx])
+            .collect::<Vec<_>>();
+
+        let mut value_type = value_fields
+            .first()
+            .map_or(DataType::Union, |field| field.data_type);
+        for field in &value_fields {
+            value_type = match (value_type, field.data_type) {
+                (lhs, rhs) if lhs == rhs => lhs,
+                (DataType::Union, _) | (_, DataType::Union) => DataType::Union,
+                (lhs, rhs) if lhs.is_numeric() && rhs.is_numeric() => lhs.promote(rhs),
+                (lhs, rhs) => {
+                    return Err(SchemaError::IncompatibleTypes {
+                        name: value_name.into(),
+                        left: lhs,
+                        right: rhs,
+                    })
+                }
+            };
+        }
+
+        let rows = self.num_rows();
+        let mut names = Vec::with_capacity(rows * values.len());
+        let mut melted = Vec::with_capacity(rows * values.len());
+
+        for field in value_fields {
+            let column = self.column_by_name(&field.name).expect("Column was found");
+
+            for idx in 0..rows {
+                let value = column.scalar(idx).expect("Row is in bounds");
+                let cast = match (value_type, value) {
+                    (_, Scalar::Null) => Some(Scalar::Null),
+                    (DataType::F64, value) => value.to_f64().ok().map(Scalar::F64),
+                    (DataType::ISize, value) => value
+                        .to_i64()
+                        .ok()
+                        .and_then(|value| isize::try_from(value).ok())
+                        .map(Scalar::ISize),
+                    (DataType::USize, Scalar::U32(value)) => Some(Scalar::USize(value as usize)),
+                    (_, value) => Some(value),
+                };
+
+                let cast = cast.ok_or_else(|| SchemaError::TypeMismatch {
+                    name: field.name.clone(),
+                    expected: value_type,
+                    got: field.data_type,
+                })?;
+
+                names.push(field.name.clone());
+                melted.push(cast);
+            }
+        }
+
+        let repeated = (0..values.len())
+            .flat_map(|_| (0..rows).map(Some))
+            .collect::<Vec<_>>();
+        let repeated = ArrayUSize::from_vec(repeated);
+
+        let mut columns = ids
+            .iter()
+            .map(|idx| {
+                self.column(*idx)
+                    .take(&repeated)
+                    .expect("Rows are in bounds")
+            })
+            .collect::<Vec<_>>();
+        columns.push(ArrayText::from(names).into());
+        columns.push(ColumnArray::from_scalars(value_type, melted));
+
+        let nullable = values.iter().any(|idx| self.schema.fields()[*idx].nullable);
+        *fields.last_mut().expect("Value field was pushed") =
+            Field::new(value_name, value_type, nullable);
+
+        RecordBatch::try_new(Schema::new(fields), columns)
+    }
+
+    /// Summarises every column, mirroring pandas' `describe`.
+    ///
+    /// The summary starts with a `statistic` column naming each of its rows,
+    /// followed by a column for each column of the batch under the same
+    /// name. Numeric columns are summarised as [`ArrayF64`]s of their
+    /// `count` of non-null values, `null_count`, `mean`, sample `std`, `min`,
+    /// `25%`, `50%` and `75%` quantiles and `max`. Other columns become
+    /// [`Union`]s of their `count`, `null_count`, number of `distinct` values
+    /// and `top`, most frequent, value with ties going to the first seen.
+    ///
+    /// Statistics a column has no value for are null, and those no column
+    /// has a value for are left out.
+    pub fn describe(&self) -> RecordBatch {
+        let numeric = |column: &ArrayRef| column.data_type().is_numeric();
+        let has_numeric = self.columns.iter().any(numeric);
+        let has_other = !self.columns.iter().all(numeric);
+
+        let mut labels = vec!["count", "null_count"];
+        if has_numeric {
+            labels.extend(["mean", "std", "min", "25%", "50%", "75%", "max"]);
+        }
+        if has_other {
+            labels.extend(["distinct", "top"]);
+        }
+
+        let mut fields = vec![Field::new("statistic", DataType::Text, false)];
+        let mut columns = vec![ArrayText::from(labels).into()];
+
+        for (field, column) in self.schema.fields().iter().zip(&self.columns) {
+            let summary: ColumnArray = match numeric(column) {
+                true => {
+                    let mut values = describe_numeric(column);
+                    if has_other {
+                        values.extend([None, None]);
+                    }
+
+                    ArrayF64::new(values).into()
+                }
+                false => {
+                    let mut values = describe_other(column);
+                    if has_numeric {
+                        values.splice(2..2, vec![Scalar::Null; 7]);
+                    }
+
+                    Union::from_vec(values).into()
+                }
+            };
+
+            fields.push(Field::new(
+                field.name.clone(),
+                summary.data_type(),
+                summary.null_count() > 0,
+            ));
+            columns.push(summary);
+        }
+
+        RecordBatch::try_new(Schema::new(fields), columns).expect("Summary columns match")
+    }
+}
+
+/// Returns the count, null count, mean, standard deviation, min, quartiles
+/// and max of the numeric `column`.
+fn describe_numeric(column: &ColumnArray) -> Vec<Option<f64>> {
+    let mut values = (0..column.len())
+        .filter_map(|idx| column.scalar(idx)?.to_f64().ok())
+        .collect::<Vec<_>>();
+    values.sort_by(f64::total_cmp);
+
+    let array = ArrayF64::from_vec(values.iter().copied().map(Some).collect());
+    let quantile = |q| quantile_of_sorted(&values, q);
+
+    vec![
+        Some(values.len() as f64),
+        Some(column.null_count() as f64),
+        mean(&array),
+        array.std_dev(),
+        values.first().copied(),
+        quantile(0.25),
+        quantile(0.5),
+        quantile(0.75),
+        values.last().copied(),
+    ]
+}
+
+/// Returns the count, null count, number of distinct values and most
+/// frequent value of `column`.
+fn describe_other(column: &ColumnArray) -> Vec<Scalar> {
+    let mut distinct = Interner::default();
+    let mut counts = vec![];
+
+    for idx in 0..column.len() {
+        let value = match column.scalar(idx) {
+            None | Some(Scalar::Null) => continue,
+            Some(value) => value,
+        };
+
+        let id = distinct.intern(value);
+        if id == counts.len() {
+            counts.push(0);
+        }
+        counts[id] += 1;
+    }
+
+    // Ids follow first sightings, so reversed the last maximum is the first
+    let top = counts
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, count)| **count)
+        .map(|(id, _)| distinct.values[id].clone())
+        .unwrap_or(Scalar::Null);
+
+    vec![
+        Scalar::USize(column.len() - column.null_count()),
+        Scalar::USize(column.null_count()),
+        Scalar::USize(distinct.values.len()),
+        top,
+    ]
+}
+
+/// Numbers distinct values from 0 in the order they are first seen.
+struct Interner<K = Scalar> {
+    /// Ids of the values of each hash.
+    buckets: HashMap<u64, Vec<usize>>,
+    /// Values by id.
+    values: Vec<K>,
+}
+
+impl<K> Default for Interner<K> {
+    fn default() -> Self {
+        Self {
+            buckets: HashMap::new(),
+            values: vec![],
+        }
+    }
+}
+
+impl<K: GroupKey> Interner<K> {
+    /// Returns the id of `value`, numbering it if it is new.
+    fn intern(&mut self, value: K) -> usize {
+        let bucket = self.buckets.entry(value.group_hash()).or_default();
+
+        if let Some(id) = bucket.iter().find(|id| self.values[**id].group_eq(&value)) {
+            return *id;
+        }
+
+        let id = self.values.len();
+        bucket.push(id);
+        self.values.push(value);
+
+        id
+    }
+}
+
+/// Values as grouped by an [`Interner`]. Nulls are equal to each other, as
+/// are float `NaN`s unlike under [`PartialEq`].
+trait GroupKey {
+    fn group_hash(&self) -> u64;
+
+    fn group_eq(&self, other: &Self) -> bool;
+}
+
+impl GroupKey for Scalar {
+    fn group_hash(&self) -> u64 {
+        match self {
+            Scalar::F32(value) if value.is_nan() => hash_scalar(&Scalar::F32(f32::NAN)),
+            Scalar::F64(value) if value.is_nan() => hash_scalar(&Scalar::F64(f64::NAN)),
+            value => hash_scalar(value),
+        }
+    }
+
+    fn group_eq(&self, other: &Self) -> bool {
+        // Only NaNs are unordered with themselves
+        let is_nan = |value: &Scalar| value.partial_cmp(value).is_none();
+
+        self == other || (is_nan(self) && is_nan(other))
+    }
+}
+
+impl GroupKey for Vec<Scalar> {
+    fn group_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.iter()
+            .for_each(|value| hasher.write_u64(value.group_hash()));
+        hasher.finish()
+    }
+
+    fn group_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(lhs, rhs)| lhs.group_eq(rhs))
+    }
+}
+
+/// Accumulates rows of [`Scalar`]s into a [`RecordBatch`] of a given schema.
+///
+/// Values go straight into a builder for each column, so nothing is copied
+/// when the batch is finished.
+#[derive(Debug)]
+pub struct RecordBatchBuilder {
+    schema: Schema,
+    columns: Vec<ColumnBuilder>,
+    len: usize,
+}
+
+impl RecordBatchBuilder {
+    pub fn new(schema: Schema) -> Self {
+        Self::with_capacity(schema, 0)
+    }
+
+    /// Creates a [`RecordBatchBuilder`] with room for `capacity` rows.
+    pub fn with_capacity(schema: Schema, capacity: usize) -> Self {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| ColumnBuilder::with_capacity(field.data_type, capacity))
+            .collect();
+
+        Self {
+            schema,
+            columns,
+            len: 0,
+        }
+    }
+
+    /// Appends a row holding a value for each field of the schema.
+    ///
+    /// [`Scalar::Null`] goes into any column, and integers into columns of
+    /// other numeric types which can hold them exactly, as in the
+    /// `TryFrom<&Union>` conversions of the arrays. Union columns take any
+    /// value. On error nothing is appended.
+    pub fn push_row(&mut self, row: Vec<Scalar>) -> Result<(), SchemaError> {
+        if row.len() != self.columns.len() {
+            return Err(SchemaError::ColumnCount {
+                expected: self.columns.len(),
+                got: row.len(),
+            });
+        }
+
+        let fields = self.schema.fields().iter().zip(&self.columns);
+
+        for ((field, column), value) in fields.zip(&row) {
+            if !column.accepts(value) {
+                return Err(SchemaError::TypeMismatch {
+                    name: field.name.clone(),
+                    expected: field.data_type,
+                    got: value.borrow().data_type().expect("Nulls are accepted"),
+                });
+            }
+        }
+
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns the number of rows pushed so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Converts the rows pushed so far into a [`RecordBatch`].
+    pub fn finish(self) -> RecordBatch {
+        let columns = self
+            .columns
+            .into_iter()
+            .map(ColumnBuilder::finish)
+            .collect();
+
+        RecordBatch::try_new(self.schema, columns).expect("Builders match the schema")
+    }
+}
+
+/// Builder of a single column of a [`RecordBatchBuilder`].
+#[derive(Debug)]
+enum ColumnBuilder {
+    Int32(MutablePrimitiveArray<i32>),
+    UInt32(MutablePrimitiveArray<u32>),
+    ISize(MutablePrimitiveArray<isize>),
+    USize(MutablePrimitiveArray<usize>),
+    Boolean(MutableArrayBoolean),
+    F32(MutablePrimitiveArray<f32>),
+    F64(MutablePrimitiveArray<f64>),
+    Text(MutableArrayText),
+    Union(UnionBuilder),
+}
+
+impl ColumnBuilder {
+    fn with_capacity(data_type: DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::Int32 => Self::Int32(MutableArray::with_capacity(capacity)),
+            DataType::UInt32 => Self::UInt32(MutableArray::with_capacity(capacity)),
+            DataType::ISize => Self::ISize(MutableArray::with_capacity(capacity)),
+            DataType::USize => Self::USize(MutableArray::with_capacity(capacity)),
+            DataType::Boolean => Self::Boolean(MutableArray::with_capacity(capacity)),
+            DataType::F32 => Self::F32(MutableArray::with_capacity(capacity)),
+            DataType::F64 => Self::F64(MutableArray::with_capacity(capacity)),
+            DataType::Text => Self::Text(MutableArray::with_capacity(capacity)),
+            DataType::Union => Self::Union(UnionBuilder::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns true if `value` can be pushed into the column.
+    fn accepts(&self, value: &Scalar) -> bool {
+        fn cast<T: FromUnionValue>(value: &Scalar) -> bool {
+            T::from_union_value(value.borrow()).is_some()
+        }
+
+        match self {
+            _ if matches!(value, Scalar::Null) => true,
+            Self::Int32(_) => cast::<i32>(value),
+            Self::UInt32(_) => cast::<u32>(value),
+            Self::ISize(_) => cast::<isize>(value),
+            Self::USize(_) => cast::<usize>(value),
+            Self::Boolean(_) => cast::<bool>(value),
+            Self::F32(_) => cast::<f32>(value),
+            Self::F64(_) => cast::<f64>(value),
+            Self::Text(_) => cast::<String>(value),
+            Self::Union(_) => true,
+        }
+    }
+
+    /// Pushes `value`, which must be accepted by the column.
+    fn push(&mut self, value: Scalar) {
+        fn push<A>(builder: &mut MutableArray<A>, value: Scalar)
+        where
+            A: Array,
+            A::Data: Clone + Default + FromUnionValue,
+        {
+            builder.push(A::Data::from_union_value(value.borrow()));
+        }
+
+        match self {
+            Self::Int32(builder) => push(builder, value),
+            Self::UInt32(builder) => push(builder, value),
+            Self::ISize(builder) => push(builder, value),
+            Self::USize(builder) => push(builder, value),
+            Self::Boolean(builder) => push(builder, value),
+            Self::F32(builder) => push(builder, value),
+            Self::F64(builder) => push(builder, value),
+            Self::Text(builder) => push(builder, value),
+            Self::Union(builder) => builder.push(value),
+        }
+    }
+
+    fn finish(self) -> ColumnArray {
+        match self {
+            Self::Int32(builder) => builder.freeze().into(),
+            Self::UInt32(builder) => builder.freeze().into(),
+            Self::ISize(builder) => builder.freeze().into(),
+            Self::USize(builder) => builder.freeze().into(),
+            Self::Boolean(builder) => builder.freeze().into(),
+            Self::F32(builder) => builder.freeze().into(),
+            Self::F64(builder) => builder.freeze().into(),
+            Self::Text(builder) => builder.freeze().into(),
+            Self::Union(builder) => Union::from_builder(builder).into(),
+        }
+    }
+}
+
+/// Compares the elements at `lhs` and `rhs` of `column` under `key`.
+fn compare_rows(column: &ColumnArray, lhs: usize, rhs: usize, key: &SortKey) -> Ordering {
+    match column {
+        ColumnArray::Int32(array) => compare(|idx| array.get(idx), Ord::cmp, lhs, rhs, key),
+        ColumnArray::UInt32(array) => compare(|idx| array.get(idx), Ord::cmp, lhs, rhs, key),
+        ColumnArray::ISize(array) => compare(|idx| array.get(idx), Ord::cmp, lhs, rhs, key),
+        ColumnArray::USize(array) => compare(|idx| array.get(idx), Ord::cmp, lhs, rhs, key),
+        ColumnArray::Boolean(array) => compare(|idx| array.get(idx), Ord::cmp, lhs, rhs, key),
+        ColumnArray::F32(array) => compare(
+            |idx| array.get(idx),
+            |lhs, rhs| lhs.total_cmp(rhs),
+            lhs,
+            rhs,
+            key,
+        ),
+        ColumnArray::F64(array) => compare(
+            |idx| array.get(idx),
+            |lhs, rhs| lhs.total_cmp(rhs),
+            lhs,
+            rhs,
+            key,
+        ),
+        // `str` ordering is already that of its UTF-8 bytes
+        ColumnArray::Text(array) => compare(|idx| array.get_ref(idx), Ord::cmp, lhs, rhs, key),
+        ColumnArray::Union(array) => compare(
+            |idx| array.get_ref(idx).filter(|value| *value != UnionRef::Null),
+            TotalOrd::total_order,
+            lhs,
+            rhs,
+            key,
+        ),
+    }
+}
+
+/// Compares the values `get` returns for `lhs` and `rhs` with `cmp`, placing
+/// nulls as `key` requires.
+fn compare<T, G, C>(get: G, cmp: C, lhs: usize, rhs: usize, key: &SortKey) -> Ordering
+where
+    G: Fn(usize) -> Option<T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    let nulls = match key.nulls_first {
+        true => Ordering::Less,
+        false => Ordering::Greater,
+    };
+
+    match (get(lhs), get(rhs)) {
+        (Some(lhs), Some(rhs)) if key.descending => cmp(&lhs, &rhs).reverse(),
+        (Some(lhs), Some(rhs)) => cmp(&lhs, &rhs),
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => nulls,
+        (Some(_), None) => nulls.reverse(),
+    }
+}
+
+/// View over a single row of a [`RecordBatch`].
+#[derive(Clone, Copy)]
+pub struct Row<'a> {
+    batch: &'a RecordBatch,
+    idx: usize,
+}
+
+impl<'a> Row<'a> {
+    /// Returns the index of the row within its batch.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Returns the value in the first column named `name`.
+    ///
+    /// Returns None if there is no such column.
+    pub fn get(&self, name: &str) -> Option<Scalar> {
+        let column = self.batch.schema().index_of(name)?;
+        self.get_at(column)
+    }
+
+    /// Returns the value in the column at `column`.
+    ///
+    /// Returns None if `column` is out of range
+    pub fn get_at(&self, column: usize) -> Option<Scalar> {
+        self.batch.columns.get(column)?.scalar(self.idx)
+    }
+
+    /// Returns a copy of every value in the row.
+    pub fn values(&self) -> Vec<Scalar> {
+        (0..self.batch.num_columns())
+            .filter_map(|column| self.get_at(column))
+            .collect()
+    }
+}
+
+impl Debug for Row<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.batch.schema().fields().iter().map(|field| &field.name);
+
+        f.debug_map().entries(names.zip(self.values())).finish()
+    }
+}
+
+/// Rows are equal if they hold equal values under the same fields.
+impl PartialEq for Row<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.batch.schema().fields() == other.batch.schema().fields()
+            && self.values() == other.values()
+    }
+}
+
+/// Iterator over the rows of a [`RecordBatch`].
+pub struct Rows<'a> {
+    batch: &'a RecordBatch,
+    idx: usize,
+}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = Row<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.batch.row(self.idx).ok()?;
+        self.idx += 1;
+
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.batch.num_rows() - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Rows<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::union::UnionType;
+    use crate::{ArrayI32, ArrayISize, ArrayU32};
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::F64, true),
+        ])
+    }
+
+    #[test]
+    fn test_try_new() {
+        let columns = vec![
+            ArrayI32::from([1, 2, 3]).into(),
+            ArrayF64::from([Some(1.5), None, Some(2.0)]).into(),
+        ];
+        let batch = RecordBatch::try_new(schema(), columns).unwrap();
+
+        assert_eq!(3, batch.num_rows());
+        assert_eq!(2, batch.num_columns());
+        assert_eq!(DataType::F64, batch.column(1).data_type());
+        assert_eq!(Some(batch.column(1)), batch.column_by_name("price"));
+        assert_eq!(None, batch.column_by_name("z"));
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        let columns = vec![ArrayI32::from([1, 2, 3]).into()];
+        assert_eq!(
+            Err(SchemaError::ColumnCount {
+                expected: 2,
+                got: 1
+            }),
+            RecordBatch::try_new(schema(), columns)
+        );
+
+        let columns = vec![
+            ArrayI32::from([1, 2, 3]).into(),
+            ArrayText::from(["1.5"]).into(),
+        ];
+        assert_eq!(
+            Err(SchemaError::TypeMismatch {
+                name: "price".into(),
+                expected: DataType::F64,
+                got: DataType::Text
+            }),
+            RecordBatch::try_new(schema(), columns)
+        );
+
+        let columns = vec![
+            ArrayI32::from([1, 2, 3]).into(),
+            ArrayF64::from([1.5]).into(),
+        ];
+        assert_eq!(
+            Err(SchemaError::LengthMismatch {
+                name: "price".into(),
+                expected: 3,
+                got: 1
+            }),
+            RecordBatch::try_new(schema(), columns)
+        );
+    }
+
+    #[test]
+    fn test_rows() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+        ]);
+        let columns = vec![
+            ArrayI32::from([1, 2]).into(),
+            ArrayF64::from([Some(1.5), None]).into(),
+            ArrayText::from([Some("Bublé".into()), Some("Mars".into())]).into(),
+        ];
+        let batch = RecordBatch::try_new(schema, columns).unwrap();
+
+        let row = batch.row(1).unwrap();
+        assert_eq!(Some(Scalar::I32(2)), row.get("id"));
+        assert_eq!(Some(Scalar::Null), row.get("price"));
+        assert_eq!(Some(Scalar::Text("Mars".into())), row.get("name"));
+        assert_eq!(None, row.get("count"));
+        assert_eq!(None, row.get_at(3));
+        assert_eq!(
+            r#"{"id": I32(2), "price": Null, "name": Text("Mars")}"#,
+            format!("{row:?}")
+        );
+
+        assert_eq!(2, batch.rows().len());
+        assert_eq!(
+            vec![batch.row(0).unwrap(), row],
+            batch.rows().collect::<Vec<_>>()
+        );
+        assert_ne!(batch.row(0).unwrap(), row);
+        assert_eq!(
+            vec![
+                vec![
+                    Scalar::I32(1),
+                    Scalar::F64(1.5),
+                    Scalar::Text("Bublé".into())
+                ],
+                vec![Scalar::I32(2), Scalar::Null, Scalar::Text("Mars".into())],
+            ],
+            batch.to_rows()
+        );
+
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 2, len: 2 }),
+            batch.row(2)
+        );
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let schema = Schema::new(vec![
+            Field::new("name", DataType::Text, true),
+            Field::new("price", DataType::F64, true),
+            Field::new("id", DataType::Int32, false),
+        ]);
+        let columns = vec![
+            ArrayText::from([
+                Some("b".into()),
+                None,
+                Some("a".into()),
+                Some("b".into()),
+                Some("B".into()),
+                Some("a".into()),
+                None,
+                Some("b".into()),
+            ])
+            .into(),
+            ArrayF64::from([
+                Some(1.0),
+                Some(5.0),
+                None,
+                Some(3.0),
+                Some(2.0),
+                Some(f64::NAN),
+                None,
+                Some(3.0),
+            ])
+            .into(),
+            ArrayI32::from_range(0..8).into(),
+        ];
+        let batch = RecordBatch::try_new(schema, columns).unwrap();
+        let ids = |batch: RecordBatch| match batch.column(2) {
+            ColumnArray::Int32(array) => array.iter().flatten().collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+
+        let keys = [SortKey::new(0), SortKey::new(1).descending()];
+        // Equal rows 3 and 7 keep their order and NaN is the largest float
+        assert_eq!(vec![4, 5, 2, 3, 7, 0, 1, 6], ids(batch.sort_by(&keys)));
+
+        let keys = [SortKey::new(0).nulls_first(), SortKey::new(1).nulls_first()];
+        assert_eq!(vec![6, 1, 4, 2, 5, 0, 3, 7], ids(batch.sort_by(&keys)));
+
+        let keys = [SortKey::new(1).descending().nulls_first()];
+        assert_eq!(vec![2, 6, 5, 1, 3, 7, 4, 0], ids(batch.sort_by(&keys)));
+
+        assert_eq!((0..8).collect::<Vec<_>>(), ids(batch.sort_by(&[])));
+    }
+
+    #[test]
+    fn test_sort_by_column() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2, 3, 4, 5]).into(),
+                ArrayF64::from([Some(2.0), None, Some(1.0), Some(2.0), Some(0.5)]).into(),
+            ],
+        )
+        .unwrap();
+
+        let ids = |batch: RecordBatch| {
+            batch
+                .rows()
+                .map(|row| row.get_at(0).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let expected = |ids: [i32; 5]| ids.map(Scalar::I32).to_vec();
+
+        let last = NullPlacement::Last;
+
+        assert_eq!(
+            expected([5, 3, 1, 4, 2]),
+            ids(batch.sort_by_column("price", false, last).unwrap())
+        );
+        assert_eq!(
+            expected([1, 4, 3, 5, 2]),
+            ids(batch.sort_by_column("price", true, last).unwrap())
+        );
+        assert_eq!(
+            expected([4, 1, 3, 5, 2]),
+            ids(batch
+                .sort_by_columns(&[("price", true), ("id", true)], last)
+                .unwrap())
+        );
+        assert_eq!(
+            batch.sort_by(&[SortKey::new(1), SortKey::new(0).descending()]),
+            batch
+                .sort_by_columns(&[("price", false), ("id", true)], last)
+                .unwrap()
+        );
+        assert_eq!(batch, batch.sort_by_columns(&[], last).unwrap());
+        assert_eq!(
+            Err(SortError::ColumnNotFound {
+                name: "missing".into()
+            }),
+            batch.sort_by_column("missing", false, last)
+        );
+    }
+
+    #[test]
+    fn test_sort_by_column_null_placement() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2, 3, 4, 5]).into(),
+                ArrayF64::from([Some(2.0), None, Some(1.0), None, Some(0.5)]).into(),
+            ],
+        )
+        .unwrap();
+
+        let ids = |batch: RecordBatch| {
+            batch
+                .rows()
+                .map(|row| row.get_at(0).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let expected = |ids: [i32; 5]| ids.map(Scalar::I32).to_vec();
+
+        for (descending, first, last) in [
+            (false, [2, 4, 5, 3, 1], [5, 3, 1, 2, 4]),
+            (true, [2, 4, 1, 3, 5], [1, 3, 5, 2, 4]),
+        ] {
+            let sort = |nulls| ids(batch.sort_by_column("price", descending, nulls).unwrap());
+
+            assert_eq!(expected(first), sort(NullPlacement::First));
+            assert_eq!(expected(last), sort(NullPlacement::Last));
+        }
+
+        assert_eq!(
+            batch.sort_by(&[SortKey::new(1).nulls_first(), SortKey::new(0).nulls_first()]),
+            batch
+                .sort_by_columns(&[("price", false), ("id", false)], NullPlacement::First)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sort_by_unsortable_column() {
+        let schema = Schema::new(vec![
+            Field::new("raw", DataType::Union, true),
+            Field::new("ints", DataType::Union, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Union::from_vec(vec![UnionType::I32(1), UnionType::Text("a".into())]).into(),
+                Union::from_vec(vec![UnionType::I32(2), UnionType::Null]).into(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(SortError::UnsortableType {
+                name: "raw".into(),
+                data_type: DataType::Union
+            }),
+            batch.sort_by_columns(&[("ints", false), ("raw", false)], NullPlacement::Last)
+        );
+        assert_eq!(
+            batch,
+            batch
+                .sort_by_column("ints", false, NullPlacement::Last)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to sort by column 3 when batch has 3 columns")]
+    fn test_sort_by_missing_column() {
+        let columns = vec![
+            ArrayI32::from([1]).into(),
+            ArrayF64::from([1.0]).into(),
+            ArrayText::from(["a"]).into(),
+        ];
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+        ]);
+
+        RecordBatch::try_new(schema, columns)
+            .unwrap()
+            .sort_by(&[SortKey::new(3)]);
+    }
+
+    #[test]
+    fn test_filter_and_take() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+        ]);
+        let columns = vec![
+            ArrayI32::from([1, 2, 3, 4]).into(),
+            ArrayF64::from([Some(1.5), None, Some(3.5), Some(4.5)]).into(),
+            ArrayText::from([Some("a".into()), Some("b".into()), None, Some("d".into())]).into(),
+        ];
+        let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+        let source = batch.clone();
+
+        // The mask is computed on one column and selects whole rows
+        let ColumnArray::F64(price) = batch.column(1) else {
+            unreachable!()
+        };
+        let mask = ArrayBoolean::new(price.iter().map(|price| price.map(|price| price > 2.0)));
+
+        let expected = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                ArrayI32::from([3, 4]).into(),
+                ArrayF64::from([3.5, 4.5]).into(),
+                ArrayText::from([None, Some("d".into())]).into(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(Ok(expected), batch.filter(&mask));
+
+        let expected = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                ArrayI32::from([Some(4), None, Some(1)]).into(),
+                ArrayF64::from([Some(4.5), None, Some(1.5)]).into(),
+                ArrayText::from([Some("d".into()), None, Some("a".into())]).into(),
+            ],
+        )
+        .unwrap();
+        let indices = ArrayUSize::from([Some(3), None, Some(0)]);
+        assert_eq!(Ok(expected), batch.take(&indices));
+
+        assert_eq!(batch.slice(0, 2), Ok(batch.head(2)));
+        assert_eq!(batch.slice(1, 3), Ok(batch.tail(3)));
+        assert_eq!(batch, batch.head(10));
+        assert_eq!(0, batch.tail(0).num_rows());
+        assert_eq!(&schema, batch.tail(0).schema());
+        assert_eq!(source, batch);
+
+        assert_eq!(
+            Err(ArrayError::LengthMismatch { left: 4, right: 1 }),
+            batch.filter(&ArrayBoolean::from([true]))
+        );
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 4, len: 4 }),
+            batch.take(&ArrayUSize::from([4]))
+        );
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 5, len: 4 }),
+            batch.slice(3, 2)
+        );
+    }
+
+    #[test]
+    fn test_with_column() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2, 3]).into(),
+                ArrayF64::from([Some(1.5), None, Some(2.0)]).into(),
+            ],
+        )
+        .unwrap();
+        let source = batch.clone();
+        let name: ArrayRef = Arc::new(ArrayText::from(["a", "b", "c"]).into());
+
+        let added = batch.with_column("name", name.clone(), false).unwrap();
+        assert_eq!(3, added.num_columns());
+        assert_eq!(
+            Some(&Field::new("name", DataType::Text, true)),
+            added.schema().field_with_name("name")
+        );
+        assert!(Arc::ptr_eq(&name, &added.columns()[2]));
+        assert!(Arc::ptr_eq(&batch.columns()[0], &added.columns()[0]));
+        assert_eq!(source, batch);
+
+        assert_eq!(
+            Err(SchemaError::DuplicateField {
+                name: "price".into()
+            }),
+            batch.with_column("price", name.clone(), false)
+        );
+
+        let replaced = batch.with_column("price", name.clone(), true).unwrap();
+        assert_eq!(2, replaced.num_columns());
+        assert_eq!(Some(1), replaced.schema().index_of("price"));
+        assert_eq!(DataType::Text, replaced.column(1).data_type());
+
+        let short = Arc::new(ArrayText::from(["a"]).into());
+        assert_eq!(
+            Err(SchemaError::LengthMismatch {
+                name: "short".into(),
+                expected: 3,
+                got: 1
+            }),
+            batch.with_column("short", short, false)
+        );
+    }
+
+    #[test]
+    fn test_drop_rename_select() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2, 3]).into(),
+                ArrayF64::from([Some(1.5), None, Some(2.0)]).into(),
+            ],
+        )
+        .unwrap()
+        .with_column(
+            "name",
+            Arc::new(ArrayText::from(["a", "b", "c"]).into()),
+            false,
+        )
+        .unwrap();
+
+        let dropped = batch.drop_column("price").unwrap();
+        assert_eq!(vec!["id", "name"], names(&dropped));
+        assert_eq!(batch.column(2), dropped.column(1));
+
+        let renamed = batch.rename_column("price", "cost").unwrap();
+        assert_eq!(vec!["id", "cost", "name"], names(&renamed));
+        assert_eq!(batch.columns(), renamed.columns());
+
+        let selected = batch.select(&["name", "id"]).unwrap();
+        assert_eq!(vec!["name", "id"], names(&selected));
+        assert!(Arc::ptr_eq(&batch.columns()[2], &selected.columns()[0]));
+        assert_eq!(3, selected.num_rows());
+
+        let missing = Err(SchemaError::FieldNotFound {
+            name: "missing".into(),
+        });
+        assert_eq!(missing, batch.drop_column("missing"));
+        assert_eq!(missing, batch.rename_column("missing", "other"));
+        assert_eq!(missing, batch.select(&["id", "missing"]));
+        assert_eq!(missing, batch.drop_columns(&["id", "missing"]));
+
+        let dropped = batch.drop_columns(&["name", "id", "name"]).unwrap();
+        assert_eq!(vec!["price"], names(&dropped));
+        assert!(Arc::ptr_eq(&batch.columns()[1], &dropped.columns()[0]));
+        assert_eq!(batch, batch.drop_columns(&[]).unwrap());
+        assert_eq!(
+            0,
+            batch
+                .drop_columns(&["id", "price", "name"])
+                .unwrap()
+                .num_columns()
+        );
+
+        assert_eq!(
+            Err(SchemaError::DuplicateField { name: "id".into() }),
+            batch.rename_column("name", "id")
+        );
+        assert_eq!(
+            Err(SchemaError::DuplicateField { name: "id".into() }),
+            batch.select(&["id", "id"])
+        );
+    }
+
+    fn names(batch: &RecordBatch) -> Vec<&str> {
+        batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_random_sort_by() {
+        let schema = Schema::new(vec![
+            Field::new("key", DataType::Int32, true),
+            Field::new("text", DataType::Text, true),
+            Field::new("value", DataType::F64, false),
+        ]);
+
+        for seed in 0..8 {
+            let one = crate::random_batch(&schema, 200, seed);
+            let sorted = one.sort_by(&[SortKey::new(0)]);
+
+            assert_eq!(one.num_rows(), sorted.num_rows());
+
+            let keys = sorted.rows().map(|row| row.get_at(0).unwrap());
+            let keys = keys.collect::<Vec<_>>();
+            let valid = keys.iter().take_while(|key| **key != Scalar::Null);
+
+            assert!(valid
+                .clone()
+                .zip(valid.skip(1))
+                .all(|(lhs, rhs)| lhs <= rhs));
+            assert!(keys
+                .iter()
+                .skip_while(|key| **key != Scalar::Null)
+                .all(|key| *key == Scalar::Null));
+
+            // Compare the rows as multisets, ordered by their debug output
+            let rows = |batch: &RecordBatch| {
+                let mut rows = batch
+                    .rows()
+                    .map(|row| format!("{row:?}"))
+                    .collect::<Vec<_>>();
+                rows.sort();
+                rows
+            };
+
+            assert_eq!(rows(&one), rows(&sorted), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_statistics_cache() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([4, 2, 9]).into(),
+                ArrayF64::from([Some(1.5), None, Some(2.0)]).into(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(Scalar::I32(15), batch.statistics(0).sum);
+        assert_eq!(1, batch.statistics(1).null_count);
+
+        let price: ArrayRef = Arc::new(ArrayF64::from([0.5, 0.5, 0.5]).into());
+        let replaced = batch.with_column("price", price, true).unwrap();
+
+        assert!(replaced.statistics.0[0].get().is_some());
+        assert!(replaced.statistics.0[1].get().is_none());
+        assert_eq!(batch.statistics(0), replaced.statistics(0));
+        assert_eq!(0, replaced.statistics(1).null_count);
+        assert_eq!(1, replaced.statistics(1).distinct_count_estimate);
+
+        let filtered = batch
+            .filter(&ArrayBoolean::from([true, false, true]))
+            .unwrap();
+        assert_eq!(Scalar::I32(13), filtered.statistics(0).sum);
+        assert_eq!(batch, batch.clone());
+    }
+
+    #[test]
+    fn test_filter_rows() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2, 3, 4]).into(),
+                ArrayF64::from([Some(1.5), None, Some(2.0), None]).into(),
+            ],
+        )
+        .unwrap();
+        let mask = ArrayBoolean::from([Some(true), Some(false), None, Some(true)]);
+
+        let filtered = batch.filter_rows(&mask);
+        assert_eq!(Ok(filtered.clone()), batch.filter(&mask));
+        assert_eq!(batch.schema(), filtered.schema());
+        assert_eq!(
+            vec![
+                vec![Scalar::I32(1), Scalar::F64(1.5)],
+                vec![Scalar::I32(4), Scalar::Null]
+            ],
+            filtered.to_rows()
+        );
+        assert_eq!(
+            0,
+            batch.filter_rows(&ArrayBoolean::full(4, false)).num_rows()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to filter a batch of 4 rows with a mask of 3 elements")]
+    fn test_filter_rows_length_mismatch() {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2, 3, 4]).into(),
+                ArrayF64::from([1.0, 2.0, 3.0, 4.0]).into(),
+            ],
+        )
+        .unwrap();
+
+        batch.filter_rows(&ArrayBoolean::from([true, false, true]));
+    }
+
+    #[test]
+    fn test_concat() {
+        let one = RecordBatch::try_new(
+            schema(),
+            vec![
+                ArrayI32::from([1, 2]).into(),
+                ArrayF64::from([Some(1.5), None]).into(),
+            ],
+        )
+        .unwrap();
+        let two = RecordBatch::try_new(
+            schema(),
+            vec![ArrayI32::from([3]).into(), ArrayF64::from([2.5]).into()],
+        )
+        .unwrap();
+
+        let joined = RecordBatch::concat(&one, &two).unwrap();
+        assert_eq!(&schema(), joined.schema());
+        assert_eq!(
+            vec![
+                vec![Scalar::I32(1), Scalar::F64(1.5)],
+                vec![Scalar::I32(2), Scalar::Null],
+                vec![Scalar::I32(3), Scalar::F64(2.5)],
+            ],
+            joined.to_rows()
+        );
+        assert_eq!(one, RecordBatch::concat(&one, &one.head(0)).unwrap());
+
+        let renamed = two.rename_column("price", "cost").unwrap();
+        assert_eq!(
+            Err(SchemaError::NameMismatch {
+                idx: 1,
+                expected: "price".into(),
+                got: "cost".into()
+            }),
+            RecordBatch::concat(&one, &renamed)
+        );
+
+        let retyped = two
+            .with_column("price", Arc::new(ArrayI32::from([4]).into()), true)
+            .unwrap();
+        assert_eq!(
+            Err(SchemaError::TypeMismatch {
+                name: "price".into(),
+                expected: DataType::F64,
+                got: DataType::Int32
+            }),
+            RecordBatch::concat(&one, &retyped)
+        );
+        assert_eq!(
+            Err(SchemaError::ColumnCount {
+                expected: 2,
+                got: 1
+            }),
+            RecordBatch::concat(&one, &two.drop_column("id").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_sample() {
+        let ids = ArrayI32::new((0..1_000).map(Some));
+        let prices = ArrayF64::new((0..1_000).map(|idx| (idx % 3 != 0).then_some(idx as f64)));
+        let batch = RecordBatch::try_new(schema(), vec![ids.into(), prices.into()]).unwrap();
+
+        let sampled = batch.sample(100, 42);
+        assert_eq!(100, sampled.num_rows());
+        assert_eq!(sampled, batch.sample(100, 42));
+        assert_ne!(sampled, batch.sample(100, 43));
+        assert!(sampled
+            .rows()
+            .any(|row| row.get_at(1) == Some(Scalar::Null)));
+
+        let ids = sampled.rows().map(|row| row.get_at(0).unwrap());
+        let ids = ids
+            .map(|id| match id {
+                Scalar::I32(id) => id,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // The rows stay aligned across columns
+        for (row, id) in sampled.rows().zip(ids) {
+            let expected = match id % 3 {
+                0 => Scalar::Null,
+                _ => Scalar::F64(id as f64),
+            };
+            assert_eq!(Some(expected), row.get_at(1));
+        }
+
+        assert_eq!(batch, batch.sample(5_000, 1));
+
+        let nth = batch.every_nth(300);
+        assert_eq!(
+            vec![
+                vec![Scalar::I32(0), Scalar::Null],
+                vec![Scalar::I32(300), Scalar::Null],
+                vec![Scalar::I32(600), Scalar::Null],
+                vec![Scalar::I32(900), Scalar::Null],
+            ],
+            nth.to_rows()
+        );
+        assert_eq!(batch, batch.every_nth(1));
+    }
+
+    #[test]
+    fn test_describe() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("x", DataType::Int32, true),
+                Field::new("name", DataType::Text, true),
+            ]),
+            vec![
+                ArrayI32::from([Some(3), Some(1), None, Some(4), Some(2)]).into(),
+                ArrayText::new([
+                    Some("b".into()),
+                    Some("a".into()),
+                    Some("b".into()),
+                    None,
+                    Some("a".into()),
+                ])
+                .into(),
+            ],
+        )
+        .unwrap();
+
+        let summary = batch.describe();
+        let labels = [
+            "count",
+            "null_count",
+            "mean",
+            "std",
+            "min",
+            "25%",
+            "50%",
+            "75%",
+            "max",
+            "distinct",
+            "top",
+        ];
+
+        assert_eq!(
+            &Schema::new(vec![
+                Field::new("statistic", DataType::Text, false),
+                Field::new("x", DataType::F64, true),
+                Field::new("name", DataType::Union, true),
+            ]),
+            summary.schema()
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayText::from(labels)),
+            summary.column(0)
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayF64::from([
+                Some(4.0),
+                Some(1.0),
+                Some(2.5),
+                Some((5.0_f64 / 3.0).sqrt()),
+                Some(1.0),
+                Some(1.75),
+                Some(2.5),
+                Some(3.25),
+                Some(4.0),
+                None,
+                None,
+            ])),
+            summary.column(1)
+        );
+
+        let mut name = vec![Scalar::USize(4), Scalar::USize(1)];
+        name.extend(vec![Scalar::Null; 7]);
+        name.extend([Scalar::USize(2), Scalar::Text("b".into())]);
+        assert_eq!(&ColumnArray::from(Union::from_vec(name)), summary.column(2));
+
+        // Rows of statistics no column has are left out
+        let summary = batch.select(&["name"]).unwrap().describe();
+        assert_eq!(
+            vec![
+                vec![Scalar::Text("count".into()), Scalar::USize(4)],
+                vec![Scalar::Text("null_count".into()), Scalar::USize(1)],
+                vec![Scalar::Text("distinct".into()), Scalar::USize(2)],
+                vec![Scalar::Text("top".into()), Scalar::Text("b".into())],
+            ],
+            summary.to_rows()
+        );
+        assert_eq!(9, batch.select(&["x"]).unwrap().describe().num_rows());
+    }
+
+    #[test]
+    fn test_pivot() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("city", DataType::Text, false),
+                Field::new("year", DataType::Int32, false),
+                Field::new("sales", DataType::Int32, true),
+            ]),
+            vec![
+                ArrayText::from(["a", "a", "b", "b", "a", "c"]).into(),
+                ArrayI32::from([2020, 2021, 2020, 2020, 2020, 2021]).into(),
+                ArrayI32::from([Some(1), Some(2), Some(3), Some(4), Some(5), None]).into(),
+            ],
+        )
+        .unwrap();
+
+        let sums = batch.pivot("city", "year", "sales", Agg::Sum).unwrap();
+        assert_eq!(
+            &Schema::new(vec![
+                Field::new("city", DataType::Text, false),
+                Field::new("2020", DataType::Int32, true),
+                Field::new("2021", DataType::Int32, true),
+            ]),
+            sums.schema()
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayText::from(["a", "b", "c"])),
+            sums.column(0)
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayI32::from([Some(6), Some(7), None])),
+            sums.column(1)
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayI32::from([Some(2), None, None])),
+            sums.column(2)
+        );
+
+        // The group of c in 2021 exists but has no values
+        let counts = batch.pivot("city", "year", "sales", Agg::Count).unwrap();
+        assert_eq!(
+            &ColumnArray::from(ArrayUSize::from([Some(1), None, Some(0)])),
+            counts.column(2)
+        );
+
+        let means = batch.pivot("city", "year", "sales", Agg::Mean).unwrap();
+        assert_eq!(
+            &ColumnArray::from(ArrayF64::from([Some(3.0), Some(3.5), None])),
+            means.column(1)
+        );
+
+        let lasts = batch.pivot("year", "city", "sales", Agg::Last).unwrap();
+        assert_eq!(
+            vec!["year", "a", "b", "c"],
+            lasts
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayI32::from([Some(5), Some(2)])),
+            lasts.column(1)
+        );
+    }
+
+    #[test]
+    fn test_pivot_errors() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("year", DataType::Int32, false),
+                Field::new("kind", DataType::Text, false),
+            ]),
+            vec![
+                ArrayI32::from([2020, 2021]).into(),
+                ArrayText::from(["x", "year"]).into(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(SchemaError::DuplicateField {
+                name: "year".into()
+            }),
+            batch.pivot("year", "kind", "kind", Agg::First)
+        );
+        assert_eq!(
+            Err(SchemaError::TypeMismatch {
+                name: "kind".into(),
+                expected: DataType::F64,
+                got: DataType::Text
+            }),
+            batch.pivot("kind", "year", "kind", Agg::Sum)
+        );
+        assert_eq!(
+            Err(SchemaError::FieldNotFound { name: "z".into() }),
+            batch.pivot("year", "z", "kind", Agg::Max)
+        );
+    }
+
+    #[test]
+    fn test_melt() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("id", DataType::Text, false),
+                Field::new("x", DataType::Int32, true),
+                Field::new("y", DataType::F64, false),
+                Field::new("z", DataType::UInt32, false),
+                Field::new("note", DataType::Text, false),
+            ]),
+            vec![
+                ArrayText::from(["a", "b"]).into(),
+                ArrayI32::from([Some(1), None]).into(),
+                ArrayF64::from([0.5, 2.5]).into(),
+                ArrayU32::from([3, 4]).into(),
+                ArrayText::from(["p", "q"]).into(),
+            ],
+        )
+        .unwrap();
+
+        let long = batch
+            .melt(&["id"], &["x", "y", "z"], "variable", "value")
+            .unwrap();
+
+        assert_eq!(batch.num_rows() * 3, long.num_rows());
+        assert_eq!(
+            &Schema::new(vec![
+                Field::new("id", DataType::Text, false),
+                Field::new("variable", DataType::Text, false),
+                Field::new("value", DataType::F64, true),
+            ]),
+            long.schema()
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayText::from(["a", "b", "a", "b", "a", "b"])),
+            long.column(0)
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayText::from(["x", "x", "y", "y", "z", "z"])),
+            long.column(1)
+        );
+        assert_eq!(
+            &ColumnArray::from(ArrayF64::from([
+                Some(1.0),
+                None,
+                Some(0.5),
+                Some(2.5),
+                Some(3.0),
+                Some(4.0)
+            ])),
+            long.column(2)
+        );
+
+        // Mixed signed and unsigned integers widen to ISize
+        let long = batch.melt(&[], &["z", "x"], "var", "val").unwrap();
+        assert_eq!(
+            &ColumnArray::from(ArrayISize::from([Some(3), Some(4), Some(1), None])),
+            long.column(1)
+        );
+    }
+
+    #[test]
+    fn test_melt_errors() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("id", DataType::Int32, false),
+                Field::new("y", DataType::F64, false),
+                Field::new("note", DataType::Text, false),
+            ]),
+            vec![
+                ArrayI32::from([1, 2]).into(),
+                ArrayF64::from([0.5, 2.5]).into(),
+                ArrayText::from(["p", "q"]).into(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(SchemaError::IncompatibleTypes {
+                name: "value".into(),
+                left: DataType::F64,
+                right: DataType::Text
+            }),
+            batch.melt(&["id"], &["y", "note"], "variable", "value")
+        );
+        assert_eq!(
+            Err(SchemaError::DuplicateField { name: "id".into() }),
+            batch.melt(&["id"], &["y"], "variable", "id")
+        );
+        assert_eq!(
+            Err(SchemaError::FieldNotFound { name: "w".into() }),
+            batch.melt(&["id"], &["w"], "variable", "value")
+        );
+    }
+
+    #[test]
+    fn test_try_append() {
+        let rows = [
+            (1, Some(1.5)),
+            (2, None),
+            (3, Some(-2.0)),
+            (4, Some(0.0)),
+            (5, None),
+        ];
+
+        let build = |rows: &[(i32, Option<f64>)]| {
+            let mut builder = RecordBatchBuilder::new(schema());
+            for (id, price) in rows {
+                let price = price.map_or(Scalar::Null, Scalar::F64);
+                builder.push_row(vec![Scalar::I32(*id), price]).unwrap();
+            }
+            builder.finish()
+        };
+
+        let appended = build(&rows[..2])
+            .try_append(&build(&rows[2..3]))
+            .unwrap()
+            .try_append(&build(&rows[3..]))
+            .unwrap();
+
+        assert_eq!(build(&rows), appended);
+        assert_eq!(5, appended.num_rows());
+
+        let other = RecordBatch::try_new(
+            Schema::new(vec![Field::new("id", DataType::Int32, false)]),
+            vec![ArrayI32::from([1]).into()],
+        )
+        .unwrap();
+        assert_eq!(
+            Err(SchemaError::ColumnCount {
+                expected: 2,
+                got: 1
+            }),
+            appended.try_append(&other)
+        );
+    }
+
+    #[test]
+    fn test_record_batch_builder() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::ISize, false),
+            Field::new("score", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+            Field::new("any", DataType::Union, true),
+        ]);
+        let mut builder = RecordBatchBuilder::with_capacity(schema.clone(), 2);
+
+        // Integers widen into wider numeric columns
+        builder
+            .push_row(vec![
+                Scalar::I32(1),
+                Scalar::U32(7),
+                Scalar::Text("a".into()),
+                Scalar::Boolean(true),
+            ])
+            .unwrap();
+        builder
+            .push_row(vec![
+                Scalar::ISize(2),
+                Scalar::Null,
+                Scalar::Null,
+                Scalar::Null,
+            ])
+            .unwrap();
+
+        assert_eq!(
+            Err(SchemaError::TypeMismatch {
+                name: "score".into(),
+                expected: DataType::F64,
+                got: DataType::Text
+            }),
+            builder.push_row(vec![
+                Scalar::ISize(3),
+                Scalar::Text("high".into()),
+                Scalar::Null,
+                Scalar::Null
+            ])
+        );
+        assert_eq!(
+            Err(SchemaError::ColumnCount {
+                expected: 4,
+                got: 1
+            }),
+            builder.push_row(vec![Scalar::ISize(3)])
+        );
+        assert_eq!(2, builder.len());
+
+        let batch = builder.finish();
+        assert_eq!(&schema, batch.schema());
+        assert_eq!(
+            vec![
+                vec![
+                    Scalar::ISize(1),
+                    Scalar::F64(7.0),
+                    Scalar::Text("a".into()),
+                    Scalar::Boolean(true)
+                ],
+                vec![Scalar::ISize(2), Scalar::Null, Scalar::Null, Scalar::Null],
+            ],
+            batch.to_rows()
+        );
+        assert_eq!(0, RecordBatchBuilder::new(schema).finish().num_rows());
+    }
+
+    #[test]
+    fn test_unique() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![
+                Field::new("id", DataType::Int32, true),
+                Field::new("score", DataType::F64, true),
+            ]),
+            vec![
+                ArrayI32::from([Some(1), Some(2), Some(1), None, Some(1), None]).into(),
+                ArrayF64::from([Some(0.5), Some(f64::NAN), Some(0.5), None, Some(0.75), None])
+                    .into(),
+            ],
+        )
+        .unwrap();
+
+        let ids = |batch: RecordBatch| batch.column(0).clone();
+
+        assert_eq!(
+            ColumnArray::from(ArrayI32::from([Some(1), Some(2), None, Some(1)])),
+            ids(batch.unique(None, Keep::First).unwrap())
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayI32::from([Some(2), Some(1), Some(1), None])),
+            ids(batch.unique(None, Keep::Last).unwrap())
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayI32::from([Some(1), Some(2), None])),
+            ids(batch.unique(Some(&["id"]), Keep::First).unwrap())
+        );
+        assert_eq!(
+            ArrayBoolean::from([false, false, true, false, false, true]),
+            batch.duplicated(None).unwrap()
+        );
+        assert_eq!(
+            Err(SchemaError::FieldNotFound { name: "z".into() }),
+            batch.unique(Some(&["z"]), Keep::First)
+        );
+    }
+
+    #[test]
+    fn test_unique_nan() {
+        let batch = RecordBatch::try_new(
+            Schema::new(vec![Field::new("x", DataType::F64, false)]),
+            vec![ArrayF64::from([f64::NAN, 1.0, -f64::NAN, 0.0, -0.0]).into()],
+        )
+        .unwrap();
+
+        // NaNs of any sign dedup together, as do zeroes
+        let unique = batch.unique(None, Keep::First).unwrap();
+        assert_eq!(3, unique.num_rows());
+        assert!(unique
+            .column(0)
+            .scalar(0)
+            .unwrap()
+            .to_f64()
+            .unwrap()
+            .is_nan());
+        assert_eq!(
+            ArrayBoolean::from([false, false, true, false, true]),
+            batch.duplicated(Some(&["x"])).unwrap()
+        );
+    }
+}
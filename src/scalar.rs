@@ -0,0 +1,447 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::union::Scalar;
+
+/// Errors raised by arithmetic on and coercion of [`Scalar`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScalarError {
+    /// An operand of an arithmetic operation is not a number.
+    NotNumeric { op: &'static str, value: Scalar },
+    /// Integer arithmetic went out of range of the result type.
+    Overflow { op: &'static str },
+    /// An integer was divided by zero.
+    DivisionByZero,
+    /// The value cannot be represented as the requested type.
+    InvalidCoercion { value: Scalar, to: &'static str },
+}
+
+impl Display for ScalarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotNumeric { op, value } => {
+                write!(f, "Cannot {op} the non-numeric value {value:?}")
+            }
+            Self::Overflow { op } => write!(f, "Integer overflow when trying to {op}"),
+            Self::DivisionByZero => write!(f, "Integer division by zero"),
+            Self::InvalidCoercion { value, to } => write!(f, "Cannot coerce {value:?} to {to}"),
+        }
+    }
+}
+
+impl Error for ScalarError {}
+
+/// Numeric view of a [`Scalar`], wide enough to hold every integer variant.
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            Self::Int(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Sub => "subtract",
+            Self::Mul => "multiply",
+            Self::Div => "divide",
+        }
+    }
+
+    fn int(self, lhs: i128, rhs: i128) -> Option<i128> {
+        match self {
+            Self::Add => lhs.checked_add(rhs),
+            Self::Sub => lhs.checked_sub(rhs),
+            Self::Mul => lhs.checked_mul(rhs),
+            Self::Div => lhs.checked_div(rhs),
+        }
+    }
+
+    fn float(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            Self::Add => lhs + rhs,
+            Self::Sub => lhs - rhs,
+            Self::Mul => lhs * rhs,
+            Self::Div => lhs / rhs,
+        }
+    }
+}
+
+impl Scalar {
+    /// Returns true if the value is one of the integer or float variants.
+    pub fn is_numeric(&self) -> bool {
+        self.number().is_some()
+    }
+
+    /// Returns true if both values are equal by value, following the
+    /// [`PartialOrd`] impl, so `I32(1).num_eq(&F64(1.0))` holds.
+    ///
+    /// Comparisons involving a float go through `f64`, so integers beyond
+    /// 2^53 may equal a float without equalling each other.
+    pub fn num_eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+
+    fn number(&self) -> Option<Number> {
+        match self {
+            Self::U32(value) => Some(Number::Int(i128::from(*value))),
+            Self::I32(value) => Some(Number::Int(i128::from(*value))),
+            Self::USize(value) => Some(Number::Int(*value as i128)),
+            Self::ISize(value) => Some(Number::Int(*value as i128)),
+            Self::F32(value) => Some(Number::Float(f64::from(*value))),
+            Self::F64(value) => Some(Number::Float(*value)),
+            Self::Boolean(_) | Self::Text(_) | Self::Null => None,
+        }
+    }
+
+    /// Applies `op` following the promotion rules of the [`Add`] impl.
+    fn arithmetic(&self, rhs: &Scalar, op: Op) -> Result<Scalar, ScalarError> {
+        if matches!(self, Self::Null) || matches!(rhs, Self::Null) {
+            return Ok(Self::Null);
+        }
+
+        let number = |value: &Scalar| {
+            value.number().ok_or_else(|| ScalarError::NotNumeric {
+                op: op.name(),
+                value: value.clone(),
+            })
+        };
+        let (lhs_number, rhs_number) = (number(self)?, number(rhs)?);
+
+        let (Number::Int(lhs_int), Number::Int(rhs_int)) = (lhs_number, rhs_number) else {
+            let value = op.float(lhs_number.as_f64(), rhs_number.as_f64());
+
+            return Ok(match (self, rhs) {
+                (Self::F32(_), Self::F32(_)) => Self::F32(value as f32),
+                _ => Self::F64(value),
+            });
+        };
+
+        if op == Op::Div && rhs_int == 0 {
+            return Err(ScalarError::DivisionByZero);
+        }
+
+        let overflow = ScalarError::Overflow { op: op.name() };
+        let value = op.int(lhs_int, rhs_int).ok_or(overflow.clone())?;
+
+        let value = match (self, rhs) {
+            (Self::U32(_), Self::U32(_)) => u32::try_from(value).map(Self::U32).ok(),
+            (Self::I32(_), Self::I32(_)) => i32::try_from(value).map(Self::I32).ok(),
+            (Self::U32(_) | Self::USize(_), Self::U32(_) | Self::USize(_)) => {
+                usize::try_from(value).map(Self::USize).ok()
+            }
+            _ => isize::try_from(value).map(Self::ISize).ok(),
+        };
+
+        value.ok_or(overflow)
+    }
+
+    /// Returns the value as an `f64`.
+    ///
+    /// Integers are rounded to the nearest `f64`, booleans become 1 or 0 and
+    /// text is parsed. Errors for nulls and unparsable text.
+    pub fn to_f64(&self) -> Result<f64, ScalarError> {
+        let coerced = match self {
+            Self::Boolean(value) => Some(f64::from(u8::from(*value))),
+            Self::Text(value) => value.trim().parse().ok(),
+            value => value.number().map(Number::as_f64),
+        };
+
+        coerced.ok_or_else(|| ScalarError::InvalidCoercion {
+            value: self.clone(),
+            to: "f64",
+        })
+    }
+
+    /// Returns the value as an `i64`.
+    ///
+    /// Floats must be whole numbers, booleans become 1 or 0 and text is
+    /// parsed. Errors for nulls, unparsable text and values out of range.
+    pub fn to_i64(&self) -> Result<i64, ScalarError> {
+        let coerced = match (self, self.number()) {
+            (_, Some(Number::Int(value))) => i64::try_from(value).ok(),
+            (_, Some(Number::Float(value))) => {
+                let in_range = (i64::MIN as f64..i64::MAX as f64).contains(&value);
+                (value.fract() == 0.0 && in_range).then_some(value as i64)
+            }
+            (Self::Boolean(value), _) => Some(i64::from(*value)),
+            (Self::Text(value), _) => value.trim().parse().ok(),
+            _ => None,
+        };
+
+        coerced.ok_or_else(|| ScalarError::InvalidCoercion {
+            value: self.clone(),
+            to: "i64",
+        })
+    }
+}
+
+/// Numbers are ordered by value regardless of variant, integers exactly and
+/// anything involving a float as `f64`, so `I32(1)` and `F64(1.0)` compare
+/// as [`Ordering::Equal`]. Booleans and text only compare with their own kind
+/// and [`Scalar::Null`] only with itself. Values of unrelated kinds are
+/// unordered.
+///
+/// Unlike `==`, which is structural and tells `I32(1)` and `F64(1.0)`
+/// apart, see [`Scalar::num_eq`].
+impl PartialOrd for Scalar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Boolean(lhs), Self::Boolean(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Text(lhs), Self::Text(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Null, Self::Null) => Some(Ordering::Equal),
+            (lhs, rhs) => match (lhs.number()?, rhs.number()?) {
+                (Number::Int(lhs), Number::Int(rhs)) => lhs.partial_cmp(&rhs),
+                (lhs, rhs) => lhs.as_f64().partial_cmp(&rhs.as_f64()),
+            },
+        }
+    }
+}
+
+/// Numbers are written as by their own [`Display`] impl, text verbatim and
+/// nulls as `null`, matching [`Scalar::parse`].
+impl Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::U32(value) => write!(f, "{value}"),
+            Self::I32(value) => write!(f, "{value}"),
+            Self::USize(value) => write!(f, "{value}"),
+            Self::ISize(value) => write!(f, "{value}"),
+            Self::F32(value) => write!(f, "{value}"),
+            Self::F64(value) => write!(f, "{value}"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value}"),
+            Self::Null => write!(f, "null"),
+        }
+    }
+}
+
+/// Adds two numbers.
+///
+/// A null operand makes the result null. Integers of the same variant keep
+/// it, mixed integers widen to [`Scalar::USize`] if both are unsigned and
+/// [`Scalar::ISize`] otherwise. Any float promotes the result to
+/// [`Scalar::F64`], unless both are [`Scalar::F32`].
+///
+/// Errors if an operand is not a number or integer arithmetic overflows.
+/// The same rules apply to [`Sub`], [`Mul`] and [`Div`], where integer
+/// division truncates and errors on a zero divisor.
+impl Add for &Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.arithmetic(rhs, Op::Add)
+    }
+}
+
+impl Sub for &Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.arithmetic(rhs, Op::Sub)
+    }
+}
+
+impl Mul for &Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.arithmetic(rhs, Op::Mul)
+    }
+}
+
+impl Div for &Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.arithmetic(rhs, Op::Div)
+    }
+}
+
+impl Add for Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl Div for Scalar {
+    type Output = Result<Scalar, ScalarError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        &self / &rhs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        assert!(matches!(
+            Scalar::I32(2) + Scalar::I32(3),
+            Ok(Scalar::I32(5))
+        ));
+        assert!(matches!(
+            Scalar::U32(2) * Scalar::USize(3),
+            Ok(Scalar::USize(6))
+        ));
+        assert!(matches!(
+            Scalar::U32(2) - Scalar::I32(3),
+            Ok(Scalar::ISize(-1))
+        ));
+        assert!(matches!(
+            Scalar::I32(7) / Scalar::I32(2),
+            Ok(Scalar::I32(3))
+        ));
+        assert!(matches!(
+            Scalar::I32(7) / Scalar::F64(2.0),
+            Ok(Scalar::F64(3.5))
+        ));
+        assert!(matches!(
+            Scalar::F32(1.5) + Scalar::F32(1.0),
+            Ok(Scalar::F32(2.5))
+        ));
+        assert!(matches!(
+            Scalar::F32(1.5) + Scalar::U32(1),
+            Ok(Scalar::F64(2.5))
+        ));
+        assert!(matches!(Scalar::Null + Scalar::I32(1), Ok(Scalar::Null)));
+        assert!(matches!(
+            Scalar::Text("a".into()) * Scalar::Null,
+            Ok(Scalar::Null)
+        ));
+
+        assert_eq!(
+            Err(ScalarError::Overflow { op: "subtract" }),
+            Scalar::U32(1) - Scalar::U32(2)
+        );
+        assert_eq!(
+            Err(ScalarError::Overflow { op: "add" }),
+            Scalar::I32(i32::MAX) + Scalar::I32(1)
+        );
+        assert_eq!(
+            Err(ScalarError::DivisionByZero),
+            Scalar::ISize(1) / Scalar::U32(0)
+        );
+        assert!(matches!(
+            Scalar::F64(1.0) / Scalar::I32(0),
+            Ok(Scalar::F64(value)) if value.is_infinite()
+        ));
+        assert_eq!(
+            Err(ScalarError::NotNumeric {
+                op: "add",
+                value: Scalar::Boolean(true)
+            }),
+            Scalar::I32(1) + Scalar::Boolean(true)
+        );
+    }
+
+    #[test]
+    fn test_comparison() {
+        assert_ne!(Scalar::I32(1), Scalar::F64(1.0));
+        assert!(Scalar::I32(1).num_eq(&Scalar::F64(1.0)));
+        assert_eq!(
+            Some(Ordering::Equal),
+            Scalar::I32(1).partial_cmp(&Scalar::F64(1.0))
+        );
+        assert!(Scalar::I32(1).num_eq(&Scalar::USize(1)));
+        assert!(!Scalar::Text("1".into()).num_eq(&Scalar::I32(1)));
+        assert_eq!(Scalar::USize(usize::MAX), Scalar::USize(usize::MAX));
+        assert_ne!(Scalar::I32(-1), Scalar::USize(usize::MAX));
+        assert!(Scalar::I32(-1) < Scalar::U32(0));
+        assert!(Scalar::F32(2.5) > Scalar::ISize(2));
+        assert!(Scalar::Text("a".into()) < Scalar::Text("b".into()));
+        assert!(Scalar::Boolean(false) < Scalar::Boolean(true));
+        assert_eq!(Scalar::Null, Scalar::Null);
+        assert!(Scalar::Null.num_eq(&Scalar::Null));
+
+        let big = 1_usize << 53;
+        assert!(Scalar::USize(big).num_eq(&Scalar::F64(big as f64)));
+        assert!(Scalar::USize(big + 1).num_eq(&Scalar::F64(big as f64)));
+        assert!(!Scalar::USize(big).num_eq(&Scalar::USize(big + 1)));
+        assert_ne!(Scalar::USize(big), Scalar::F64(big as f64));
+
+        assert_ne!(Scalar::F64(f64::NAN), Scalar::F64(f64::NAN));
+        assert_ne!(Scalar::Text("1".into()), Scalar::I32(1));
+        assert_ne!(Scalar::Boolean(true), Scalar::I32(1));
+        assert_eq!(None, Scalar::Null.partial_cmp(&Scalar::I32(0)));
+        assert_eq!(
+            None,
+            Scalar::Text("a".into()).partial_cmp(&Scalar::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn test_coercion_and_display() {
+        assert_eq!(Ok(3.0), Scalar::U32(3).to_f64());
+        assert_eq!(Ok(1.0), Scalar::Boolean(true).to_f64());
+        assert_eq!(Ok(2.5), Scalar::Text(" 2.5".into()).to_f64());
+        assert_eq!(
+            Err(ScalarError::InvalidCoercion {
+                value: Scalar::Null,
+                to: "f64"
+            }),
+            Scalar::Null.to_f64()
+        );
+
+        assert_eq!(Ok(-3), Scalar::F64(-3.0).to_i64());
+        assert_eq!(Ok(42), Scalar::Text("42".into()).to_i64());
+        assert!(Scalar::F64(2.5).to_i64().is_err());
+        assert!(Scalar::F64(f64::NAN).to_i64().is_err());
+        assert!(Scalar::F64(1e19).to_i64().is_err());
+        assert!(Scalar::USize(usize::MAX).to_i64().is_err());
+        assert!(Scalar::Text("4.0".into()).to_i64().is_err());
+
+        let values = [
+            Scalar::I32(-4),
+            Scalar::F64(2.5),
+            Scalar::Boolean(true),
+            Scalar::Text("hello".into()),
+            Scalar::Null,
+        ];
+        let displayed = values.iter().map(Scalar::to_string).collect::<Vec<_>>();
+
+        assert_eq!(vec!["-4", "2.5", "true", "hello", "null"], displayed);
+        assert!(values
+            .iter()
+            .all(|value| Scalar::parse(value.to_string()).num_eq(value)));
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::utils::DataType;
+
+/// Description of a single named column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub data_type: DataType,
+    pub nullable: bool,
+    /// Application defined key value pairs attached to the field.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Field {
+    pub fn new(name: impl Into<String>, data_type: DataType, nullable: bool) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            nullable,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: BTreeMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Combines two fields of the same name into one which is nullable if
+    /// either is.
+    fn try_merge(&self, other: &Field) -> Result<Field, SchemaError> {
+        if self.data_type != other.data_type {
+            return Err(SchemaError::IncompatibleTypes {
+                name: self.name.clone(),
+                left: self.data_type,
+                right: other.data_type,
+            });
+        }
+
+        Ok(Self {
+            name: self.name.clone(),
+            data_type: self.data_type,
+            nullable: self.nullable || other.nullable,
+            metadata: merge_metadata(&self.metadata, &other.metadata)?,
+        })
+    }
+}
+
+/// Ordered collection of [`Field`]s describing the columns of a
+/// [`RecordBatch`](crate::RecordBatch).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schema {
+    fields: Vec<Field>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl Schema {
+    pub fn new(fields: Vec<Field>) -> Self {
+        Self {
+            fields,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: BTreeMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Returns the field at `idx`, if any.
+    pub fn field(&self, idx: usize) -> Option<&Field> {
+        self.fields.get(idx)
+    }
+
+    /// Returns the number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Application defined key value pairs attached to the schema.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Returns the position of the first field named `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
+
+    /// Returns the first field named `name`.
+    pub fn field_with_name(&self, name: &str) -> Option<&Field> {
+        self.index_of(name).map(|idx| &self.fields[idx])
+    }
+
+    /// Unifies `self` with `other`, such as when appending batches read from
+    /// different files.
+    ///
+    /// Fields are matched by name and must agree in type, becoming nullable
+    /// if either side is. Fields only found in `other` are appended in
+    /// order. Metadata is combined and must not map a key to two values.
+    pub fn try_merge(&self, other: &Schema) -> Result<Schema, SchemaError> {
+        let mut fields = self.fields.clone();
+
+        for field in &other.fields {
+            match self.index_of(&field.name) {
+                Some(idx) => fields[idx] = fields[idx].try_merge(field)?,
+                None => fields.push(field.clone()),
+            }
+        }
+
+        Ok(Self {
+            fields,
+            metadata: merge_metadata(&self.metadata, &other.metadata)?,
+        })
+    }
+}
+
+impl Display for Schema {
+    /// Formats the fields as a table of their name, type and nullability.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .fields
+            .iter()
+            .map(|field| {
+                let data_type = format!("{:?}", field.data_type);
+                (field.name.as_str(), data_type, field.nullable.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        let name = rows
+            .iter()
+            .map(|row| row.0.chars().count())
+            .fold("name".len(), usize::max);
+        let data_type = rows
+            .iter()
+            .map(|row| row.1.len())
+            .fold("type".len(), usize::max);
+
+        writeln!(f, "{:name$} | {:data_type$} | nullable", "name", "type")?;
+        write!(f, "{:-<name$}-+-{:-<data_type$}-+---------", "", "")?;
+
+        for (field, kind, nullable) in rows.iter() {
+            write!(f, "\n{field:name$} | {kind:data_type$} | {nullable}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn merge_metadata(
+    left: &BTreeMap<String, String>,
+    right: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, SchemaError> {
+    let mut merged = left.clone();
+
+    for (key, value) in right {
+        match merged.get(key) {
+            Some(existing) if existing != value => {
+                return Err(SchemaError::MetadataConflict {
+                    key: key.clone(),
+                    left: existing.clone(),
+                    right: value.clone(),
+                });
+            }
+            Some(_) => {}
+            None => {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Errors raised when columns do not conform to a [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    /// The number of columns differs from the number of fields.
+    ColumnCount { expected: usize, got: usize },
+    /// A column's length differs from that of the first column.
+    LengthMismatch {
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    /// A column's type differs from that of its field.
+    TypeMismatch {
+        name: String,
+        expected: DataType,
+        got: DataType,
+    },
+    /// Two fields of the same name being merged differ in type.
+    IncompatibleTypes {
+        name: String,
+        left: DataType,
+        right: DataType,
+    },
+    /// Metadata being merged maps the same key to different values.
+    MetadataConflict {
+        key: String,
+        left: String,
+        right: String,
+    },
+    /// A field of the same name already exists.
+    DuplicateField { name: String },
+    /// No field has the given name.
+    FieldNotFound { name: String },
+    /// The field at `idx` has a different name than expected.
+    NameMismatch {
+        idx: usize,
+        expected: String,
+        got: String,
+    },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ColumnCount { expected, got } => {
+                write!(f, "Expected {expected} columns but got {got}")
+            }
+            Self::LengthMismatch {
+                name,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Column \"{name}\" has length {got} when {expected} was expected"
+            ),
+            Self::TypeMismatch {
+                name,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Column \"{name}\" has type {got:?} when {expected:?} was expected"
+            ),
+            Self::IncompatibleTypes { name, left, right } => write!(
+                f,
+                "Cannot merge field \"{name}\" of types {left:?} and {right:?}"
+            ),
+            Self::MetadataConflict { key, left, right } => write!(
+                f,
+                "Cannot merge metadata key \"{key}\" with values \"{left}\" and \"{right}\""
+            ),
+            Self::DuplicateField { name } => write!(f, "Field \"{name}\" already exists"),
+            Self::FieldNotFound { name } => write!(f, "No field named \"{name}\""),
+            Self::NameMismatch { idx, expected, got } => write!(
+                f,
+                "Field {idx} is named \"{got}\" when \"{expected}\" was expected"
+            ),
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn metadata(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lookup() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Text, true),
+        ]);
+
+        assert_eq!(Some(1), schema.index_of("name"));
+        assert_eq!(None, schema.index_of("Name"));
+        assert_eq!(
+            Some(&Field::new("id", DataType::Int32, false)),
+            schema.field_with_name("id")
+        );
+        assert_eq!(None, schema.field_with_name("price"));
+
+        assert_eq!(2, schema.len());
+        assert_eq!(
+            Some("name"),
+            schema.field(1).map(|field| field.name.as_str())
+        );
+        assert_eq!(None, schema.field(2));
+        assert!(Schema::default().is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("description", DataType::Text, true),
+        ]);
+
+        let expected = [
+            "name        | type  | nullable",
+            "------------+-------+---------",
+            "id          | Int32 | false",
+            "description | Text  | true",
+        ];
+        assert_eq!(expected.join("\n"), schema.to_string());
+
+        let expected = "name | type | nullable\n-----+------+---------";
+        assert_eq!(expected, Schema::default().to_string());
+    }
+
+    #[test]
+    fn test_merge() {
+        let one = Schema::new(vec![
+            Field::new("id", DataType::Int32, false).with_metadata(metadata(&[("unit", "none")])),
+            Field::new("name", DataType::Text, false),
+        ])
+        .with_metadata(metadata(&[("source", "a.csv")]));
+
+        let two = Schema::new(vec![
+            Field::new("price", DataType::F64, true),
+            Field::new("name", DataType::Text, true),
+            Field::new("id", DataType::Int32, false)
+                .with_metadata(metadata(&[("unit", "none"), ("sorted", "true")])),
+        ])
+        .with_metadata(metadata(&[("rows", "10")]));
+
+        let three = Schema::new(vec![
+            Field::new("id", DataType::Int32, false)
+                .with_metadata(metadata(&[("sorted", "true"), ("unit", "none")])),
+            Field::new("name", DataType::Text, true),
+            Field::new("price", DataType::F64, true),
+        ])
+        .with_metadata(metadata(&[("rows", "10"), ("source", "a.csv")]));
+
+        assert_eq!(Ok(three.clone()), one.try_merge(&two));
+        assert_eq!(Ok(three.clone()), three.try_merge(&Schema::default()));
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let one = Schema::new(vec![Field::new("id", DataType::Int32, false)]);
+        let two = Schema::new(vec![Field::new("id", DataType::ISize, false)]);
+
+        assert_eq!(
+            Err(SchemaError::IncompatibleTypes {
+                name: "id".into(),
+                left: DataType::Int32,
+                right: DataType::ISize,
+            }),
+            one.try_merge(&two)
+        );
+
+        let two = one.clone().with_metadata(metadata(&[("source", "b.csv")]));
+        let one = one.with_metadata(metadata(&[("source", "a.csv")]));
+
+        assert_eq!(
+            Err(SchemaError::MetadataConflict {
+                key: "source".into(),
+                left: "a.csv".into(),
+                right: "b.csv".into(),
+            }),
+            one.try_merge(&two)
+        );
+    }
+}
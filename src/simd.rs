@@ -0,0 +1,242 @@
+//! Kernels working through values eight at a time, laid out so the compiler
+//! can vectorize them. The validity is read a word of 64 elements at a
+//! time, so fully valid words skip null checks and fully null ones are
+//! skipped outright. Within the other words, each chunk of eight lines up
+//! with a byte of the word, so fully valid chunks still skip null checks.
+
+use crate::utils::{BitmapBuffer, Numeric, ValidityWords};
+use crate::ArrayBoolean;
+
+#[cfg(test)]
+use crate::utils::VALID_WORDS;
+
+const LANES: usize = 8;
+
+/// Folds the valid elements of `values`, whose validity is `word`, into
+/// `lanes`, the `i`th element of every chunk going into the `i`th lane.
+fn fold_word<T, F>(values: &[T], word: u64, lanes: &mut [T; LANES], fold: F)
+where
+    T: Copy,
+    F: Fn(&mut T, T),
+{
+    if word.count_ones() as usize == values.len() {
+        #[cfg(test)]
+        VALID_WORDS.with(|count| count.set(count.get() + 1));
+
+        for chunk in values.chunks(LANES) {
+            for (lane, value) in lanes.iter_mut().zip(chunk) {
+                fold(lane, *value);
+            }
+        }
+
+        return;
+    }
+
+    // Bits past the end of the array are cleared, so a full byte always
+    // has a full chunk
+    for (chunk, bits) in values.chunks(LANES).zip(word.to_le_bytes()) {
+        if bits == u8::MAX {
+            for (lane, value) in lanes.iter_mut().zip(chunk) {
+                fold(lane, *value);
+            }
+        } else if bits != 0 {
+            for (bit, value) in chunk.iter().enumerate() {
+                if (bits >> bit) & 1 == 1 {
+                    fold(&mut lanes[bit], *value);
+                }
+            }
+        }
+    }
+}
+
+/// Adds up the valid `values` in eight lanes, or returns None if none is
+/// valid.
+///
+/// Integer sums wrap around as in [`crate::sum`] and are identical to it,
+/// while float sums may differ from it by rounding.
+pub(crate) fn sum<T: Numeric>(values: &[T], words: ValidityWords<'_>) -> Option<T> {
+    let mut lanes = [T::default(); LANES];
+    let mut any = false;
+
+    for (range, word) in words.filter(|(_, word)| *word != 0) {
+        any = true;
+        fold_word(&values[range], word, &mut lanes, |lane, value| {
+            *lane = lane.add_wrapping(value);
+        });
+    }
+
+    any.then(|| lanes.into_iter().fold(T::default(), Numeric::add_wrapping))
+}
+
+/// Returns the smallest valid value, ignoring NaNs.
+pub(crate) fn min<T: Numeric>(values: &[T], words: ValidityWords<'_>) -> Option<T> {
+    extreme(values, words, |value, acc| value < acc)
+}
+
+/// Returns the largest valid value, ignoring NaNs.
+pub(crate) fn max<T: Numeric>(values: &[T], words: ValidityWords<'_>) -> Option<T> {
+    extreme(values, words, |value, acc| value > acc)
+}
+
+/// Returns the valid value no other is `better` than.
+fn extreme<T: Numeric>(
+    values: &[T],
+    words: ValidityWords<'_>,
+    better: fn(T, T) -> bool,
+) -> Option<T> {
+    let mut lanes = None;
+
+    for (range, word) in words.filter(|(_, word)| *word != 0) {
+        if lanes.is_none() {
+            // Seeded with an ordered value, which a NaN is never better than
+            lanes = (0..range.len())
+                .filter(|bit| (word >> bit) & 1 == 1)
+                .map(|bit| values[range.start + bit])
+                .find(|value| value.partial_cmp(value).is_some())
+                .map(|seed| [seed; LANES]);
+        }
+
+        if let Some(lanes) = &mut lanes {
+            fold_word(&values[range], word, lanes, |lane, value| {
+                if better(value, *lane) {
+                    *lane = value;
+                }
+            });
+        }
+    }
+
+    lanes?
+        .into_iter()
+        .reduce(|acc, value| if better(value, acc) { value } else { acc })
+}
+
+/// Compares every value with `rhs`, eight to a byte of the result, before
+/// masking out the nulls given by `validity` and `nulls`.
+pub(crate) fn compare_scalar<T, F>(
+    values: &[T],
+    rhs: &T,
+    validity: Option<&BitmapBuffer>,
+    nulls: usize,
+    keep: F,
+) -> ArrayBoolean
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let bytes = values
+        .chunks(LANES)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0, |byte, (bit, lhs)| {
+                byte | (u8::from(keep(lhs, rhs)) << (7 - bit))
+            })
+        })
+        .collect();
+
+    ArrayBoolean::from_parts(bytes, validity.cloned(), nulls, values.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cmp::Ordering;
+
+    use crate::arithmetic::{max_of, min_of, sum_of};
+    use crate::comparison::compare_scalar as compare_scalar_of;
+    use crate::utils::{validity_words, Array};
+    use crate::{ArrayF64, ArrayI32};
+
+    /// Lengths that are and are not multiples of eight, covering partial,
+    /// full and empty chunks.
+    const LENS: [usize; 10] = [0, 1, 7, 8, 9, 16, 23, 40, 64, 203];
+
+    /// Arrays of every length in [`LENS`], both null free and with a
+    /// varying pattern of nulls.
+    fn arrays<F>(value: F) -> impl Iterator<Item = ArrayI32>
+    where
+        F: Fn(usize) -> i32 + Copy,
+    {
+        LENS.into_iter().flat_map(move |len| {
+            let dense = ArrayI32::new((0..len).map(|idx| Some(value(idx))));
+            let sparse = ArrayI32::new(
+                (0..len).map(|idx| (idx % 7 != 3 && idx / 8 != 2).then(|| value(idx))),
+            );
+
+            [dense, sparse]
+        })
+    }
+
+    #[test]
+    fn test_integers_match_scalar() {
+        let value = |idx: usize| (idx as i32 * 7919) % 201 - 100;
+
+        for array in arrays(value).chain([ArrayI32::from([i32::MAX, 1, i32::MAX])]) {
+            let values = array.values().unwrap_or(&[]);
+            let scalar = || array.copied_iter().flatten();
+
+            assert_eq!(
+                sum_of(scalar()),
+                sum(values, validity_words(&array)),
+                "{array:?}"
+            );
+            assert_eq!(
+                min_of(scalar()),
+                min(values, validity_words(&array)),
+                "{array:?}"
+            );
+            assert_eq!(
+                max_of(scalar()),
+                max(values, validity_words(&array)),
+                "{array:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_floats_within_tolerance() {
+        for len in LENS.into_iter().chain([1000, 1003]) {
+            let dense = ArrayF64::new((0..len).map(|idx| Some(1.0 / (idx + 1) as f64)));
+            let sparse =
+                ArrayF64::new((0..len).map(|idx| (idx % 13 != 0).then(|| 1.0 / (idx + 1) as f64)));
+
+            for array in [dense, sparse] {
+                let values = array.values().unwrap_or(&[]);
+                let scalar = || array.copied_iter().flatten();
+
+                match (sum_of(scalar()), sum(values, validity_words(&array))) {
+                    (Some(scalar), Some(chunked)) => {
+                        assert!((scalar - chunked).abs() <= scalar.abs() * 1e-12, "{len}")
+                    }
+                    (scalar, chunked) => assert_eq!(scalar, chunked),
+                }
+
+                assert_eq!(min_of(scalar()), min(values, validity_words(&array)));
+                assert_eq!(max_of(scalar()), max(values, validity_words(&array)));
+            }
+        }
+
+        let two = ArrayF64::from([Some(f64::NAN), None, Some(2.0), Some(-1.0), Some(f64::NAN)]);
+        assert_eq!(Some(-1.0), min(two.values().unwrap(), validity_words(&two)));
+        assert_eq!(Some(2.0), max(two.values().unwrap(), validity_words(&two)));
+
+        let three = ArrayF64::from([f64::NAN]);
+        assert_eq!(None, max(three.values().unwrap(), validity_words(&three)));
+    }
+
+    #[test]
+    fn test_compare_scalar_matches_scalar() {
+        for array in arrays(|idx| (idx % 5) as i32) {
+            let values = array.values().unwrap_or(&[]);
+            let (validity, nulls) = (array.validity(), array.null_count());
+
+            assert_eq!(
+                compare_scalar_of(&array, &2, |ordering| ordering.is_some_and(Ordering::is_eq)),
+                compare_scalar(values, &2, validity, nulls, |lhs, rhs| lhs == rhs),
+                "{array:?}"
+            );
+            assert_eq!(
+                compare_scalar_of(&array, &2, |ordering| ordering.is_some_and(Ordering::is_lt)),
+                compare_scalar(values, &2, validity, nulls, |lhs, rhs| lhs < rhs),
+                "{array:?}"
+            );
+        }
+    }
+}
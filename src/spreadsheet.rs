@@ -0,0 +1,299 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, Reader};
+
+use crate::column::ColumnArray;
+use crate::record_batch::RecordBatch;
+use crate::schema::{Field, Schema, SchemaError};
+use crate::union::{BuildError, Union, UnionBuilder};
+use crate::utils::Array;
+use crate::ArrayText;
+
+/// Errors raised while reading a spreadsheet.
+#[derive(Debug)]
+pub enum SpreadsheetError {
+    /// The workbook could not be opened or its sheet could not be read.
+    Calamine(calamine::Error),
+    /// The workbook has no sheet of the given name or index.
+    SheetNotFound(String),
+    Schema(SchemaError),
+}
+
+impl Display for SpreadsheetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Calamine(error) => write!(f, "Spreadsheet error: {error}"),
+            Self::SheetNotFound(sheet) => write!(f, "Workbook has no sheet {sheet}"),
+            Self::Schema(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for SpreadsheetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Calamine(error) => Some(error),
+            Self::Schema(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<calamine::Error> for SpreadsheetError {
+    fn from(value: calamine::Error) -> Self {
+        Self::Calamine(value)
+    }
+}
+
+impl From<SchemaError> for SpreadsheetError {
+    fn from(value: SchemaError) -> Self {
+        Self::Schema(value)
+    }
+}
+
+/// Sheet of a workbook, given by name or by position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sheet<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+impl<'a> From<&'a str> for Sheet<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Name(value)
+    }
+}
+
+impl From<usize> for Sheet<'_> {
+    fn from(value: usize) -> Self {
+        Self::Index(value)
+    }
+}
+
+/// Reads `sheet` of the Excel or OpenDocument workbook at `path` into a
+/// single [`RecordBatch`].
+///
+/// Cells map to values by their type: numbers to `f64`, booleans to
+/// `bool`, and empty cells to nulls. Text cells are parsed as in
+/// [`UnionBuilder::parse_push`]. Dates and durations become ISO 8601 text
+/// and error cells their code, such as `#DIV/0!`. Trailing rows with no
+/// values are dropped.
+///
+/// Columns are built with [`UnionBuilder::build_as_typed_column`], so mixed
+/// numbers are promoted to a common type while other mixed columns are
+/// read as a [`Union`]. Columns of empty cells are read as text. Fields are
+/// named `column_1`, `column_2`, ... when there is no header, or its cell
+/// is empty.
+pub fn read_sheet<'a, P>(
+    path: P,
+    sheet: impl Into<Sheet<'a>>,
+    has_header: bool,
+) -> Result<RecordBatch, SpreadsheetError>
+where
+    P: AsRef<Path>,
+{
+    let mut workbook = open_workbook_auto(path)?;
+
+    let range = match sheet.into() {
+        Sheet::Name(name) => {
+            if !workbook.sheet_names().iter().any(|sheet| sheet == name) {
+                return Err(SpreadsheetError::SheetNotFound(format!("\"{name}\"")));
+            }
+
+            workbook.worksheet_range(name)?
+        }
+        Sheet::Index(idx) => workbook
+            .worksheet_range_at(idx)
+            .ok_or_else(|| SpreadsheetError::SheetNotFound(idx.to_string()))??,
+    };
+
+    let mut rows = range.rows().collect::<Vec<_>>();
+
+    while rows.last().is_some_and(|row| row.iter().all(is_empty)) {
+        rows.pop();
+    }
+
+    let width = range.width();
+    let header = match has_header && !rows.is_empty() {
+        true => Some(rows.remove(0)),
+        false => None,
+    };
+
+    let mut fields = Vec::with_capacity(width);
+    let mut columns = Vec::with_capacity(width);
+
+    for idx in 0..width {
+        let mut builder = UnionBuilder::with_capacity(rows.len());
+
+        for row in &rows {
+            push_cell(&mut builder, &row[idx]);
+        }
+
+        let name = match header.map(|header| &header[idx]) {
+            Some(cell) if !is_empty(cell) => cell.to_string(),
+            _ => format!("column_{}", idx + 1),
+        };
+
+        // Columns of empty cells have no type to take
+        let column = match builder.build_as_typed_column() {
+            Ok(column) => column,
+            Err(BuildError::OnlyNulls { len }) => ArrayText::from_vec(vec![None; len]).into(),
+        };
+        fields.push(Field::new(
+            name,
+            column.data_type(),
+            column.null_count() > 0,
+        ));
+        columns.push(column);
+    }
+
+    Ok(RecordBatch::try_new(Schema::new(fields), columns)?)
+}
+
+fn is_empty(cell: &Data) -> bool {
+    match cell {
+        Data::Empty => true,
+        Data::String(text) => text.is_empty(),
+        _ => false,
+    }
+}
+
+fn push_cell(builder: &mut UnionBuilder, cell: &Data) {
+    match cell {
+        Data::Empty => builder.push_none(),
+        Data::Int(value) => builder.push_f64(*value as f64),
+        Data::Float(value) => builder.push_f64(*value),
+        Data::Bool(value) => builder.push_bool(*value),
+        Data::String(text) => {
+            builder.parse_push(text.as_str());
+        }
+        Data::DateTime(value) if value.is_duration() => match value.as_duration() {
+            Some(duration) => builder.push_string(duration.to_string()),
+            None => builder.push_f64(value.as_f64()),
+        },
+        Data::DateTime(value) => match value.as_datetime() {
+            Some(datetime) => builder.push_string(iso_datetime(datetime.and_utc().timestamp())),
+            None => builder.push_f64(value.as_f64()),
+        },
+        Data::DateTimeIso(text) | Data::DurationIso(text) => builder.push_string(text.clone()),
+        Data::Error(error) => builder.push_string(error.to_string()),
+    }
+}
+
+/// Formats seconds since the Unix epoch as an ISO 8601 date, followed by
+/// the time unless it is midnight.
+fn iso_datetime(timestamp: i64) -> String {
+    let (days, seconds) = (timestamp.div_euclid(86_400), timestamp.rem_euclid(86_400));
+
+    // Civil from days, counting in 400 year eras starting on March 1st
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    let date = format!("{year:04}-{month:02}-{day:02}");
+
+    match seconds {
+        0 => date,
+        _ => format!(
+            "{date}T{:02}:{:02}:{:02}",
+            seconds / 3_600,
+            seconds / 60 % 60,
+            seconds % 60
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::union::UnionType;
+    use crate::utils::DataType;
+    use crate::{ArrayBoolean, ArrayF64};
+
+    fn fixture() -> String {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/cities.xlsx").into()
+    }
+
+    #[test]
+    fn test_read_sheet() {
+        let batch = read_sheet(fixture(), "Cities", true).unwrap();
+
+        assert_eq!(4, batch.num_rows());
+        assert_eq!(
+            &Schema::new(vec![
+                Field::new("Date", DataType::Text, true),
+                Field::new("City", DataType::Text, false),
+                Field::new("Population", DataType::Union, false),
+                Field::new("Coastal", DataType::Boolean, true),
+            ]),
+            batch.schema()
+        );
+
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+
+        assert_eq!(
+            ColumnArray::from(ArrayText::new([
+                Some("2024-01-01".into()),
+                Some("2024-01-02T12:00:00".into()),
+                Some("2024-01-03".into()),
+                None,
+            ])),
+            column("Date")
+        );
+        assert_eq!(
+            ColumnArray::from(Union::from([
+                UnionType::F64(2_500_000.0),
+                UnionType::F64(3_490_000.0),
+                UnionType::Text("n/a".into()),
+                UnionType::F64(169_894.5),
+            ])),
+            column("Population")
+        );
+        assert_eq!(
+            ColumnArray::from(ArrayBoolean::from([
+                Some(true),
+                Some(false),
+                None,
+                Some(true)
+            ])),
+            column("Coastal")
+        );
+    }
+
+    #[test]
+    fn test_read_sheet_selection() {
+        let one = read_sheet(fixture(), 1, true).unwrap();
+        assert_eq!(one, read_sheet(fixture(), "Ids", true).unwrap());
+        assert_eq!(ColumnArray::from(ArrayF64::from([1.0])), *one.columns()[0]);
+
+        let two = read_sheet(fixture(), Sheet::Index(0), false).unwrap();
+        assert_eq!(5, two.num_rows());
+        assert_eq!("column_1", two.schema().fields()[0].name);
+        assert_eq!(DataType::Text, two.schema().fields()[1].data_type);
+
+        assert!(matches!(
+            read_sheet(fixture(), "Towns", true),
+            Err(SpreadsheetError::SheetNotFound(_))
+        ));
+        assert!(matches!(
+            read_sheet(fixture(), 2, true),
+            Err(SpreadsheetError::SheetNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_iso_datetime() {
+        assert_eq!("1970-01-01", iso_datetime(0));
+        assert_eq!("2000-02-29T23:59:59", iso_datetime(951_868_799));
+        assert_eq!("1899-12-30T06:00:00", iso_datetime(-2_209_140_000));
+    }
+}
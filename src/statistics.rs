@@ -0,0 +1,228 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+
+use crate::union::Scalar;
+
+/// Number of hashes kept by the distinct count sketch. Counts below this
+/// are exact.
+const SKETCH_SIZE: usize = 256;
+
+/// Summary of the values of an array, as returned by
+/// [`Array::statistics`](crate::Array::statistics).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayStatistics {
+    /// The least non-null value, or [`Scalar::Null`] if there is none.
+    /// Text is ordered by its UTF-8 bytes, float `NaN`s are skipped and
+    /// union values are only compared with values of a comparable type.
+    pub min: Scalar,
+    /// The greatest non-null value, or [`Scalar::Null`] if there is none.
+    pub max: Scalar,
+    pub null_count: usize,
+    /// Estimated number of distinct non-null values.
+    ///
+    /// Exact, barring hash collisions, below 256 distinct values. Larger
+    /// counts are estimated from the 256 smallest value hashes and are
+    /// typically within 7% of the true count.
+    pub distinct_count_estimate: usize,
+    /// Sum of the non-null values following the promotion rules of
+    /// [`Scalar`] addition, switching to `f64` should an integer sum
+    /// overflow. [`Scalar::Null`] if there are no values or any is not a
+    /// number.
+    pub sum: Scalar,
+}
+
+impl ArrayStatistics {
+    /// Computes the statistics of `values` in a single pass, with both None
+    /// and [`Scalar::Null`] counting as null.
+    pub(crate) fn from_values<I>(values: I) -> Self
+    where
+        I: Iterator<Item = Option<Scalar>>,
+    {
+        let mut min: Option<Scalar> = None;
+        let mut max: Option<Scalar> = None;
+        let mut sum: Option<Scalar> = None;
+        let mut numeric = true;
+        let mut null_count = 0;
+        let mut sketch = DistinctSketch::default();
+
+        for value in values {
+            let value = match value {
+                None | Some(Scalar::Null) => {
+                    null_count += 1;
+                    continue;
+                }
+                Some(value) => value,
+            };
+
+            sketch.insert(&value);
+
+            // Only NaNs are unordered with themselves
+            if value.partial_cmp(&value).is_some() {
+                if min.as_ref().is_none_or(|min| value < *min) {
+                    min = Some(value.clone());
+                }
+
+                if max.as_ref().is_none_or(|max| value > *max) {
+                    max = Some(value.clone());
+                }
+            }
+
+            numeric &= value.is_numeric();
+            if numeric {
+                sum = Some(match sum {
+                    None => value,
+                    Some(sum) => (&sum + &value).unwrap_or_else(|_| {
+                        let sum = sum.to_f64().expect("Numbers coerce to f64");
+                        let value = value.to_f64().expect("Numbers coerce to f64");
+
+                        Scalar::F64(sum + value)
+                    }),
+                });
+            }
+        }
+
+        Self {
+            min: min.unwrap_or(Scalar::Null),
+            max: max.unwrap_or(Scalar::Null),
+            null_count,
+            distinct_count_estimate: sketch.estimate(),
+            sum: sum.filter(|_| numeric).unwrap_or(Scalar::Null),
+        }
+    }
+}
+
+/// K minimum values sketch, estimating the number of distinct values from
+/// the [`SKETCH_SIZE`] smallest of their hashes.
+#[derive(Default)]
+struct DistinctSketch {
+    hashes: BTreeSet<u64>,
+}
+
+impl DistinctSketch {
+    fn insert(&mut self, value: &Scalar) {
+        let hash = hash_scalar(value);
+
+        if self.hashes.len() < SKETCH_SIZE {
+            self.hashes.insert(hash);
+        } else if self.hashes.last().is_some_and(|last| hash < *last) && self.hashes.insert(hash) {
+            self.hashes.pop_last();
+        }
+    }
+
+    fn estimate(&self) -> usize {
+        match self.hashes.last() {
+            Some(last) if self.hashes.len() == SKETCH_SIZE => {
+                // The kth smallest of n uniform hashes lies near k / n
+                let fraction = *last as f64 / u64::MAX as f64;
+                ((SKETCH_SIZE - 1) as f64 / fraction).round() as usize
+            }
+            _ => self.hashes.len(),
+        }
+    }
+}
+
+/// Hashes `value` such that equal values of the same type hash equally.
+pub(crate) fn hash_scalar(value: &Scalar) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    std::mem::discriminant(value).hash(&mut hasher);
+
+    // Zeroes of either sign are equal
+    match value {
+        Scalar::U32(value) => value.hash(&mut hasher),
+        Scalar::I32(value) => value.hash(&mut hasher),
+        Scalar::USize(value) => value.hash(&mut hasher),
+        Scalar::ISize(value) => value.hash(&mut hasher),
+        Scalar::F32(value) => (value + 0.0).to_bits().hash(&mut hasher),
+        Scalar::F64(value) => (value + 0.0).to_bits().hash(&mut hasher),
+        Scalar::Boolean(value) => value.hash(&mut hasher),
+        Scalar::Text(value) => value.hash(&mut hasher),
+        Scalar::Null => {}
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Array, ArrayBoolean, ArrayF64, ArrayI32, ArrayText, ArrayU32, ColumnArray, Union};
+
+    #[test]
+    fn test_statistics() {
+        let one = ArrayI32::from([Some(3), None, Some(-2), Some(3), Some(7)]);
+
+        assert_eq!(
+            ArrayStatistics {
+                min: Scalar::I32(-2),
+                max: Scalar::I32(7),
+                null_count: 1,
+                distinct_count_estimate: 3,
+                sum: Scalar::I32(11),
+            },
+            one.statistics()
+        );
+
+        let one = ArrayF64::from([Some(f64::NAN), Some(-0.0), Some(0.0), None, Some(1.5)]);
+        let stats = one.statistics();
+        assert_eq!((Scalar::F64(0.0), Scalar::F64(1.5)), (stats.min, stats.max));
+        assert_eq!(3, stats.distinct_count_estimate);
+        assert!(stats.sum.to_f64().unwrap().is_nan());
+
+        let one = ArrayText::from([Some("b".into()), Some("B".into()), None, Some("ab".into())]);
+        let stats = one.statistics();
+        assert_eq!(Scalar::Text("B".into()), stats.min);
+        assert_eq!(Scalar::Text("b".into()), stats.max);
+        assert_eq!(Scalar::Null, stats.sum);
+
+        let stats = ArrayBoolean::from([true, false, true]).statistics();
+        assert_eq!(
+            (Scalar::Boolean(false), Scalar::Boolean(true)),
+            (stats.min, stats.max)
+        );
+        assert_eq!(2, stats.distinct_count_estimate);
+
+        let stats = ArrayU32::from([u32::MAX, u32::MAX]).statistics();
+        assert_eq!(Scalar::F64(2.0 * u32::MAX as f64), stats.sum);
+
+        let one = Union::from([Scalar::I32(2), Scalar::Null, Scalar::F64(0.5)]);
+        let stats = ColumnArray::from(one).statistics();
+        assert_eq!((Scalar::F64(0.5), Scalar::I32(2)), (stats.min, stats.max));
+        assert_eq!(1, stats.null_count);
+        assert_eq!(Scalar::F64(2.5), stats.sum);
+    }
+
+    #[test]
+    fn test_statistics_all_null() {
+        let expected = ArrayStatistics {
+            min: Scalar::Null,
+            max: Scalar::Null,
+            null_count: 4,
+            distinct_count_estimate: 0,
+            sum: Scalar::Null,
+        };
+
+        assert_eq!(expected, ArrayF64::nulls(4).statistics());
+        assert_eq!(expected, ArrayText::from_vec(vec![None; 4]).statistics());
+        assert_eq!(
+            0,
+            ArrayI32::from_vec(vec![])
+                .statistics()
+                .distinct_count_estimate
+        );
+    }
+
+    #[test]
+    fn test_distinct_estimate() {
+        for distinct in [1_000, 20_000] {
+            let one = ArrayI32::new((0..distinct * 3).map(|idx| Some(idx % distinct)));
+            let estimate = one.statistics().distinct_count_estimate as f64;
+
+            // Three standard errors
+            assert!((estimate / distinct as f64 - 1.0).abs() < 0.2, "{estimate}");
+        }
+
+        let one = ArrayI32::new((0..1_000).map(|idx| Some(idx % 200)));
+        assert_eq!(200, one.statistics().distinct_count_estimate);
+    }
+}
@@ -0,0 +1,664 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::utils::Array;
+use crate::{ArrayBoolean, ArrayText, ArrayUSize};
+
+/// Element of a compiled [`like`] pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Literal(char),
+    /// `_`, matching any single character.
+    One,
+    /// `%`, matching any run of characters, including none.
+    Many,
+}
+
+/// Splits `pattern` into tokens, with `\` making the character after it
+/// literal. A trailing `\` is itself literal.
+fn compile_like(pattern: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+
+    while let Some(char) = chars.next() {
+        let token = match char {
+            '\\' => Token::Literal(chars.next().unwrap_or('\\')),
+            '_' => Token::One,
+            '%' => Token::Many,
+            char => Token::Literal(char),
+        };
+
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Returns true if `tokens` match the whole of `text`.
+///
+/// On a mismatch the most recent `%` is made to swallow one more character
+/// and matching resumes after it, which never needs to revisit earlier
+/// `%`s.
+fn matches_like(tokens: &[Token], text: &str) -> bool {
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut token, mut pos) = (0, 0);
+    let mut retry = None;
+
+    while pos < text.len() {
+        match tokens.get(token) {
+            Some(Token::One) => {
+                token += 1;
+                pos += 1;
+            }
+            Some(Token::Literal(char)) if *char == text[pos] => {
+                token += 1;
+                pos += 1;
+            }
+            Some(Token::Many) => {
+                retry = Some((token, pos));
+                token += 1;
+            }
+            _ => match retry {
+                Some((many, from)) => {
+                    retry = Some((many, from + 1));
+                    token = many + 1;
+                    pos = from + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    tokens[token..].iter().all(|token| *token == Token::Many)
+}
+
+/// Returns whether each element of `array` matches the SQL `LIKE` pattern
+/// `pattern` in full.
+///
+/// `%` matches any run of characters and `_` any single character, unless
+/// escaped with `\`. Nulls produce a null.
+pub fn like(array: &ArrayText, pattern: &str) -> ArrayBoolean {
+    let tokens = compile_like(pattern);
+
+    ArrayBoolean::new(
+        array
+            .iter()
+            .map(|value| value.map(|value| matches_like(&tokens, value))),
+    )
+}
+
+/// Returns up to `length` characters of each element of `array` from the
+/// character at `start`, or every character from it if `length` is None.
+///
+/// A negative `start` counts back from the end, clamping at the first
+/// character, while one past the end gives an empty string. Indices are of
+/// characters rather than bytes. Nulls produce a null.
+pub fn substring(array: &ArrayText, start: i64, length: Option<u64>) -> ArrayText {
+    // Lengths beyond `usize` take every character anyway
+    let length = length.map_or(usize::MAX, |length| {
+        usize::try_from(length).unwrap_or(usize::MAX)
+    });
+
+    let values = array
+        .iter()
+        .map(|value| {
+            let value = value?;
+            let start = match start < 0 {
+                true => (value.chars().count() as i64 + start).max(0),
+                false => start,
+            };
+            let skip = usize::try_from(start).unwrap_or(usize::MAX);
+
+            Some(value.chars().skip(skip).take(length).collect::<String>())
+        })
+        .collect::<Vec<_>>();
+
+    ArrayText::new(values)
+}
+
+/// Returns each element of `array` preceded by as many `fill`s as it takes
+/// to be `width` characters long. Longer elements are left as they are and
+/// nulls produce a null.
+pub fn pad_start(array: &ArrayText, width: usize, fill: char) -> ArrayText {
+    pad(array, width, fill, true)
+}
+
+/// Returns each element of `array` followed by as many `fill`s as it takes
+/// to be `width` characters long. See [`pad_start`].
+pub fn pad_end(array: &ArrayText, width: usize, fill: char) -> ArrayText {
+    pad(array, width, fill, false)
+}
+
+fn pad(array: &ArrayText, width: usize, fill: char, start: bool) -> ArrayText {
+    let values = array
+        .iter()
+        .map(|value| {
+            let value = value?;
+            let padding = std::iter::repeat_n(fill, width.saturating_sub(value.chars().count()));
+
+            Some(match start {
+                true => padding.chain(value.chars()).collect::<String>(),
+                false => value.chars().chain(padding).collect::<String>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    ArrayText::new(values)
+}
+
+/// Returns each element of `array` with the first `max` occurrences of
+/// `from` replaced by `to`, or every occurrence if `max` is None.
+///
+/// Occurrences are found from the start and never overlap, so replacing
+/// `"aa"` in `"aaa"` leaves the last `a`. Nulls produce a null. Errors if
+/// `from` is empty.
+pub fn replace(
+    array: &ArrayText,
+    from: &str,
+    to: &str,
+    max: Option<usize>,
+) -> Result<ArrayText, EmptyPatternError> {
+    if from.is_empty() {
+        return Err(EmptyPatternError);
+    }
+
+    let values = array
+        .iter()
+        .map(|value| {
+            value.map(|value| match max {
+                Some(max) => value.replacen(from, to, max),
+                None => value.replace(from, to),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(ArrayText::new(values))
+}
+
+/// Error raised by [`replace`] when given an empty pattern, which would
+/// match between every character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyPatternError;
+
+impl Display for EmptyPatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tried to replace an empty pattern")
+    }
+}
+
+impl Error for EmptyPatternError {}
+
+/// How [`sort_text_to_indices`] orders text. The default orders by UTF-8
+/// bytes with nulls last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextSortOptions {
+    /// Compare the lowercase forms of characters, so `"apple"` comes
+    /// before `"Zebra"`.
+    pub case_insensitive: bool,
+    /// Compare runs of ASCII digits by their numeric value, so `"file2"`
+    /// comes before `"file10"`.
+    pub natural: bool,
+    pub nulls_first: bool,
+}
+
+/// Part of the key a text value is sorted by.
+#[derive(Debug)]
+enum SortChunk {
+    /// A run of digits without its leading zeros.
+    Number {
+        digits: String,
+        zeros: usize,
+    },
+    Text(String),
+}
+
+impl SortChunk {
+    fn number(run: &str) -> Self {
+        let digits = run.trim_start_matches('0');
+
+        Self::Number {
+            digits: digits.to_owned(),
+            zeros: run.len() - digits.len(),
+        }
+    }
+
+    /// Orders numbers by value, then by their number of leading zeros, and
+    /// before any text.
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (
+                Self::Number { digits, zeros },
+                Self::Number {
+                    digits: other,
+                    zeros: other_zeros,
+                },
+            ) => (digits.len(), digits, zeros).cmp(&(other.len(), other, other_zeros)),
+            (Self::Number { .. }, Self::Text(_)) => Ordering::Less,
+            (Self::Text(_), Self::Number { .. }) => Ordering::Greater,
+            (Self::Text(lhs), Self::Text(rhs)) => lhs.cmp(rhs),
+        }
+    }
+}
+
+/// Compares chunk by chunk, with a prefix ordered first.
+fn compare_chunks(lhs: &[SortChunk], rhs: &[SortChunk]) -> Ordering {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(lhs, rhs)| lhs.compare(rhs))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| lhs.len().cmp(&rhs.len()))
+}
+
+/// Splits `value` into the chunks it is sorted by under `options`.
+fn sort_chunks(value: &str, options: TextSortOptions) -> Vec<SortChunk> {
+    let value = match options.case_insensitive {
+        true => value.chars().flat_map(char::to_lowercase).collect(),
+        false => value.to_owned(),
+    };
+
+    if !options.natural {
+        return vec![SortChunk::Text(value)];
+    }
+
+    let mut chunks = Vec::new();
+    let mut rest = value.as_str();
+
+    while let Some(first) = rest.chars().next() {
+        let digit = first.is_ascii_digit();
+        let end = rest
+            .find(|char: char| char.is_ascii_digit() != digit)
+            .unwrap_or(rest.len());
+
+        chunks.push(match digit {
+            true => SortChunk::number(&rest[..end]),
+            false => SortChunk::Text(rest[..end].to_owned()),
+        });
+        rest = &rest[end..];
+    }
+
+    chunks
+}
+
+/// Returns the indices that order `array` ascending under `options`.
+///
+/// The sort is stable, so values equal under `options`, such as `"a"` and
+/// `"A"` when case insensitive, keep their order.
+pub fn sort_text_to_indices(array: &ArrayText, options: TextSortOptions) -> ArrayUSize {
+    let keys = array
+        .iter()
+        .map(|value| value.map(|value| sort_chunks(value, options)))
+        .collect::<Vec<_>>();
+
+    let nulls = match options.nulls_first {
+        true => Ordering::Less,
+        false => Ordering::Greater,
+    };
+
+    let mut indices = (0..array.len()).collect::<Vec<_>>();
+    indices.sort_by(|lhs, rhs| match (&keys[*lhs], &keys[*rhs]) {
+        (Some(lhs), Some(rhs)) => compare_chunks(lhs, rhs),
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => nulls,
+        (Some(_), None) => nulls.reverse(),
+    });
+
+    ArrayUSize::new(indices.into_iter().map(Some))
+}
+
+/// Returns each element of `array` with the first `max` matches of the
+/// regular expression `pattern` replaced by `to`, or every match if `max`
+/// is None.
+///
+/// `to` may refer to capture groups as `$1` or `$name`. Nulls produce a
+/// null. Errors if `pattern` is not a valid regular expression.
+#[cfg(feature = "regex")]
+pub fn replace_regex(
+    array: &ArrayText,
+    pattern: &str,
+    to: &str,
+    max: Option<usize>,
+) -> Result<ArrayText, regex::Error> {
+    let regex = regex::Regex::new(pattern)?;
+
+    if max == Some(0) {
+        return Ok(array.clone());
+    }
+
+    // The regex crate takes a limit of 0 to mean every match
+    let limit = max.unwrap_or(0);
+
+    let values = array
+        .iter()
+        .map(|value| value.map(|value| regex.replacen(value, limit, to).into_owned()))
+        .collect::<Vec<_>>();
+
+    Ok(ArrayText::new(values))
+}
+
+/// Returns whether each element of `array` contains a match of the regular
+/// expression `pattern`. Nulls produce a null.
+///
+/// Errors if `pattern` is not a valid regular expression.
+#[cfg(feature = "regex")]
+pub fn regex_match(array: &ArrayText, pattern: &str) -> Result<ArrayBoolean, regex::Error> {
+    let regex = regex::Regex::new(pattern)?;
+
+    Ok(ArrayBoolean::new(
+        array
+            .iter()
+            .map(|value| value.map(|value| regex.is_match(value))),
+    ))
+}
+
+/// Returns the text captured by `group` in the first match of the regular
+/// expression `pattern` in each element of `array`, with group 0 being the
+/// whole match.
+///
+/// Nulls, elements without a match and matches the group takes no part in
+/// produce a null. Errors if `pattern` is not a valid regular expression.
+#[cfg(feature = "regex")]
+pub fn regex_extract(
+    array: &ArrayText,
+    pattern: &str,
+    group: usize,
+) -> Result<ArrayText, regex::Error> {
+    let regex = regex::Regex::new(pattern)?;
+
+    let values = array
+        .iter()
+        .map(|value| {
+            let captures = regex.captures(value?)?;
+            captures.get(group).map(|group| group.as_str().to_owned())
+        })
+        .collect::<Vec<_>>();
+
+    Ok(ArrayText::new(values))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(values: &[Option<&str>]) -> ArrayText {
+        ArrayText::new(values.iter().map(|value| value.map(str::to_owned)))
+    }
+
+    #[test]
+    fn test_like() {
+        let one = text(&[
+            Some("apple"),
+            Some("pineapple"),
+            None,
+            Some("apples"),
+            Some(""),
+        ]);
+
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(false), None, Some(true), Some(false)]),
+            like(&one, "app%")
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(true), None, Some(false), Some(false)]),
+            like(&one, "%apple")
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(true), None, Some(true), Some(false)]),
+            like(&one, "%p_l%")
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(false), None, Some(false), Some(false)]),
+            like(&one, "_____")
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(true), Some(true), None, Some(true), Some(true)]),
+            like(&one, "%%")
+        );
+        assert_eq!(
+            ArrayBoolean::from([Some(false), Some(false), None, Some(false), Some(true)]),
+            like(&one, "")
+        );
+
+        // Backtracking past an earlier partial match
+        assert_eq!(
+            ArrayBoolean::from([true]),
+            like(&text(&[Some("aab")]), "%ab")
+        );
+        assert_eq!(ArrayBoolean::from([true]), like(&text(&[Some("né")]), "n_"));
+    }
+
+    #[test]
+    fn test_like_escape() {
+        let one = text(&[Some("50%"), Some("50 percent"), Some("a_b"), Some("axb")]);
+
+        assert_eq!(
+            ArrayBoolean::from([true, false, false, false]),
+            like(&one, "%\\%")
+        );
+        assert_eq!(
+            ArrayBoolean::from([false, false, true, false]),
+            like(&one, "a\\_b")
+        );
+        assert_eq!(
+            ArrayBoolean::from([true]),
+            like(&text(&[Some("a\\")]), "a\\")
+        );
+    }
+
+    #[test]
+    fn test_substring() {
+        let one = text(&[Some("héllo"), Some("🦀🦀ab"), None, Some("")]);
+
+        assert_eq!(
+            text(&[Some("él"), Some("🦀a"), None, Some("")]),
+            substring(&one, 1, Some(2))
+        );
+        assert_eq!(
+            text(&[Some("lo"), Some("ab"), None, Some("")]),
+            substring(&one, -2, None)
+        );
+        assert_eq!(
+            text(&[Some("hé"), Some("🦀🦀"), None, Some("")]),
+            substring(&one, -10, Some(2))
+        );
+        assert_eq!(
+            text(&[Some(""), Some(""), None, Some("")]),
+            substring(&one, 5, None)
+        );
+        assert_eq!(one, substring(&one, 0, None));
+    }
+
+    #[test]
+    fn test_pad() {
+        let one = text(&[Some("7"), Some("1234"), None, Some("é🦀")]);
+
+        assert_eq!(
+            text(&[Some("0007"), Some("1234"), None, Some("00é🦀")]),
+            pad_start(&one, 4, '0')
+        );
+        assert_eq!(
+            text(&[Some("7··"), Some("1234"), None, Some("é🦀·")]),
+            pad_end(&one, 3, '·')
+        );
+        assert_eq!(one, pad_start(&one, 0, ' '));
+    }
+
+    #[test]
+    fn test_replace() {
+        let one = text(&[Some("aaa"), Some("banana"), None, Some("")]);
+
+        assert_eq!(
+            Ok(text(&[Some("ba"), Some("banana"), None, Some("")])),
+            replace(&one, "aa", "b", None)
+        );
+        assert_eq!(
+            Ok(text(&[Some(""), Some("bnn"), None, Some("")])),
+            replace(&one, "a", "", None)
+        );
+        assert_eq!(
+            Ok(text(&[Some("[a]aa"), Some("b[a]nana"), None, Some("")])),
+            replace(&one, "a", "[a]", Some(1))
+        );
+        assert_eq!(Ok(one.clone()), replace(&one, "a", "b", Some(0)));
+        assert_eq!(Err(EmptyPatternError), replace(&one, "", "x", None));
+    }
+
+    #[test]
+    fn test_sort_text_to_indices() {
+        let one = ArrayText::from([
+            "file10.txt",
+            "File2.txt",
+            "file2.txt",
+            "file1.txt",
+            "file02.txt",
+        ]);
+        let sorted = |options| {
+            let indices = sort_text_to_indices(&one, options);
+            one.take(&indices).unwrap()
+        };
+
+        assert_eq!(
+            ArrayText::from([
+                "File2.txt",
+                "file02.txt",
+                "file1.txt",
+                "file10.txt",
+                "file2.txt"
+            ]),
+            sorted(TextSortOptions::default())
+        );
+        assert_eq!(
+            ArrayText::from([
+                "File2.txt",
+                "file1.txt",
+                "file2.txt",
+                "file02.txt",
+                "file10.txt"
+            ]),
+            sorted(TextSortOptions {
+                natural: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            ArrayText::from([
+                "file1.txt",
+                "File2.txt",
+                "file2.txt",
+                "file02.txt",
+                "file10.txt"
+            ]),
+            sorted(TextSortOptions {
+                natural: true,
+                case_insensitive: true,
+                ..Default::default()
+            })
+        );
+
+        let two = text(&[
+            Some("Zebra"),
+            None,
+            Some("apple"),
+            Some("Äpfel"),
+            Some("banana"),
+        ]);
+        let sorted = |options| {
+            let indices = sort_text_to_indices(&two, options);
+            two.take(&indices).unwrap()
+        };
+
+        assert_eq!(
+            text(&[
+                None,
+                Some("apple"),
+                Some("banana"),
+                Some("Zebra"),
+                Some("Äpfel")
+            ]),
+            sorted(TextSortOptions {
+                case_insensitive: true,
+                nulls_first: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            text(&[
+                Some("Zebra"),
+                Some("apple"),
+                Some("banana"),
+                Some("Äpfel"),
+                None
+            ]),
+            sorted(TextSortOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_natural_versions() {
+        let one = ArrayText::from(["v1.10.0", "v1.2.10", "v1.2.9", "v1.2", "v10.0", "v9.9.9"]);
+        let options = TextSortOptions {
+            natural: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ArrayText::from(["v1.2", "v1.2.9", "v1.2.10", "v1.10.0", "v9.9.9", "v10.0"]),
+            one.take(&sort_text_to_indices(&one, options)).unwrap()
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_replace_regex() {
+        let one = text(&[Some("id-42, id-7"), None, Some("none")]);
+
+        assert_eq!(
+            Ok(text(&[Some("#42, #7"), None, Some("none")])),
+            replace_regex(&one, r"id-(\d+)", "#$1", None)
+        );
+        assert_eq!(
+            Ok(text(&[Some("id-0, id-7"), None, Some("none")])),
+            replace_regex(&one, r"\d+", "0", Some(1))
+        );
+        assert_eq!(Ok(one.clone()), replace_regex(&one, "id", "x", Some(0)));
+        assert!(replace_regex(&one, "[", "", None).is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex() {
+        let one = text(&[Some("id-42"), Some("id-"), None, Some("x id-7 y")]);
+
+        assert_eq!(
+            Ok(ArrayBoolean::from([
+                Some(true),
+                Some(false),
+                None,
+                Some(true)
+            ])),
+            regex_match(&one, r"id-\d+")
+        );
+        assert_eq!(
+            Ok(ArrayBoolean::from([
+                Some(true),
+                Some(true),
+                None,
+                Some(false)
+            ])),
+            regex_match(&one, "^id")
+        );
+        assert_eq!(
+            Ok(text(&[Some("42"), None, None, Some("7")])),
+            regex_extract(&one, r"id-(\d+)", 1)
+        );
+        assert_eq!(
+            Ok(text(&[Some("id-42"), Some("id-"), None, Some("id-7")])),
+            regex_extract(&one, r"id-(\d+)?", 0)
+        );
+        assert_eq!(
+            Ok(text(&[Some("42"), None, None, Some("7")])),
+            regex_extract(&one, r"id-(\d+)?", 1)
+        );
+        assert!(regex_match(&one, "(").is_err());
+    }
+}
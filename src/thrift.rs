@@ -0,0 +1,310 @@
+//! Minimal encoding of the Thrift compact protocol, covering just what the
+//! Parquet metadata needs.
+
+/// Compact protocol type ids.
+const TYPE_TRUE: u8 = 1;
+const TYPE_FALSE: u8 = 2;
+const TYPE_I8: u8 = 3;
+const TYPE_I16: u8 = 4;
+const TYPE_I32: u8 = 5;
+const TYPE_I64: u8 = 6;
+const TYPE_DOUBLE: u8 = 7;
+const TYPE_BINARY: u8 = 8;
+const TYPE_LIST: u8 = 9;
+const TYPE_SET: u8 = 10;
+const TYPE_STRUCT: u8 = 12;
+
+/// Value of a single struct field.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Bool(bool),
+    I8(i8),
+    I32(i32),
+    I64(i64),
+    Binary(Vec<u8>),
+    Struct(Struct),
+    /// List whose elements all have the same type.
+    List(Vec<Value>),
+}
+
+impl Value {
+    pub(crate) fn string(value: impl Into<String>) -> Self {
+        Self::Binary(value.into().into_bytes())
+    }
+
+    fn type_id(&self) -> u8 {
+        match self {
+            Self::Bool(true) => TYPE_TRUE,
+            Self::Bool(false) => TYPE_FALSE,
+            Self::I8(_) => TYPE_I8,
+            Self::I32(_) => TYPE_I32,
+            Self::I64(_) => TYPE_I64,
+            Self::Binary(_) => TYPE_BINARY,
+            Self::Struct(_) => TYPE_STRUCT,
+            Self::List(_) => TYPE_LIST,
+        }
+    }
+}
+
+/// Struct whose fields are identified by their id in the IDL.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Struct {
+    fields: Vec<(i16, Value)>,
+}
+
+impl Struct {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with(mut self, id: i16, value: Value) -> Self {
+        self.fields.push((id, value));
+        self
+    }
+
+    /// Returns the value of the field with `id`, if present.
+    pub(crate) fn get(&self, id: i16) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == id)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Encodes `root` as a compact protocol struct.
+pub(crate) fn encode(root: &Struct) -> Vec<u8> {
+    let mut buf = vec![];
+    write_struct(&mut buf, root);
+    buf
+}
+
+fn write_struct(buf: &mut Vec<u8>, value: &Struct) {
+    let mut fields = value.fields.iter().collect::<Vec<_>>();
+    fields.sort_by_key(|(id, _)| *id);
+
+    let mut last = 0;
+
+    for (id, value) in fields {
+        match id - last {
+            delta @ 1..=15 => buf.push(((delta as u8) << 4) | value.type_id()),
+            _ => {
+                buf.push(value.type_id());
+                write_varint(buf, zigzag(*id as i64));
+            }
+        }
+
+        last = *id;
+
+        // Booleans are held by the field header
+        if !matches!(value, Value::Bool(_)) {
+            write_value(buf, value);
+        }
+    }
+
+    buf.push(0);
+}
+
+fn write_value(buf: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Bool(value) => buf.push(if *value { TYPE_TRUE } else { TYPE_FALSE }),
+        Value::I8(value) => buf.push(*value as u8),
+        Value::I32(value) => write_varint(buf, zigzag(*value as i64)),
+        Value::I64(value) => write_varint(buf, zigzag(*value)),
+        Value::Binary(bytes) => {
+            write_varint(buf, bytes.len() as u64);
+            buf.extend_from_slice(bytes);
+        }
+        Value::Struct(value) => write_struct(buf, value),
+        Value::List(values) => {
+            let kind = values.first().map_or(TYPE_STRUCT, |value| match value {
+                Value::Bool(_) => TYPE_TRUE,
+                value => value.type_id(),
+            });
+
+            match values.len() {
+                len @ 0..=14 => buf.push(((len as u8) << 4) | kind),
+                len => {
+                    buf.push(0xF0 | kind);
+                    write_varint(buf, len as u64);
+                }
+            }
+
+            values.iter().for_each(|value| write_value(buf, value));
+        }
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Writes `value` as a ULEB128 varint.
+pub(crate) fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    buf.push(value as u8);
+}
+
+/// Decodes the compact protocol struct at the start of `buf`, returning it
+/// along with the number of bytes it took up.
+///
+/// Only used to check encoded metadata, so doubles are skipped and maps are
+/// not supported.
+#[cfg(test)]
+pub(crate) fn decode(buf: &[u8]) -> Option<(Struct, usize)> {
+    let mut pos = 0;
+    let root = read_struct(buf, &mut pos)?;
+    Some((root, pos))
+}
+
+#[cfg(test)]
+fn read_struct(buf: &[u8], pos: &mut usize) -> Option<Struct> {
+    let mut value = Struct::new();
+    let mut last = 0;
+
+    loop {
+        let header = *buf.get(*pos)?;
+        *pos += 1;
+
+        if header == 0 {
+            return Some(value);
+        }
+
+        let id = match header >> 4 {
+            0 => unzigzag(read_varint(buf, pos)?) as i16,
+            delta => last + delta as i16,
+        };
+        last = id;
+
+        let field = match header & 0x0F {
+            TYPE_TRUE => Value::Bool(true),
+            TYPE_FALSE => Value::Bool(false),
+            TYPE_DOUBLE => {
+                *pos += 8;
+                continue;
+            }
+            kind => read_value(buf, pos, kind)?,
+        };
+
+        value.fields.push((id, field));
+    }
+}
+
+#[cfg(test)]
+fn read_value(buf: &[u8], pos: &mut usize, kind: u8) -> Option<Value> {
+    let value = match kind {
+        TYPE_TRUE | TYPE_FALSE => {
+            let byte = *buf.get(*pos)?;
+            *pos += 1;
+            Value::Bool(byte == TYPE_TRUE)
+        }
+        TYPE_I8 => {
+            let byte = *buf.get(*pos)?;
+            *pos += 1;
+            Value::I8(byte as i8)
+        }
+        TYPE_I16 | TYPE_I32 => Value::I32(unzigzag(read_varint(buf, pos)?) as i32),
+        TYPE_I64 => Value::I64(unzigzag(read_varint(buf, pos)?)),
+        TYPE_BINARY => {
+            let len = read_varint(buf, pos)? as usize;
+            let bytes = buf.get(*pos..*pos + len)?.to_vec();
+            *pos += len;
+            Value::Binary(bytes)
+        }
+        TYPE_STRUCT => Value::Struct(read_struct(buf, pos)?),
+        TYPE_LIST | TYPE_SET => {
+            let header = *buf.get(*pos)?;
+            *pos += 1;
+
+            let len = match header >> 4 {
+                0x0F => read_varint(buf, pos)? as usize,
+                len => len as usize,
+            };
+
+            let values = (0..len)
+                .map(|_| read_value(buf, pos, header & 0x0F))
+                .collect::<Option<Vec<_>>>()?;
+
+            Value::List(values)
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+#[cfg(test)]
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+pub(crate) fn read_varint(buf: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7F) as u64) << shift;
+
+        if byte < 0x80 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let one = Struct::new()
+            .with(1, Value::I32(-1))
+            .with(3, Value::Bool(true))
+            .with(40, Value::string("ab"));
+
+        // Field 40 is too far from 3 for a delta
+        assert_eq!(
+            vec![0x15, 0x01, 0x21, 0x08, 0x50, 0x02, b'a', b'b', 0x00],
+            encode(&one)
+        );
+
+        let two = Struct::new().with(
+            2,
+            Value::List((0..20).map(|idx| Value::I64(idx * 1_000)).collect()),
+        );
+        let bytes = encode(&two);
+        assert_eq!([0x29, 0xF6, 20], bytes[..3]);
+        assert_eq!(Some((two, bytes.len())), decode(&bytes));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let one = Struct::new()
+            .with(1, Value::I8(-3))
+            .with(2, Value::Bool(false))
+            .with(4, Value::I64(i64::MIN))
+            .with(
+                5,
+                Value::List(vec![
+                    Value::Struct(Struct::new().with(1, Value::string("x"))),
+                    Value::Struct(Struct::new()),
+                ]),
+            )
+            .with(6, Value::List(vec![Value::Bool(true), Value::Bool(false)]))
+            .with(300, Value::Struct(Struct::new().with(-2, Value::I32(7))));
+
+        let mut bytes = encode(&one);
+        let len = bytes.len();
+        bytes.extend_from_slice(b"trailing");
+
+        assert_eq!(Some((one, len)), decode(&bytes));
+        assert_eq!(None, decode(&bytes[..len - 1]));
+    }
+}
@@ -1,18 +1,25 @@
 use std::alloc::{self, Layout};
 use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 use std::ptr::{self, NonNull};
 use std::str::FromStr;
 
-use crate::utils::{Array, DataType, IntoIter, Iter};
+use crate::column::ColumnArray;
+use crate::utils::{Array, BitmapBuffer, DataType, IntoIter, Iter, TotalOrd, ALIGNMENT};
 use crate::{
     ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayText, ArrayU32, ArrayUSize,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 /// Owned value contained in a [`Union`].
+///
+/// Equality is structural, ordering compares numbers by value across
+/// variants, see [`Scalar`].
 pub enum UnionType {
     U32(u32),
     I32(i32),
@@ -29,6 +36,9 @@ impl UnionType {
     /// Attempts to parse `input` into a [`UnionType`].
     ///
     /// Both an empty string and the string `"null"` are parsed as [`UnionType::Null`].
+    /// Integers prefixed by `0x` or `0X` are parsed as hexadecimal and are
+    /// always stored as unsigned, in a `u32` if they fit and a `usize`
+    /// otherwise.
     pub fn parse(input: impl Into<String>) -> Self {
         let input: String = input.into();
 
@@ -36,6 +46,10 @@ impl UnionType {
             return Self::Null;
         }
 
+        if let Some(parsed) = parse_hex(&input) {
+            return u32::try_from(parsed).map_or(Self::USize(parsed), Self::U32);
+        }
+
         if let Ok(parsed_u32) = input.parse::<u32>() {
             return Self::U32(parsed_u32);
         }
@@ -86,6 +100,10 @@ impl UnionType {
     }
 }
 
+/// Owned value of any supported type, such as a single cell of a
+/// [`ColumnArray`](crate::ColumnArray).
+pub type Scalar = UnionType;
+
 #[derive(Debug, Clone, PartialEq)]
 /// Shared reference to values in a [`Union`].
 pub enum UnionRef<'a> {
@@ -101,6 +119,23 @@ pub enum UnionRef<'a> {
 }
 
 impl<'a> UnionRef<'a> {
+    /// Returns the type of the value, or None if it is null.
+    pub fn data_type(&self) -> Option<DataType> {
+        let data_type = match self {
+            Self::U32(_) => DataType::UInt32,
+            Self::I32(_) => DataType::Int32,
+            Self::USize(_) => DataType::USize,
+            Self::ISize(_) => DataType::ISize,
+            Self::F32(_) => DataType::F32,
+            Self::F64(_) => DataType::F64,
+            Self::Boolean(_) => DataType::Boolean,
+            Self::Text(_) => DataType::Text,
+            Self::Null => return None,
+        };
+
+        Some(data_type)
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn to_owned(self) -> UnionType {
         match self {
@@ -117,6 +152,44 @@ impl<'a> UnionRef<'a> {
     }
 }
 
+/// Orders values by their type, in type id order, then by value.
+impl TotalOrd for UnionRef<'_> {
+    fn total_order(&self, other: &Self) -> Ordering {
+        fn type_id(value: &UnionRef<'_>) -> u8 {
+            match value {
+                UnionRef::U32(_) => 0,
+                UnionRef::I32(_) => 1,
+                UnionRef::USize(_) => 2,
+                UnionRef::ISize(_) => 3,
+                UnionRef::F32(_) => 4,
+                UnionRef::F64(_) => 5,
+                UnionRef::Boolean(_) => 6,
+                UnionRef::Text(_) => 7,
+                UnionRef::Null => 8,
+            }
+        }
+
+        match (self, other) {
+            (Self::U32(lhs), Self::U32(rhs)) => lhs.cmp(rhs),
+            (Self::I32(lhs), Self::I32(rhs)) => lhs.cmp(rhs),
+            (Self::USize(lhs), Self::USize(rhs)) => lhs.cmp(rhs),
+            (Self::ISize(lhs), Self::ISize(rhs)) => lhs.cmp(rhs),
+            (Self::F32(lhs), Self::F32(rhs)) => lhs.total_cmp(rhs),
+            (Self::F64(lhs), Self::F64(rhs)) => lhs.total_cmp(rhs),
+            (Self::Boolean(lhs), Self::Boolean(rhs)) => lhs.cmp(rhs),
+            (Self::Text(lhs), Self::Text(rhs)) => lhs.cmp(rhs),
+            (lhs, rhs) => type_id(lhs).cmp(&type_id(rhs)),
+        }
+    }
+}
+
+/// See the [`TotalOrd`] impl of [`UnionRef`].
+impl TotalOrd for UnionType {
+    fn total_order(&self, other: &Self) -> Ordering {
+        self.borrow().total_order(&other.borrow())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct UnionBuilder {
     tracker: Vec<(u8, usize)>,
@@ -136,6 +209,11 @@ pub struct UnionBuilder {
     boolean: Vec<bool>,
     /// 7
     text: Vec<String>,
+    /// Whether [`UnionBuilder::try_parse_push`] holds values to the type of
+    /// the first non-null value.
+    strict: bool,
+    /// Values [`UnionBuilder::parse_push`] fell back to storing as text.
+    parse_errors: Vec<ParseError>,
 }
 
 impl UnionBuilder {
@@ -143,6 +221,36 @@ impl UnionBuilder {
         Self::default()
     }
 
+    /// Creates a builder with room for `capacity` values before
+    /// reallocating.
+    ///
+    /// Each type is expected to make up a quarter of the values, so a
+    /// builder filled with values of a single type will still grow.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let per_type = capacity / 4;
+
+        Self {
+            tracker: Vec::with_capacity(capacity),
+            uint32: Vec::with_capacity(per_type),
+            int32: Vec::with_capacity(per_type),
+            uintsize: Vec::with_capacity(per_type),
+            intsize: Vec::with_capacity(per_type),
+            float32: Vec::with_capacity(per_type),
+            float64: Vec::with_capacity(per_type),
+            boolean: Vec::with_capacity(per_type),
+            text: Vec::with_capacity(per_type),
+            strict: false,
+            parse_errors: vec![],
+        }
+    }
+
+    /// Makes [`UnionBuilder::try_parse_push`] reject values which do not
+    /// parse as the type of the first non-null value pushed.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
     pub fn push(&mut self, kind: UnionType) {
         match kind {
             UnionType::Null => self.push_none(),
@@ -188,50 +296,187 @@ impl UnionBuilder {
     /// self.
     ///
     /// Both an empty string and the string `"null"` are parsed as None.
+    /// Integers prefixed by `0x` or `0X` are parsed as hexadecimal and are
+    /// always stored as unsigned, in a `u32` if they fit and a `usize`
+    /// otherwise.
+    ///
+    /// Input shaped like a number as a whole but failing to parse as one,
+    /// such as `"1.2.3"` or `"0xg"`, is stored as text and recorded in
+    /// [`UnionBuilder::parse_errors`]. Text such as `"3rd"` is not. See [`UnionBuilder::parse_push_checked`]
+    /// to learn how each value was stored.
     pub fn parse_push(&mut self, input: impl Into<String>) {
-        let input: String = input.into();
+        self.parse_push_checked(input);
+    }
 
-        if input.is_empty() || input == *"null" {
-            self.push_none();
-            return;
-        }
+    /// Parses `input` as in [`UnionBuilder::parse_push`], returning how it
+    /// was stored.
+    pub fn parse_push_checked(&mut self, input: impl Into<String>) -> ParseResult {
+        let value = UnionType::parse(input);
 
-        if let Ok(parsed_u32) = input.parse::<u32>() {
-            self.push_u32(parsed_u32);
-            return;
-        }
+        if let UnionType::Text(input) = &value {
+            if let Some(expected) = numeric_shape(input) {
+                self.parse_errors.push(ParseError {
+                    input: input.clone(),
+                    row: self.len(),
+                    expected,
+                });
+                self.push(value);
 
-        if let Ok(parsed_i32) = input.parse::<i32>() {
-            self.push_i32(parsed_i32);
-            return;
+                return ParseResult::TextFallback(expected);
+            }
         }
 
-        if let Ok(parsed_usize) = input.parse::<usize>() {
-            self.push_usize(parsed_usize);
-            return;
+        let result = match value.borrow().data_type() {
+            None => ParseResult::Null,
+            Some(DataType::Boolean) => ParseResult::Boolean,
+            Some(DataType::Text) => ParseResult::Text,
+            Some(data_type) => ParseResult::Numeric(data_type),
+        };
+        self.push(value);
+
+        result
+    }
+
+    /// Returns the values [`UnionBuilder::parse_push`] stored as text after
+    /// failing to parse them as numbers, in the order they were pushed.
+    ///
+    /// Each [`ParseError`] holds the row, input and attempted type of a
+    /// value. They are returned as errors rather than `(usize, &str,
+    /// DataType)` tuples, as a slice of those would have to borrow the
+    /// inputs from storage the builder itself owns.
+    pub fn parse_errors(&self) -> &[ParseError] {
+        &self.parse_errors
+    }
+
+    /// Parses `input` as `expected`, pushing the result onto self.
+    ///
+    /// Without an `expected` type, `input` is parsed as in
+    /// [`UnionBuilder::parse_push`], unless the builder is
+    /// [strict](UnionBuilder::strict) and has seen a non-null value. Strict
+    /// builders expect every value to have the type of the first non-null
+    /// value. Null tokens are accepted regardless of the expected type.
+    ///
+    /// Errors without pushing anything if `input` does not parse as the
+    /// expected type.
+    pub fn try_parse_push(
+        &mut self,
+        input: &str,
+        expected: Option<DataType>,
+    ) -> Result<(), ParseError> {
+        let expected = match expected {
+            Some(expected) => Some(expected),
+            None if self.strict => self.seen_type(),
+            None => None,
+        };
+
+        let Some(expected) = expected else {
+            self.parse_push(input);
+            return Ok(());
+        };
+
+        if is_null_token(input) {
+            self.push_none();
+            return Ok(());
         }
 
-        if let Ok(parsed_isize) = input.parse::<isize>() {
-            self.push_isize(parsed_isize);
-            return;
+        match parse_as(input, expected) {
+            Some(value) => {
+                self.push(value);
+                Ok(())
+            }
+            None => Err(ParseError {
+                input: input.into(),
+                row: self.len(),
+                expected,
+            }),
         }
+    }
 
-        if let Ok(parsed_f32) = input.parse::<f32>() {
-            self.push_f32(parsed_f32);
-            return;
+    /// Returns the type of the first non-null value pushed, if any.
+    fn seen_type(&self) -> Option<DataType> {
+        let (kind, _) = self.tracker.iter().find(|(kind, _)| *kind != 8)?;
+
+        let data_type = match kind {
+            0 => DataType::UInt32,
+            1 => DataType::Int32,
+            2 => DataType::USize,
+            3 => DataType::ISize,
+            4 => DataType::F32,
+            5 => DataType::F64,
+            6 => DataType::Boolean,
+            _ => DataType::Text,
+        };
+
+        Some(data_type)
+    }
+
+    /// Finishes the builder into the array of the only type pushed, with
+    /// the pushed nulls as its nulls.
+    ///
+    /// Errors with the finished [`Union`] if values of more than one type
+    /// were pushed. As there is no array of only nulls, builders holding
+    /// nothing but nulls also finish into a [`Union`].
+    #[allow(clippy::result_large_err)]
+    pub fn finish_single(self) -> Result<ColumnArray, Union> {
+        let kinds = self.tracker.iter().map(|(kind, _)| *kind);
+        let mut kinds = kinds.filter(|kind| *kind != 8);
+
+        let kind = match kinds.next() {
+            Some(kind) if kinds.all(|other| other == kind) => kind,
+            _ => return Err(Union::from_builder(self)),
+        };
+
+        let tracker = &self.tracker;
+        let column = match kind {
+            0 => single::<ArrayU32>(tracker, self.uint32).into(),
+            1 => single::<ArrayI32>(tracker, self.int32).into(),
+            2 => single::<ArrayUSize>(tracker, self.uintsize).into(),
+            3 => single::<ArrayISize>(tracker, self.intsize).into(),
+            4 => single::<ArrayF32>(tracker, self.float32).into(),
+            5 => single::<ArrayF64>(tracker, self.float64).into(),
+            6 => single::<ArrayBoolean>(tracker, self.boolean).into(),
+            _ => single::<ArrayText>(tracker, self.text).into(),
+        };
+
+        Ok(column)
+    }
+
+    /// Finishes the builder into the array of the only type pushed or, for
+    /// numbers of mixed types, the array of the type they promote to.
+    ///
+    /// Unlike [`UnionBuilder::finish_single`], integers of mixed types
+    /// become an [`ArrayUSize`] if all are unsigned and an [`ArrayISize`]
+    /// otherwise, and integers mixed with floats become an [`ArrayF64`], as
+    /// in [`Scalar`] arithmetic. Values of other mixed types, and numbers
+    /// which do not all fit the type they promote to losslessly, finish into
+    /// a [`Union`].
+    ///
+    /// Errors if only nulls were pushed.
+    pub fn build_as_typed_column(self) -> Result<ColumnArray, BuildError> {
+        let union = match self.finish_single() {
+            Ok(column) => return Ok(column),
+            Err(union) => union,
+        };
+
+        let types = union.type_distribution();
+
+        if types.is_empty() {
+            return Err(BuildError::OnlyNulls { len: union.len() });
         }
 
-        if let Ok(parsed_f64) = input.parse::<f64>() {
-            self.push_f64(parsed_f64);
-            return;
+        if !types.keys().all(DataType::is_numeric) {
+            return Ok(union.into());
         }
 
-        if let Ok(parsed_bool) = input.parse::<bool>() {
-            self.push_bool(parsed_bool);
-            return;
+        let column = if types.keys().any(DataType::is_float) {
+            ArrayF64::try_from(&union).map(ColumnArray::from)
+        } else if types.keys().all(DataType::is_unsigned) {
+            ArrayUSize::try_from(&union).map(ColumnArray::from)
+        } else {
+            ArrayISize::try_from(&union).map(ColumnArray::from)
         };
 
-        self.push_string(input);
+        Ok(column.unwrap_or_else(|_| union.into()))
     }
 
     pub fn get(&self, idx: usize) -> Option<UnionType> {
@@ -305,8 +550,165 @@ impl UnionBuilder {
     pub fn len(&self) -> usize {
         self.tracker.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracker.is_empty()
+    }
+
+    /// Removes every value pushed so far while keeping the allocated
+    /// capacity, so the builder can be reused for the next batch.
+    ///
+    /// Whether the builder is [`UnionBuilder::strict`] is kept, though the
+    /// type values are held to is picked anew from the next values.
+    pub fn reset(&mut self) {
+        self.tracker.clear();
+        self.uint32.clear();
+        self.int32.clear();
+        self.uintsize.clear();
+        self.intsize.clear();
+        self.float32.clear();
+        self.float64.clear();
+        self.boolean.clear();
+        self.text.clear();
+        self.parse_errors.clear();
+    }
+}
+
+/// Builds the array of `values`, with nulls wherever `tracker` holds one.
+///
+/// Assumes every other element of `tracker` refers to the next of `values`.
+fn single<A: Array>(tracker: &[(u8, usize)], values: Vec<A::Data>) -> A {
+    let mut values = values.into_iter();
+
+    A::new(tracker.iter().map(|(kind, _)| match kind {
+        8 => None,
+        _ => values.next(),
+    }))
+}
+
+/// Returns true if `input` stands for a null when parsed.
+fn is_null_token(input: &str) -> bool {
+    input.is_empty() || input == "null"
+}
+
+/// Returns the type `input` was meant to be if the whole of it is shaped
+/// like a number: a `0x` prefix followed by letters and digits, or digits
+/// and decimal points after an optional sign, with an optional exponent.
+///
+/// Text which merely starts with a digit, such as `"2024-01-01"` or
+/// `"3rd"`, is not.
+fn numeric_shape(input: &str) -> Option<DataType> {
+    if let Some(hex) = input.strip_prefix("0x").or(input.strip_prefix("0X")) {
+        let is_hex = !hex.is_empty() && hex.chars().all(|c| c.is_ascii_alphanumeric());
+        return is_hex.then_some(DataType::USize);
+    }
+
+    let unsigned = input.strip_prefix(['+', '-']).unwrap_or(input);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+
+    let is_mantissa = mantissa.contains(|c: char| c.is_ascii_digit())
+        && mantissa.chars().all(|c| c.is_ascii_digit() || c == '.');
+    let is_exponent = exponent.is_none_or(|exponent| {
+        let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        digits.chars().all(|c| c.is_ascii_digit())
+    });
+
+    if !is_mantissa || !is_exponent {
+        return None;
+    }
+
+    if mantissa.contains('.') || exponent.is_some() {
+        Some(DataType::F64)
+    } else if input.starts_with('-') {
+        Some(DataType::ISize)
+    } else {
+        Some(DataType::USize)
+    }
+}
+
+/// Parses `input` as a `0x` or `0X` prefixed hexadecimal integer.
+fn parse_hex(input: &str) -> Option<usize> {
+    let hex = input.strip_prefix("0x").or(input.strip_prefix("0X"))?;
+
+    // `from_str_radix` accepts a leading sign, which hex literals lack
+    if !hex.starts_with(|c: char| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    usize::from_str_radix(hex, 16).ok()
+}
+
+/// Parses `input` as a decimal or, as in [`UnionType::parse`], hexadecimal
+/// integer.
+fn parse_integer<T>(input: &str) -> Option<T>
+where
+    T: FromStr + TryFrom<usize>,
+{
+    match parse_hex(input) {
+        Some(parsed) => T::try_from(parsed).ok(),
+        None => input.parse().ok(),
+    }
+}
+
+/// Parses `input` as a value of `data_type`. Text and unions accept any
+/// input.
+fn parse_as(input: &str, data_type: DataType) -> Option<UnionType> {
+    let value = match data_type {
+        DataType::Int32 => UnionType::I32(parse_integer(input)?),
+        DataType::UInt32 => UnionType::U32(parse_integer(input)?),
+        DataType::ISize => UnionType::ISize(parse_integer(input)?),
+        DataType::USize => UnionType::USize(parse_integer(input)?),
+        DataType::Boolean => UnionType::Boolean(input.parse().ok()?),
+        DataType::F32 => UnionType::F32(input.parse().ok()?),
+        DataType::F64 => UnionType::F64(input.parse().ok()?),
+        DataType::Text => UnionType::Text(input.into()),
+        DataType::Union => UnionType::parse(input),
+    };
+
+    Some(value)
+}
+
+/// How [`UnionBuilder::parse_push_checked`] stored a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseResult {
+    Null,
+    /// Stored as a number of the given type.
+    Numeric(DataType),
+    Boolean,
+    /// Stored as text, the input not looking like any other type.
+    Text,
+    /// Stored as text after failing to parse as a number of the given type.
+    TextFallback(DataType),
+}
+
+/// Error raised by [`UnionBuilder::try_parse_push`] for a value which does
+/// not parse as the expected type, also recorded by
+/// [`UnionBuilder::parse_push`] for numbers falling back to text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The offending input.
+    pub input: String,
+    /// The index the value would have been pushed at.
+    pub row: usize,
+    /// The type `input` was expected to parse as.
+    pub expected: DataType,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Cannot parse {:?} at row {} as {:?}",
+            self.input, self.row, self.expected
+        )
+    }
 }
 
+impl Error for ParseError {}
+
 /// An array of mixed types corresponding to Apache Arrow's Dense Union type
 pub struct Union {
     /// Pointer to the types buffer.
@@ -337,7 +739,8 @@ pub struct Union {
 }
 
 impl Union {
-    fn empty() -> Self {
+    /// Creates an empty [`Union`].
+    pub const fn empty() -> Self {
         Self {
             types_ptr: None,
             offsets_ptr: None,
@@ -381,6 +784,7 @@ impl Union {
             float64,
             boolean,
             text,
+            ..
         } = builder;
 
         for (idx, (types, offset)) in tracker.into_iter().enumerate() {
@@ -485,6 +889,47 @@ impl Union {
         Self::from_sized_iter(values.into_iter())
     }
 
+    /// Returns an iterator over references to every element, with nulls as
+    /// [`UnionRef::Null`] rather than None.
+    pub fn iter_values(&self) -> impl ExactSizeIterator<Item = UnionRef<'_>> + '_ {
+        (0..self.len).map(|idx| self.get_ref(idx).unwrap_or(UnionRef::Null))
+    }
+
+    /// Returns the type of the element at `idx`, or None if it is null.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn type_at(&self, idx: usize) -> Option<DataType> {
+        if self.check_null(idx) {
+            return None;
+        }
+
+        let types_ptr = self.types_ptr?;
+        let kind = unsafe { ptr::read(types_ptr.as_ptr().add(idx)) };
+
+        kind_type(kind)
+    }
+
+    /// Returns the number of elements of each type present in the union.
+    ///
+    /// Nulls have no type and are left out, see [`Array::null_count`].
+    pub fn type_distribution(&self) -> HashMap<DataType, usize> {
+        let mut counts = HashMap::new();
+
+        let Some(types_ptr) = self.types_ptr else {
+            return counts;
+        };
+
+        for idx in 0..self.len {
+            let kind = unsafe { ptr::read(types_ptr.as_ptr().add(idx)) };
+
+            if let Some(data_type) = kind_type(kind) {
+                *counts.entry(data_type).or_insert(0) += 1;
+            }
+        }
+
+        counts
+    }
+
     /// Returns true if the types buffers of `Self` and `Other` are equal.
     ///
     /// Assumes both buffers are equal in length.
@@ -613,7 +1058,7 @@ impl Union {
     fn allocate(len: usize) -> (NonNull<u8>, NonNull<u32>) {
         // Offsets
         let offsets_size = len * std::mem::size_of::<u32>();
-        let offsets_layout = Layout::from_size_align(offsets_size, 8)
+        let offsets_layout = Layout::from_size_align(offsets_size, ALIGNMENT)
             .expect("Union: offsets size overflowed isize::max");
 
         let offsets_ptr = unsafe { alloc::alloc(offsets_layout) };
@@ -625,7 +1070,7 @@ impl Union {
 
         // Types
         let types_size = len;
-        let types_layout = Layout::from_size_align(types_size, 8)
+        let types_layout = Layout::from_size_align(types_size, ALIGNMENT)
             .expect("Union: types size overflowed isize::max");
 
         let types_ptr = unsafe { alloc::alloc(types_layout) };
@@ -641,8 +1086,8 @@ impl Union {
     fn dealloc_types(ptr: Option<NonNull<u8>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let size = len * std::mem::size_of::<u8>();
-        let layout =
-            Layout::from_size_align(size, 8).expect("Union: types size overflowed isize::max");
+        let layout = Layout::from_size_align(size, ALIGNMENT)
+            .expect("Union: types size overflowed isize::max");
         let ptr = ptr.as_ptr();
 
         unsafe { alloc::dealloc(ptr, layout) }
@@ -651,7 +1096,7 @@ impl Union {
     fn dealloc_offsets(ptr: Option<NonNull<u32>>, len: usize) {
         let Some(ptr) = ptr else { return };
         let offsets_size = len * std::mem::size_of::<u32>();
-        let offsets_layout = Layout::from_size_align(offsets_size, 8)
+        let offsets_layout = Layout::from_size_align(offsets_size, ALIGNMENT)
             .expect("Union: offsets size overflowed isize::max");
 
         let ptr = ptr.as_ptr() as *mut u8;
@@ -662,8 +1107,10 @@ impl Union {
 
 impl Array for Union {
     type Data = UnionType;
-    type Ref<'a> = UnionRef<'a>
-        where Self: 'a;
+    type Ref<'a>
+        = UnionRef<'a>
+    where
+        Self: 'a;
 
     fn new<I>(values: I) -> Self
     where
@@ -741,6 +1188,16 @@ impl Array for Union {
     fn all_null(&self) -> bool {
         self.nulls == self.len
     }
+
+    fn null_count(&self) -> usize {
+        self.nulls
+    }
+
+    /// Always None, as nulls are stored as their own type in the types
+    /// buffer rather than in a validity buffer.
+    fn validity(&self) -> Option<&BitmapBuffer> {
+        None
+    }
 }
 
 impl IntoIterator for Union {
@@ -752,6 +1209,17 @@ impl IntoIterator for Union {
     }
 }
 
+// SAFETY: The array uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for Union {}
+unsafe impl Sync for Union {}
+
+impl Default for Union {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Drop for Union {
     fn drop(&mut self) {
         Self::dealloc_offsets(self.offsets_ptr, self.len);
@@ -1056,7 +1524,264 @@ impl<const N: usize> From<[Option<UnionType>; N]> for Union {
     }
 }
 
+/// Error raised converting a [`Union`] or a slice of scalars into a typed
+/// array when some of its values cannot be losslessly cast to the array's
+/// type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionCastError {
+    /// The type of the array converted to.
+    pub to: DataType,
+    /// The index and type of every value which could not be cast.
+    pub rows: Vec<(usize, DataType)>,
+}
+
+impl Display for UnionCastError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot cast union values to {:?}:", self.to)?;
+
+        for (row, data_type) in self.rows.iter() {
+            write!(f, " row {row} ({data_type:?})")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for UnionCastError {}
+
+/// Error raised by [`UnionBuilder::build_as_typed_column`] when the values
+/// pushed cannot make up a typed column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// Only nulls were pushed, leaving no type for the column.
+    OnlyNulls { len: usize },
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OnlyNulls { len } => write!(f, "Cannot type a column of {len} nulls"),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// Element types a [`Union`] value can be losslessly cast to.
+///
+/// Integers convert between each other when in range, and into floats when
+/// exactly representable. Floats never convert into integers, while
+/// booleans and text only convert into themselves.
+pub(crate) trait FromUnionValue: Sized {
+    const DATA_TYPE: DataType;
+
+    fn from_union_value(value: UnionRef<'_>) -> Option<Self>;
+}
+
+macro_rules! from_union_integer {
+    ($($kind:ty => $data_type:expr),*) => {
+        $(
+            impl FromUnionValue for $kind {
+                const DATA_TYPE: DataType = $data_type;
+
+                fn from_union_value(value: UnionRef<'_>) -> Option<Self> {
+                    match value {
+                        UnionRef::U32(value) => value.try_into().ok(),
+                        UnionRef::I32(value) => value.try_into().ok(),
+                        UnionRef::USize(value) => value.try_into().ok(),
+                        UnionRef::ISize(value) => value.try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_union_integer!(
+    u32 => DataType::UInt32,
+    i32 => DataType::Int32,
+    usize => DataType::USize,
+    isize => DataType::ISize
+);
+
+macro_rules! from_union_float {
+    ($($kind:ty => $data_type:expr),*) => {
+        $(
+            impl FromUnionValue for $kind {
+                const DATA_TYPE: DataType = $data_type;
+
+                fn from_union_value(value: UnionRef<'_>) -> Option<Self> {
+                    // Lossless if casting back restores the original value
+                    let integer = |value: i128| {
+                        let cast = value as $kind;
+                        (cast, cast as i128 == value)
+                    };
+                    let float = |value: f64| {
+                        let cast = value as $kind;
+                        (cast, cast as f64 == value || value.is_nan())
+                    };
+
+                    let (cast, exact) = match value {
+                        UnionRef::U32(value) => integer(value as i128),
+                        UnionRef::I32(value) => integer(value as i128),
+                        UnionRef::USize(value) => integer(value as i128),
+                        UnionRef::ISize(value) => integer(value as i128),
+                        UnionRef::F32(value) => float(value as f64),
+                        UnionRef::F64(value) => float(value),
+                        _ => return None,
+                    };
+
+                    exact.then_some(cast)
+                }
+            }
+        )*
+    };
+}
+
+from_union_float!(f32 => DataType::F32, f64 => DataType::F64);
+
+impl FromUnionValue for bool {
+    const DATA_TYPE: DataType = DataType::Boolean;
+
+    fn from_union_value(value: UnionRef<'_>) -> Option<Self> {
+        match value {
+            UnionRef::Boolean(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromUnionValue for String {
+    const DATA_TYPE: DataType = DataType::Text;
+
+    fn from_union_value(value: UnionRef<'_>) -> Option<Self> {
+        match value {
+            UnionRef::Text(value) => Some(value.into()),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the type stored under `kind` in the types buffer, or None for
+/// nulls.
+fn kind_type(kind: u8) -> Option<DataType> {
+    let data_type = match kind {
+        0 => DataType::UInt32,
+        1 => DataType::Int32,
+        2 => DataType::USize,
+        3 => DataType::ISize,
+        4 => DataType::F32,
+        5 => DataType::F64,
+        6 => DataType::Boolean,
+        7 => DataType::Text,
+        _ => return None,
+    };
+
+    Some(data_type)
+}
+
+/// Returns the type of a non-null union value.
+fn union_value_type(value: &UnionRef<'_>) -> DataType {
+    match value {
+        UnionRef::U32(_) => DataType::UInt32,
+        UnionRef::I32(_) => DataType::Int32,
+        UnionRef::USize(_) => DataType::USize,
+        UnionRef::ISize(_) => DataType::ISize,
+        UnionRef::F32(_) => DataType::F32,
+        UnionRef::F64(_) => DataType::F64,
+        UnionRef::Boolean(_) => DataType::Boolean,
+        UnionRef::Text(_) | UnionRef::Null => DataType::Text,
+    }
+}
+
+/// Casts every value of `union` into an array of `A`, keeping nulls.
+fn cast_union<A>(union: &Union) -> Result<A, UnionCastError>
+where
+    A: Array,
+    A::Data: FromUnionValue,
+{
+    cast_values((0..union.len()).map(|idx| union.get_ref(idx)))
+}
+
+/// Casts `values` into an array of `A`, keeping nulls, erroring with the
+/// index of every value which cannot be cast.
+fn cast_values<'a, A, I>(values: I) -> Result<A, UnionCastError>
+where
+    A: Array,
+    A::Data: FromUnionValue,
+    I: ExactSizeIterator<Item = Option<UnionRef<'a>>>,
+{
+    let mut cast = Vec::with_capacity(values.len());
+    let mut rows = Vec::new();
+
+    for (idx, value) in values.enumerate() {
+        let value = match value {
+            None | Some(UnionRef::Null) => None,
+            Some(value) => {
+                let data_type = union_value_type(&value);
+
+                let value = A::Data::from_union_value(value);
+                if value.is_none() {
+                    rows.push((idx, data_type));
+                }
+
+                value
+            }
+        };
+
+        cast.push(value);
+    }
+
+    if !rows.is_empty() {
+        return Err(UnionCastError {
+            to: A::Data::DATA_TYPE,
+            rows,
+        });
+    }
+
+    Ok(A::new(cast))
+}
+
+macro_rules! try_from_union {
+    ($($array:ty),*) => {
+        $(
+            impl TryFrom<&Union> for $array {
+                type Error = UnionCastError;
+
+                /// Casts every value of `union` into the array, erroring with
+                /// the values which cannot be cast losslessly.
+                fn try_from(union: &Union) -> Result<Self, Self::Error> {
+                    cast_union(union)
+                }
+            }
+
+            impl TryFrom<&[Scalar]> for $array {
+                type Error = UnionCastError;
+
+                /// Casts every scalar into the array as with a [`Union`],
+                /// erroring with the indices of those which cannot be cast.
+                fn try_from(scalars: &[Scalar]) -> Result<Self, Self::Error> {
+                    cast_values(scalars.iter().map(|scalar| Some(scalar.borrow())))
+                }
+            }
+        )*
+    };
+}
+
+try_from_union!(
+    ArrayU32,
+    ArrayI32,
+    ArrayUSize,
+    ArrayISize,
+    ArrayF32,
+    ArrayF64,
+    ArrayBoolean,
+    ArrayText
+);
+
 #[cfg(test)]
+#[allow(clippy::manual_map)]
 mod test {
     use super::*;
 
@@ -1241,4 +1966,533 @@ mod test {
         builder.parse_push(max.to_string());
         assert_eq!(UnionType::F32(f32::INFINITY), builder.get(9).unwrap());
     }
+
+    #[test]
+    fn test_parse_push_hex() {
+        let mut builder = UnionBuilder::new();
+
+        let elems = [
+            "0x0",
+            "0xFFFFFFFF",
+            "0X1a",
+            "0x100000000",
+            "0xg",
+            "0x",
+            "0x-1",
+            "0x+1",
+        ];
+        elems.into_iter().for_each(|val| builder.parse_push(val));
+
+        assert_eq!(UnionType::U32(0), builder.get(0).unwrap());
+        assert_eq!(UnionType::U32(u32::MAX), builder.get(1).unwrap());
+        assert_eq!(UnionType::U32(26), builder.get(2).unwrap());
+        assert_eq!(UnionType::USize(1 << 32), builder.get(3).unwrap());
+        assert_eq!(UnionType::Text("0xg".into()), builder.get(4).unwrap());
+        assert_eq!(UnionType::Text("0x".into()), builder.get(5).unwrap());
+        assert_eq!(UnionType::Text("0x-1".into()), builder.get(6).unwrap());
+        assert_eq!(UnionType::Text("0x+1".into()), builder.get(7).unwrap());
+    }
+
+    #[test]
+    fn test_parse_hex_entry_points() {
+        let elems = ["0x1F", "0X100000000", "0xg", "0x-1"];
+        let expected = [
+            UnionType::U32(31),
+            UnionType::USize(1 << 32),
+            UnionType::Text("0xg".into()),
+            UnionType::Text("0x-1".into()),
+        ];
+
+        let parsed = elems.map(UnionType::parse);
+        assert_eq!(expected, parsed);
+
+        let mut builder = UnionBuilder::new();
+        for value in elems {
+            assert_eq!(Ok(()), builder.try_parse_push(value, None));
+        }
+        assert_eq!(
+            expected.to_vec(),
+            (0..builder.len())
+                .map(|idx| builder.get(idx).unwrap())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(Some(UnionType::I32(31)), parse_as("0x1f", DataType::Int32));
+        assert_eq!(None, parse_as("0x100000000", DataType::UInt32));
+        assert_eq!(None, parse_as("0x-1", DataType::ISize));
+
+        let mut builder = UnionBuilder::new().strict();
+        assert_eq!(Ok(()), builder.try_parse_push("0x10", None));
+        assert_eq!(Ok(()), builder.try_parse_push("17", None));
+        assert_eq!(Ok(()), builder.try_parse_push("0xff", None));
+        assert!(builder.try_parse_push("0x100000000", None).is_err());
+
+        let values = [UnionType::U32(16), UnionType::U32(17), UnionType::U32(255)];
+        assert_eq!(
+            values.to_vec(),
+            (0..builder.len())
+                .map(|idx| builder.get(idx).unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let mut builder = UnionBuilder::new();
+
+        assert_eq!(ParseResult::Text, builder.parse_push_checked("one"));
+        assert_eq!(ParseResult::Null, builder.parse_push_checked("null"));
+        assert_eq!(
+            ParseResult::Numeric(DataType::Int32),
+            builder.parse_push_checked("-2")
+        );
+        assert_eq!(ParseResult::Boolean, builder.parse_push_checked("true"));
+        assert_eq!(
+            ParseResult::TextFallback(DataType::F64),
+            builder.parse_push_checked("1.2.3")
+        );
+        assert_eq!(
+            ParseResult::TextFallback(DataType::F64),
+            builder.parse_push_checked("-1e+")
+        );
+        assert_eq!(
+            ParseResult::TextFallback(DataType::USize),
+            builder.parse_push_checked("0xg")
+        );
+        assert_eq!(
+            ParseResult::TextFallback(DataType::USize),
+            builder.parse_push_checked("0x1FFFFFFFFFFFFFFFF")
+        );
+        assert_eq!(ParseResult::Text, builder.parse_push_checked("-a"));
+
+        for value in ["-12a", "2024-01-01", "3rd", "1 apple", "0x 1", "1e5e3"] {
+            assert_eq!(ParseResult::Text, builder.parse_push_checked(value));
+        }
+
+        assert_eq!(UnionType::Text("1.2.3".into()), builder.get(4).unwrap());
+        assert_eq!(
+            vec![
+                ParseError {
+                    input: "1.2.3".into(),
+                    row: 4,
+                    expected: DataType::F64
+                },
+                ParseError {
+                    input: "-1e+".into(),
+                    row: 5,
+                    expected: DataType::F64
+                },
+                ParseError {
+                    input: "0xg".into(),
+                    row: 6,
+                    expected: DataType::USize
+                },
+                ParseError {
+                    input: "0x1FFFFFFFFFFFFFFFF".into(),
+                    row: 7,
+                    expected: DataType::USize
+                },
+            ],
+            builder.parse_errors()
+        );
+
+        builder.reset();
+        assert!(builder.parse_errors().is_empty());
+    }
+
+    #[test]
+    fn test_try_parse_push() {
+        let mut builder = UnionBuilder::new();
+
+        for value in ["1", "-2", "30"] {
+            assert_eq!(Ok(()), builder.try_parse_push(value, Some(DataType::Int32)));
+        }
+        assert_eq!(UnionType::I32(1), builder.get(0).unwrap());
+
+        assert_eq!(
+            Err(ParseError {
+                input: "2.5".into(),
+                row: 3,
+                expected: DataType::Int32
+            }),
+            builder.try_parse_push("2.5", Some(DataType::Int32))
+        );
+        assert_eq!(3, builder.len());
+
+        assert_eq!(Ok(()), builder.try_parse_push("2.5", Some(DataType::F64)));
+        assert_eq!(UnionType::F64(2.5), builder.get(3).unwrap());
+        assert_eq!(Ok(()), builder.try_parse_push("x", Some(DataType::Text)));
+    }
+
+    #[test]
+    fn test_try_parse_push_strict() {
+        let mut builder = UnionBuilder::new();
+        for value in ["1", "one", "true"] {
+            assert_eq!(Ok(()), builder.try_parse_push(value, None));
+        }
+
+        let mut builder = UnionBuilder::new().strict();
+        for value in ["null", "", "1.5", "-3", "null"] {
+            assert_eq!(Ok(()), builder.try_parse_push(value, None));
+        }
+
+        // Every value after the first parses as its type
+        assert_eq!(UnionType::Null, builder.get(0).unwrap());
+        assert_eq!(UnionType::F32(-3.0), builder.get(3).unwrap());
+
+        let error = builder.try_parse_push("three", None).unwrap_err();
+        assert_eq!(5, error.row);
+        assert_eq!(DataType::F32, error.expected);
+        assert_eq!("Cannot parse \"three\" at row 5 as F32", error.to_string());
+    }
+
+    #[test]
+    fn test_finish_single() {
+        let mut builder = UnionBuilder::new();
+        for val in ["-1", "", "7", "null", "3"] {
+            builder.try_parse_push(val, Some(DataType::Int32)).unwrap();
+        }
+
+        assert_eq!(
+            Ok(ColumnArray::Int32(ArrayI32::from([
+                Some(-1),
+                None,
+                Some(7),
+                None,
+                Some(3)
+            ]))),
+            builder.finish_single()
+        );
+
+        let mut builder = UnionBuilder::new();
+        builder.push_string("a".into());
+        builder.push_none();
+        assert_eq!(
+            Ok(ColumnArray::Text(ArrayText::from([Some("a".into()), None]))),
+            builder.finish_single()
+        );
+
+        let mut builder = UnionBuilder::new();
+        builder.push_none();
+        builder.push_none();
+        let one = builder.finish_single().unwrap_err();
+        assert_eq!(2, one.len());
+        assert!(one.all_null());
+
+        let mut builder = UnionBuilder::new();
+        ["1", "", "one"]
+            .into_iter()
+            .for_each(|val| builder.parse_push(val));
+        assert_eq!(
+            Err(Union::from_vec(vec![
+                UnionType::U32(1),
+                UnionType::Null,
+                UnionType::Text("one".into())
+            ])),
+            builder.finish_single()
+        );
+    }
+
+    #[test]
+    fn test_build_as_typed_column() {
+        let typed = |values: &[&str]| {
+            let mut builder = UnionBuilder::new();
+            values.iter().for_each(|value| builder.parse_push(*value));
+            builder.build_as_typed_column()
+        };
+
+        assert_eq!(
+            Ok(ColumnArray::ISize(ArrayISize::from([
+                Some(1),
+                Some(-14),
+                None
+            ]))),
+            typed(&["1", "-14", ""])
+        );
+        assert_eq!(
+            Ok(ColumnArray::USize(ArrayUSize::from([1, 1 << 40]))),
+            typed(&["1", "0x10000000000"])
+        );
+        assert_eq!(
+            Ok(ColumnArray::F64(ArrayF64::from([1.0, 0.5, -2.0]))),
+            typed(&["1", "0.5", "-2"])
+        );
+        assert_eq!(
+            Ok(ColumnArray::Boolean(ArrayBoolean::from([true, false]))),
+            typed(&["true", "false"])
+        );
+        assert_eq!(
+            Ok(DataType::Union),
+            typed(&["1", "one"]).map(|column| column.data_type())
+        );
+
+        // usize::MAX is not exactly an f64
+        assert_eq!(
+            Ok(ColumnArray::Union(Union::from_vec(vec![
+                UnionType::USize(usize::MAX),
+                UnionType::F32(0.5)
+            ]))),
+            typed(&[&usize::MAX.to_string(), "0.5"])
+        );
+    }
+
+    #[test]
+    fn test_build_as_typed_column_errors() {
+        let mut builder = UnionBuilder::new();
+        builder.parse_push("");
+        builder.parse_push("null");
+
+        assert_eq!(
+            Err(BuildError::OnlyNulls { len: 2 }),
+            builder.build_as_typed_column()
+        );
+        assert_eq!(
+            Err(BuildError::OnlyNulls { len: 0 }),
+            UnionBuilder::new().build_as_typed_column()
+        );
+    }
+
+    #[test]
+    fn test_try_from_union() {
+        let one = Union::from([
+            Some(UnionType::I32(-3)),
+            None,
+            Some(UnionType::U32(7)),
+            Some(UnionType::F32(0.5)),
+        ]);
+
+        assert_eq!(
+            Ok(ArrayF64::from([Some(-3.0), None, Some(7.0), Some(0.5)])),
+            ArrayF64::try_from(&one)
+        );
+        assert_eq!(
+            Err(UnionCastError {
+                to: DataType::Int32,
+                rows: vec![(3, DataType::F32)]
+            }),
+            ArrayI32::try_from(&one)
+        );
+
+        let error = ArrayU32::try_from(&one).unwrap_err();
+        assert_eq!(vec![(0, DataType::Int32), (3, DataType::F32)], error.rows);
+        assert_eq!(
+            "Cannot cast union values to UInt32: row 0 (Int32) row 3 (F32)",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_try_from_union_lossy() {
+        let one = Union::from([
+            UnionType::USize((1 << 30) + 1),
+            UnionType::F64(0.1),
+            UnionType::Null,
+        ]);
+
+        assert_eq!(
+            Ok(ArrayF64::from([Some(1_073_741_825.0), Some(0.1), None])),
+            ArrayF64::try_from(&one)
+        );
+        assert_eq!(
+            vec![(0, DataType::USize), (1, DataType::F64)],
+            ArrayF32::try_from(&one).unwrap_err().rows
+        );
+
+        let one = Union::from([
+            UnionType::USize((1 << 53) + 1),
+            UnionType::USize(usize::MAX),
+        ]);
+        assert_eq!(2, ArrayF64::try_from(&one).unwrap_err().rows.len());
+
+        let one = Union::from([
+            Some(UnionType::Text("a".into())),
+            None,
+            Some(UnionType::Boolean(true)),
+        ]);
+        assert_eq!(
+            vec![(2, DataType::Boolean)],
+            ArrayText::try_from(&one).unwrap_err().rows
+        );
+
+        let one = Union::from([None::<UnionType>, None]);
+        assert_eq!(
+            Ok(ArrayBoolean::from([None, None])),
+            ArrayBoolean::try_from(&one)
+        );
+    }
+
+    #[test]
+    fn test_type_at() {
+        let one = Union::from([
+            UnionType::I32(1),
+            UnionType::Null,
+            UnionType::Text("a".into()),
+            UnionType::F64(0.5),
+            UnionType::Boolean(true),
+        ]);
+
+        assert_eq!(5, one.len());
+        assert_eq!(1, one.null_count());
+        assert_eq!(
+            vec![
+                Some(DataType::Int32),
+                None,
+                Some(DataType::Text),
+                Some(DataType::F64),
+                Some(DataType::Boolean)
+            ],
+            (0..5).map(|idx| one.type_at(idx)).collect::<Vec<_>>()
+        );
+
+        let two = Union::from([UnionType::Null, UnionType::Null]);
+        assert_eq!((2, 2), (two.len(), two.null_count()));
+        assert_eq!(None, two.type_at(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to index 1 when array length is 1")]
+    fn test_type_at_out_of_bounds() {
+        Union::from([UnionType::U32(3)]).type_at(1);
+    }
+
+    #[test]
+    fn test_iter_values() {
+        let one = Union::from([
+            UnionType::U32(7),
+            UnionType::Null,
+            UnionType::Text("a".into()),
+            UnionType::F32(1.5),
+        ]);
+
+        assert_eq!(
+            vec![
+                UnionRef::U32(7),
+                UnionRef::Null,
+                UnionRef::Text("a"),
+                UnionRef::F32(1.5)
+            ],
+            one.iter_values().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                Some(DataType::UInt32),
+                None,
+                Some(DataType::Text),
+                Some(DataType::F32)
+            ],
+            one.iter_values()
+                .map(|value| value.data_type())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(4, one.iter_values().len());
+        assert_eq!(
+            2,
+            Union::from([UnionType::Null, UnionType::Null])
+                .iter_values()
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_builder_with_capacity() {
+        let mut one = UnionBuilder::with_capacity(100);
+
+        assert!(one.is_empty());
+        assert!(one.tracker.capacity() >= 100);
+        assert!(one.text.capacity() >= 25 && one.float64.capacity() >= 25);
+
+        one.parse_push("1.5");
+        one.parse_push("a");
+        one.push_none();
+
+        let two = Union::from([
+            UnionType::F32(1.5),
+            UnionType::Text("a".into()),
+            UnionType::Null,
+        ]);
+        assert_eq!(two, Union::from_builder(one));
+        assert!(UnionBuilder::with_capacity(0).is_empty());
+    }
+
+    #[test]
+    fn test_builder_reset() {
+        let mut one = UnionBuilder::new().strict();
+        for idx in 0..100 {
+            one.push_i32(idx);
+            one.push_string(idx.to_string());
+        }
+
+        let capacity = (one.tracker.capacity(), one.text.capacity());
+        one.reset();
+
+        assert!(one.is_empty());
+        assert_eq!(capacity, (one.tracker.capacity(), one.text.capacity()));
+
+        one.push_f64(0.5);
+        one.push_none();
+        assert_eq!(
+            Union::from([UnionType::F64(0.5), UnionType::Null]),
+            Union::from_builder(one.clone())
+        );
+
+        // Still strict, now about floats rather than integers
+        assert!(one.try_parse_push("2", None).is_ok());
+        assert!(one.try_parse_push("two", None).is_err());
+    }
+
+    #[test]
+    fn test_type_distribution() {
+        let mut builder = UnionBuilder::new();
+        for value in ["1.5", "2.5", "x", "", "3.5", "7"] {
+            builder.parse_push(value);
+        }
+        let one = Union::from_builder(builder);
+
+        let expected = HashMap::from([
+            (DataType::F32, 3),
+            (DataType::Text, 1),
+            (DataType::UInt32, 1),
+        ]);
+        assert_eq!(expected, one.type_distribution());
+        assert_eq!(
+            one.len() - one.null_count(),
+            expected.values().sum::<usize>()
+        );
+
+        assert!(Union::from([UnionType::Null])
+            .type_distribution()
+            .is_empty());
+        assert!(Union::from_vec(vec![]).type_distribution().is_empty());
+    }
+
+    #[test]
+    fn test_try_from_scalars() {
+        let one = [Scalar::I32(3), Scalar::Null, Scalar::U32(7)];
+        assert_eq!(
+            Ok(ArrayF64::from([Some(3.0), None, Some(7.0)])),
+            ArrayF64::try_from(&one[..])
+        );
+        assert_eq!(
+            Ok(ArrayI32::from([Some(3), None, Some(7)])),
+            ArrayI32::try_from(one.as_slice())
+        );
+
+        let two = [
+            Scalar::Text("a".into()),
+            Scalar::F64(1.5),
+            Scalar::Text("b".into()),
+            Scalar::Boolean(true),
+        ];
+        assert_eq!(
+            Err(UnionCastError {
+                to: DataType::Text,
+                rows: vec![(1, DataType::F64), (3, DataType::Boolean)],
+            }),
+            ArrayText::try_from(&two[..])
+        );
+        assert_eq!(
+            Ok(ArrayBoolean::empty()),
+            ArrayBoolean::try_from(&[] as &[Scalar])
+        );
+    }
 }
@@ -1,7 +1,23 @@
-use std::fmt::Debug;
+use std::alloc::{self, Layout};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, VecDeque};
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::iter::Enumerate;
+use std::ops::Range;
+use std::ptr::{self, NonNull};
+
+use crate::statistics::ArrayStatistics;
+use crate::union::Scalar;
+use crate::{ArrayBoolean, ArrayF32, ArrayF64, ArrayI32, ArrayISize, ArrayU32, ArrayUSize};
+
+/// Alignment in bytes of every buffer allocated by the arrays, enough for
+/// aligned AVX-512 loads and matching Arrow's recommended alignment.
+pub(crate) const ALIGNMENT: usize = 64;
 
 /// Data types supported by the current implementation of Apache Arrow.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DataType {
     Int32,
     UInt32,
@@ -14,6 +30,123 @@ pub enum DataType {
     Union,
 }
 
+impl DataType {
+    /// Returns the number of bytes taken by each element of fixed size
+    /// types, or None for bit packed and variable sized types.
+    pub fn byte_width(&self) -> Option<usize> {
+        match self {
+            Self::Int32 => Some(size_of::<i32>()),
+            Self::UInt32 => Some(size_of::<u32>()),
+            Self::ISize => Some(size_of::<isize>()),
+            Self::USize => Some(size_of::<usize>()),
+            Self::F32 => Some(size_of::<f32>()),
+            Self::F64 => Some(size_of::<f64>()),
+            Self::Boolean | Self::Text | Self::Union => None,
+        }
+    }
+
+    /// Returns true if every element of the type takes the same whole
+    /// number of bytes.
+    pub fn is_fixed_size(&self) -> bool {
+        self.byte_width().is_some()
+    }
+
+    /// Returns true for the types stored as plain fixed width values, the
+    /// same types as [`DataType::is_fixed_size`].
+    pub fn is_primitive(&self) -> bool {
+        self.is_fixed_size()
+    }
+
+    /// Returns true for the integer and float types.
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::F32 | Self::F64)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.is_signed() || self.is_unsigned()
+    }
+
+    /// Returns true for the signed integer types.
+    ///
+    /// Floats are not counted despite being signed.
+    pub fn is_signed(&self) -> bool {
+        matches!(self, Self::Int32 | Self::ISize)
+    }
+
+    /// Returns true for the unsigned integer types.
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self, Self::UInt32 | Self::USize)
+    }
+
+    /// Returns the type of the result of arithmetic on numbers of `self` and
+    /// `other`, following the promotion rules of [`Scalar`] addition.
+    pub(crate) fn promote(&self, other: DataType) -> DataType {
+        match (*self, other) {
+            (lhs, rhs) if lhs == rhs => lhs,
+            (Self::F32 | Self::F64, _) | (_, Self::F32 | Self::F64) => Self::F64,
+            (Self::UInt32 | Self::USize, Self::UInt32 | Self::USize) => Self::USize,
+            _ => Self::ISize,
+        }
+    }
+
+    /// Returns the number of bits holding the magnitude of integer types, or
+    /// of the mantissa of float types.
+    fn value_bits(&self) -> Option<u32> {
+        match self {
+            Self::Int32 => Some(i32::BITS - 1),
+            Self::UInt32 => Some(u32::BITS),
+            Self::ISize => Some(isize::BITS - 1),
+            Self::USize => Some(usize::BITS),
+            Self::F32 => Some(f32::MANTISSA_DIGITS),
+            Self::F64 => Some(f64::MANTISSA_DIGITS),
+            Self::Boolean | Self::Text | Self::Union => None,
+        }
+    }
+
+    /// Returns true if every value of `self` can be converted to `target`
+    /// without loss.
+    ///
+    /// Integers widen into integers of the same or larger range and into
+    /// floats whose mantissa can hold them exactly, so `Int32` widens to
+    /// `ISize` and `F64` but not to `F32` or `UInt32`. Booleans widen into
+    /// any numeric type as 0 or 1. Every type widens into `Text` and `Union`.
+    pub fn can_cast_to(&self, target: DataType) -> bool {
+        if *self == target || matches!(target, Self::Text | Self::Union) {
+            return true;
+        }
+
+        if *self == Self::Boolean {
+            return target.is_numeric();
+        }
+
+        match (self.value_bits(), target.value_bits()) {
+            (Some(from), Some(to)) if self.is_integer() && target.is_integer() => {
+                !(self.is_signed() && target.is_unsigned()) && from <= to
+            }
+            (Some(from), Some(to)) if self.is_integer() => from <= to,
+            _ => *self == Self::F32 && target == Self::F64,
+        }
+    }
+
+    /// Returns true if values of `self` can be converted to `target`, even if
+    /// some values lose precision, wrap, or fail to convert.
+    ///
+    /// This covers every [`DataType::can_cast_to`] conversion along with
+    /// numeric narrowing, numbers to booleans and parsing `Text`. `Union`
+    /// values are converted individually so they may be cast to any type.
+    pub fn can_lossy_cast_to(&self, target: DataType) -> bool {
+        match self {
+            _ if self.can_cast_to(target) => true,
+            Self::Text | Self::Union => true,
+            _ => target.is_numeric() || target == Self::Boolean,
+        }
+    }
+}
+
 pub trait Array:
     Clone + PartialEq + Debug + IntoIterator<Item = Option<Self::Data>, IntoIter = IntoIter<Self>>
 {
@@ -44,9 +177,59 @@ pub trait Array:
     /// May panic if `idx` is out of bounds
     fn check_null(&self, idx: usize) -> bool;
 
+    /// Returns an owned value at `idx` if any.
+    ///
+    /// Errors if `idx` is out of bounds
+    fn try_get(&self, idx: usize) -> Result<Option<Self::Data>, ArrayError> {
+        check_bounds(self, idx)?;
+
+        Ok(self.get(idx))
+    }
+
+    /// Returns true if the value contained at `idx` is null
+    ///
+    /// Errors if `idx` is out of bounds
+    fn try_is_null(&self, idx: usize) -> Result<bool, ArrayError> {
+        check_bounds(self, idx)?;
+
+        Ok(self.check_null(idx))
+    }
+
+    /// Returns a shared reference to the value at `idx`, or None if it is
+    /// null.
+    ///
+    /// Unlike [`Array::get_ref`], panics if `idx` is out of bounds.
+    fn at(&self, idx: usize) -> Option<Self::Ref<'_>> {
+        assert!(
+            idx < self.len(),
+            "Tried to index {} when array length is {}",
+            idx,
+            self.len()
+        );
+
+        self.get_ref(idx)
+    }
+
     /// Returns true if the array contains only `null` elements
     fn all_null(&self) -> bool;
 
+    /// Returns the number of `null` elements in the array.
+    fn null_count(&self) -> usize;
+
+    /// Returns the validity buffer of the array.
+    ///
+    /// None if the array has no nulls, is entirely null or keeps track of
+    /// its nulls in some other way.
+    fn validity(&self) -> Option<&BitmapBuffer>;
+
+    /// Returns the values buffer of arrays storing their elements as a
+    /// contiguous slice, with arbitrary values in the slots of nulls.
+    ///
+    /// None for other arrays and those entirely null.
+    fn values(&self) -> Option<&[Self::Data]> {
+        None
+    }
+
     /// Returns true if the array is completely empty.
     fn is_empty(&self) -> bool {
         self.len() == 0
@@ -71,161 +254,2781 @@ pub trait Array:
     {
         CopiedIter::new(self)
     }
-}
 
-pub struct Iter<'a, T: Array> {
-    array: &'a T,
-    idx: usize,
-}
+    /// Returns an iterator over the values in the array along with their
+    /// indices, as with `iter().enumerate()`.
+    fn enumerate_iter(&self) -> Enumerate<Iter<'_, Self>> {
+        self.iter().enumerate()
+    }
 
-impl<'a, T> Iter<'a, T>
-where
-    T: Array,
-{
-    fn new(array: &'a T) -> Self {
-        Self { array, idx: 0 }
+    /// Returns an iterator over copied array values along with their
+    /// indices, as with `copied_iter().enumerate()`.
+    fn enumerate_copied_iter(&self) -> Enumerate<CopiedIter<'_, Self>>
+    where
+        Self::Data: Copy,
+    {
+        self.copied_iter().enumerate()
     }
-}
 
-impl<'a, T> Iterator for Iter<'a, T>
-where
-    T: Array,
-{
-    type Item = Option<T::Ref<'a>>;
+    /// Returns a copy of the array with its elements in reverse order.
+    fn reverse(&self) -> Self {
+        let len = self.len();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let idx = self.idx;
-        self.idx += 1;
-        if idx >= self.array.len() {
-            None
-        } else {
-            Some(self.array.get_ref(idx))
+        Self::new((0..len).map(|idx| self.get(len - 1 - idx)))
+    }
+
+    /// Splits the array into copies of the elements before `mid` and those
+    /// from `mid` onwards.
+    ///
+    /// Errors if `mid` is greater than the length of the array.
+    fn split_at(&self, mid: usize) -> Result<(Self, Self), ArrayError> {
+        if mid > self.len() {
+            return Err(ArrayError::IndexOutOfBounds {
+                idx: mid,
+                len: self.len(),
+            });
         }
+
+        let head = Self::new((0..mid).map(|idx| self.get(idx)));
+        let tail = Self::new((mid..self.len()).map(|idx| self.get(idx)));
+
+        Ok((head, tail))
     }
 
-    fn count(self) -> usize
+    /// Returns `n` elements picked uniformly at random without
+    /// replacement, in their original order.
+    ///
+    /// The same `seed` always picks the same elements. Returns a copy of
+    /// the whole array if `n` is at least its length.
+    fn sample(&self, n: usize, seed: u64) -> Self {
+        let indices = sample_indices(self.len(), n, seed);
+
+        Self::new(indices.into_iter().map(|idx| self.get(idx)))
+    }
+
+    /// Returns every `n`th element, starting with the first.
+    ///
+    /// Panics if `n` is 0.
+    fn every_nth(&self, n: usize) -> Self {
+        let indices = every_nth_indices(self.len(), n);
+
+        Self::new(indices.into_iter().map(|idx| self.get(idx)))
+    }
+
+    /// Collapses runs of consecutive equal elements into one, as with
+    /// `Vec::dedup`. Only neighbouring elements are compared, so only sorted
+    /// arrays are left without duplicates.
+    ///
+    /// See [`Array::dedup_with_indices`] for the treatment of nulls and NaNs.
+    fn dedup(&self) -> Self
     where
-        Self: Sized,
+        Self::Data: PartialEq,
     {
-        self.array.len() - self.idx
+        self.dedup_with_indices().0
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.array.len() - self.idx;
-        (len, Some(len))
+    /// Collapses runs of consecutive equal elements into their first
+    /// element, returning the result with the indices of the kept elements.
+    ///
+    /// Consecutive nulls form a run like any other value, while as with
+    /// `==` NaNs never do. Taking the indices from other arrays of the same
+    /// length keeps them aligned with the result.
+    fn dedup_with_indices(&self) -> (Self, ArrayUSize)
+    where
+        Self::Data: PartialEq,
+    {
+        let kept = (0..self.len())
+            .filter(|idx| *idx == 0 || self.get(*idx) != self.get(idx - 1))
+            .collect::<Vec<_>>();
+
+        let values = kept.iter().map(|idx| self.get(*idx)).collect::<Vec<_>>();
+        let indices = ArrayUSize::new(kept.into_iter().map(Some));
+
+        (Self::new(values), indices)
     }
-}
 
-impl<'a, T> ExactSizeIterator for Iter<'a, T>
-where
-    T: Array,
-{
-    fn len(&self) -> usize {
-        self.array.len() - self.idx
+    /// Returns a copy of the array with each null replaced by the closest
+    /// valid element before it. Leading nulls are left as they are.
+    fn fill_null_forward(&self) -> Self {
+        let mut last = None;
+
+        let values = (0..self.len())
+            .map(|idx| {
+                if !self.check_null(idx) {
+                    last = Some(idx);
+                }
+
+                last.and_then(|last| self.get(last))
+            })
+            .collect::<Vec<_>>();
+
+        Self::new(values)
     }
-}
 
-pub struct CopiedIter<'a, T: Array> {
-    array: &'a T,
-    idx: usize,
-}
+    /// Returns a copy of the array with each null replaced by the closest
+    /// valid element after it. Trailing nulls are left as they are.
+    fn fill_null_backward(&self) -> Self {
+        let mut next = None;
 
-impl<'a, T> CopiedIter<'a, T>
-where
-    T: Array,
-{
-    fn new(array: &'a T) -> Self {
-        Self { array, idx: 0 }
+        let mut values = (0..self.len())
+            .rev()
+            .map(|idx| {
+                if !self.check_null(idx) {
+                    next = Some(idx);
+                }
+
+                next.and_then(|next| self.get(next))
+            })
+            .collect::<Vec<_>>();
+        values.reverse();
+
+        Self::new(values)
     }
-}
 
-impl<'a, T> Iterator for CopiedIter<'a, T>
-where
-    T: Array,
-{
-    type Item = Option<T::Data>;
+    /// Returns an array holding the elements of `self` repeated `n` times.
+    fn repeat(&self, n: usize) -> Self {
+        let len = self.len();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let idx = self.idx;
-        self.idx += 1;
+        Self::new((0..len * n).map(|idx| self.get(idx % len)))
+    }
 
-        if idx >= self.array.len() {
-            None
-        } else {
-            Some(self.array.get(idx))
+    /// Returns an array holding the elements of `self` followed by those of
+    /// `other`.
+    fn concat(&self, other: &Self) -> Self {
+        let len = self.len();
+
+        let values = (0..len + other.len()).map(|idx| match idx < len {
+            true => self.get(idx),
+            false => other.get(idx - len),
+        });
+
+        Self::new(values)
+    }
+
+    /// Returns a copy of the `len` elements starting at `offset`.
+    ///
+    /// Errors if the range goes past the end of the array.
+    fn slice(&self, offset: usize, len: usize) -> Result<Self, ArrayError> {
+        let end = offset.saturating_add(len);
+
+        if end > self.len() {
+            return Err(ArrayError::IndexOutOfBounds {
+                idx: end,
+                len: self.len(),
+            });
         }
+
+        Ok(Self::new((offset..end).map(|idx| self.get(idx))))
     }
 
-    fn count(self) -> usize
+    /// Returns a copy of the elements whose counterpart in `mask` is true.
+    /// Nulls in `mask` count as false.
+    ///
+    /// Errors if `mask` differs in length from the array.
+    fn filter(&self, mask: &ArrayBoolean) -> Result<Self, ArrayError> {
+        if mask.len() != self.len() {
+            return Err(ArrayError::LengthMismatch {
+                left: self.len(),
+                right: mask.len(),
+            });
+        }
+
+        let values = (0..self.len())
+            .filter(|idx| mask.get(*idx) == Some(true))
+            .map(|idx| self.get(idx))
+            .collect::<Vec<_>>();
+
+        Ok(Self::new(values))
+    }
+
+    /// Splits the array into copies of the elements satisfying `pred` and
+    /// those that do not, each keeping their relative order. Nulls are
+    /// passed to `pred` as None.
+    ///
+    /// Unlike [`Array::filter`], both halves are kept.
+    fn partition_by<'a, F>(&'a self, pred: F) -> (Self, Self)
     where
-        Self: Sized,
+        F: Fn(Option<Self::Ref<'a>>) -> bool,
     {
-        self.array.len() - self.idx
+        let (kept, rest): (Vec<_>, Vec<_>) =
+            (0..self.len()).partition(|idx| pred(self.get_ref(*idx)));
+
+        (
+            Self::new(kept.into_iter().map(|idx| self.get(idx))),
+            Self::new(rest.into_iter().map(|idx| self.get(idx))),
+        )
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.array.len() - self.idx;
-        (len, Some(len))
+    /// Returns a copy of the elements at `indices`, in order. Null indices
+    /// produce nulls.
+    ///
+    /// Errors if any index is out of bounds.
+    fn take(&self, indices: &ArrayUSize) -> Result<Self, ArrayError> {
+        let values = indices
+            .copied_iter()
+            .map(|idx| match idx {
+                Some(idx) => check_bounds(self, idx).map(|_| self.get(idx)),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(values))
     }
-}
 
-impl<'a, T> ExactSizeIterator for CopiedIter<'a, T>
-where
-    T: Array,
-{
-    fn len(&self) -> usize {
-        self.array.len() - self.idx
+    /// Returns true if any element of the array equals `value`.
+    fn contains<'a>(&'a self, value: Self::Ref<'a>) -> bool
+    where
+        Self::Ref<'a>: PartialEq,
+    {
+        self.index_of(value).is_some()
     }
-}
 
-pub struct IntoIter<T: Array> {
-    array: T,
-    idx: usize,
-}
+    /// Returns the index of the first element equal to `value`, if any.
+    ///
+    /// Nulls never match. As with `==`, a NaN is never found in float
+    /// arrays.
+    fn index_of<'a>(&'a self, value: Self::Ref<'a>) -> Option<usize>
+    where
+        Self::Ref<'a>: PartialEq,
+    {
+        self.index_of_from(value, 0)
+    }
 
-impl<T> IntoIter<T>
-where
-    T: Array,
-{
-    pub fn new(array: T) -> Self {
-        Self { array, idx: 0 }
+    /// Returns the index of the first element at or after `idx` equal to
+    /// `value`, if any.
+    fn index_of_from<'a>(&'a self, value: Self::Ref<'a>, idx: usize) -> Option<usize>
+    where
+        Self::Ref<'a>: PartialEq,
+    {
+        (idx..self.len())
+            .find(|idx| !self.check_null(*idx) && self.get_ref(*idx).as_ref() == Some(&value))
     }
-}
 
-impl<T> Iterator for IntoIter<T>
-where
-    T: Array,
-{
-    type Item = Option<T::Data>;
+    /// Returns the index of the first non-null element satisfying `pred`,
+    /// if any, without looking at the elements past it.
+    fn find_first<'a, F>(&'a self, pred: F) -> Option<usize>
+    where
+        F: Fn(Self::Ref<'a>) -> bool,
+    {
+        (0..self.len()).find(|idx| satisfies(self, *idx, &pred))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let idx = self.idx;
-        self.idx += 1;
-        if idx >= self.array.len() {
-            None
-        } else {
-            Some(self.array.get(idx))
-        }
+    /// Returns the index of the last non-null element satisfying `pred`,
+    /// if any.
+    fn find_last<'a, F>(&'a self, pred: F) -> Option<usize>
+    where
+        F: Fn(Self::Ref<'a>) -> bool,
+    {
+        (0..self.len())
+            .rev()
+            .find(|idx| satisfies(self, *idx, &pred))
     }
 
-    fn count(self) -> usize
+    /// Returns the indices of every non-null element satisfying `pred`, in
+    /// order.
+    fn find_all<'a, F>(&'a self, pred: F) -> ArrayUSize
     where
-        Self: Sized,
+        F: Fn(Self::Ref<'a>) -> bool,
     {
-        self.array.len() - self.idx
+        let indices = (0..self.len())
+            .filter(|idx| satisfies(self, *idx, &pred))
+            .collect::<Vec<_>>();
+
+        ArrayUSize::from(indices)
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.array.len() - self.idx;
-        (len, Some(len))
+    /// Applies `f` to every non null element, returning the results as a
+    /// primitive array. Nulls stay null.
+    fn map<'a, U, F>(&'a self, f: F) -> U::Array
+    where
+        U: NativeType,
+        F: Fn(Self::Ref<'a>) -> U,
+    {
+        self.map_into(f)
     }
-}
 
-impl<T> ExactSizeIterator for IntoIter<T>
-where
-    T: Array,
-{
-    fn len(&self) -> usize {
-        self.array.len() - self.idx
+    /// Applies `f` to every non null element, returning the results as an
+    /// array of any type. Nulls stay null.
+    fn map_into<'a, A, F>(&'a self, f: F) -> A
+    where
+        A: Array,
+        F: Fn(Self::Ref<'a>) -> A::Data,
+    {
+        let values = (0..self.len()).map(|idx| match self.check_null(idx) {
+            true => None,
+            false => self.get_ref(idx).map(&f),
+        });
+
+        A::new(values)
+    }
+
+    /// Fallible version of [`Array::map`], stopping at the first error.
+    fn try_map<'a, U, E, F>(&'a self, f: F) -> Result<U::Array, MapError<E>>
+    where
+        U: NativeType,
+        F: Fn(Self::Ref<'a>) -> Result<U, E>,
+    {
+        self.try_map_into(f)
+    }
+
+    /// Fallible version of [`Array::map_into`], stopping at the first
+    /// error.
+    fn try_map_into<'a, A, E, F>(&'a self, f: F) -> Result<A, MapError<E>>
+    where
+        A: Array,
+        F: Fn(Self::Ref<'a>) -> Result<A::Data, E>,
+    {
+        let values = (0..self.len())
+            .map(|idx| {
+                if self.check_null(idx) {
+                    return Ok(None);
+                }
+
+                self.get_ref(idx)
+                    .map(&f)
+                    .transpose()
+                    .map_err(|error| MapError { idx, error })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(A::new(values))
+    }
+
+    /// Returns the dot product of `self` and `other`, the sum of the
+    /// products of their elements, skipping positions where either is null.
+    ///
+    /// Returns None if no position is non-null in both arrays. Integer
+    /// arithmetic wraps around on overflow, see [`Array::dot_f64`].
+    ///
+    /// Panics if both arrays differ in length.
+    fn dot(&self, other: &Self) -> Option<Self::Data>
+    where
+        Self::Data: Numeric,
+    {
+        dot_pairs(self, other)
+            .map(|(lhs, rhs)| lhs.mul_wrapping(rhs))
+            .reduce(Numeric::add_wrapping)
+    }
+
+    /// Returns the dot product of `self` and `other` computed with `f64`s,
+    /// so integer arrays cannot overflow.
+    ///
+    /// See [`Array::dot`].
+    fn dot_f64(&self, other: &Self) -> Option<f64>
+    where
+        Self::Data: Numeric,
+    {
+        dot_pairs(self, other)
+            .map(|(lhs, rhs)| lhs.as_f64() * rhs.as_f64())
+            .reduce(|acc, val| acc + val)
+    }
+
+    /// Counts the non-null elements falling in each of `bins` equally wide
+    /// bins spanning the least to the greatest element, returning the
+    /// `bins + 1` bin edges alongside the counts.
+    ///
+    /// Bins include their lower edge and the last its upper edge too. If
+    /// every element is equal they all fall in the first bin, and if there
+    /// are none the bins span `[0, 1]`. Float `NaN`s are ignored.
+    ///
+    /// Panics if `bins` is 0.
+    fn histogram(&self, bins: usize) -> (ArrayF64, ArrayUSize)
+    where
+        Self::Data: Numeric,
+    {
+        assert!(bins != 0, "Tried to compute a histogram with 0 bins");
+
+        let values = self
+            .copied_iter()
+            .flatten()
+            .map(Numeric::as_f64)
+            .filter(|value| !value.is_nan())
+            .collect::<Vec<_>>();
+
+        let (min, max) = values
+            .iter()
+            .fold(None, |acc: Option<(f64, f64)>, value| match acc {
+                None => Some((*value, *value)),
+                Some((min, max)) => Some((min.min(*value), max.max(*value))),
+            })
+            .unwrap_or((0.0, 1.0));
+
+        let mut counts = vec![0; bins];
+        let width = (max - min) / bins as f64;
+
+        for value in values {
+            let bin = match width > 0.0 {
+                true => (((value - min) / width) as usize).min(bins - 1),
+                false => 0,
+            };
+            counts[bin] += 1;
+        }
+
+        (
+            ArrayF64::linspace(min, max, bins + 1),
+            ArrayUSize::from(counts),
+        )
+    }
+
+    /// Returns true if the non-null elements are in non-decreasing order,
+    /// nulls being skipped over. Elements unordered with themselves, such
+    /// as float `NaN`s, are never sorted.
+    fn is_sorted(&self) -> bool
+    where
+        Self::Data: PartialOrd,
+    {
+        is_ordered(self, Ordering::is_le)
+    }
+
+    /// Returns true if the non-null elements are in non-increasing order.
+    ///
+    /// See [`Array::is_sorted`].
+    fn is_sorted_descending(&self) -> bool
+    where
+        Self::Data: PartialOrd,
+    {
+        is_ordered(self, Ordering::is_ge)
+    }
+
+    /// Returns the index of the least non-null element, the first if several
+    /// tie, or None if every element is null.
+    ///
+    /// Elements unordered with themselves, such as float `NaN`s, are ignored.
+    fn argmin(&self) -> Option<usize>
+    where
+        Self::Data: PartialOrd,
+    {
+        extreme_index(self, Ordering::Less)
+    }
+
+    /// Returns the index of the greatest non-null element, the first if
+    /// several tie, or None if every element is null.
+    ///
+    /// See [`Array::argmin`].
+    fn argmax(&self) -> Option<usize>
+    where
+        Self::Data: PartialOrd,
+    {
+        extreme_index(self, Ordering::Greater)
+    }
+
+    /// Returns the greatest non-null element of each window of `window`
+    /// elements ending at every position.
+    ///
+    /// The first `window - 1` positions, lacking a full window, are null as
+    /// are windows of nulls. Elements unordered with themselves, such as
+    /// float `NaN`s, are ignored. Runs in linear time regardless of
+    /// `window` by keeping the candidate maxima in a monotone deque.
+    ///
+    /// Panics if `window` is 0.
+    fn sliding_max(&self, window: usize) -> Self
+    where
+        Self::Data: PartialOrd + Clone,
+    {
+        sliding_extreme(self, window, Ordering::Greater)
+    }
+
+    /// Returns the least non-null element of each window of `window`
+    /// elements ending at every position.
+    ///
+    /// See [`Array::sliding_max`].
+    fn sliding_min(&self, window: usize) -> Self
+    where
+        Self::Data: PartialOrd + Clone,
+    {
+        sliding_extreme(self, window, Ordering::Less)
+    }
+
+    /// Returns the minimum, maximum, null count, distinct count estimate
+    /// and sum of the array, computed in a single pass.
+    ///
+    /// See [`ArrayStatistics`].
+    fn statistics(&self) -> ArrayStatistics
+    where
+        Self::Data: Into<Scalar>,
+    {
+        ArrayStatistics::from_values((0..self.len()).map(|idx| self.get(idx).map(Into::into)))
+    }
+
+    /// Compares `self` and `other` element by element, returning the first
+    /// difference under [`TotalOrd`].
+    ///
+    /// Nulls are ordered before any value and an array before the longer
+    /// arrays it is a prefix of.
+    fn lex_cmp(&self, other: &Self) -> Ordering
+    where
+        Self::Data: TotalOrd,
+    {
+        for idx in 0..self.len().min(other.len()) {
+            let ordering = match (self.get(idx), other.get(idx)) {
+                (Some(lhs), Some(rhs)) => lhs.total_order(&rhs),
+                (lhs, rhs) => lhs.is_some().cmp(&rhs.is_some()),
+            };
+
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        self.len().cmp(&other.len())
+    }
+}
+
+/// Returns true if every pair of consecutive non-null elements of `array`
+/// is ordered as `wanted`, in a single pass.
+fn is_ordered<A>(array: &A, wanted: fn(Ordering) -> bool) -> bool
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    let mut last: Option<A::Data> = None;
+
+    for value in (0..array.len()).filter_map(|idx| array.get(idx)) {
+        if value.partial_cmp(&value).is_none() {
+            return false;
+        }
+
+        if last.is_some_and(|last| !last.partial_cmp(&value).is_some_and(wanted)) {
+            return false;
+        }
+
+        last = Some(value);
+    }
+
+    true
+}
+
+/// Returns true if the element of `array` at `idx` is non-null and
+/// satisfies `pred`.
+fn satisfies<'a, A, F>(array: &'a A, idx: usize, pred: &F) -> bool
+where
+    A: Array,
+    F: Fn(A::Ref<'a>) -> bool,
+{
+    !array.check_null(idx) && array.get_ref(idx).is_some_and(pred)
+}
+
+/// Returns the index of the first element of `array` which no other element
+/// is ordered `wanted` to, in a single pass.
+fn extreme_index<A>(array: &A, wanted: Ordering) -> Option<usize>
+where
+    A: Array,
+    A::Data: PartialOrd,
+{
+    let mut extreme: Option<(usize, A::Data)> = None;
+
+    for idx in 0..array.len() {
+        let Some(value) = array.get(idx) else {
+            continue;
+        };
+
+        if value.partial_cmp(&value).is_none() {
+            continue;
+        }
+
+        match &extreme {
+            Some((_, current)) if value.partial_cmp(current) != Some(wanted) => {}
+            _ => extreme = Some((idx, value)),
+        }
+    }
+
+    extreme.map(|(idx, _)| idx)
+}
+
+/// Returns the element of each window of `array` which no other element of
+/// the window is ordered `wanted` to.
+fn sliding_extreme<A>(array: &A, window: usize, wanted: Ordering) -> A
+where
+    A: Array,
+    A::Data: PartialOrd + Clone,
+{
+    assert!(window != 0, "Tried to slide a window of 0 elements");
+
+    // Candidates in window order, each ordered `wanted` to all after it
+    let mut candidates: VecDeque<(usize, A::Data)> = VecDeque::new();
+
+    let values = (0..array.len()).map(|idx| {
+        let value = array
+            .get(idx)
+            .filter(|value| value.partial_cmp(value).is_some());
+
+        if let Some(value) = value {
+            while candidates
+                .back()
+                .is_some_and(|(_, back)| value.partial_cmp(back) != Some(wanted.reverse()))
+            {
+                candidates.pop_back();
+            }
+
+            candidates.push_back((idx, value));
+        }
+
+        while candidates
+            .front()
+            .is_some_and(|(front, _)| front + window <= idx)
+        {
+            candidates.pop_front();
+        }
+
+        match idx + 1 < window {
+            true => None,
+            false => candidates.front().map(|(_, value)| value.clone()),
+        }
+    });
+
+    A::new(values.collect::<Vec<_>>())
+}
+
+/// Returns the pairs of elements at the same index of `lhs` and `rhs` where
+/// neither is null.
+///
+/// Panics if the arrays differ in length.
+fn dot_pairs<'a, A: Array>(
+    lhs: &'a A,
+    rhs: &'a A,
+) -> impl Iterator<Item = (A::Data, A::Data)> + 'a {
+    assert_eq!(
+        lhs.len(),
+        rhs.len(),
+        "Tried to compute the dot product of arrays with lengths {} and {}",
+        lhs.len(),
+        rhs.len()
+    );
+
+    (0..lhs.len()).filter_map(|idx| Some((lhs.get(idx)?, rhs.get(idx)?)))
+}
+
+fn check_bounds<A: Array>(array: &A, idx: usize) -> Result<(), ArrayError> {
+    match idx < array.len() {
+        true => Ok(()),
+        false => Err(ArrayError::IndexOutOfBounds {
+            idx,
+            len: array.len(),
+        }),
+    }
+}
+
+/// Errors raised by fallible array operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayError {
+    IndexOutOfBounds {
+        idx: usize,
+        len: usize,
+    },
+    /// A value was required at `idx` but it is null.
+    NullAtIndex {
+        idx: usize,
+    },
+    LengthMismatch {
+        left: usize,
+        right: usize,
+    },
+    TypeMismatch {
+        expected: DataType,
+        got: DataType,
+    },
+    InvalidUtf8(std::str::Utf8Error),
+    AllocationFailed,
+}
+
+impl Display for ArrayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IndexOutOfBounds { idx, len } => {
+                write!(f, "Tried to index {idx} when array length is {len}")
+            }
+            Self::NullAtIndex { idx } => write!(f, "Value at index {idx} is null"),
+            Self::LengthMismatch { left, right } => {
+                write!(f, "Array lengths {left} and {right} differ")
+            }
+            Self::TypeMismatch { expected, got } => {
+                write!(f, "Expected an array of {expected:?} but got {got:?}")
+            }
+            Self::InvalidUtf8(error) => write!(f, "Invalid UTF-8: {error}"),
+            Self::AllocationFailed => write!(f, "Failed to allocate array buffers"),
+        }
+    }
+}
+
+impl Error for ArrayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidUtf8(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::str::Utf8Error> for ArrayError {
+    fn from(value: std::str::Utf8Error) -> Self {
+        Self::InvalidUtf8(value)
+    }
+}
+
+/// Error returned by [`Array::try_map`], holding the index of the element
+/// which failed to map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapError<E> {
+    pub idx: usize,
+    pub error: E,
+}
+
+impl<E: Display> Display for MapError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to map element {}: {}", self.idx, self.error)
+    }
+}
+
+impl<E: Error + 'static> Error for MapError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+pub struct Iter<'a, T: Array> {
+    array: &'a T,
+    idx: usize,
+}
+
+impl<'a, T> Iter<'a, T>
+where
+    T: Array,
+{
+    fn new(array: &'a T) -> Self {
+        Self { array, idx: 0 }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: Array,
+{
+    type Item = Option<T::Ref<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.idx;
+        self.idx += 1;
+        if idx >= self.array.len() {
+            None
+        } else {
+            Some(self.array.get_ref(idx))
+        }
+    }
+
+    fn count(self) -> usize
+    where
+        Self: Sized,
+    {
+        self.array.len() - self.idx
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.array.len() - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T>
+where
+    T: Array,
+{
+    fn len(&self) -> usize {
+        self.array.len() - self.idx
+    }
+}
+
+pub struct CopiedIter<'a, T: Array> {
+    array: &'a T,
+    idx: usize,
+}
+
+impl<'a, T> CopiedIter<'a, T>
+where
+    T: Array,
+{
+    fn new(array: &'a T) -> Self {
+        Self { array, idx: 0 }
+    }
+}
+
+impl<'a, T> Iterator for CopiedIter<'a, T>
+where
+    T: Array,
+{
+    type Item = Option<T::Data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.idx;
+        self.idx += 1;
+
+        if idx >= self.array.len() {
+            None
+        } else {
+            Some(self.array.get(idx))
+        }
+    }
+
+    fn count(self) -> usize
+    where
+        Self: Sized,
+    {
+        self.array.len() - self.idx
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.array.len() - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for CopiedIter<'a, T>
+where
+    T: Array,
+{
+    fn len(&self) -> usize {
+        self.array.len() - self.idx
+    }
+}
+
+pub struct IntoIter<T: Array> {
+    array: T,
+    idx: usize,
+}
+
+impl<T> IntoIter<T>
+where
+    T: Array,
+{
+    pub fn new(array: T) -> Self {
+        Self { array, idx: 0 }
+    }
+}
+
+impl<T> Iterator for IntoIter<T>
+where
+    T: Array,
+{
+    type Item = Option<T::Data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.idx;
+        self.idx += 1;
+        if idx >= self.array.len() {
+            None
+        } else {
+            Some(self.array.get(idx))
+        }
+    }
+
+    fn count(self) -> usize
+    where
+        Self: Sized,
+    {
+        self.array.len() - self.idx
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.array.len() - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T>
+where
+    T: Array,
+{
+    fn len(&self) -> usize {
+        self.array.len() - self.idx
+    }
+}
+
+/// Iterator over the pairs of elements at the same index of two arrays.
+pub struct ZipIter<'a, A: Array, B: Array> {
+    lhs: &'a A,
+    rhs: &'a B,
+    idx: usize,
+}
+
+impl<'a, A, B> Iterator for ZipIter<'a, A, B>
+where
+    A: Array,
+    B: Array,
+{
+    type Item = (Option<A::Ref<'a>>, Option<B::Ref<'a>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.idx;
+
+        if idx >= self.lhs.len() {
+            return None;
+        }
+
+        self.idx += 1;
+
+        Some((self.lhs.get_ref(idx), self.rhs.get_ref(idx)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.lhs.len() - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl<'a, A, B> ExactSizeIterator for ZipIter<'a, A, B>
+where
+    A: Array,
+    B: Array,
+{
+    fn len(&self) -> usize {
+        self.lhs.len() - self.idx
+    }
+}
+
+/// Returns an iterator over the pairs of elements at the same index of
+/// `lhs` and `rhs`.
+///
+/// Errors if the arrays differ in length.
+pub fn zip_iter<'a, A, B>(lhs: &'a A, rhs: &'a B) -> Result<ZipIter<'a, A, B>, ArrayError>
+where
+    A: Array,
+    B: Array,
+{
+    if lhs.len() != rhs.len() {
+        return Err(ArrayError::LengthMismatch {
+            left: lhs.len(),
+            right: rhs.len(),
+        });
+    }
+
+    Ok(ZipIter { lhs, rhs, idx: 0 })
+}
+
+/// Returns an array picking, for each `(array, idx)` pair of `indices`,
+/// the element at `idx` of `arrays[array]`, nulls included.
+///
+/// Errors if an array or element index is out of bounds.
+pub fn interleave<A: Array>(arrays: &[&A], indices: &[(usize, usize)]) -> Result<A, ArrayError> {
+    let values = indices
+        .iter()
+        .map(|&(array, idx)| {
+            let array = arrays.get(array).ok_or(ArrayError::IndexOutOfBounds {
+                idx: array,
+                len: arrays.len(),
+            })?;
+
+            array.try_get(idx)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(A::new(values))
+}
+
+/// Applies `f` to the pairs of elements at the same index of `lhs` and
+/// `rhs`. The result is null wherever either side is null.
+///
+/// Errors if the arrays differ in length.
+pub fn zip_map<'a, A, B, C, F>(lhs: &'a A, rhs: &'a B, f: F) -> Result<C, ArrayError>
+where
+    A: Array,
+    B: Array,
+    C: Array,
+    F: Fn(A::Ref<'a>, B::Ref<'a>) -> C::Data,
+{
+    let pairs = zip_iter(lhs, rhs)?;
+    let len = lhs.len();
+    let validity = BitmapBuffer::intersect(
+        validity_bitmap(lhs, len).as_deref(),
+        validity_bitmap(rhs, len).as_deref(),
+        len,
+    );
+
+    let values = pairs.enumerate().map(|(idx, pair)| {
+        if validity
+            .as_ref()
+            .is_some_and(|validity| !validity.is_set(idx))
+        {
+            return None;
+        }
+
+        match pair {
+            (Some(left), Some(right)) => Some(f(left, right)),
+            _ => None,
+        }
+    });
+
+    Ok(C::new(values))
+}
+
+/// Returns the number of values counting from `start` up to, but
+/// excluding, `stop` in increments of `step`.
+///
+/// Panics if `step` is 0 or leads away from `stop`.
+pub(crate) fn integer_steps(start: i128, stop: i128, step: i128) -> usize {
+    assert!(step != 0, "Tried to create a range with a step of 0");
+
+    let span = stop - start;
+    assert!(
+        span == 0 || span.signum() == step.signum(),
+        "Tried to count from {start} to {stop} in steps of {step}"
+    );
+
+    ((span.abs() + step.abs() - 1) / step.abs()) as usize
+}
+
+/// Float counterpart of [`integer_steps`].
+pub(crate) fn float_steps(start: f64, stop: f64, step: f64) -> usize {
+    assert!(step != 0.0, "Tried to create a range with a step of 0");
+
+    let steps = (stop - start) / step;
+    assert!(
+        steps >= 0.0,
+        "Tried to count from {start} to {stop} in steps of {step}"
+    );
+
+    steps.ceil() as usize
+}
+
+/// SplitMix64 generator, small and plenty random for sampling and test
+/// data.
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut mixed = self.0;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        mixed ^ (mixed >> 31)
+    }
+
+    /// Returns a float in `0.0..1.0`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Returns true with probability `p`.
+    pub(crate) fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Returns an integer in `0..n`.
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Returns an integer in `range`.
+    ///
+    /// Panics if `range` is empty.
+    pub(crate) fn in_range(&mut self, range: Range<i64>) -> i64 {
+        assert!(
+            range.start < range.end,
+            "Tried to generate values in the empty range {range:?}"
+        );
+
+        range.start + self.below(range.end.abs_diff(range.start)) as i64
+    }
+}
+
+/// Returns `n` distinct indices below `len` picked uniformly at random, in
+/// ascending order, or every index if `n` is at least `len`.
+pub(crate) fn sample_indices(len: usize, n: usize, seed: u64) -> Vec<usize> {
+    if n >= len {
+        return (0..len).collect();
+    }
+
+    // Floyd's algorithm, drawing one index per sampled element
+    let mut rng = Rng(seed);
+    let mut picked = BTreeSet::new();
+
+    for bound in len - n..len {
+        let idx = rng.below(bound as u64 + 1) as usize;
+
+        if !picked.insert(idx) {
+            picked.insert(bound);
+        }
+    }
+
+    picked.into_iter().collect()
+}
+
+/// Returns the indices below `len` which are multiples of `n`.
+///
+/// Panics if `n` is 0.
+pub(crate) fn every_nth_indices(len: usize, n: usize) -> Vec<usize> {
+    assert!(n != 0, "Tried to take every 0th element");
+
+    (0..len).step_by(n).collect()
+}
+
+/// Total order over the values of an array, where floats follow
+/// [`f64::total_cmp`].
+pub trait TotalOrd {
+    fn total_order(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! total_ord {
+    ($($kind:ty => $cmp:path),* $(,)?) => {
+        $(
+            impl TotalOrd for $kind {
+                fn total_order(&self, other: &Self) -> Ordering {
+                    $cmp(self, other)
+                }
+            }
+        )*
+    };
+}
+
+total_ord! {
+    i32 => Ord::cmp,
+    u32 => Ord::cmp,
+    isize => Ord::cmp,
+    usize => Ord::cmp,
+    bool => Ord::cmp,
+    String => Ord::cmp,
+    f32 => f32::total_cmp,
+    f64 => f64::total_cmp,
+}
+
+/// Native types backing the fixed sized primitive arrays.
+pub trait NativeType: Copy + PartialOrd + Debug + Default {
+    /// The array storing values of this type.
+    type Array: Array<Data = Self>;
+}
+
+impl NativeType for i32 {
+    type Array = ArrayI32;
+}
+
+impl NativeType for u32 {
+    type Array = ArrayU32;
+}
+
+impl NativeType for isize {
+    type Array = ArrayISize;
+}
+
+impl NativeType for usize {
+    type Array = ArrayUSize;
+}
+
+impl NativeType for f32 {
+    type Array = ArrayF32;
+}
+
+impl NativeType for f64 {
+    type Array = ArrayF64;
+}
+
+/// Native numeric types backing the fixed sized primitive arrays.
+pub trait Numeric: NativeType {
+    /// Returns `self % rhs`, or None if the remainder is undefined.
+    ///
+    /// For integers a zero `rhs` is undefined. Overflowing remainders
+    /// such as `i32::MIN % -1` are mathematically zero and are returned
+    /// as such.
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+
+    /// Returns the least nonnegative remainder of `self (mod rhs)`, or None
+    /// if the remainder is undefined.
+    ///
+    /// Follows the same rules as [`Numeric::checked_rem`] for undefined and
+    /// overflowing remainders.
+    fn checked_rem_euclid(self, rhs: Self) -> Option<Self>;
+
+    /// Returns `self + rhs`, wrapping around on integer overflow.
+    fn add_wrapping(self, rhs: Self) -> Self;
+
+    /// Returns `self * rhs`, wrapping around on integer overflow.
+    fn mul_wrapping(self, rhs: Self) -> Self;
+
+    /// Returns `self + rhs`, or None if integer addition overflows.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Returns `self - rhs`, or None if integer subtraction overflows.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Returns `self * rhs`, or None if integer multiplication overflows.
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    /// Returns `self / rhs`, or None for an integer division by zero or one
+    /// which overflows. Float division follows IEEE 754.
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+
+    /// Returns the value as the nearest `f64`.
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! numeric_integer {
+    ($($kind:ty),*) => {
+        $(
+            impl Numeric for $kind {
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    if rhs == 0 {
+                        return None;
+                    }
+
+                    Some(self.wrapping_rem(rhs))
+                }
+
+                fn checked_rem_euclid(self, rhs: Self) -> Option<Self> {
+                    if rhs == 0 {
+                        return None;
+                    }
+
+                    Some(self.wrapping_rem_euclid(rhs))
+                }
+
+                fn add_wrapping(self, rhs: Self) -> Self {
+                    self.wrapping_add(rhs)
+                }
+
+                fn mul_wrapping(self, rhs: Self) -> Self {
+                    self.wrapping_mul(rhs)
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$kind>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$kind>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$kind>::checked_mul(self, rhs)
+                }
+
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$kind>::checked_div(self, rhs)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! numeric_float {
+    ($($kind:ty),*) => {
+        $(
+            impl Numeric for $kind {
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    Some(self % rhs)
+                }
+
+                fn checked_rem_euclid(self, rhs: Self) -> Option<Self> {
+                    Some(self.rem_euclid(rhs))
+                }
+
+                fn add_wrapping(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn mul_wrapping(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    Some(self + rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    Some(self - rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    Some(self * rhs)
+                }
+
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    Some(self / rhs)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+numeric_integer!(i32, u32, isize, usize);
+numeric_float!(f32, f64);
+
+#[cfg(test)]
+thread_local! {
+    /// The number of fully valid words the aggregate kernels have read
+    /// without checking individual bits.
+    pub(crate) static VALID_WORDS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Returns the validity buffer and null count of an array of length `len`
+/// whose valid elements are the true ones of `mask`, nulls counting as
+/// false.
+///
+/// As for arrays built from values, the buffer is None when there are no
+/// nulls or only nulls.
+///
+/// Panics if `mask` is not of length `len`
+pub(crate) fn validity_from_mask(len: usize, mask: &ArrayBoolean) -> (Option<BitmapBuffer>, usize) {
+    assert!(
+        len == mask.len(),
+        "Tried to set a validity of length {} on an array of length {}",
+        mask.len(),
+        len
+    );
+
+    let validity = mask.true_bits();
+    let nulls = len - validity.count_ones();
+
+    match nulls {
+        0 => (None, 0),
+        nulls if nulls == len => (None, nulls),
+        nulls => (Some(validity), nulls),
+    }
+}
+
+/// Returns the validity of `array` as `len` bits, for combining with
+/// [`BitmapBuffer::intersect`]. None stands for only valid elements.
+///
+/// A single element array shorter than `len` is broadcast, and arrays
+/// keeping track of their nulls in some other way get a bitmap built.
+pub(crate) fn validity_bitmap<A: Array>(array: &A, len: usize) -> Option<Cow<'_, BitmapBuffer>> {
+    if array.len() != len {
+        return array
+            .check_null(0)
+            .then(|| Cow::Owned(BitmapBuffer::new(len)));
+    }
+
+    if let Some(validity) = array.validity() {
+        return Some(Cow::Borrowed(validity));
+    }
+
+    match array.null_count() {
+        0 => None,
+        _ => {
+            let valid = (0..len).map(|idx| !array.check_null(idx));
+            Some(Cow::Owned(BitmapBuffer::from_bools(valid)))
+        }
+    }
+}
+
+/// Returns an iterator over the validity of `array`, 64 elements at a time.
+pub(crate) fn validity_words<A: Array>(array: &A) -> ValidityWords<'_> {
+    let source = if array.all_null() {
+        WordSource::Null
+    } else if let Some(validity) = array.validity() {
+        WordSource::Bitmap(validity.as_bytes())
+    } else if array.null_count() == 0 {
+        WordSource::Valid
+    } else {
+        WordSource::Each(Box::new(|idx| !array.check_null(idx)))
+    };
+
+    ValidityWords {
+        source,
+        len: array.len(),
+        start: 0,
+    }
+}
+
+/// Returns an iterator over the valid elements of `array`, or None if it
+/// does not expose its values buffer.
+///
+/// See [`Array::values`].
+pub(crate) fn valid_values<A>(array: &A) -> Option<ValidValues<'_, A::Data>>
+where
+    A: Array,
+    A::Data: Copy,
+{
+    let values = match array.values() {
+        Some(values) => values,
+        None if array.all_null() => &[],
+        None => return None,
+    };
+
+    Some(ValidValues {
+        values,
+        words: validity_words(array),
+        dense: 0..0,
+        bits: 0,
+        base: 0,
+    })
+}
+
+enum WordSource<'a> {
+    Bitmap(&'a [u8]),
+    Valid,
+    Null,
+    /// Validity of arrays without a validity buffer, such as [`crate::Union`].
+    Each(Box<dyn Fn(usize) -> bool + 'a>),
+}
+
+/// Iterator over the validity of an array, 64 elements at a time.
+///
+/// Yields the range of indices covered by each word alongside the word,
+/// whose bit `i` is set if the element at `range.start + i` is valid. Bits
+/// past the end of the array are cleared. Lets kernels branch once per
+/// word, skipping null checks for fully valid words and fully null ones
+/// altogether.
+pub(crate) struct ValidityWords<'a> {
+    source: WordSource<'a>,
+    len: usize,
+    /// The index of the first element of the next word
+    start: usize,
+}
+
+impl Iterator for ValidityWords<'_> {
+    type Item = (std::ops::Range<usize>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.len {
+            return None;
+        }
+
+        let range = self.start..self.len.min(self.start + 64);
+        self.start = range.end;
+
+        let word = match &self.source {
+            WordSource::Bitmap(bytes) => {
+                let from = range.start / 8;
+                let to = bytes.len().min(from + 8);
+                let mut word = [0; 8];
+                word[..to - from].copy_from_slice(&bytes[from..to]);
+
+                u64::from_le_bytes(word)
+            }
+            WordSource::Valid => u64::MAX,
+            WordSource::Null => 0,
+            WordSource::Each(is_valid) => range
+                .clone()
+                .enumerate()
+                .filter(|(_, idx)| is_valid(*idx))
+                .fold(0, |word, (bit, _)| word | (1 << bit)),
+        };
+
+        let mask = u64::MAX >> (64 - range.len());
+
+        Some((range, word & mask))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.len - self.start.min(self.len)).div_ceil(64);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ValidityWords<'_> {}
+
+/// Iterator over the valid elements of a values buffer.
+///
+/// Fully valid words are read straight from the buffer while the set bits
+/// of partially valid ones are visited in turn.
+pub(crate) struct ValidValues<'a, T> {
+    values: &'a [T],
+    words: ValidityWords<'a>,
+    /// Remaining indices of the current fully valid word
+    dense: std::ops::Range<usize>,
+    /// Remaining bits of the current partially valid word
+    bits: u64,
+    /// The index of the first element of the current partially valid word
+    base: usize,
+}
+
+impl<T: Copy> Iterator for ValidValues<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(idx) = self.dense.next() {
+                return Some(self.values[idx]);
+            }
+
+            if self.bits != 0 {
+                let idx = self.base + self.bits.trailing_zeros() as usize;
+                self.bits &= self.bits - 1;
+
+                return Some(self.values[idx]);
+            }
+
+            let (range, word) = self.words.next()?;
+
+            if word.count_ones() as usize == range.len() {
+                #[cfg(test)]
+                VALID_WORDS.with(|count| count.set(count.get() + 1));
+
+                self.dense = range;
+            } else {
+                self.bits = word;
+                self.base = range.start;
+            }
+        }
+    }
+}
+
+/// Bit packed buffer of `len` bits, least significant bit first, as used
+/// by the validity buffers of every array.
+pub struct BitmapBuffer {
+    /// Pointer to the bytes of the buffer, None when `len` is 0
+    ptr: Option<NonNull<u8>>,
+    /// The number of bits in the buffer
+    len: usize,
+}
+
+impl BitmapBuffer {
+    /// Creates a [`BitmapBuffer`] of `len` cleared bits.
+    pub fn new(len: usize) -> Self {
+        if len == 0 {
+            return Self { ptr: None, len };
+        }
+
+        let layout = Self::layout(len);
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+
+        match NonNull::new(ptr) {
+            Some(ptr) => Self {
+                ptr: Some(ptr),
+                len,
+            },
+            None => alloc::handle_alloc_error(layout),
+        }
+    }
+
+    /// Creates a [`BitmapBuffer`] with a bit set for every true in `bits`.
+    pub fn from_bools<I>(bits: I) -> Self
+    where
+        I: IntoIterator<Item = bool>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let bits = bits.into_iter();
+        let mut own = Self::new(bits.len());
+
+        for (idx, bit) in bits.enumerate() {
+            if bit {
+                own.set_bit(idx);
+            }
+        }
+
+        own
+    }
+
+    fn layout(len: usize) -> Layout {
+        Layout::from_size_align(len.div_ceil(8), ALIGNMENT)
+            .expect("BitmapBuffer: size overflowed isize::max")
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bytes of the buffer. Bits past `len` are always cleared.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.ptr {
+            Some(ptr) => unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len.div_ceil(8)) },
+            None => &[],
+        }
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        match self.ptr {
+            Some(ptr) => unsafe {
+                std::slice::from_raw_parts_mut(ptr.as_ptr(), self.len.div_ceil(8))
+            },
+            None => &mut [],
+        }
+    }
+
+    fn check_idx(&self, idx: usize) {
+        assert!(
+            idx < self.len,
+            "Tried to index {} when array length is {}",
+            idx,
+            self.len
+        );
+    }
+
+    /// Returns true if the bit at `idx` is set.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn is_set(&self, idx: usize) -> bool {
+        self.check_idx(idx);
+        self.as_bytes()[idx / 8] & (1 << (idx % 8)) != 0
+    }
+
+    /// Panics if `idx` is out of bounds.
+    pub fn set_bit(&mut self, idx: usize) {
+        self.check_idx(idx);
+        self.as_bytes_mut()[idx / 8] |= 1 << (idx % 8);
+    }
+
+    /// Panics if `idx` is out of bounds.
+    pub fn clear_bit(&mut self, idx: usize) {
+        self.check_idx(idx);
+        self.as_bytes_mut()[idx / 8] &= !(1 << (idx % 8));
+    }
+
+    /// Returns the number of set bits.
+    ///
+    /// Whole words are counted 64 bits at a time, which compiles down to a
+    /// single `popcnt` on targets supporting it.
+    pub fn count_ones(&self) -> usize {
+        let Some(ptr) = self.ptr else {
+            return 0;
+        };
+
+        let words = self.len / 64;
+        // The buffer is 8 byte aligned so every whole word is too
+        let mut count = (0..words)
+            .map(|idx| unsafe { ptr::read(ptr.as_ptr().cast::<u64>().add(idx)) })
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>();
+
+        let bytes = &self.as_bytes()[words * 8..];
+
+        if let Some((last, rest)) = bytes.split_last() {
+            count += rest
+                .iter()
+                .map(|byte| byte.count_ones() as usize)
+                .sum::<usize>();
+
+            // Mask out the padding bits past the length
+            let mask = match self.len % 8 {
+                0 => u8::MAX,
+                bits => (1 << bits) - 1,
+            };
+            count += (last & mask).count_ones() as usize;
+        }
+
+        count
+    }
+
+    /// Combines the bytes of `self` and `other` with `f`.
+    ///
+    /// Panics if both buffers differ in length.
+    fn zip_with(&self, other: &Self, f: fn(u8, u8) -> u8) -> Self {
+        assert_eq!(
+            self.len, other.len,
+            "Tried to combine bitmaps with lengths {} and {}",
+            self.len, other.len
+        );
+
+        let mut own = Self::new(self.len);
+        let bytes = self.as_bytes().iter().zip(other.as_bytes());
+
+        for (out, (lhs, rhs)) in own.as_bytes_mut().iter_mut().zip(bytes) {
+            *out = f(*lhs, *rhs);
+        }
+
+        own.clear_padding();
+        own
+    }
+
+    /// Clears the bits of the last byte past `len`.
+    fn clear_padding(&mut self) {
+        let len = self.len;
+
+        if let Some(last) = self.as_bytes_mut().last_mut() {
+            if !len.is_multiple_of(8) {
+                *last &= (1 << (len % 8)) - 1;
+            }
+        }
+    }
+
+    /// Panics if both buffers differ in length.
+    pub fn and(&self, other: &Self) -> Self {
+        self.zip_with(other, |lhs, rhs| lhs & rhs)
+    }
+
+    /// Panics if both buffers differ in length.
+    pub fn or(&self, other: &Self) -> Self {
+        self.zip_with(other, |lhs, rhs| lhs | rhs)
+    }
+
+    /// Panics if both buffers differ in length.
+    pub fn xor(&self, other: &Self) -> Self {
+        self.zip_with(other, |lhs, rhs| lhs ^ rhs)
+    }
+
+    /// Returns a copy of the buffer with every bit flipped.
+    pub fn not(&self) -> Self {
+        self.zip_with(self, |lhs, _| !lhs)
+    }
+
+    /// Returns the validity of `len` elements valid where both `lhs` and
+    /// `rhs` are, as needed by binary kernels. None stands for a buffer of
+    /// only valid elements.
+    ///
+    /// Panics if either buffer is not of length `len`.
+    pub fn intersect(lhs: Option<&Self>, rhs: Option<&Self>, len: usize) -> Option<Self> {
+        Self::check_len(lhs, rhs, len);
+
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => Some(lhs.and(rhs)),
+            (Some(one), None) | (None, Some(one)) => Some(one.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the validity of `len` elements valid where either `lhs` or
+    /// `rhs` is, as when coalescing two arrays. None stands for a buffer of
+    /// only valid elements.
+    ///
+    /// Panics if either buffer is not of length `len`.
+    pub fn union(lhs: Option<&Self>, rhs: Option<&Self>, len: usize) -> Option<Self> {
+        Self::check_len(lhs, rhs, len);
+
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => Some(lhs.or(rhs)),
+            _ => None,
+        }
+    }
+
+    fn check_len(lhs: Option<&Self>, rhs: Option<&Self>, len: usize) {
+        for buffer in [lhs, rhs].into_iter().flatten() {
+            assert!(
+                buffer.len == len,
+                "Tried to combine a bitmap of length {} as one of length {}",
+                buffer.len,
+                len
+            );
+        }
+    }
+}
+
+// SAFETY: The buffer uniquely owns its allocations and only mutates them
+// through `&mut self`, so sharing or sending it across threads is sound.
+unsafe impl Send for BitmapBuffer {}
+unsafe impl Sync for BitmapBuffer {}
+
+impl Drop for BitmapBuffer {
+    fn drop(&mut self) {
+        let Some(ptr) = self.ptr else { return };
+        unsafe { alloc::dealloc(ptr.as_ptr(), Self::layout(self.len)) };
+    }
+}
+
+impl Clone for BitmapBuffer {
+    fn clone(&self) -> Self {
+        let mut own = Self::new(self.len);
+        own.as_bytes_mut().copy_from_slice(self.as_bytes());
+        own
+    }
+}
+
+impl PartialEq for BitmapBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for BitmapBuffer {}
+
+impl Debug for BitmapBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bits = (0..self.len)
+            .map(|idx| if self.is_set(idx) { '1' } else { '0' })
+            .collect::<String>();
+
+        write!(f, "BitmapBuffer [{bits}]")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayBoolean, ArrayText, Union, UnionRef, UnionType};
+
+    /// Checks the slicing helpers on `array` for every split point.
+    fn check<A: Array>(array: &A) {
+        assert_eq!(*array, array.reverse().reverse());
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds {
+                idx: array.len() + 1,
+                len: array.len()
+            }),
+            array.split_at(array.len() + 1)
+        );
+
+        for mid in 0..=array.len() {
+            let (head, tail) = array.split_at(mid).unwrap();
+
+            assert_eq!(mid, head.len());
+            assert_eq!(array.len() - mid, tail.len());
+            assert_eq!(*array, head.concat(&tail));
+        }
+    }
+
+    /// Null patterns applied to arrays of every length in `0..20`.
+    fn patterns() -> impl Iterator<Item = Vec<bool>> {
+        let patterns: [fn(usize) -> bool; 4] = [
+            |_| true,
+            |_| false,
+            |idx| idx % 3 != 1,
+            |idx| idx % 8 == 7 || idx == 2,
+        ];
+
+        (0..20).flat_map(move |len| {
+            patterns
+                .into_iter()
+                .map(move |pattern| (0..len).map(pattern).collect())
+        })
+    }
+
+    #[test]
+    fn test_split_and_reverse() {
+        for valid in patterns() {
+            let values = valid.iter().enumerate();
+
+            check(&ArrayI32::new(
+                values
+                    .clone()
+                    .map(|(idx, valid)| valid.then_some(idx as i32)),
+            ));
+            check(&ArrayF64::new(
+                values
+                    .clone()
+                    .map(|(idx, valid)| valid.then_some(idx as f64 / 3.0)),
+            ));
+            check(&ArrayBoolean::new(
+                values
+                    .clone()
+                    .map(|(idx, valid)| valid.then_some(idx % 3 == 0)),
+            ));
+            check(&ArrayText::new(
+                values
+                    .clone()
+                    .map(|(idx, valid)| valid.then(|| "é".repeat(idx + 1))),
+            ));
+            check(&Union::new(values.map(|(idx, valid)| {
+                valid.then(|| match idx % 2 {
+                    0 => UnionType::USize(idx),
+                    _ => UnionType::Text(idx.to_string()),
+                })
+            })));
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        let one = ArrayI32::from([Some(1), None, Some(3), Some(4), None]);
+        assert_eq!(
+            ArrayI32::from([None, Some(4), Some(3), None, Some(1)]),
+            one.reverse()
+        );
+
+        let two = ArrayText::from([Some("a".into()), None]);
+        assert_eq!(ArrayText::from([None, Some("a".into())]), two.reverse());
+    }
+
+    #[test]
+    fn test_split_at() {
+        let one = ArrayU32::new((0..10).map(|idx| (idx % 4 != 0).then_some(idx)));
+        let (head, tail) = one.split_at(3).unwrap();
+
+        assert_eq!(ArrayU32::from([None, Some(1), Some(2)]), head);
+        assert_eq!(
+            ArrayU32::from([Some(3), None, Some(5), Some(6), Some(7), None, Some(9)]),
+            tail
+        );
+        assert!(one.split_at(10).is_ok());
+        assert!(one.split_at(11).is_err());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let one = ArrayBoolean::from([Some(true), None, Some(false)]);
+
+        assert!(one.repeat(0).is_empty());
+        assert_eq!(one, one.repeat(1));
+        assert_eq!(one.concat(&one).concat(&one), one.repeat(3));
+        assert_eq!(
+            ArrayBoolean::from([Some(true), None, Some(false), Some(true), None, Some(false)]),
+            one.repeat(2)
+        );
+        assert!(ArrayF32::from_vec(vec![]).repeat(5).is_empty());
+    }
+
+    #[test]
+    fn test_index_of() {
+        let one = ArrayI32::from([None, None, Some(4), Some(1), Some(4)]);
+
+        assert_eq!(Some(2), one.index_of(4));
+        assert_eq!(Some(4), one.index_of_from(4, 3));
+        assert_eq!(None, one.index_of_from(4, 5));
+        assert_eq!(None, one.index_of_from(4, 10));
+        assert_eq!(None, one.index_of(0));
+        assert!(one.contains(1));
+        assert!(!one.contains(7));
+
+        let two = ArrayText::from([None, Some("".into()), Some("b".into()), Some("b".into())]);
+        assert_eq!(Some(2), two.index_of("b"));
+        assert_eq!(Some(3), two.index_of_from("b", 3));
+        assert!(!two.contains("a"));
+
+        let three = Union::from_vec(vec![UnionType::Null, UnionType::F32(1.0), UnionType::Null]);
+        assert_eq!(Some(1), three.index_of(UnionRef::F32(1.0)));
+        assert!(!three.contains(UnionRef::Null));
+    }
+
+    #[test]
+    fn test_map() {
+        let celsius = ArrayF64::from([Some(0.0), None, Some(100.0), Some(-40.0)]);
+        let fahrenheit = celsius.map(|value| value * 9.0 / 5.0 + 32.0);
+
+        assert_eq!(
+            ArrayF64::from([Some(32.0), None, Some(212.0), Some(-40.0)]),
+            fahrenheit
+        );
+
+        let lengths = ArrayText::from([Some("Bublé".into()), None, Some("".into())]);
+        assert_eq!(
+            ArrayUSize::from([Some(6), None, Some(0)]),
+            lengths.map(str::len)
+        );
+
+        let upper: ArrayText = lengths.map_into(str::to_uppercase);
+        assert_eq!(
+            ArrayText::from([Some("BUBLÉ".into()), None, Some("".into())]),
+            upper
+        );
+
+        let positive: ArrayBoolean = fahrenheit.map_into(|value| value > 0.0);
+        assert_eq!(
+            ArrayBoolean::from([Some(true), None, Some(true), Some(false)]),
+            positive
+        );
+    }
+
+    #[test]
+    fn test_try_map() {
+        let one = ArrayText::from([Some("1".into()), None, Some("-3".into())]);
+        let two = one.try_map(str::parse::<i32>).unwrap();
+        assert_eq!(ArrayI32::from([Some(1), None, Some(-3)]), two);
+
+        let one = ArrayText::from([Some("1".into()), Some("x".into()), Some("y".into())]);
+        let error = one.try_map(str::parse::<i32>).unwrap_err();
+        assert_eq!(1, error.idx);
+        assert_eq!("x".parse::<i32>().unwrap_err(), error.error);
+
+        let three = ArrayU32::from([Some(1), None, Some(0)]);
+        let error = three
+            .try_map_into::<ArrayText, _, _>(|value| match value {
+                0 => Err("zero"),
+                value => Ok(value.to_string()),
+            })
+            .unwrap_err();
+        assert_eq!(
+            MapError {
+                idx: 2,
+                error: "zero"
+            },
+            error
+        );
+    }
+
+    #[test]
+    fn test_try_get() {
+        let one = ArrayText::from([Some("a".into()), None]);
+
+        assert_eq!(Ok(Some("a".into())), one.try_get(0));
+        assert_eq!(Ok(None), one.try_get(1));
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 2, len: 2 }),
+            one.try_get(2)
+        );
+
+        assert_eq!(Ok(false), one.try_is_null(0));
+        assert_eq!(Ok(true), one.try_is_null(1));
+        assert!(one.try_is_null(5).is_err());
+
+        let two = Union::from_vec(vec![]);
+        assert_eq!(
+            "Tried to index 0 when array length is 0",
+            two.try_get(0).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_zip_iter() {
+        let one = ArrayF64::from([Some(1.0), None, Some(3.0)]);
+        let two = ArrayI32::from([Some(2), Some(4), None]);
+
+        let pairs = zip_iter(&one, &two).unwrap();
+        assert_eq!(3, pairs.len());
+        assert_eq!(
+            vec![(Some(1.0), Some(2)), (None, Some(4)), (Some(3.0), None)],
+            pairs.collect::<Vec<_>>()
+        );
+
+        let three = ArrayI32::from([1]);
+        assert_eq!(
+            Some(ArrayError::LengthMismatch { left: 3, right: 1 }),
+            zip_iter(&one, &three).err()
+        );
+    }
+
+    #[test]
+    fn test_zip_map() {
+        let price = ArrayF64::from([Some(10.0), None, Some(9.0), Some(5.0), None]);
+        let units = ArrayI32::from([Some(4), Some(2), None, Some(0), None]);
+
+        let ratio: ArrayF64 = zip_map(&price, &units, |price, units| price / units as f64).unwrap();
+
+        assert_eq!(
+            ArrayF64::from([Some(2.5), None, None, Some(f64::INFINITY), None]),
+            ratio
+        );
+        assert_eq!(
+            vec![false, true, true, false, true],
+            (0..5).map(|idx| ratio.check_null(idx)).collect::<Vec<_>>()
+        );
+
+        let names = Union::from_vec(vec![UnionType::Null, UnionType::I32(1)]);
+        let flags = ArrayBoolean::from([true, true]);
+        let joined: ArrayText =
+            zip_map(&names, &flags, |name, flag| format!("{name:?} {flag}")).unwrap();
+        assert_eq!(ArrayText::from([None, Some("I32(1) true".into())]), joined);
+
+        let error = zip_map::<_, _, ArrayF64, _>(&price, &flags, |price, _| price);
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn test_byte_width() {
+        assert_eq!(Some(4), DataType::Int32.byte_width());
+        assert_eq!(Some(4), DataType::F32.byte_width());
+        assert_eq!(Some(8), DataType::F64.byte_width());
+        assert_eq!(
+            Some(std::mem::size_of::<usize>()),
+            DataType::USize.byte_width()
+        );
+
+        assert!(DataType::ISize.is_fixed_size());
+        assert!(!DataType::Boolean.is_fixed_size());
+        assert!(!DataType::Text.is_fixed_size());
+        assert!(!DataType::Union.is_fixed_size());
+    }
+
+    #[test]
+    fn test_type_predicates() {
+        use DataType::*;
+
+        let types = [Int32, UInt32, ISize, USize, Boolean, F32, F64, Text, Union];
+        let select = |pred: fn(&DataType) -> bool| {
+            types
+                .iter()
+                .filter(|kind| pred(kind))
+                .copied()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![Int32, UInt32, ISize, USize, F32, F64],
+            select(DataType::is_numeric)
+        );
+        assert_eq!(select(DataType::is_numeric), select(DataType::is_primitive));
+        assert_eq!(
+            vec![Int32, UInt32, ISize, USize],
+            select(DataType::is_integer)
+        );
+        assert_eq!(vec![F32, F64], select(DataType::is_float));
+        assert_eq!(vec![Int32, ISize], select(DataType::is_signed));
+        assert_eq!(vec![UInt32, USize], select(DataType::is_unsigned));
+    }
+
+    #[test]
+    fn test_can_cast_to() {
+        use DataType::*;
+
+        let widen = [
+            (Int32, ISize),
+            (ISize, ISize),
+            (UInt32, USize),
+            (Int32, F64),
+        ];
+        let widen = widen
+            .into_iter()
+            .chain([(UInt32, F64), (F32, F64), (Boolean, UInt32), (F64, Text)])
+            .chain([(Text, Union), (Union, Union), (UInt32, ISize)]);
+
+        for (from, to) in widen {
+            assert!(from.can_cast_to(to), "{from:?} to {to:?}");
+            assert!(from.can_lossy_cast_to(to), "{from:?} to {to:?}");
+        }
+
+        let narrow = [(F64, Int32), (Int32, UInt32), (USize, ISize), (Int32, F32)];
+        let narrow = narrow
+            .into_iter()
+            .chain([(ISize, F64), (F64, F32), (Int32, Boolean), (Text, F64)])
+            .chain([(Union, Boolean), (ISize, Int32)]);
+
+        for (from, to) in narrow {
+            assert!(!from.can_cast_to(to), "{from:?} to {to:?}");
+            assert!(from.can_lossy_cast_to(to), "{from:?} to {to:?}");
+        }
+    }
+
+    #[test]
+    fn test_bitmap_buffer() {
+        let mut one = BitmapBuffer::new(11);
+        assert_eq!(0, one.count_ones());
+
+        one.set_bit(0);
+        one.set_bit(9);
+        one.set_bit(10);
+        one.clear_bit(10);
+        assert!(one.is_set(9) && !one.is_set(10));
+        assert_eq!(&[0b0000_0001, 0b0000_0010], one.as_bytes());
+
+        let two = BitmapBuffer::from_bools((0..11).map(|idx| idx % 3 == 0));
+        assert_eq!(4, two.count_ones());
+
+        assert_eq!(
+            BitmapBuffer::from_bools((0..11).map(|idx| idx == 0 || idx == 9)),
+            one.and(&two)
+        );
+        assert_eq!(4, one.or(&two).count_ones());
+        assert_eq!(2, one.xor(&two).count_ones());
+        // Padding bits past the length stay cleared
+        assert_eq!(9, one.not().count_ones());
+        assert_eq!(&[0b1111_1110, 0b0000_0101], one.not().as_bytes());
+        assert_eq!(one, one.clone().not().not());
+        assert!(BitmapBuffer::new(0).as_bytes().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to combine bitmaps with lengths 3 and 4")]
+    fn test_bitmap_buffer_length_mismatch() {
+        BitmapBuffer::new(3).or(&BitmapBuffer::new(4));
+    }
+
+    #[test]
+    fn test_count_ones() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for len in 0..=200 {
+            let mut one = BitmapBuffer::from_bools((0..len).map(|_| next() % 3 != 0));
+            let naive = (0..len).filter(|idx| one.is_set(*idx)).count();
+
+            assert_eq!(naive, one.count_ones(), "length {len}");
+            assert_eq!(len - naive, one.not().count_ones(), "length {len}");
+
+            // Padding bits are never counted, even if set
+            one.as_bytes_mut().fill(u8::MAX);
+            assert_eq!(len, one.count_ones(), "length {len}");
+        }
+    }
+
+    #[test]
+    fn test_slice_filter_take() {
+        let one = ArrayI32::from([Some(1), None, Some(3), Some(4)]);
+
+        assert_eq!(Ok(ArrayI32::from([None, Some(3)])), one.slice(1, 2));
+        assert_eq!(Ok(ArrayI32::from_vec(vec![])), one.slice(4, 0));
+
+        let mask = ArrayBoolean::from([Some(true), Some(true), None, Some(false)]);
+        assert_eq!(Ok(ArrayI32::from([Some(1), None])), one.filter(&mask));
+
+        let indices = ArrayUSize::from([Some(3), None, Some(3), Some(1)]);
+        assert_eq!(
+            Ok(ArrayI32::from([Some(4), None, Some(4), None])),
+            one.take(&indices)
+        );
+
+        assert_eq!(
+            Err(ArrayError::LengthMismatch { left: 4, right: 1 }),
+            one.filter(&ArrayBoolean::from([true]))
+        );
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 9, len: 4 }),
+            one.take(&ArrayUSize::from([9]))
+        );
+    }
+
+    #[test]
+    fn test_dot() {
+        let features = ArrayF64::from([Some(1.0), Some(2.0), None, Some(4.0)]);
+        let weights = ArrayF64::from([Some(0.5), Some(-1.0), Some(3.0), None]);
+
+        assert_eq!(Some(-1.5), features.dot(&weights));
+        assert_eq!(Some(21.0), features.dot(&features));
+        assert_eq!(
+            None,
+            ArrayF64::from_vec(vec![]).dot(&ArrayF64::from_vec(vec![]))
+        );
+        assert_eq!(
+            None,
+            ArrayI32::from([Some(1), None]).dot(&ArrayI32::from([None, Some(2)]))
+        );
+
+        let one = ArrayI32::from([i32::MAX, 2]);
+        let two = ArrayI32::from([2, 3]);
+
+        assert_eq!(Some(4), one.dot(&two));
+        assert_eq!(Some(2.0 * i32::MAX as f64 + 6.0), one.dot_f64(&two));
+    }
+
+    #[test]
+    fn test_histogram() {
+        let one = ArrayF64::from([
+            Some(1.0),
+            Some(9.0),
+            None,
+            Some(f64::NAN),
+            Some(3.0),
+            Some(4.9),
+            Some(5.0),
+        ]);
+        let (edges, counts) = one.histogram(4);
+
+        assert_eq!(ArrayF64::from([1.0, 3.0, 5.0, 7.0, 9.0]), edges);
+        assert_eq!(ArrayUSize::from([1, 2, 1, 1]), counts);
+
+        let (edges, counts) = ArrayI32::from([7, 7, 7]).histogram(3);
+        assert_eq!(ArrayF64::full(4, 7.0), edges);
+        assert_eq!(ArrayUSize::from([3, 0, 0]), counts);
+
+        let (edges, counts) = ArrayI32::nulls(2).histogram(2);
+        assert_eq!(ArrayF64::from([0.0, 0.5, 1.0]), edges);
+        assert_eq!(ArrayUSize::from([0, 0]), counts);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to compute a histogram with 0 bins")]
+    fn test_histogram_zero_bins() {
+        ArrayU32::from([1]).histogram(0);
+    }
+
+    #[test]
+    fn test_sliding_max_min() {
+        let one = ArrayI32::from([
+            Some(3),
+            Some(1),
+            None,
+            Some(4),
+            Some(1),
+            Some(5),
+            None,
+            None,
+            None,
+            Some(2),
+        ]);
+
+        assert_eq!(
+            ArrayI32::from([
+                None,
+                None,
+                Some(3),
+                Some(4),
+                Some(4),
+                Some(5),
+                Some(5),
+                Some(5),
+                None,
+                Some(2)
+            ]),
+            one.sliding_max(3)
+        );
+        assert_eq!(
+            ArrayI32::from([
+                None,
+                None,
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(5),
+                None,
+                Some(2)
+            ]),
+            one.sliding_min(3)
+        );
+        assert_eq!(one, one.sliding_max(1));
+
+        // Every window matches the naive maximum
+        let two = ArrayF64::from_vec(
+            (0..200)
+                .map(|idx| Some(((idx * 37) % 101) as f64))
+                .collect(),
+        );
+        let naive = (0..200)
+            .map(|idx: usize| {
+                (idx >= 15).then(|| {
+                    (idx - 15..=idx)
+                        .map(|idx| two.get(idx).unwrap())
+                        .fold(f64::MIN, f64::max)
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ArrayF64::from_vec(naive), two.sliding_max(16));
+
+        let three = ArrayF64::from([Some(f64::NAN), Some(1.0), Some(f64::NAN)]);
+        assert_eq!(
+            ArrayF64::from([None, Some(1.0), Some(1.0)]),
+            three.sliding_min(2)
+        );
+        assert_eq!(
+            ArrayText::from(["b", "b", "c"]),
+            ArrayText::from(["b", "a", "c"])
+                .sliding_max(2)
+                .fill_null_backward()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to slide a window of 0 elements")]
+    fn test_sliding_zero_window() {
+        ArrayU32::from([1]).sliding_max(0);
+    }
+
+    #[test]
+    fn test_argmin_argmax() {
+        let one = ArrayF64::from([
+            Some(f64::NAN),
+            Some(2.0),
+            None,
+            Some(-1.0),
+            Some(5.0),
+            Some(-1.0),
+            Some(5.0),
+        ]);
+
+        assert_eq!(Some(3), one.argmin());
+        assert_eq!(Some(4), one.argmax());
+
+        let two = ArrayI32::from([7]);
+        assert_eq!((Some(0), Some(0)), (two.argmin(), two.argmax()));
+
+        let three = ArrayI32::nulls(3);
+        assert_eq!((None, None), (three.argmin(), three.argmax()));
+        assert_eq!(None, ArrayF64::from([f64::NAN]).argmax());
+        assert_eq!(None, ArrayU32::from_vec(vec![]).argmin());
+
+        let four = ArrayText::from(["pear", "apple", "plum", "apple"]);
+        assert_eq!((Some(1), Some(2)), (four.argmin(), four.argmax()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to compute the dot product of arrays with lengths 2 and 1")]
+    fn test_dot_length_mismatch() {
+        ArrayU32::from([1, 2]).dot(&ArrayU32::from([1]));
+    }
+
+    /// Builds the validity words of `array` one element at a time.
+    fn naive_words<A: Array>(array: &A) -> Vec<(std::ops::Range<usize>, u64)> {
+        (0..array.len())
+            .step_by(64)
+            .map(|start| {
+                let range = start..array.len().min(start + 64);
+                let word = range
+                    .clone()
+                    .filter(|idx| !array.check_null(*idx))
+                    .fold(0, |word, idx| word | (1 << (idx - start)));
+
+                (range, word)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validity_words() {
+        for len in [0, 1, 63, 64, 65, 130] {
+            let one = ArrayI32::new((0..len).map(|idx| (idx % 3 != 0).then_some(idx)));
+            let two = ArrayI32::new((0..len).map(Some));
+            let three = ArrayI32::nulls(len as usize);
+            let four =
+                Union::new((0..len).map(|idx| (idx % 3 != 0).then_some(UnionType::I32(idx))));
+
+            for array in [&one, &two, &three] {
+                assert_eq!(
+                    naive_words(array),
+                    validity_words(array).collect::<Vec<_>>(),
+                    "length {len}"
+                );
+
+                let naive = array.copied_iter().flatten();
+                assert!(valid_values(array).unwrap().eq(naive), "length {len}");
+            }
+
+            assert_eq!(
+                naive_words(&four),
+                validity_words(&four).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_split_and_reverse() {
+        for seed in 0..8 {
+            let len = seed as usize * 9;
+
+            check(&crate::random_f64(len, 0.2, -5.0..5.0, seed));
+            check(&crate::random_i32(len, 0.5, -100..100, seed));
+            check(&crate::random_bool(len, 0.1, seed));
+            check(&crate::random_text(len, 0.3, "aé€", 5, seed));
+        }
+    }
+
+    #[test]
+    fn test_lex_cmp() {
+        use crate::ArrayI32;
+
+        let one = ArrayI32::from([1, 2, 3]);
+
+        assert_eq!(Ordering::Equal, one.lex_cmp(&one.clone()));
+        assert_eq!(Ordering::Less, one.lex_cmp(&ArrayI32::from([1, 3])));
+        assert_eq!(Ordering::Less, one.lex_cmp(&ArrayI32::from([1, 2, 3, 0])));
+        assert_eq!(Ordering::Greater, one.lex_cmp(&ArrayI32::from([1, 2])));
+        assert_eq!(Ordering::Greater, one.lex_cmp(&ArrayI32::from_vec(vec![])));
+
+        let two = ArrayI32::from([Some(1), None, Some(3)]);
+        assert_eq!(Ordering::Greater, one.lex_cmp(&two));
+        assert_eq!(
+            Ordering::Less,
+            two.lex_cmp(&ArrayI32::from([Some(1), Some(i32::MIN)]))
+        );
+        assert_eq!(Ordering::Equal, two.lex_cmp(&two.clone()));
+
+        let mut groups = vec![
+            one.clone(),
+            ArrayI32::from([0, 9]),
+            two.clone(),
+            one.slice(0, 2).unwrap(),
+        ];
+        groups.sort_by(|lhs, rhs| lhs.lex_cmp(rhs));
+        assert_eq!(
+            vec![ArrayI32::from([0, 9]), two, one.slice(0, 2).unwrap(), one],
+            groups
+        );
+    }
+
+    #[test]
+    fn test_lex_cmp_floats() {
+        use crate::ArrayF64;
+
+        let one = ArrayF64::from([1.0, f64::NAN]);
+
+        assert_eq!(Ordering::Equal, one.lex_cmp(&one.clone()));
+        assert_eq!(
+            Ordering::Greater,
+            one.lex_cmp(&ArrayF64::from([1.0, f64::INFINITY]))
+        );
+        assert_eq!(
+            Ordering::Less,
+            ArrayF64::from([-0.0]).lex_cmp(&ArrayF64::from([0.0]))
+        );
+        assert_eq!(
+            Ordering::Less,
+            ArrayF64::from([None, Some(f64::NAN)]).lex_cmp(&ArrayF64::from([-f64::INFINITY]))
+        );
+
+        let two = ArrayText::from(["b", "a"]);
+        assert_eq!(Ordering::Greater, two.lex_cmp(&ArrayText::from(["B", "z"])));
+
+        let three = Union::from([UnionType::I32(5), UnionType::Text("a".into())]);
+        let four = Union::from([UnionType::I32(5), UnionType::F64(9.0)]);
+        assert_eq!(Ordering::Greater, three.lex_cmp(&four));
+    }
+
+    #[test]
+    fn test_interleave() {
+        use crate::ArrayF64;
+
+        let one = ArrayF64::from([Some(1.0), Some(4.0), Some(6.0), None]);
+        let two = ArrayF64::from([Some(2.0), Some(3.0), Some(7.0), None, None]);
+
+        // Merge the sorted runs, nulls last
+        let (mut lhs, mut rhs) = (0, 0);
+        let mut indices = Vec::new();
+        while lhs < one.len() || rhs < two.len() {
+            let take_one = match (one.get(lhs), two.get(rhs)) {
+                (Some(left), Some(right)) => left <= right,
+                (_, None) => lhs < one.len(),
+                (None, Some(_)) => false,
+            };
+
+            if take_one {
+                indices.push((0, lhs));
+                lhs += 1;
+            } else {
+                indices.push((1, rhs));
+                rhs += 1;
+            }
+        }
+
+        let merged = interleave(&[&one, &two], &indices).unwrap();
+        let mut expected = vec![1.0, 2.0, 3.0, 4.0, 6.0, 7.0]
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        expected.extend([None; 3]);
+        assert_eq!(ArrayF64::new(expected), merged);
+
+        let three = ArrayText::from([Some("a".into()), None]);
+        let four = ArrayText::from(["b"]);
+        assert_eq!(
+            ArrayText::from([None, Some("b".into()), Some("a".into())]),
+            interleave(&[&three, &four], &[(0, 1), (1, 0), (0, 0)]).unwrap()
+        );
+        assert_eq!(
+            ArrayBoolean::from([true, true]),
+            interleave(&[&ArrayBoolean::from([false, true])], &[(0, 1), (0, 1)]).unwrap()
+        );
+        assert_eq!(
+            Ok(ArrayText::from_vec(vec![])),
+            interleave::<ArrayText>(&[], &[])
+        );
+
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 2, len: 2 }),
+            interleave(&[&one, &two], &[(0, 0), (2, 0)])
+        );
+        assert_eq!(
+            Err(ArrayError::IndexOutOfBounds { idx: 4, len: 4 }),
+            interleave(&[&one, &two], &[(0, 4)])
+        );
+    }
+
+    #[test]
+    fn test_dedup_with_indices() {
+        use crate::ArrayI32;
+
+        let one = ArrayI32::from([
+            Some(1),
+            Some(1),
+            None,
+            None,
+            Some(1),
+            Some(2),
+            Some(2),
+            None,
+        ]);
+        let (deduped, indices) = one.dedup_with_indices();
+
+        assert_eq!(
+            ArrayI32::from([Some(1), None, Some(1), Some(2), None]),
+            deduped
+        );
+        assert_eq!(ArrayUSize::from([0, 2, 4, 5, 7]), indices);
+
+        // Other columns stay aligned through the indices
+        let two = ArrayText::from(["a", "b", "c", "d", "e", "f", "g", "h"]);
+        assert_eq!(
+            ArrayText::from(["a", "c", "e", "f", "h"]),
+            two.take(&indices).unwrap()
+        );
+        assert_eq!(deduped, one.take(&indices).unwrap());
+
+        let (deduped, indices) = ArrayText::from_vec(vec![]).dedup_with_indices();
+        assert_eq!((0, 0), (deduped.len(), indices.len()));
+    }
+
+    #[test]
+    fn test_fill_null() {
+        use crate::ArrayF64;
+
+        let one = ArrayF64::from([None, None, Some(1.0), None, Some(2.5), None, None]);
+
+        assert_eq!(
+            ArrayF64::from([
+                None,
+                None,
+                Some(1.0),
+                Some(1.0),
+                Some(2.5),
+                Some(2.5),
+                Some(2.5)
+            ]),
+            one.fill_null_forward()
+        );
+        assert_eq!(
+            ArrayF64::from([
+                Some(1.0),
+                Some(1.0),
+                Some(1.0),
+                Some(2.5),
+                Some(2.5),
+                None,
+                None
+            ]),
+            one.fill_null_backward()
+        );
+
+        let two = ArrayText::from([Some("a".into()), None, Some("b".into())]);
+        assert_eq!(ArrayText::from(["a", "a", "b"]), two.fill_null_forward());
+        assert_eq!(ArrayText::from(["a", "b", "b"]), two.fill_null_backward());
+
+        let three = ArrayF64::nulls(3);
+        assert_eq!(three, three.fill_null_forward());
+        assert_eq!(three, three.fill_null_backward());
+    }
+
+    #[test]
+    fn test_enumerate_iter() {
+        let one = ArrayText::from(vec!["a", "b"]);
+        assert_eq!(
+            vec![(0, Some("a")), (1, Some("b"))],
+            one.enumerate_iter().collect::<Vec<_>>()
+        );
+
+        let two = ArrayI32::from([Some(3), None, Some(5)]);
+        let valid = two
+            .enumerate_copied_iter()
+            .filter_map(|(idx, value)| Some((idx, value?)))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(0, 3), (2, 5)], valid);
+        assert_eq!(0, ArrayI32::from_vec(vec![]).enumerate_iter().count());
+    }
+
+    #[test]
+    fn test_bitmap_intersect_union() {
+        for len in [0, 5, 8, 13, 70] {
+            let one = BitmapBuffer::from_bools((0..len).map(|idx| idx % 2 == 0));
+            let two = BitmapBuffer::from_bools((0..len).map(|idx| idx % 3 == 0));
+
+            let both = BitmapBuffer::intersect(Some(&one), Some(&two), len).unwrap();
+            let either = BitmapBuffer::union(Some(&one), Some(&two), len).unwrap();
+
+            for idx in 0..len {
+                assert_eq!(idx % 6 == 0, both.is_set(idx), "length {len}");
+                assert_eq!(idx % 2 == 0 || idx % 3 == 0, either.is_set(idx));
+            }
+
+            // Padding bits stay cleared so whole bytes can be compared
+            assert_eq!(both.count_ones(), len.div_ceil(6));
+            assert_eq!(&one, &both.or(&one));
+        }
+    }
+
+    #[test]
+    fn test_bitmap_intersect_union_missing() {
+        let one = BitmapBuffer::from_bools([true, false, true, false, false]);
+
+        assert_eq!(
+            Some(one.clone()),
+            BitmapBuffer::intersect(Some(&one), None, 5)
+        );
+        assert_eq!(
+            Some(one.clone()),
+            BitmapBuffer::intersect(None, Some(&one), 5)
+        );
+        assert_eq!(None, BitmapBuffer::intersect(None, None, 5));
+
+        assert_eq!(None, BitmapBuffer::union(Some(&one), None, 5));
+        assert_eq!(None, BitmapBuffer::union(None, None, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to combine a bitmap of length 5 as one of length 6")]
+    fn test_bitmap_intersect_length() {
+        let one = BitmapBuffer::new(5);
+        BitmapBuffer::intersect(None, Some(&one), 6);
+    }
+
+    #[test]
+    fn test_find() {
+        let one = ArrayI32::from([Some(3), None, Some(12), Some(-4), Some(15), None]);
+
+        assert_eq!(Some(2), one.find_first(|value| value > 10));
+        assert_eq!(Some(4), one.find_last(|value| value > 10));
+        assert_eq!(ArrayUSize::from([2, 4]), one.find_all(|value| value > 10));
+        assert_eq!(None, one.find_first(|value| value > 100));
+        assert_eq!(
+            ArrayUSize::from_vec(vec![]),
+            one.find_all(|value| value > 100)
+        );
+
+        let two = ArrayText::from(vec!["apple", "banana", "avocado"]);
+        assert_eq!(Some(2), two.find_last(|value| value.starts_with('a')));
+
+        // Short circuits on the first match
+        let seen = std::cell::Cell::new(0);
+        one.find_first(|value| {
+            seen.set(seen.get() + 1);
+            value > 10
+        });
+        assert_eq!(2, seen.get());
+    }
+
+    #[test]
+    fn test_partition_by() {
+        let one = ArrayI32::from([Some(4), None, Some(-1), Some(7), None, Some(0)]);
+
+        let (positive, rest) = one.partition_by(|value| value.is_some_and(|value| value > 0));
+        assert_eq!(ArrayI32::from([4, 7]), positive);
+        assert_eq!(ArrayI32::from([None, Some(-1), None, Some(0)]), rest);
+
+        let (nulls, valid) = one.partition_by(|value| value.is_none());
+        assert_eq!(ArrayI32::nulls(2), nulls);
+        assert_eq!(ArrayI32::from([4, -1, 7, 0]), valid);
+
+        let two = ArrayText::from(vec!["a", "bb", "ccc"]);
+        let (long, short) = two.partition_by(|value| value.is_some_and(|value| value.len() > 1));
+        assert_eq!(ArrayText::from(vec!["bb", "ccc"]), long);
+        assert_eq!(ArrayText::from(vec!["a"]), short);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let one = ArrayI32::from([Some(1), Some(1), None, None, Some(2), Some(1), Some(1)]);
+        assert_eq!(
+            ArrayI32::from([Some(1), None, Some(2), Some(1)]),
+            one.dedup()
+        );
+
+        let two = ArrayF64::from([f64::NAN, f64::NAN, 0.5, 0.5]);
+        assert_eq!(3, two.dedup().len());
+
+        let three = ArrayText::from(vec!["a", "a", "b"]);
+        assert_eq!(ArrayText::from(vec!["a", "b"]), three.dedup());
+        assert!(ArrayI32::empty().dedup().is_empty());
+    }
+
+    #[test]
+    fn test_is_sorted() {
+        let one = ArrayI32::from([Some(1), None, Some(1), Some(4), None, Some(9)]);
+        assert!(one.is_sorted());
+        assert!(!one.is_sorted_descending());
+        assert!(one.reverse().is_sorted_descending());
+
+        let two = ArrayText::from(vec!["b", "a", "a"]);
+        assert!(!two.is_sorted());
+        assert!(two.is_sorted_descending());
+
+        assert!(!ArrayF64::from([1.0, f64::NAN, 2.0]).is_sorted());
+        assert!(!ArrayF64::from([f64::NAN]).is_sorted_descending());
+
+        assert!(ArrayI32::nulls(3).is_sorted());
+        assert!(ArrayI32::empty().is_sorted_descending());
+    }
+
+    #[test]
+    fn test_at() {
+        let one = ArrayText::new([Some("a".into()), None]);
+        assert_eq!(Some("a"), one.at(0));
+        assert_eq!(None, one.at(1));
+
+        let two = ArrayI32::from([Some(4), None]);
+        assert_eq!((Some(4), None), (two.at(0), two.at(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to index 5 when array length is 2")]
+    fn test_at_out_of_bounds() {
+        ArrayF64::from([1.0, 2.0]).at(5);
     }
 }